@@ -0,0 +1,39 @@
+/*
+ * tilth C API — build with, in bindings/c:
+ *   cargo build --release
+ * which leaves target/release/libtilth_c.{so,dylib} or tilth_c.dll. A panic
+ * inside a call comes back as {"error": ...} rather than aborting the host.
+ *
+ * Arguments are NUL-terminated UTF-8. Every call except tilth_version
+ * returns a JSON string owned by the caller, released with tilth_free:
+ * the result, or {"error": "..."}.
+ */
+#ifndef TILTH_H
+#define TILTH_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* {"symbols": [{name, kind, start_line, end_line, signature, children}]} */
+char *tilth_outline(const char *path);
+
+/* kind: "symbol" (default when NULL), "content" or "regex".
+ * {"query", "total", "definitions", "usages",
+ *  "matches": [{path, line, text, kind, range, name}]} */
+char *tilth_search(const char *query, const char *scope, const char *kind);
+
+/* section: NULL, a line range like "45-89", or a markdown heading.
+ * {"text": "..."} */
+char *tilth_read(const char *path, const char *section);
+
+void tilth_free(char *s);
+
+/* Static; do not free. */
+const char *tilth_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TILTH_H */
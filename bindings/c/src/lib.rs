@@ -0,0 +1,208 @@
+//! A C ABI over [`tilth::api`], for editor plugins and other non-Rust hosts
+//! that want the engine in-process instead of spawning the MCP server. The
+//! declarations are in `include/tilth.h`.
+//!
+//! Arguments are NUL-terminated UTF-8. Every call returns a JSON string —
+//! the result, or `{"error": "..."}` — that the caller owns and releases
+//! with [`tilth_free`]. Panics are caught at the boundary and reported the
+//! same way; this crate's profiles unwind, so they never abort the host.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use tilth::api::{self, ReadOptions, SearchKind, SearchOptions, Symbol};
+
+/// Outline of the file at `path`: `{"symbols": [{name, kind, start_line,
+/// end_line, signature?, children}]}`.
+///
+/// # Safety
+///
+/// `path` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tilth_outline(path: *const c_char) -> *mut c_char {
+    respond(|| {
+        let path = arg(path, "path")?;
+        let symbols = api::outline(Path::new(path)).map_err(|e| e.to_string())?;
+        Ok(json!({ "symbols": symbols.iter().map(symbol).collect::<Vec<_>>() }))
+    })
+}
+
+/// Search under `scope` for `query`. `kind` is `"symbol"` (the default when
+/// null), `"content"` or `"regex"`. Returns `{"query", "total",
+/// "definitions", "usages", "matches": [{path, line, text, kind, range?,
+/// name?}]}`.
+///
+/// # Safety
+///
+/// Each argument must be null or point to a NUL-terminated string; only
+/// `kind` may be null.
+#[no_mangle]
+pub unsafe extern "C" fn tilth_search(
+    query: *const c_char,
+    scope: *const c_char,
+    kind: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let query = arg(query, "query")?;
+        let scope = arg(scope, "scope")?;
+        let kind = match optional(kind)? {
+            None | Some("symbol") => SearchKind::Symbol,
+            Some("content") => SearchKind::Content,
+            Some("regex") => SearchKind::Regex,
+            Some(other) => return Err(format!("unknown kind {other:?}")),
+        };
+        let options = SearchOptions::new(scope).kind(kind);
+        let results = api::search(query, &options).map_err(|e| e.to_string())?;
+        let matches: Vec<Value> = results
+            .matches
+            .iter()
+            .map(|m| {
+                json!({
+                    "path": m.path.display().to_string(),
+                    "line": m.line,
+                    "text": m.text,
+                    "kind": m.kind.as_str(),
+                    "range": m.range.map(|(start, end)| [start, end]),
+                    "name": m.name,
+                })
+            })
+            .collect();
+        Ok(json!({
+            "query": results.query,
+            "total": results.total,
+            "definitions": results.definitions,
+            "usages": results.usages,
+            "matches": matches,
+        }))
+    })
+}
+
+/// The file at `path` as tilth renders it — `section` (nullable) is a line
+/// range like `"45-89"` or a markdown heading. Returns `{"text": "..."}`.
+///
+/// # Safety
+///
+/// Each argument must be null or point to a NUL-terminated string; only
+/// `section` may be null.
+#[no_mangle]
+pub unsafe extern "C" fn tilth_read(path: *const c_char, section: *const c_char) -> *mut c_char {
+    respond(|| {
+        let path = arg(path, "path")?;
+        let mut options = ReadOptions::new();
+        if let Some(section) = optional(section)? {
+            options = options.section(section);
+        }
+        let text = api::read(Path::new(path), &options).map_err(|e| e.to_string())?;
+        Ok(json!({ "text": text }))
+    })
+}
+
+/// Release a string returned by any `tilth_*` call. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by this library, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tilth_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// The library version, e.g. `"0.4.3"`. Static; not to be freed.
+#[no_mangle]
+pub extern "C" fn tilth_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Run `call` and hand its JSON, or its error, to the caller.
+fn respond(call: impl FnOnce() -> Result<Value, String>) -> *mut c_char {
+    let value = match catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(error)) => json!({ "error": error }),
+        Err(_) => json!({ "error": "internal error (panic)" }),
+    };
+    // serde_json escapes NUL inside strings, so the text has none
+    CString::new(value.to_string()).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// A required string argument.
+unsafe fn arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    optional(ptr)?.ok_or_else(|| format!("{name} is null"))
+}
+
+unsafe fn optional<'a>(ptr: *const c_char) -> Result<Option<&'a str>, String> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|_| "argument is not valid UTF-8".to_string())
+}
+
+fn symbol(s: &Symbol) -> Value {
+    json!({
+        "name": s.name,
+        "kind": s.kind.as_str(),
+        "start_line": s.start_line,
+        "end_line": s.end_line,
+        "signature": s.signature,
+        "children": s.children.iter().map(symbol).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Call through the C ABI and parse what comes back.
+    fn call(f: impl FnOnce() -> *mut c_char) -> Value {
+        let ptr = f();
+        assert!(!ptr.is_null());
+        let text = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { tilth_free(ptr) };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_the_c_abi() {
+        let dir = std::env::temp_dir().join("tilth_test_ffi");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("lib.rs"),
+            "pub fn parse() {}\n\nfn main() {\n    parse();\n}\n",
+        )
+        .unwrap();
+        let c = |s: &str| CString::new(s).unwrap();
+        let scope = c(dir.to_str().unwrap());
+        let file = c(dir.join("lib.rs").to_str().unwrap());
+
+        let found =
+            call(|| unsafe { tilth_search(c("parse").as_ptr(), scope.as_ptr(), std::ptr::null()) });
+        assert_eq!(found["definitions"], 1, "{found}");
+        assert_eq!(found["matches"][0]["kind"], "definition", "{found}");
+
+        let outline = call(|| unsafe { tilth_outline(file.as_ptr()) });
+        assert_eq!(outline["symbols"][0]["name"], "parse", "{outline}");
+        assert_eq!(outline["symbols"][0]["kind"], "function", "{outline}");
+
+        let read = call(|| unsafe { tilth_read(file.as_ptr(), c("4-4").as_ptr()) });
+        assert!(
+            read["text"].as_str().unwrap().contains("parse();"),
+            "{read}"
+        );
+
+        let bad =
+            call(|| unsafe { tilth_search(std::ptr::null(), scope.as_ptr(), c("fuzzy").as_ptr()) });
+        assert_eq!(bad["error"], "query is null");
+
+        let version = unsafe { CStr::from_ptr(tilth_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,164 @@
+//! Python bindings for [`tilth::api`]: search, read, outline and map as
+//! in-process calls, for agent frameworks that would otherwise run the MCP
+//! server and talk JSON-RPC to it over stdio.
+//!
+//! Results are plain dicts, lists and strings so they drop straight into a
+//! `LangChain` or `LlamaIndex` tool. The GIL is released while tilth works.
+
+use std::path::Path;
+
+use pyo3::exceptions::{PyFileNotFoundError, PyOSError, PyPermissionError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use tilth::api::{self, ReadOptions, SearchKind, SearchOptions, SearchResults, Symbol, TilthError};
+
+/// Search under `scope` for `query`. `kind` is "symbol" (definitions first,
+/// then usages), "content" or "regex"; `context` is the file being worked
+/// on, whose neighbours rank higher.
+#[pyfunction]
+#[pyo3(signature = (query, scope = ".", kind = "symbol", context = None))]
+fn search(
+    py: Python<'_>,
+    query: &str,
+    scope: &str,
+    kind: &str,
+    context: Option<&str>,
+) -> PyResult<PyObject> {
+    let options = search_options(scope, kind, context)?;
+    let results = py
+        .allow_threads(|| api::search(query, &options))
+        .map_err(error)?;
+    results_dict(py, &results)
+}
+
+/// `search` for each of `queries`, sharing one walk of `scope`.
+#[pyfunction]
+#[pyo3(signature = (queries, scope = ".", kind = "symbol"))]
+fn search_many(
+    py: Python<'_>,
+    queries: Vec<String>,
+    scope: &str,
+    kind: &str,
+) -> PyResult<PyObject> {
+    let options = search_options(scope, kind, None)?;
+    let queries: Vec<&str> = queries.iter().map(String::as_str).collect();
+    let results = py
+        .allow_threads(|| api::search_many(&queries, &options))
+        .map_err(error)?;
+    let list = PyList::empty(py);
+    for r in &results {
+        list.append(results_dict(py, r)?)?;
+    }
+    Ok(list.into_any().unbind())
+}
+
+/// The file at `path` as tilth renders it: small files whole, large ones as
+/// an outline. `section` is a line range like "45-89" or a markdown
+/// heading; `full` forces the whole file.
+#[pyfunction]
+#[pyo3(signature = (path, section = None, full = false))]
+fn read(py: Python<'_>, path: &str, section: Option<String>, full: bool) -> PyResult<String> {
+    let mut options = ReadOptions::new().full(full);
+    if let Some(section) = section {
+        options = options.section(section);
+    }
+    py.allow_threads(|| api::read(Path::new(path), &options))
+        .map_err(error)
+}
+
+/// The definitions in the file at `path`, nested, as dicts with `name`,
+/// `kind`, `start_line`, `end_line`, `signature` and `children`.
+#[pyfunction]
+fn outline(py: Python<'_>, path: &str) -> PyResult<PyObject> {
+    let symbols = py
+        .allow_threads(|| api::outline(Path::new(path)))
+        .map_err(error)?;
+    symbols_list(py, &symbols)
+}
+
+/// The skeleton of `scope` to `depth` directory levels.
+#[pyfunction]
+#[pyo3(signature = (scope = ".", depth = 3))]
+fn map(py: Python<'_>, scope: &str, depth: usize) -> String {
+    py.allow_threads(|| api::map(Path::new(scope), depth))
+}
+
+#[pymodule]
+#[pyo3(name = "tilth")]
+fn tilth_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(search, m)?)?;
+    m.add_function(wrap_pyfunction!(search_many, m)?)?;
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(outline, m)?)?;
+    m.add_function(wrap_pyfunction!(map, m)?)?;
+    Ok(())
+}
+
+fn search_options(scope: &str, kind: &str, context: Option<&str>) -> PyResult<SearchOptions> {
+    let kind = match kind {
+        "symbol" => SearchKind::Symbol,
+        "content" => SearchKind::Content,
+        "regex" => SearchKind::Regex,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unknown kind {other:?} — expected \"symbol\", \"content\" or \"regex\""
+            )))
+        }
+    };
+    let options = SearchOptions::new(Path::new(scope)).kind(kind);
+    Ok(match context {
+        Some(path) => options.context(path),
+        None => options,
+    })
+}
+
+/// The Python exception closest to `e`, carrying tilth's message.
+fn error(e: TilthError) -> PyErr {
+    let message = e.to_string();
+    match e {
+        TilthError::NotFound { .. } => PyFileNotFoundError::new_err(message),
+        TilthError::PermissionDenied { .. } => PyPermissionError::new_err(message),
+        TilthError::InvalidQuery { .. } | TilthError::OutOfScope { .. } => {
+            PyValueError::new_err(message)
+        }
+        _ => PyOSError::new_err(message),
+    }
+}
+
+fn results_dict(py: Python<'_>, results: &SearchResults) -> PyResult<PyObject> {
+    let matches = PyList::empty(py);
+    for m in &results.matches {
+        let d = PyDict::new(py);
+        d.set_item("path", m.path.display().to_string())?;
+        d.set_item("line", m.line)?;
+        d.set_item("text", &m.text)?;
+        d.set_item("kind", m.kind.as_str())?;
+        d.set_item("range", m.range)?;
+        d.set_item("name", &m.name)?;
+        matches.append(d)?;
+    }
+    let d = PyDict::new(py);
+    d.set_item("query", &results.query)?;
+    d.set_item("total", results.total)?;
+    d.set_item("definitions", results.definitions)?;
+    d.set_item("usages", results.usages)?;
+    d.set_item("matches", matches)?;
+    Ok(d.into_any().unbind())
+}
+
+fn symbols_list(py: Python<'_>, symbols: &[Symbol]) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    for s in symbols {
+        let d = PyDict::new(py);
+        d.set_item("name", &s.name)?;
+        d.set_item("kind", s.kind.as_str())?;
+        d.set_item("start_line", s.start_line)?;
+        d.set_item("end_line", s.end_line)?;
+        d.set_item("signature", &s.signature)?;
+        d.set_item("children", symbols_list(py, &s.children)?)?;
+        list.append(d)?;
+    }
+    Ok(list.into_any().unbind())
+}
@@ -0,0 +1,493 @@
+//! Build and CI configuration: workflows, Dockerfiles, Makefiles, justfiles
+//! and compose files, each outlined down to its jobs, stages or targets.
+//!
+//! YAML is scanned by indentation, like the structured-data outline — no parser.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::{collect_files, rel};
+use crate::read::outline::buildfile;
+
+/// Outline entries shown per file before the rest are summarised as a count.
+const MAX_ENTRIES: usize = 40;
+
+/// Top-level `.gitlab-ci.yml` keys that configure the pipeline rather than define a job.
+const GITLAB_RESERVED: &[&str] = &[
+    "default",
+    "include",
+    "stages",
+    "variables",
+    "workflow",
+    "image",
+    "services",
+    "cache",
+    "before_script",
+    "after_script",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigKind {
+    GithubActions,
+    GitlabCi,
+    Docker,
+    Compose,
+    Make,
+    Just,
+}
+
+impl ConfigKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::GithubActions => "GitHub Actions",
+            Self::GitlabCi => "GitLab CI",
+            Self::Docker => "Docker",
+            Self::Compose => "Compose",
+            Self::Make => "Make",
+            Self::Just => "just",
+        }
+    }
+}
+
+/// Locate build/CI configs under `scope` and outline each one.
+pub fn analyze(scope: &Path) -> String {
+    let files = collect_files(scope, |p| config_kind(p).is_some());
+
+    let mut out = format!(
+        "# Build & CI config: {} — {} files",
+        scope.display(),
+        files.len()
+    );
+    if files.is_empty() {
+        out.push_str(
+            "\n\nNo CI workflows, Dockerfiles, Makefiles, justfiles or compose files found.",
+        );
+        return out;
+    }
+
+    for path in &files {
+        let Some(kind) = config_kind(path) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+
+        let entries = match kind {
+            ConfigKind::GithubActions => github_workflow(&content),
+            ConfigKind::GitlabCi => gitlab_jobs(&content),
+            ConfigKind::Docker => docker_stages(&content),
+            ConfigKind::Compose => compose_services(&content),
+            ConfigKind::Make => buildfile::make_targets(&content)
+                .into_iter()
+                .map(|t| {
+                    if t.prereqs.is_empty() {
+                        format!("[{}] {}", t.line, t.name)
+                    } else {
+                        format!("[{}] {}: {}", t.line, t.name, t.prereqs)
+                    }
+                })
+                .collect(),
+            ConfigKind::Just => just_recipes(&content),
+        };
+
+        let _ = write!(out, "\n\n## {} [{}]", rel(path, scope), kind.label());
+        if entries.is_empty() {
+            out.push_str("\n  (no jobs or targets found)");
+        }
+        for entry in entries.iter().take(MAX_ENTRIES) {
+            let _ = write!(out, "\n  {entry}");
+        }
+        if entries.len() > MAX_ENTRIES {
+            let _ = write!(out, "\n  ... and {} more", entries.len() - MAX_ENTRIES);
+        }
+    }
+
+    out
+}
+
+fn config_kind(path: &Path) -> Option<ConfigKind> {
+    let name = path.file_name()?.to_str()?;
+    let ext = path.extension().and_then(|e| e.to_str());
+
+    let in_workflows = path.parent().is_some_and(|dir| {
+        dir.file_name().is_some_and(|n| n == "workflows")
+            && dir
+                .parent()
+                .and_then(Path::file_name)
+                .is_some_and(|n| n == ".github")
+    });
+    if in_workflows && matches!(ext, Some("yml" | "yaml")) {
+        return Some(ConfigKind::GithubActions);
+    }
+
+    match name {
+        ".gitlab-ci.yml" => Some(ConfigKind::GitlabCi),
+        "Dockerfile" | "Containerfile" => Some(ConfigKind::Docker),
+        n if n.starts_with("Dockerfile.") || n.ends_with(".dockerfile") => Some(ConfigKind::Docker),
+        "docker-compose.yml" | "docker-compose.yaml" | "compose.yml" | "compose.yaml" => {
+            Some(ConfigKind::Compose)
+        }
+        "Makefile" | "GNUmakefile" | "makefile" => Some(ConfigKind::Make),
+        _ if ext == Some("mk") => Some(ConfigKind::Make),
+        "justfile" | "Justfile" | ".justfile" => Some(ConfigKind::Just),
+        _ => None,
+    }
+}
+
+/// A YAML mapping entry: key plus the 0-based line span `[start, end)` of its block.
+struct Block<'a> {
+    name: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl Block<'_> {
+    fn range(&self) -> String {
+        if self.end > self.start + 1 {
+            format!("[{}-{}]", self.start + 1, self.end)
+        } else {
+            format!("[{}]", self.start + 1)
+        }
+    }
+}
+
+/// Key of a `key: value` / `key:` line (already trimmed). Strips surrounding quotes.
+fn yaml_key(trimmed: &str) -> Option<&str> {
+    if trimmed.starts_with('-') || trimmed.starts_with('#') {
+        return None;
+    }
+    let colon = trimmed.find(':')?;
+    let after = &trimmed[colon + 1..];
+    if !(after.is_empty() || after.starts_with(' ')) {
+        return None;
+    }
+    let key = trimmed[..colon].trim_matches(|c| c == '"' || c == '\'');
+    (!key.is_empty() && !key.contains(' ')).then_some(key)
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_content(line: &str) -> bool {
+    let t = line.trim_start();
+    !t.is_empty() && !t.starts_with('#')
+}
+
+/// Children of the mapping at `lines[parent]`, or of the document root when `parent` is `None`.
+fn yaml_children<'a>(lines: &[&'a str], parent: Option<usize>) -> Vec<Block<'a>> {
+    let (first, parent_indent) = match parent {
+        Some(p) => (p + 1, Some(indent(lines[p]))),
+        None => (0, None),
+    };
+
+    let mut out: Vec<Block> = Vec::new();
+    let mut child_indent = None;
+    for (i, line) in lines.iter().enumerate().skip(first) {
+        if !is_content(line) || line.starts_with("---") {
+            continue;
+        }
+        let ind = indent(line);
+        if parent_indent.is_some_and(|p| ind <= p) {
+            break;
+        }
+        let ci = *child_indent.get_or_insert(ind);
+        match yaml_key(line.trim_start()) {
+            Some(key) if ind == ci => out.push(Block {
+                name: key,
+                start: i,
+                end: i + 1,
+            }),
+            _ => {
+                if let Some(last) = out.last_mut() {
+                    last.end = i + 1;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Value of `key:` among the direct children of `block`, if written inline.
+fn child_value<'a>(lines: &[&'a str], block: &Block, key: &str) -> Option<&'a str> {
+    yaml_children(lines, Some(block.start))
+        .into_iter()
+        .find(|c| c.name == key)
+        .map(|c| {
+            let line = lines[c.start].trim_start();
+            line[line.find(':').map_or(0, |p| p + 1)..].trim()
+        })
+        .filter(|v| !v.is_empty())
+}
+
+fn root_block<'a>(lines: &[&'a str], key: &str) -> Option<Block<'a>> {
+    yaml_children(lines, None)
+        .into_iter()
+        .find(|b| b.name == key)
+}
+
+/// Number of list items directly under `block`.
+fn list_len(lines: &[&str], block: &Block) -> usize {
+    let base = indent(lines[block.start]);
+    let mut item_indent = None;
+    lines[block.start + 1..block.end]
+        .iter()
+        .filter(|l| is_content(l) && indent(l) > base && l.trim_start().starts_with('-'))
+        .filter(|l| *item_indent.get_or_insert(indent(l)) == indent(l))
+        .count()
+}
+
+fn shorten(value: &str) -> String {
+    if value.len() > 40 {
+        format!("{}...", crate::types::truncate_str(value, 37))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Triggers plus one entry per job with its runner, dependencies and step count.
+fn github_workflow(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+
+    // `on` may be quoted to dodge YAML 1.1's boolean reading
+    if let Some(on) = root_block(&lines, "on") {
+        let inline = lines[on.start].trim_start();
+        let inline = inline[inline.find(':').map_or(0, |p| p + 1)..].trim();
+        let triggers = if inline.is_empty() {
+            yaml_children(&lines, Some(on.start))
+                .iter()
+                .map(|c| c.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            inline.to_string()
+        };
+        out.push(format!("{} on: {triggers}", on.range()));
+    }
+
+    let Some(jobs) = root_block(&lines, "jobs") else {
+        return out;
+    };
+    for job in yaml_children(&lines, Some(jobs.start)) {
+        let mut details = Vec::new();
+        if let Some(runner) = child_value(&lines, &job, "runs-on") {
+            details.push(format!("runs-on: {}", shorten(runner)));
+        }
+        if let Some(uses) = child_value(&lines, &job, "uses") {
+            details.push(format!("uses: {}", shorten(uses)));
+        }
+        if let Some(needs) = child_value(&lines, &job, "needs") {
+            details.push(format!("needs: {}", shorten(needs)));
+        }
+        if let Some(steps) = yaml_children(&lines, Some(job.start))
+            .into_iter()
+            .find(|c| c.name == "steps")
+        {
+            details.push(format!("{} steps", list_len(&lines, &steps)));
+        }
+
+        let mut entry = format!("{} job {}", job.range(), job.name);
+        if !details.is_empty() {
+            let _ = write!(entry, " — {}", details.join(", "));
+        }
+        out.push(entry);
+    }
+    out
+}
+
+/// Every top-level key that isn't pipeline config or a hidden (`.`-prefixed) template.
+fn gitlab_jobs(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+
+    if let Some(stages) = root_block(&lines, "stages") {
+        let names: Vec<&str> = lines[stages.start + 1..stages.end]
+            .iter()
+            .filter_map(|l| l.trim_start().strip_prefix('-'))
+            .map(str::trim)
+            .collect();
+        out.push(format!("{} stages: {}", stages.range(), names.join(", ")));
+    }
+
+    for job in yaml_children(&lines, None) {
+        if job.name.starts_with('.') || GITLAB_RESERVED.contains(&job.name) {
+            continue;
+        }
+        let mut entry = format!("{} job {}", job.range(), job.name);
+        if let Some(stage) = child_value(&lines, &job, "stage") {
+            let _ = write!(entry, " — stage: {}", shorten(stage));
+        }
+        out.push(entry);
+    }
+    out
+}
+
+/// One entry per service with its image or build context.
+fn compose_services(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(services) = root_block(&lines, "services") else {
+        return Vec::new();
+    };
+    yaml_children(&lines, Some(services.start))
+        .into_iter()
+        .map(|svc| {
+            let mut entry = format!("{} service {}", svc.range(), svc.name);
+            if let Some(image) = child_value(&lines, &svc, "image") {
+                let _ = write!(entry, " — image: {}", shorten(image));
+            } else if let Some(build) = child_value(&lines, &svc, "build") {
+                let _ = write!(entry, " — build: {}", shorten(build));
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Build stages (`FROM`) plus the runtime instructions that matter to a
+/// caller: `EXPOSE`, `ENTRYPOINT`, `CMD`.
+fn docker_stages(content: &str) -> Vec<String> {
+    buildfile::dockerfile_instructions(content)
+        .into_iter()
+        .filter(|ins| {
+            matches!(
+                ins.keyword.as_str(),
+                "FROM" | "EXPOSE" | "ENTRYPOINT" | "CMD"
+            )
+        })
+        .map(|ins| format!("[{}] {} {}", ins.line, ins.keyword, shorten(ins.args)))
+        .collect()
+}
+
+/// Recipes of a justfile: `name params: deps` at column 0.
+/// Skips settings, aliases, variable assignments and attributes.
+fn just_recipes(content: &str) -> Vec<String> {
+    const DIRECTIVES: &[&str] = &["set ", "alias ", "export ", "import ", "mod "];
+
+    let mut out = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.is_empty()
+            || line.starts_with([' ', '\t', '#', '['])
+            || DIRECTIVES.iter().any(|d| line.starts_with(d))
+        {
+            continue;
+        }
+        let Some(colon) = line.find(':') else {
+            continue;
+        };
+        let (head, rest) = line.split_at(colon);
+        if rest.starts_with(":=") {
+            continue;
+        }
+        let head = head.trim_start_matches('@').trim();
+        let deps = rest[1..].trim();
+        let entry = if deps.is_empty() {
+            format!("[{}] {head}", i + 1)
+        } else {
+            format!("[{}] {head}: {deps}", i + 1)
+        };
+        out.push(entry);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = "\
+name: CI
+on:
+  push:
+    branches: [main]
+  pull_request:
+
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - run: cargo test
+        env:
+          RUST_LOG: debug
+
+  lint:
+    needs: test
+    runs-on: ubuntu-latest
+    steps:
+      - run: cargo clippy
+";
+
+    #[test]
+    fn workflow_triggers_and_jobs() {
+        let entries = github_workflow(WORKFLOW);
+        assert_eq!(entries[0], "[2-5] on: push, pull_request");
+        assert_eq!(
+            entries[1],
+            "[8-14] job test — runs-on: ubuntu-latest, 2 steps"
+        );
+        assert_eq!(
+            entries[2],
+            "[16-20] job lint — runs-on: ubuntu-latest, needs: test, 1 steps"
+        );
+    }
+
+    #[test]
+    fn workflow_inline_trigger() {
+        let entries =
+            github_workflow("on: [push]\njobs:\n  a:\n    uses: ./.github/workflows/x.yml\n");
+        assert_eq!(entries[0], "[1] on: [push]");
+        assert_eq!(entries[1], "[3-4] job a — uses: ./.github/workflows/x.yml");
+    }
+
+    #[test]
+    fn gitlab_skips_reserved_and_hidden() {
+        let yml = "stages:\n  - build\n  - test\n\n.template:\n  image: x\n\nvariables:\n  A: b\n\nunit:\n  stage: test\n  script: make test\n";
+        let entries = gitlab_jobs(yml);
+        assert_eq!(
+            entries,
+            vec![
+                "[1-3] stages: build, test",
+                "[11-13] job unit — stage: test"
+            ]
+        );
+    }
+
+    #[test]
+    fn dockerfile_stages_skip_continuations() {
+        let df = "FROM rust:1.80 AS builder\nRUN apt-get update && \\\n    FROM-looking-arg\nCOPY . .\nfrom debian:slim\nEXPOSE 8080\nCMD [\"app\"]\n";
+        let entries = docker_stages(df);
+        assert_eq!(
+            entries,
+            vec![
+                "[1] FROM rust:1.80 AS builder",
+                "[5] FROM debian:slim",
+                "[6] EXPOSE 8080",
+                "[7] CMD [\"app\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn just_recipes_skip_settings() {
+        let just = "set shell := [\"bash\", \"-c\"]\nversion := \"1\"\n\n# Run tests\ntest filter='': build\n    cargo test {{filter}}\n\n@build:\n    cargo build\n";
+        assert_eq!(
+            just_recipes(just),
+            vec!["[5] test filter='': build", "[8] build"]
+        );
+    }
+
+    #[test]
+    fn workflow_paths_detected() {
+        assert_eq!(
+            config_kind(Path::new("repo/.github/workflows/ci.yml")),
+            Some(ConfigKind::GithubActions)
+        );
+        assert_eq!(config_kind(Path::new("repo/workflows/ci.yml")), None);
+        assert_eq!(
+            config_kind(Path::new("Dockerfile.dev")),
+            Some(ConfigKind::Docker)
+        );
+    }
+}
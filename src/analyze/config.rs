@@ -0,0 +1,279 @@
+//! Environment/config keys: where each key is defined (`.env*` files) and
+//! where code reads it. Surfaces keys nobody reads and reads nobody defines.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::{collect_files, rel};
+use crate::read::detect_file_type;
+use crate::types::FileType;
+
+/// Usage sites shown per key before the rest are summarised as a count.
+const MAX_SITES: usize = 5;
+
+/// Call prefixes that take the key as a string literal: `getenv("KEY")`.
+const CALL_READS: &[&str] = &[
+    "env::var(",
+    "env::var_os(",
+    "env!(",
+    "option_env!(",
+    "process.env[",
+    "import.meta.env[",
+    "environ[",
+    "environ.get(",
+    "getenv(",
+    "Getenv(",
+    "LookupEnv(",
+    "ENV[",
+    "ENV.fetch(",
+    "$_ENV[",
+    "GetEnvironmentVariable(",
+];
+
+/// Property-style reads: `process.env.KEY`.
+const PROPERTY_READS: &[&str] = &["process.env.", "import.meta.env."];
+
+/// Set by the OS, shell or toolchain — reading them without a definition is expected.
+const PROVIDED: &[&str] = &[
+    "PATH", "HOME", "USER", "SHELL", "PWD", "TMPDIR", "TERM", "LANG", "CI", "NODE_ENV",
+];
+const PROVIDED_PREFIXES: &[&str] = &["CARGO_", "GITHUB_", "npm_"];
+
+/// `path:line` of a definition or read.
+struct Site {
+    path: String,
+    line: usize,
+}
+
+/// Cross-reference keys defined in env files against reads in code under `scope`.
+pub fn analyze(scope: &Path) -> String {
+    let files = collect_files(scope, |p| {
+        is_env_file(p) || matches!(detect_file_type(p), FileType::Code(_))
+    });
+
+    let mut defined: BTreeMap<String, Vec<Site>> = BTreeMap::new();
+    let mut read: BTreeMap<String, Vec<Site>> = BTreeMap::new();
+    for path in &files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let (keys, target) = if is_env_file(path) {
+            (env_definitions(&content), &mut defined)
+        } else {
+            (env_reads(&content), &mut read)
+        };
+        for (key, line) in keys {
+            target.entry(key.to_string()).or_default().push(Site {
+                path: rel(path, scope),
+                line,
+            });
+        }
+    }
+
+    report(scope, &defined, &read)
+}
+
+/// `.env`, `.env.local`, `.env.example`, `prod.env`, `.envrc`.
+fn is_env_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with(".env"))
+        || path.extension().is_some_and(|e| e == "env")
+}
+
+/// `KEY=value` / `export KEY=value` lines, with their 1-based line numbers.
+fn env_definitions(content: &str) -> Vec<(&str, usize)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim_start();
+            let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+            let (key, _) = line.split_once('=')?;
+            let key = key.trim_end();
+            is_key(key).then_some((key, i + 1))
+        })
+        .collect()
+}
+
+/// Environment reads in source code, with their 1-based line numbers.
+fn env_reads(content: &str) -> Vec<(&str, usize)> {
+    let mut out = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        for prefix in CALL_READS {
+            for (at, _) in line.match_indices(prefix) {
+                let rest = line[at + prefix.len()..].trim_start();
+                let Some(quote) = rest
+                    .chars()
+                    .next()
+                    .filter(|c| matches!(c, '"' | '\'' | '`'))
+                else {
+                    continue;
+                };
+                let key = rest[1..].split(quote).next().unwrap_or("");
+                if is_key(key) {
+                    out.push((key, i + 1));
+                }
+            }
+        }
+        for prefix in PROPERTY_READS {
+            for (at, _) in line.match_indices(prefix) {
+                let rest = &line[at + prefix.len()..];
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                if is_key(&rest[..end]) {
+                    out.push((&rest[..end], i + 1));
+                }
+            }
+        }
+    }
+    // Overlapping prefixes (`env!(` inside `option_env!(`) report the same read twice
+    out.sort_unstable();
+    out.dedup();
+    out.sort_by_key(|&(_, line)| line);
+    out
+}
+
+fn is_key(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_provided(key: &str) -> bool {
+    PROVIDED.contains(&key) || PROVIDED_PREFIXES.iter().any(|p| key.starts_with(p))
+}
+
+fn sites(sites: &[Site]) -> String {
+    let mut out = sites
+        .iter()
+        .take(MAX_SITES)
+        .map(|s| format!("{}:{}", s.path, s.line))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if sites.len() > MAX_SITES {
+        let _ = write!(out, " (+{} more)", sites.len() - MAX_SITES);
+    }
+    out
+}
+
+fn report(
+    scope: &Path,
+    defined: &BTreeMap<String, Vec<Site>>,
+    read: &BTreeMap<String, Vec<Site>>,
+) -> String {
+    let mut out = format!(
+        "# Config keys: {} — {} defined, {} read",
+        scope.display(),
+        defined.len(),
+        read.len()
+    );
+    if defined.is_empty() && read.is_empty() {
+        out.push_str("\n\nNo .env files or environment reads found.");
+        return out;
+    }
+
+    let used: Vec<&String> = defined.keys().filter(|k| read.contains_key(*k)).collect();
+    if !used.is_empty() {
+        out.push_str("\n\n## Defined and read");
+        for key in used {
+            let _ = write!(
+                out,
+                "\n  {key} — defined {} — read {}",
+                sites(&defined[key]),
+                sites(&read[key])
+            );
+        }
+    }
+
+    let unused: Vec<&String> = defined.keys().filter(|k| !read.contains_key(*k)).collect();
+    if !unused.is_empty() {
+        out.push_str("\n\n## Defined, never read");
+        for key in unused {
+            let _ = write!(out, "\n  {key} — {}", sites(&defined[key]));
+        }
+    }
+
+    let undefined: Vec<&String> = read
+        .keys()
+        .filter(|k| !defined.contains_key(*k) && !is_provided(k))
+        .collect();
+    if !undefined.is_empty() {
+        out.push_str("\n\n## Read, never defined");
+        for key in undefined {
+            let _ = write!(out, "\n  {key} — {}", sites(&read[key]));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_file_definitions() {
+        let env = "# db\nDATABASE_URL=postgres://x\nexport API_KEY = abc\n\nnot a key\n9BAD=1\n";
+        assert_eq!(
+            env_definitions(env),
+            vec![("DATABASE_URL", 2), ("API_KEY", 3)]
+        );
+    }
+
+    #[test]
+    fn reads_across_languages() {
+        let code = r#"let url = std::env::var("DATABASE_URL")?;
+const key = process.env.API_KEY || process.env["FALLBACK"];
+port = os.environ.get('PORT', 8080)
+host := os.Getenv("HOST")
+let dynamic = env::var(name);
+"#;
+        assert_eq!(
+            env_reads(code),
+            vec![
+                ("DATABASE_URL", 1),
+                ("API_KEY", 2),
+                ("FALLBACK", 2),
+                ("PORT", 3),
+                ("HOST", 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn report_sections() {
+        let site = |path: &str, line| Site {
+            path: path.to_string(),
+            line,
+        };
+        let defined = BTreeMap::from([
+            ("DATABASE_URL".to_string(), vec![site(".env", 1)]),
+            ("STALE".to_string(), vec![site(".env", 2)]),
+        ]);
+        let read = BTreeMap::from([
+            ("DATABASE_URL".to_string(), vec![site("src/db.rs", 4)]),
+            ("MISSING".to_string(), vec![site("src/app.rs", 9)]),
+            ("HOME".to_string(), vec![site("src/app.rs", 10)]),
+        ]);
+        let out = report(Path::new("."), &defined, &read);
+        assert!(
+            out.contains("## Defined and read\n  DATABASE_URL — defined .env:1 — read src/db.rs:4"),
+            "{out}"
+        );
+        assert!(
+            out.contains("## Defined, never read\n  STALE — .env:2"),
+            "{out}"
+        );
+        assert!(
+            out.contains("## Read, never defined\n  MISSING — src/app.rs:9"),
+            "{out}"
+        );
+        assert!(!out.contains("HOME"), "{out}");
+    }
+}
@@ -0,0 +1,426 @@
+//! Executable entry points: binaries, package scripts, `main` functions and
+//! make targets — everything that answers "how do I run this?".
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{collect_files, line_of, rel};
+use crate::read::outline::buildfile;
+
+/// Entries shown per kind before the rest are summarised as a count.
+const MAX_PER_KIND: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    RustBin,
+    NpmScript,
+    NpmBin,
+    PythonScript,
+    PythonMain,
+    GoMain,
+    MakeTarget,
+}
+
+impl Kind {
+    fn heading(self) -> &'static str {
+        match self {
+            Self::RustBin => "Rust binaries",
+            Self::NpmScript => "npm scripts",
+            Self::NpmBin => "npm bins",
+            Self::PythonScript => "Python console scripts",
+            Self::PythonMain => "Python __main__",
+            Self::GoMain => "Go main packages",
+            Self::MakeTarget => "Make targets",
+        }
+    }
+}
+
+/// One runnable thing and where it is declared.
+#[derive(Debug)]
+struct EntryPoint {
+    kind: Kind,
+    name: String,
+    path: String,
+    line: Option<usize>,
+    /// Script command, `module:func` target, or make prerequisites.
+    detail: Option<String>,
+}
+
+/// List executable entry points under `scope`, grouped by kind.
+pub fn analyze(scope: &Path) -> String {
+    let files = collect_files(scope, is_candidate);
+    let mut entries: Vec<EntryPoint> = Vec::new();
+
+    for path in &files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let display = rel(path, scope);
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+        match (name, ext) {
+            ("Cargo.toml", _) => entries.extend(cargo_bins(path, &content, scope)),
+            ("package.json", _) => entries.extend(npm_entries(&content, &display)),
+            ("pyproject.toml", _) => entries.extend(python_scripts(&content, &display)),
+            ("Makefile" | "GNUmakefile" | "makefile", _) | (_, "mk") => {
+                entries.extend(make_targets(&content, &display));
+            }
+            (_, "py") => entries.extend(python_main(path, &content, scope)),
+            (_, "go") => entries.extend(go_main(path, &content, scope)),
+            _ => {}
+        }
+    }
+
+    format_entries(scope, &mut entries)
+}
+
+fn is_candidate(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    matches!(
+        name,
+        "Cargo.toml" | "package.json" | "pyproject.toml" | "Makefile" | "GNUmakefile" | "makefile"
+    ) || matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("py" | "go" | "mk")
+    )
+}
+
+/// Binaries of a Cargo package: explicit `[[bin]]` targets, `src/main.rs`,
+/// and auto-discovered `src/bin/*.rs`.
+fn cargo_bins(manifest: &Path, content: &str, scope: &Path) -> Vec<EntryPoint> {
+    let Ok(value) = content.parse::<toml::Value>() else {
+        return Vec::new();
+    };
+    let dir = manifest.parent().unwrap_or(Path::new("."));
+    let package = value
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(toml::Value::as_str);
+
+    let mut bins: Vec<(String, PathBuf)> = Vec::new();
+    if let Some(targets) = value.get("bin").and_then(toml::Value::as_array) {
+        for bin in targets {
+            let Some(name) = bin.get("name").and_then(toml::Value::as_str) else {
+                continue;
+            };
+            let src = bin.get("path").and_then(toml::Value::as_str).map_or_else(
+                || {
+                    if package == Some(name) {
+                        "src/main.rs".to_string()
+                    } else {
+                        format!("src/bin/{name}.rs")
+                    }
+                },
+                String::from,
+            );
+            bins.push((name.to_string(), dir.join(src)));
+        }
+    }
+
+    let main = dir.join("src/main.rs");
+    if let Some(name) = package {
+        if main.exists() && !bins.iter().any(|(_, p)| *p == main) {
+            bins.push((name.to_string(), main));
+        }
+    }
+
+    if let Ok(read_dir) = fs::read_dir(dir.join("src/bin")) {
+        let mut auto: Vec<PathBuf> = read_dir
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+            .collect();
+        auto.sort();
+        for p in auto {
+            if !bins.iter().any(|(_, b)| *b == p) {
+                let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+                bins.push((stem.into_owned(), p));
+            }
+        }
+    }
+
+    bins.into_iter()
+        .map(|(name, src)| {
+            let line = fs::read_to_string(&src)
+                .ok()
+                .and_then(|c| line_of(&c, "fn main("));
+            EntryPoint {
+                kind: Kind::RustBin,
+                name,
+                path: rel(&src, scope),
+                line,
+                detail: None,
+            }
+        })
+        .collect()
+}
+
+/// `scripts` and `bin` from a package.json.
+fn npm_entries(content: &str, path: &str) -> Vec<EntryPoint> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+
+    if let Some(scripts) = value.get("scripts").and_then(|s| s.as_object()) {
+        let from = line_of(content, "\"scripts\"").unwrap_or(1);
+        for (name, cmd) in scripts {
+            out.push(EntryPoint {
+                kind: Kind::NpmScript,
+                name: name.clone(),
+                path: path.to_string(),
+                line: line_from(content, from, &format!("\"{name}\"")),
+                detail: cmd.as_str().map(String::from),
+            });
+        }
+    }
+
+    let from = line_of(content, "\"bin\"");
+    match value.get("bin") {
+        Some(serde_json::Value::String(target)) => {
+            if let Some(name) = value.get("name").and_then(|n| n.as_str()) {
+                out.push(EntryPoint {
+                    kind: Kind::NpmBin,
+                    name: name.to_string(),
+                    path: path.to_string(),
+                    line: from,
+                    detail: Some(target.clone()),
+                });
+            }
+        }
+        Some(serde_json::Value::Object(bins)) => {
+            for (name, target) in bins {
+                out.push(EntryPoint {
+                    kind: Kind::NpmBin,
+                    name: name.clone(),
+                    path: path.to_string(),
+                    line: from.and_then(|f| line_from(content, f, &format!("\"{name}\""))),
+                    detail: target.as_str().map(String::from),
+                });
+            }
+        }
+        _ => {}
+    }
+
+    out
+}
+
+/// `[project.scripts]` and `[tool.poetry.scripts]` from a pyproject.toml.
+fn python_scripts(content: &str, path: &str) -> Vec<EntryPoint> {
+    let Ok(value) = content.parse::<toml::Value>() else {
+        return Vec::new();
+    };
+    let tables = [
+        ("[project.scripts]", value.get("project")),
+        (
+            "[tool.poetry.scripts]",
+            value.get("tool").and_then(|t| t.get("poetry")),
+        ),
+    ];
+
+    let mut out = Vec::new();
+    for (header, parent) in tables {
+        let Some(scripts) = parent
+            .and_then(|p| p.get("scripts"))
+            .and_then(toml::Value::as_table)
+        else {
+            continue;
+        };
+        let from = line_of(content, header).unwrap_or(1);
+        for (name, target) in scripts {
+            out.push(EntryPoint {
+                kind: Kind::PythonScript,
+                name: name.clone(),
+                path: path.to_string(),
+                line: line_from(content, from, name),
+                detail: target.as_str().map(String::from),
+            });
+        }
+    }
+    out
+}
+
+/// `if __name__ == "__main__":` blocks and `__main__.py` package runners.
+fn python_main(path: &Path, content: &str, scope: &Path) -> Option<EntryPoint> {
+    let display = rel(path, scope);
+    if path.file_name().is_some_and(|n| n == "__main__.py") {
+        let package = path.parent().map_or_else(String::new, |p| rel(p, scope));
+        return Some(EntryPoint {
+            kind: Kind::PythonMain,
+            name: format!("python -m {}", package.replace('/', ".")),
+            path: display,
+            line: None,
+            detail: None,
+        });
+    }
+
+    let line = content
+        .lines()
+        .position(|l| l.starts_with("if __name__") && l.contains("__main__"))?;
+    Some(EntryPoint {
+        kind: Kind::PythonMain,
+        name: format!("python {display}"),
+        path: display,
+        line: Some(line + 1),
+        detail: None,
+    })
+}
+
+/// `func main()` in a `package main` file — named by its directory, as `go run` takes it.
+fn go_main(path: &Path, content: &str, scope: &Path) -> Option<EntryPoint> {
+    if !content.lines().any(|l| l.trim_end() == "package main") {
+        return None;
+    }
+    let line = line_of(content, "func main()")?;
+    let dir = path.parent().map_or_else(String::new, |p| rel(p, scope));
+    Some(EntryPoint {
+        kind: Kind::GoMain,
+        name: if dir.is_empty() {
+            ".".to_string()
+        } else {
+            format!("./{dir}")
+        },
+        path: rel(path, scope),
+        line: Some(line),
+        detail: None,
+    })
+}
+
+/// Make targets with their prerequisites.
+fn make_targets(content: &str, path: &str) -> Vec<EntryPoint> {
+    buildfile::make_targets(content)
+        .into_iter()
+        .map(|t| EntryPoint {
+            kind: Kind::MakeTarget,
+            name: t.name.to_string(),
+            path: path.to_string(),
+            line: Some(t.line as usize),
+            detail: (!t.prereqs.is_empty()).then(|| t.prereqs.to_string()),
+        })
+        .collect()
+}
+
+/// 1-based line of the first line at or after `from` whose trimmed text starts with `needle`.
+fn line_from(content: &str, from: usize, needle: &str) -> Option<usize> {
+    content
+        .lines()
+        .enumerate()
+        .skip(from.saturating_sub(1))
+        .find(|(_, l)| l.trim_start().starts_with(needle))
+        .map(|(i, _)| i + 1)
+}
+
+fn format_entries(scope: &Path, entries: &mut [EntryPoint]) -> String {
+    entries.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| a.path.cmp(&b.path))
+            .then(a.line.cmp(&b.line))
+    });
+
+    let mut out = format!(
+        "# Entry points: {} — {} found",
+        scope.display(),
+        entries.len()
+    );
+    if entries.is_empty() {
+        out.push_str("\n\nNo binaries, scripts, main functions or make targets found.");
+        return out;
+    }
+
+    for group in entries.chunk_by(|a, b| a.kind == b.kind) {
+        let _ = write!(out, "\n\n## {}", group[0].kind.heading());
+        for e in group.iter().take(MAX_PER_KIND) {
+            let location = match e.line {
+                Some(n) => format!("{}:{n}", e.path),
+                None => e.path.clone(),
+            };
+            let _ = write!(out, "\n  {}  {location}", e.name);
+            if let Some(ref detail) = e.detail {
+                let _ = write!(out, "  → {}", crate::types::truncate_str(detail, 80));
+            }
+        }
+        if group.len() > MAX_PER_KIND {
+            let _ = write!(out, "\n  ... and {} more", group.len() - MAX_PER_KIND);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn npm_scripts_and_bin() {
+        let pkg = r#"{
+  "name": "web",
+  "bin": "cli.js",
+  "devDependencies": {
+    "test": "1.0"
+  },
+  "scripts": {
+    "build": "tsc",
+    "test": "jest"
+  }
+}"#;
+        let entries = npm_entries(pkg, "package.json");
+        let test = entries.iter().find(|e| e.name == "test").unwrap();
+        assert_eq!(test.kind, Kind::NpmScript);
+        // Line lookup starts at "scripts", skipping the same key in devDependencies
+        assert_eq!(test.line, Some(9));
+        assert_eq!(test.detail.as_deref(), Some("jest"));
+
+        let bin = entries.iter().find(|e| e.kind == Kind::NpmBin).unwrap();
+        assert_eq!(bin.name, "web");
+        assert_eq!(bin.detail.as_deref(), Some("cli.js"));
+    }
+
+    #[test]
+    fn pyproject_console_scripts() {
+        let toml = "[project]\nname = \"x\"\n\n[project.scripts]\nserve = \"x.app:main\"\n";
+        let entries = python_scripts(toml, "pyproject.toml");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "serve");
+        assert_eq!(entries[0].line, Some(5));
+        assert_eq!(entries[0].detail.as_deref(), Some("x.app:main"));
+    }
+
+    #[test]
+    fn make_targets_skip_specials_and_assignments() {
+        let mk = ".PHONY: build test\nCC := gcc\nURL = http://x\n\nbuild: deps\n\tcargo build\n\ntest:\n\tcargo test\n%.o: %.c\n";
+        let entries = make_targets(mk, "Makefile");
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "test"]);
+        assert_eq!(entries[0].line, Some(5));
+        assert_eq!(entries[0].detail.as_deref(), Some("deps"));
+        assert_eq!(entries[1].detail, None);
+    }
+
+    #[test]
+    fn go_main_requires_package_main() {
+        let scope = Path::new("/repo");
+        let main = "package main\n\nfunc main() {\n}\n";
+        let e = go_main(Path::new("/repo/cmd/server/main.go"), main, scope).unwrap();
+        assert_eq!(e.name, "./cmd/server");
+        assert_eq!(e.line, Some(3));
+
+        let lib = "package server\n\nfunc main() {\n}\n";
+        assert!(go_main(Path::new("/repo/server/x.go"), lib, scope).is_none());
+    }
+
+    #[test]
+    fn python_dunder_main() {
+        let scope = Path::new("/repo");
+        let script = "import sys\n\nif __name__ == \"__main__\":\n    run()\n";
+        let e = python_main(Path::new("/repo/tools/run.py"), script, scope).unwrap();
+        assert_eq!(e.name, "python tools/run.py");
+        assert_eq!(e.line, Some(3));
+
+        let pkg = python_main(Path::new("/repo/pkg/cli/__main__.py"), "", scope).unwrap();
+        assert_eq!(pkg.name, "python -m pkg.cli");
+    }
+}
@@ -0,0 +1,200 @@
+//! Feature-flag usage: every call into a flag SDK or a homegrown
+//! `is_enabled("x")`, grouped by flag key — the list to work through
+//! before removing a flag.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::{collect_files, rel};
+use crate::read::detect_file_type;
+use crate::types::{truncate_str, FileType};
+
+/// Flag-check function names. The flag key is the first string literal
+/// (or Ruby `:symbol`) among the call's arguments on the same line.
+const FLAG_CALLS: &[&str] = &[
+    // LaunchDarkly
+    "variation",
+    "variationDetail",
+    "boolVariation",
+    "stringVariation",
+    "numberVariation",
+    "intVariation",
+    "doubleVariation",
+    "jsonVariation",
+    "jsonValueVariation",
+    "BoolVariation",
+    "StringVariation",
+    "IntVariation",
+    "Float64Variation",
+    "JSONVariation",
+    "bool_variation",
+    "string_variation",
+    // Unleash, Optimizely and homegrown checks
+    "isEnabled",
+    "is_enabled",
+    "IsEnabled",
+    "getVariant",
+    "get_variant",
+    "GetVariant",
+    "isFeatureEnabled",
+    "is_feature_enabled",
+    "IsFeatureEnabled",
+    "featureEnabled",
+    "feature_enabled",
+    "isFlagEnabled",
+    "is_flag_enabled",
+    "flagEnabled",
+    "flag_enabled",
+    // Statsig
+    "checkGate",
+    "check_gate",
+    "CheckGate",
+    // GrowthBook
+    "isOn",
+    "getFeatureValue",
+    // Split
+    "getTreatment",
+    "get_treatment",
+    // Flipper
+    "enabled?",
+];
+
+/// One flag check: the key, the SDK call, and its 1-based line.
+struct FlagUse<'a> {
+    key: &'a str,
+    call: &'static str,
+    line: usize,
+}
+
+/// List every feature-flag key under `scope` with its usage sites.
+pub fn analyze(scope: &Path) -> String {
+    let files = collect_files(scope, |p| matches!(detect_file_type(p), FileType::Code(_)));
+
+    // key → (path, line, SDK call, source line)
+    let mut flags: BTreeMap<String, Vec<(String, usize, &str, String)>> = BTreeMap::new();
+    for path in &files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        for u in flag_uses(&content) {
+            flags.entry(u.key.to_string()).or_default().push((
+                rel(path, scope),
+                u.line,
+                u.call,
+                lines[u.line - 1].trim().to_string(),
+            ));
+        }
+    }
+
+    let total: usize = flags.values().map(Vec::len).sum();
+    let mut out = format!(
+        "# Feature flags: {} — {} flags, {} usage sites",
+        scope.display(),
+        flags.len(),
+        total
+    );
+    if flags.is_empty() {
+        out.push_str("\n\nNo feature-flag SDK calls found.");
+        return out;
+    }
+
+    for (key, sites) in &flags {
+        let _ = write!(out, "\n\n## {key} ({} sites)", sites.len());
+        for (path, line, call, text) in sites {
+            let text = if text.len() > 80 {
+                format!("{}...", truncate_str(text, 77))
+            } else {
+                text.clone()
+            };
+            let _ = write!(out, "\n  {path}:{line}  [{call}] {text}");
+        }
+    }
+    out
+}
+
+fn flag_uses(content: &str) -> Vec<FlagUse<'_>> {
+    let mut out = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let mut on_line: Vec<(usize, FlagUse)> = Vec::new();
+        for &call in FLAG_CALLS {
+            for (at, _) in line.match_indices(call) {
+                // Whole identifier only: `variation(` must not match `deviation(`
+                let before = line[..at].chars().next_back();
+                if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+                let Some(args) = line[at + call.len()..].strip_prefix('(') else {
+                    continue;
+                };
+                if let Some(key) = first_literal(args) {
+                    let line = i + 1;
+                    on_line.push((at, FlagUse { key, call, line }));
+                }
+            }
+        }
+        on_line.sort_by_key(|(at, _)| *at);
+        out.extend(on_line.into_iter().map(|(_, u)| u));
+    }
+    out
+}
+
+/// First string literal (or `:symbol`) before the call's closing paren.
+fn first_literal(args: &str) -> Option<&str> {
+    let args = &args[..args.find(')').unwrap_or(args.len())];
+    let start = args.find(['"', '\'', '`', ':'])?;
+    let rest = &args[start + 1..];
+    let key = if args[start..].starts_with(':') {
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        &rest[..end]
+    } else {
+        let quote = args[start..].chars().next()?;
+        rest.split(quote).next()?
+    };
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/'));
+    valid.then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sdk_and_homegrown_calls() {
+        let code = r#"if ld.boolVariation("new-checkout", ctx, false) {
+if unleash.is_enabled("beta_search") && flags.isEnabled('dark-mode') {
+statsig.check_gate(user, "gate.v2")
+if Flipper.enabled?(:new_ui, current_user)
+let d = deviation("not-a-flag");
+is_enabled(name)
+"#;
+        let found: Vec<(&str, &str, usize)> = flag_uses(code)
+            .iter()
+            .map(|u| (u.key, u.call, u.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("new-checkout", "boolVariation", 1),
+                ("beta_search", "is_enabled", 2),
+                ("dark-mode", "isEnabled", 2),
+                ("gate.v2", "check_gate", 3),
+                ("new_ui", "enabled?", 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_must_be_inside_call() {
+        assert_eq!(first_literal("name) || log(\"x\")"), None);
+        assert_eq!(first_literal("'a b', ctx)"), None);
+        assert_eq!(first_literal("`flag-1`)"), Some("flag-1"));
+    }
+}
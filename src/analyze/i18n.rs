@@ -0,0 +1,420 @@
+//! Translation keys: what the resource files define (JSON, YAML, gettext
+//! `.po`) against the string literals code passes to translation calls.
+//! Surfaces keys nothing references and references nothing translates.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::{collect_files, line_of, rel};
+use crate::index::bloom::{string_literals, Syntax};
+use crate::read::detect_file_type;
+use crate::types::FileType;
+
+/// Sites shown per key before the rest are summarised as a count.
+const MAX_SITES: usize = 5;
+
+/// Directories whose JSON/YAML files are translation resources.
+const I18N_DIRS: &[&str] = &[
+    "i18n",
+    "l10n",
+    "lang",
+    "langs",
+    "locale",
+    "locales",
+    "messages",
+    "translations",
+];
+
+/// Calls that take a translation key as their first argument, matched as the
+/// text right before the literal: `t("home.title")`, `i18n.t('x')`, `_("Hi")`.
+const TRANSLATE_CALLS: &[&str] = &[
+    "t(",
+    "$t(",
+    "t!(",
+    "tc(",
+    "tr(",
+    "_(",
+    "gettext(",
+    "ngettext(",
+    "pgettext(",
+    "translate(",
+    "formatMessage({ id:",
+    "i18nKey=",
+];
+
+/// `path:line` of a definition or reference.
+struct Site {
+    path: String,
+    line: usize,
+}
+
+/// Cross-reference keys defined in translation files against references in code.
+pub fn analyze(scope: &Path) -> String {
+    let files = collect_files(scope, |p| {
+        is_resource(p) || matches!(detect_file_type(p), FileType::Code(_))
+    });
+
+    let mut defined: BTreeMap<String, Vec<Site>> = BTreeMap::new();
+    let mut referenced: BTreeMap<String, Vec<Site>> = BTreeMap::new();
+    // Every string literal in code — a key passed through a constant still counts
+    let mut literals: HashSet<String> = HashSet::new();
+    for path in &files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        if is_resource(path) {
+            for (key, line) in resource_keys(path, &content) {
+                defined.entry(key).or_default().push(Site {
+                    path: rel(path, scope),
+                    line,
+                });
+            }
+        } else if let Some(syntax) = Syntax::for_path(path) {
+            for (key, line, call) in literal_refs(&content, syntax) {
+                if call {
+                    referenced.entry(key.to_string()).or_default().push(Site {
+                        path: rel(path, scope),
+                        line,
+                    });
+                }
+                literals.insert(key.to_string());
+            }
+        }
+    }
+
+    report(scope, &defined, &referenced, &literals)
+}
+
+/// `.po` files anywhere; JSON and YAML under a translations directory.
+/// File names alone say too little — `ci.yml` looks like a locale code.
+fn is_resource(path: &Path) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("po") => true,
+        Some("json" | "yaml" | "yml") => path.parent().is_some_and(|dir| {
+            dir.components().any(|c| {
+                c.as_os_str()
+                    .to_str()
+                    .is_some_and(|c| I18N_DIRS.contains(&c))
+            })
+        }),
+        _ => false,
+    }
+}
+
+/// `en`, `fra`, `en-US`, `pt_BR`, `zh-Hans`.
+fn is_locale_code(s: &str) -> bool {
+    let (lang, region) = match s.split_once(['-', '_']) {
+        Some((lang, region)) => (lang, Some(region)),
+        None => (s, None),
+    };
+    (2..=3).contains(&lang.len())
+        && lang.bytes().all(|b| b.is_ascii_lowercase())
+        && region.is_none_or(|r| {
+            (2..=4).contains(&r.len()) && r.bytes().all(|b| b.is_ascii_alphanumeric())
+        })
+}
+
+/// Keys defined in one resource file, with 1-based lines.
+fn resource_keys(path: &Path, content: &str) -> Vec<(String, usize)> {
+    let keys = match path.extension().and_then(|e| e.to_str()) {
+        Some("po") => return po_keys(content),
+        Some("json") => json_keys(content),
+        _ => yaml_keys(content),
+    };
+    strip_locale_root(keys)
+}
+
+/// Nested objects flattened to dotted keys. JSON carries no positions, so a
+/// key's line is the first line quoting its last segment.
+fn json_keys(content: &str) -> Vec<(String, usize)> {
+    fn walk(
+        value: &serde_json::Value,
+        prefix: &str,
+        content: &str,
+        out: &mut Vec<(String, usize)>,
+    ) {
+        let serde_json::Value::Object(map) = value else {
+            return;
+        };
+        for (name, child) in map {
+            let key = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            if child.is_object() {
+                walk(child, &key, content, out);
+            } else {
+                let line = line_of(content, &format!("\"{name}\"")).unwrap_or(1);
+                out.push((key, line));
+            }
+        }
+    }
+
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    walk(&value, "", content, &mut out);
+    out
+}
+
+/// Mapping keys by indentation, joined with dots. Leaves are keys with a
+/// value on the same line; block scalars (`|`, `>`) are skipped over.
+fn yaml_keys(content: &str) -> Vec<(String, usize)> {
+    let mut out = Vec::new();
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    let mut block_indent: Option<usize> = None;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if trimmed.is_empty() || block_indent.is_some_and(|b| indent > b) {
+            continue;
+        }
+        block_indent = None;
+        if trimmed.starts_with(['#', '-']) {
+            continue;
+        }
+        let Some((name, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let name = name.trim().trim_matches(['"', '\'']);
+        if name.is_empty() {
+            continue;
+        }
+        while parents.last().is_some_and(|&(d, _)| d >= indent) {
+            parents.pop();
+        }
+        let value = value.trim();
+        if value.is_empty() || value.starts_with('#') {
+            parents.push((indent, name));
+            continue;
+        }
+        if value.starts_with(['|', '>']) {
+            block_indent = Some(indent);
+        }
+        let mut key: Vec<&str> = parents.iter().map(|&(_, p)| p).collect();
+        key.push(name);
+        out.push((key.join("."), i + 1));
+    }
+    out
+}
+
+/// Non-empty `msgid`s, continuation lines joined.
+fn po_keys(content: &str) -> Vec<(String, usize)> {
+    let unquote = |s: &str| s.trim().trim_matches('"').to_string();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(rest) = line.strip_prefix("msgid ") else {
+            continue;
+        };
+        let mut id = unquote(rest);
+        for cont in lines[i + 1..].iter().take_while(|l| l.starts_with('"')) {
+            id.push_str(&unquote(cont));
+        }
+        if !id.is_empty() {
+            out.push((id, i + 1));
+        }
+    }
+    out
+}
+
+/// Rails-style files nest everything under the locale (`en: {home: ...}`);
+/// drop that root so keys match what code passes.
+fn strip_locale_root(keys: Vec<(String, usize)>) -> Vec<(String, usize)> {
+    let root = keys
+        .first()
+        .and_then(|(k, _)| k.split_once('.'))
+        .map(|(root, _)| root.to_string());
+    match root {
+        Some(root)
+            if is_locale_code(&root)
+                && keys.iter().all(|(k, _)| k.starts_with(&format!("{root}."))) =>
+        {
+            keys.into_iter()
+                .map(|(k, line)| (k[root.len() + 1..].to_string(), line))
+                .collect()
+        }
+        _ => keys,
+    }
+}
+
+/// String literals in code with their 1-based line and whether they are the
+/// first argument of a translation call. Template literals with `${` are
+/// dynamic and skipped.
+fn literal_refs(content: &str, syntax: Syntax) -> Vec<(&str, usize, bool)> {
+    let mut line = 1;
+    let mut counted = 0;
+    string_literals(content, syntax)
+        .into_iter()
+        .filter(|(_, s)| !s.is_empty() && !s.contains("${"))
+        .map(|(at, s)| {
+            line += content[counted..at].matches('\n').count();
+            counted = at;
+            let line_start = content[..at].rfind('\n').map_or(0, |i| i + 1);
+            let before = content[line_start..at].trim_end();
+            let call = TRANSLATE_CALLS.iter().any(|c| {
+                before.strip_suffix(c).is_some_and(|head| {
+                    !head
+                        .chars()
+                        .next_back()
+                        .is_some_and(|ch| ch.is_alphanumeric() || ch == '_')
+                })
+            });
+            (s, line, call)
+        })
+        .collect()
+}
+
+fn sites(sites: &[Site]) -> String {
+    let mut out = sites
+        .iter()
+        .take(MAX_SITES)
+        .map(|s| format!("{}:{}", s.path, s.line))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if sites.len() > MAX_SITES {
+        let _ = write!(out, " (+{} more)", sites.len() - MAX_SITES);
+    }
+    out
+}
+
+fn report(
+    scope: &Path,
+    defined: &BTreeMap<String, Vec<Site>>,
+    referenced: &BTreeMap<String, Vec<Site>>,
+    literals: &HashSet<String>,
+) -> String {
+    let mut out = format!(
+        "# i18n keys: {} — {} defined, {} referenced",
+        scope.display(),
+        defined.len(),
+        referenced.len()
+    );
+    if defined.is_empty() {
+        out.push_str(
+            "\n\nNo translation files (JSON/YAML under a locales directory, or .po) found.",
+        );
+        return out;
+    }
+
+    // A literal ending in `.` is a prefix built on at runtime: `t("errors." + code)`
+    let prefixes: Vec<&str> = literals
+        .iter()
+        .filter(|l| l.ends_with('.'))
+        .map(String::as_str)
+        .collect();
+    let unused: Vec<&String> = defined
+        .keys()
+        .filter(|k| !literals.contains(*k) && !prefixes.iter().any(|p| k.starts_with(p)))
+        .collect();
+    if !unused.is_empty() {
+        let _ = write!(out, "\n\n## Defined, never referenced ({})", unused.len());
+        for key in unused {
+            let _ = write!(out, "\n  {key} — {}", sites(&defined[key]));
+        }
+    }
+
+    let missing: Vec<&String> = referenced
+        .keys()
+        .filter(|k| !defined.contains_key(*k) && !k.ends_with('.'))
+        .collect();
+    if !missing.is_empty() {
+        let _ = write!(out, "\n\n## Referenced, no translation ({})", missing.len());
+        for key in missing {
+            let _ = write!(out, "\n  {key} — {}", sites(&referenced[key]));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_formats() {
+        let json = "{\n  \"home\": {\n    \"title\": \"Home\"\n  },\n  \"save\": \"Save\"\n}";
+        assert_eq!(
+            json_keys(json),
+            vec![("home.title".to_string(), 3), ("save".to_string(), 5)]
+        );
+
+        let yaml = "en:\n  home:\n    title: Home\n    intro: |\n      Welcome: friend\n  save: \"Save\"\n";
+        assert_eq!(
+            strip_locale_root(yaml_keys(yaml)),
+            vec![
+                ("home.title".to_string(), 3),
+                ("home.intro".to_string(), 4),
+                ("save".to_string(), 6)
+            ]
+        );
+
+        let po = "msgid \"\"\nmsgstr \"\"\n\nmsgid \"Hello\"\nmsgstr \"Hallo\"\nmsgid \"\"\n\"Long \"\n\"text\"\nmsgstr \"\"\n";
+        assert_eq!(
+            po_keys(po),
+            vec![("Hello".to_string(), 4), ("Long text".to_string(), 6)]
+        );
+    }
+
+    #[test]
+    fn translation_calls_in_code() {
+        let code = "const a = t(\"home.title\");\nsplit(\"x\"); i18n.t('save')\nconst k = \"menu.open\";\nt(`errors.${code}`)\n";
+        assert_eq!(
+            literal_refs(code, Syntax::CFamily),
+            vec![
+                ("home.title", 1, true),
+                ("x", 2, false),
+                ("save", 2, true),
+                ("menu.open", 3, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn locale_files() {
+        assert!(is_resource(Path::new("src/locales/app.json")));
+        assert!(is_resource(Path::new("config/locales/pt-BR.yml")));
+        assert!(is_resource(Path::new("po/de.po")));
+        assert!(!is_resource(Path::new("package.json")));
+        assert!(!is_resource(Path::new(".github/workflows/ci.yml")));
+    }
+
+    #[test]
+    fn report_sections() {
+        let site = |path: &str, line| Site {
+            path: path.to_string(),
+            line,
+        };
+        let defined = BTreeMap::from([
+            ("home.title".to_string(), vec![site("locales/en.json", 3)]),
+            ("menu.open".to_string(), vec![site("locales/en.json", 4)]),
+            (
+                "errors.timeout".to_string(),
+                vec![site("locales/en.json", 6)],
+            ),
+            ("stale".to_string(), vec![site("locales/en.json", 8)]),
+        ]);
+        let referenced = BTreeMap::from([
+            ("home.title".to_string(), vec![site("src/app.tsx", 2)]),
+            ("checkout.total".to_string(), vec![site("src/cart.tsx", 9)]),
+        ]);
+        let literals = ["home.title", "checkout.total", "menu.open", "errors."]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let out = report(Path::new("."), &defined, &referenced, &literals);
+        assert!(
+            out.contains("## Defined, never referenced (1)\n  stale — locales/en.json:8"),
+            "{out}"
+        );
+        assert!(
+            out.contains("## Referenced, no translation (1)\n  checkout.total — src/cart.tsx:9"),
+            "{out}"
+        );
+    }
+}
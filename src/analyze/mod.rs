@@ -0,0 +1,82 @@
+//! Repo-level analyses. Each action answers one orientation question
+//! ("how do I run this?") in a single call instead of a chain of searches.
+
+pub mod ci;
+pub mod config;
+pub mod entry;
+pub mod flags;
+pub mod i18n;
+pub mod testcmd;
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::TilthError;
+
+/// Actions accepted by [`run`], in the order they are listed to agents.
+pub const ACTIONS: &[&str] = &[
+    "entry_points",
+    "build_config",
+    "config_keys",
+    "feature_flags",
+    "i18n_keys",
+    "test_commands",
+];
+
+/// Run an analyze action over `scope`. `path` narrows `test_commands` to
+/// the tests for one file; other actions ignore it.
+pub fn run(action: &str, scope: &Path, path: Option<&Path>) -> Result<String, TilthError> {
+    match action {
+        "entry_points" => Ok(entry::analyze(scope)),
+        "build_config" => Ok(ci::analyze(scope)),
+        "config_keys" => Ok(config::analyze(scope)),
+        "feature_flags" => Ok(flags::analyze(scope)),
+        "i18n_keys" => Ok(i18n::analyze(scope)),
+        "test_commands" => Ok(testcmd::analyze(scope, path)),
+        _ => Err(TilthError::InvalidQuery {
+            query: action.to_string(),
+            reason: format!("unknown action — expected one of: {}", ACTIONS.join(", ")),
+        }),
+    }
+}
+
+/// Files under `scope` accepted by `keep`, sorted by path.
+/// Uses the same walker (and skip list) as search.
+pub(crate) fn collect_files(scope: &Path, keep: impl Fn(&Path) -> bool + Sync) -> Vec<PathBuf> {
+    let found: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+    crate::search::walker(scope).run(|| {
+        let keep = &keep;
+        let found = &found;
+        Box::new(move |entry| {
+            if let Ok(entry) = entry {
+                if entry.file_type().is_some_and(|ft| ft.is_file()) && keep(entry.path()) {
+                    found
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .push(entry.into_path());
+                }
+            }
+            ignore::WalkState::Continue
+        })
+    });
+
+    let mut files = found
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    files.sort();
+    files
+}
+
+/// Path relative to scope for display.
+pub(crate) fn rel(path: &Path, scope: &Path) -> String {
+    crate::pathnorm::relative(path, scope)
+}
+
+/// 1-based line of the first line containing `needle`, if any.
+pub(crate) fn line_of(content: &str, needle: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|l| l.contains(needle))
+        .map(|i| i + 1)
+}
@@ -0,0 +1,538 @@
+//! Test runners and the commands that run them: every project under the
+//! scope with the command for its whole suite, or — given a file — the
+//! narrowest commands that run the tests covering that file.
+//!
+//! Runners are detected from manifests (`Cargo.toml`, `go.mod`,
+//! `package.json` dependencies, `pyproject.toml`, `pom.xml`, Gradle and sbt
+//! builds, a `Gemfile`). Test files are tied to source files by the usual
+//! naming conventions: `test_x.py`, `x.test.ts`, `XTest.java`, `x_spec.rb`.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{collect_files, rel};
+use crate::map::is_test_path;
+
+/// Test classes or functions listed individually before falling back to
+/// the whole file.
+const MAX_NAMED: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Runner {
+    Cargo { package: String },
+    Go,
+    Pytest,
+    Unittest,
+    Jest,
+    Vitest,
+    Mocha,
+    Npm,
+    Maven,
+    Gradle { wrapper: bool },
+    Rspec,
+    Sbt,
+}
+
+impl Runner {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Cargo { .. } => "cargo test",
+            Self::Go => "go test",
+            Self::Pytest => "pytest",
+            Self::Unittest => "unittest",
+            Self::Jest => "jest",
+            Self::Vitest => "vitest",
+            Self::Mocha => "mocha",
+            Self::Npm => "npm test",
+            Self::Maven => "maven",
+            Self::Gradle { .. } => "gradle",
+            Self::Rspec => "rspec",
+            Self::Sbt => "sbt",
+        }
+    }
+
+    /// The command for the whole suite.
+    fn all(&self) -> String {
+        match self {
+            Self::Cargo { package } => format!("cargo test -p {package}"),
+            Self::Go => "go test ./...".into(),
+            Self::Pytest => "pytest".into(),
+            Self::Unittest => "python -m unittest discover".into(),
+            Self::Jest => "npx jest".into(),
+            Self::Vitest => "npx vitest run".into(),
+            Self::Mocha => "npx mocha".into(),
+            Self::Npm => "npm test".into(),
+            Self::Maven => "mvn test".into(),
+            Self::Gradle { .. } => format!("{} test", self.gradle()),
+            Self::Rspec => "bundle exec rspec".into(),
+            Self::Sbt => "sbt test".into(),
+        }
+    }
+
+    fn gradle(&self) -> &'static str {
+        match self {
+            Self::Gradle { wrapper: true } => "./gradlew",
+            _ => "gradle",
+        }
+    }
+}
+
+/// A runner and the directory its commands run in.
+#[derive(Debug)]
+struct Project {
+    dir: PathBuf,
+    runner: Runner,
+    /// The manifest it was detected from.
+    manifest: PathBuf,
+}
+
+/// Every project under `scope` with its suite command, or with `file`, the
+/// commands for the tests relevant to it.
+pub fn analyze(scope: &Path, file: Option<&Path>) -> String {
+    match file {
+        Some(file) => for_file(scope, file),
+        None => all_projects(scope),
+    }
+}
+
+fn all_projects(scope: &Path) -> String {
+    let manifests = collect_files(scope, is_manifest);
+    let mut dirs: Vec<&Path> = manifests.iter().filter_map(|m| m.parent()).collect();
+    dirs.sort();
+    dirs.dedup();
+    let projects: Vec<Project> = dirs.into_iter().flat_map(detect).collect();
+
+    let mut out = format!(
+        "# Test commands: {} — {} projects",
+        scope.display(),
+        projects.len()
+    );
+    if projects.is_empty() {
+        out.push_str("\n\nNo test runner detected from manifests.");
+        return out;
+    }
+    for project in &projects {
+        heading(project, scope, &mut out);
+        let _ = write!(out, "\n  {}", project.runner.all());
+    }
+    out.push_str("\n\nPass `path` for the commands that run one file's tests.");
+    out
+}
+
+fn for_file(scope: &Path, file: &Path) -> String {
+    let file = if file.is_absolute() {
+        file.to_path_buf()
+    } else {
+        scope.join(file)
+    };
+    let mut out = format!("# Test commands: {}", rel(&file, scope));
+    let Some(projects) = file
+        .ancestors()
+        .skip(1)
+        .map(detect)
+        .find(|projects| !projects.is_empty())
+    else {
+        out.push_str("\n\nNo test runner detected in any directory above this file.");
+        return out;
+    };
+    for project in &projects {
+        heading(project, scope, &mut out);
+        let rel_path = rel(&file, &project.dir);
+        for (command, why) in commands(project, &file, &rel_path) {
+            let _ = write!(out, "\n  {command}    # {why}");
+        }
+    }
+    out
+}
+
+/// `## cargo test — crates/core/Cargo.toml`, with where to run from when it
+/// isn't the scope.
+fn heading(project: &Project, scope: &Path, out: &mut String) {
+    let _ = write!(
+        out,
+        "\n\n## {} — {}",
+        project.runner.name(),
+        rel(&project.manifest, scope)
+    );
+    let dir = rel(&project.dir, scope);
+    if !dir.is_empty() && project.dir != scope {
+        let _ = write!(out, " (run in {dir}/)");
+    }
+}
+
+fn is_manifest(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    matches!(
+        name,
+        "Cargo.toml"
+            | "go.mod"
+            | "package.json"
+            | "pyproject.toml"
+            | "setup.py"
+            | "setup.cfg"
+            | "pytest.ini"
+            | "pom.xml"
+            | "build.gradle"
+            | "build.gradle.kts"
+            | "Gemfile"
+            | "build.sbt"
+    )
+}
+
+/// The runners whose manifests are in `dir`.
+fn detect(dir: &Path) -> Vec<Project> {
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+    let project = |runner: Runner, manifest: &str| Project {
+        dir: dir.to_path_buf(),
+        runner,
+        manifest: dir.join(manifest),
+    };
+    let mut found = Vec::new();
+
+    if let Some(package) = read("Cargo.toml")
+        .and_then(|c| c.parse::<toml::Value>().ok())
+        .and_then(|v| v.get("package")?.get("name")?.as_str().map(String::from))
+    {
+        found.push(project(Runner::Cargo { package }, "Cargo.toml"));
+    }
+    if dir.join("go.mod").is_file() {
+        found.push(project(Runner::Go, "go.mod"));
+    }
+    if let Some(runner) = read("package.json").and_then(|c| js_runner(&c)) {
+        found.push(project(runner, "package.json"));
+    }
+    let python = ["pyproject.toml", "pytest.ini", "setup.cfg", "setup.py"]
+        .into_iter()
+        .find(|m| dir.join(m).is_file());
+    if let Some(manifest) = python {
+        let pytest = dir.join("pytest.ini").is_file()
+            || dir.join("conftest.py").is_file()
+            || ["pyproject.toml", "setup.cfg", "setup.py", "tox.ini"]
+                .iter()
+                .any(|m| read(m).is_some_and(|c| c.contains("pytest")));
+        let runner = if pytest {
+            Runner::Pytest
+        } else {
+            Runner::Unittest
+        };
+        found.push(project(runner, manifest));
+    }
+    if dir.join("pom.xml").is_file() {
+        found.push(project(Runner::Maven, "pom.xml"));
+    }
+    if let Some(manifest) = ["build.gradle.kts", "build.gradle"]
+        .into_iter()
+        .find(|m| dir.join(m).is_file())
+    {
+        let wrapper = dir.join("gradlew").is_file();
+        found.push(project(Runner::Gradle { wrapper }, manifest));
+    }
+    if read("Gemfile").is_some_and(|c| c.contains("rspec")) || dir.join(".rspec").is_file() {
+        found.push(project(Runner::Rspec, "Gemfile"));
+    }
+    if dir.join("build.sbt").is_file() {
+        found.push(project(Runner::Sbt, "build.sbt"));
+    }
+    found
+}
+
+/// The test framework a package.json depends on, else its `test` script.
+fn js_runner(content: &str) -> Option<Runner> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let depends = |name: &str| {
+        ["dependencies", "devDependencies"]
+            .iter()
+            .any(|d| value.get(d).and_then(|d| d.get(name)).is_some())
+    };
+    if depends("vitest") {
+        return Some(Runner::Vitest);
+    }
+    if depends("jest") {
+        return Some(Runner::Jest);
+    }
+    if depends("mocha") {
+        return Some(Runner::Mocha);
+    }
+    let script = value.get("scripts")?.get("test")?.as_str()?;
+    // The placeholder npm init writes
+    (!script.contains("no test specified")).then_some(Runner::Npm)
+}
+
+/// Commands for the tests covering `file`, narrowest first, each with what
+/// it runs. `rel_path` is `file` relative to the project.
+fn commands(project: &Project, file: &Path, rel_path: &str) -> Vec<(String, String)> {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let is_test = is_test_path(Path::new(rel_path));
+    let content = fs::read_to_string(file).unwrap_or_default();
+    let mut out: Vec<(String, String)> = Vec::new();
+
+    match &project.runner {
+        Runner::Cargo { package } => out.push(cargo(package, rel_path)),
+        Runner::Go => {
+            let dir = Path::new(rel_path)
+                .parent()
+                .map_or_else(String::new, |d| d.to_string_lossy().into_owned());
+            let pkg = if dir.is_empty() {
+                ".".to_string()
+            } else {
+                format!("./{dir}")
+            };
+            let tests = named(&content, "func Test", |rest| {
+                rest.split('(').next().map(|n| format!("Test{n}"))
+            });
+            if file.to_string_lossy().ends_with("_test.go") && !tests.is_empty() {
+                out.push((
+                    format!("go test {pkg} -run '^({})$'", tests.join("|")),
+                    "the tests in this file".into(),
+                ));
+            }
+            out.push((format!("go test {pkg}"), "its package".into()));
+        }
+        Runner::Pytest => {
+            if is_test {
+                for class in named(&content, "class Test", |rest| {
+                    rest.split(['(', ':']).next().map(|n| format!("Test{n}"))
+                }) {
+                    out.push((format!("pytest {rel_path}::{class}"), class));
+                }
+                out.push((format!("pytest {rel_path}"), "this file".into()));
+            } else {
+                for test in related(&project.dir, stem, &["py"]) {
+                    out.push((
+                        format!("pytest {test}"),
+                        format!("tests named after {stem}"),
+                    ));
+                }
+                out.push((
+                    format!("pytest -k {stem}"),
+                    format!("tests whose names mention {stem}"),
+                ));
+            }
+        }
+        Runner::Unittest => {
+            if is_test {
+                let module = rel_path.trim_end_matches(".py").replace('/', ".");
+                out.push((format!("python -m unittest {module}"), "this file".into()));
+            } else {
+                out.push((
+                    format!("python -m unittest discover -k {stem}"),
+                    format!("tests whose names mention {stem}"),
+                ));
+            }
+        }
+        Runner::Jest | Runner::Vitest | Runner::Mocha | Runner::Npm => {
+            out.push(js(&project.runner, rel_path, is_test));
+        }
+        Runner::Maven | Runner::Gradle { .. } | Runner::Sbt => {
+            let classes = if is_test_class(stem) {
+                vec![stem.to_string()]
+            } else {
+                related(&project.dir, stem, &["java", "kt", "scala"])
+                    .iter()
+                    .filter_map(|t| Path::new(t).file_stem()?.to_str().map(String::from))
+                    .filter(|t| is_test_class(t))
+                    .collect()
+            };
+            let pattern = if classes.is_empty() {
+                format!("{stem}*")
+            } else {
+                classes.join(",")
+            };
+            let command = match &project.runner {
+                Runner::Maven => format!("mvn test -Dtest='{pattern}'"),
+                Runner::Sbt => format!("sbt \"testOnly {}\"", pattern.replace(',', " ")),
+                gradle => {
+                    let filters: Vec<String> = pattern
+                        .split(',')
+                        .map(|c| format!("--tests '*{c}'"))
+                        .collect();
+                    format!("{} test {}", gradle.gradle(), filters.join(" "))
+                }
+            };
+            let why = if classes.is_empty() {
+                format!("test classes starting with {stem}")
+            } else {
+                format!("test classes for {stem}")
+            };
+            out.push((command, why));
+        }
+        Runner::Rspec => {
+            if is_test {
+                out.push((format!("bundle exec rspec {rel_path}"), "this spec".into()));
+            } else {
+                let spec = Path::new(rel_path)
+                    .strip_prefix("lib")
+                    .or_else(|_| Path::new(rel_path).strip_prefix("app"))
+                    .unwrap_or(Path::new(rel_path))
+                    .with_file_name(format!("{stem}_spec.rb"));
+                let spec = Path::new("spec").join(spec);
+                if project.dir.join(&spec).is_file() {
+                    out.push((
+                        format!("bundle exec rspec {}", crate::pathnorm::display(&spec)),
+                        "its spec".into(),
+                    ));
+                }
+            }
+        }
+    }
+    out.push((project.runner.all(), "everything".into()));
+    out.dedup_by(|a, b| a.0 == b.0);
+    out
+}
+
+/// The `cargo test` filter for a file of a package: its module path under
+/// `src/`, or the integration test, bench or binary it belongs to.
+fn cargo(package: &str, rel_path: &str) -> (String, String) {
+    let path = rel_path.trim_end_matches(".rs");
+    let mut parts = path.split('/');
+    let target = |kind: &str, name: &str| {
+        (
+            format!("cargo test -p {package} --{kind} {name}"),
+            format!("the {name} {kind} target"),
+        )
+    };
+    match (parts.next(), parts.next()) {
+        (Some("tests"), Some(name)) => target("test", name),
+        (Some("benches"), Some(name)) => target("bench", name),
+        (Some("examples"), Some(name)) => target("example", name),
+        (Some("src"), Some("bin")) => target("bin", parts.next().unwrap_or(package)),
+        (Some("src"), Some("main")) => target("bin", package),
+        (Some("src"), Some("lib")) => (
+            format!("cargo test -p {package} --lib"),
+            "the library's unit tests".into(),
+        ),
+        (Some("src"), Some(first)) => {
+            let module: Vec<&str> = std::iter::once(first)
+                .chain(parts)
+                .filter(|p| *p != "mod")
+                .collect();
+            let module = module.join("::");
+            (
+                format!("cargo test -p {package} {module}::"),
+                format!("unit tests in {module} and below"),
+            )
+        }
+        _ => (format!("cargo test -p {package}"), "everything".into()),
+    }
+}
+
+fn js(runner: &Runner, rel_path: &str, is_test: bool) -> (String, String) {
+    match (runner, is_test) {
+        (Runner::Jest, true) => (format!("npx jest {rel_path}"), "this file".into()),
+        (Runner::Jest, false) => (
+            format!("npx jest --findRelatedTests {rel_path}"),
+            "tests that import it".into(),
+        ),
+        (Runner::Vitest, true) => (format!("npx vitest run {rel_path}"), "this file".into()),
+        (Runner::Vitest, false) => (
+            format!("npx vitest related --run {rel_path}"),
+            "tests that import it".into(),
+        ),
+        (Runner::Mocha, true) => (format!("npx mocha {rel_path}"), "this file".into()),
+        (_, true) => (format!("npm test -- {rel_path}"), "this file".into()),
+        _ => (runner.all(), "everything".into()),
+    }
+}
+
+/// `FooTest`, `FooTests`, `FooSpec`, `FooSuite`, `FooIT`.
+fn is_test_class(stem: &str) -> bool {
+    ["Test", "Tests", "Spec", "Suite", "IT"]
+        .iter()
+        .any(|s| stem.len() > s.len() && stem.ends_with(s))
+}
+
+/// Names declared on lines starting with `prefix`, from what follows it.
+fn named(content: &str, prefix: &str, name: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut names: Vec<String> = content
+        .lines()
+        .filter_map(|l| l.strip_prefix(prefix))
+        .filter_map(name)
+        .filter(|n| n.len() > 4)
+        .collect();
+    if names.len() > MAX_NAMED {
+        names.clear();
+    }
+    names
+}
+
+/// Test files under `dir` with one of `exts` whose name contains `stem`,
+/// relative to `dir`.
+fn related(dir: &Path, stem: &str, exts: &[&str]) -> Vec<String> {
+    if stem.len() < 3 {
+        return Vec::new();
+    }
+    collect_files(dir, |p| {
+        let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        p.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| exts.contains(&e))
+            && name.contains(stem)
+            && (is_test_path(p.strip_prefix(dir).unwrap_or(p))
+                || is_test_class(p.file_stem().and_then(|s| s.to_str()).unwrap_or("")))
+    })
+    .iter()
+    .map(|p| rel(p, dir))
+    .take(MAX_NAMED)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cargo_filters_follow_module_paths() {
+        assert_eq!(
+            cargo("tilth", "src/search/mod.rs").0,
+            "cargo test -p tilth search::"
+        );
+        assert_eq!(
+            cargo("tilth", "src/search/rank.rs").0,
+            "cargo test -p tilth search::rank::"
+        );
+        assert_eq!(cargo("tilth", "src/lib.rs").0, "cargo test -p tilth --lib");
+        assert_eq!(
+            cargo("tilth", "tests/edit.rs").0,
+            "cargo test -p tilth --test edit"
+        );
+        assert_eq!(
+            cargo("tilth", "src/bin/bench.rs").0,
+            "cargo test -p tilth --bin bench"
+        );
+    }
+
+    #[test]
+    fn pytest_commands_for_test_and_source_files() {
+        let dir = std::env::temp_dir().join("tilth_test_testcmd");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        fs::create_dir_all(dir.join("app")).unwrap();
+        fs::write(dir.join("pyproject.toml"), "[tool.pytest.ini_options]\n").unwrap();
+        fs::write(dir.join("app/parser.py"), "def parse(): pass\n").unwrap();
+        fs::write(
+            dir.join("tests/test_parser.py"),
+            "class TestParse:\n    pass\n\nclass TestErrors(Base):\n    pass\n",
+        )
+        .unwrap();
+
+        let out = analyze(&dir, Some(Path::new("tests/test_parser.py")));
+        assert!(out.contains("## pytest — pyproject.toml"), "{out}");
+        assert!(
+            out.contains("\n  pytest tests/test_parser.py::TestParse    # TestParse"),
+            "{out}"
+        );
+        assert!(out.contains("::TestErrors"), "{out}");
+
+        let out = analyze(&dir, Some(Path::new("app/parser.py")));
+        assert!(
+            out.contains("\n  pytest tests/test_parser.py    # tests named after parser"),
+            "{out}"
+        );
+        assert!(out.contains("\n  pytest    # everything"), "{out}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,495 @@
+//! The embedding API: search, read and outline as plain Rust calls with
+//! typed results, for tools that link tilth instead of talking to its MCP
+//! server.
+//!
+//! This module is what semver covers. Options are set through builder
+//! methods and results are `#[non_exhaustive]`, so new options and fields
+//! arrive in minor releases; the rest of the crate may change in any release.
+//!
+//! ```no_run
+//! use tilth::api::{self, SearchKind, SearchOptions};
+//!
+//! let options = SearchOptions::new("src").context("src/main.rs");
+//! for m in api::search("parse_args", &options)?.matches {
+//!     println!("{}:{} {:?} {}", m.path.display(), m.line, m.kind, m.text);
+//! }
+//! let todos = api::search("TODO", &options.kind(SearchKind::Content))?;
+//! println!("{} TODOs", todos.total);
+//! # Ok::<(), tilth::api::TilthError>(())
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub use crate::error::TilthError;
+pub use crate::read::viewer::{FileView, Viewer};
+pub use crate::types::{FileType, Lang};
+
+use crate::cache::OutlineCache;
+use crate::read::outline::code;
+use crate::search::{callers, content, qualified, symbol};
+use crate::types::{OutlineEntry, OutlineKind};
+
+/// How a search query is matched.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchKind {
+    /// Definitions of the identifier first (tree-sitter), then its usages.
+    /// `Type::method`-style qualified names narrow to the matching container.
+    #[default]
+    Symbol,
+    /// Literal text.
+    Content,
+    /// A regular expression (Rust `regex` syntax).
+    Regex,
+}
+
+/// Where and how to search. Start from [`SearchOptions::new`].
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    scope: PathBuf,
+    kind: SearchKind,
+    context: Option<PathBuf>,
+}
+
+impl SearchOptions {
+    /// Symbol search under `scope`.
+    #[must_use]
+    pub fn new(scope: impl Into<PathBuf>) -> Self {
+        Self {
+            scope: scope.into(),
+            kind: SearchKind::default(),
+            context: None,
+        }
+    }
+
+    #[must_use]
+    pub fn kind(mut self, kind: SearchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// The file being worked on: matches near it rank higher.
+    #[must_use]
+    pub fn context(mut self, path: impl Into<PathBuf>) -> Self {
+        self.context = Some(path.into());
+        self
+    }
+}
+
+/// One search's matches, best first.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    pub query: String,
+    /// Matches found in all, before `matches` was cut to the top ones.
+    pub total: usize,
+    pub definitions: usize,
+    pub usages: usize,
+    pub matches: Vec<SearchMatch>,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub path: PathBuf,
+    /// 1-based.
+    pub line: u32,
+    /// The matched line, or a definition's full signature.
+    pub text: String,
+    pub kind: MatchKind,
+    /// First and last line of a definition.
+    pub range: Option<(u32, u32)>,
+    /// The defined name, e.g. `impl Display for Config` for a trait impl.
+    pub name: Option<String>,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    Definition,
+    Usage,
+}
+
+impl MatchKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Definition => "definition",
+            Self::Usage => "usage",
+        }
+    }
+}
+
+/// Search for `query` as `options` say.
+pub fn search(query: &str, options: &SearchOptions) -> Result<SearchResults, TilthError> {
+    let scope = options.scope.as_path();
+    let context = options.context.as_deref();
+    let result = match options.kind {
+        SearchKind::Symbol => match qualified::try_search(query, scope, context)? {
+            Some(result) => result,
+            None => symbol::search(query, scope, context)?,
+        },
+        SearchKind::Content => {
+            content::search(query, scope, false, context, content::Within::Anywhere)?
+        }
+        SearchKind::Regex => {
+            content::search(query, scope, true, context, content::Within::Anywhere)?
+        }
+    };
+    Ok(SearchResults::from(result))
+}
+
+/// [`search`] for each of `queries`, in order. Symbol searches share one
+/// walk of the scope, reading and parsing each file once.
+pub fn search_many(
+    queries: &[&str],
+    options: &SearchOptions,
+) -> Result<Vec<SearchResults>, TilthError> {
+    if options.kind == SearchKind::Symbol && options.context.is_none() {
+        let results = symbol::search_many(queries, &options.scope)?;
+        return queries
+            .iter()
+            .zip(results)
+            .map(|(query, result)| {
+                let narrowed = qualified::try_search(query, &options.scope, None)?;
+                Ok(SearchResults::from(narrowed.unwrap_or(result)))
+            })
+            .collect();
+    }
+    queries.iter().map(|q| search(q, options)).collect()
+}
+
+impl From<crate::types::SearchResult> for SearchResults {
+    fn from(result: crate::types::SearchResult) -> Self {
+        let matches = result
+            .matches
+            .into_iter()
+            .map(|m| SearchMatch {
+                path: m.path,
+                line: m.line,
+                text: m.text,
+                kind: if m.is_definition {
+                    MatchKind::Definition
+                } else {
+                    MatchKind::Usage
+                },
+                range: m.def_range,
+                name: m.def_name,
+            })
+            .collect();
+        Self {
+            query: result.query,
+            total: result.total_found,
+            definitions: result.definitions,
+            usages: result.usages,
+            matches,
+        }
+    }
+}
+
+/// A call of a function, from [`callers`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    pub path: PathBuf,
+    /// 1-based.
+    pub line: u32,
+    /// The call as written, trimmed.
+    pub text: String,
+    /// The function making the call; `<top-level>` outside any.
+    pub caller: String,
+    /// First and last line of the calling function.
+    pub caller_range: Option<(u32, u32)>,
+}
+
+/// Every call site of the function `name` under the options' scope, found
+/// by tree-sitter and ranked as search ranks usages: the context file
+/// first, then by path. The options' kind is ignored.
+pub fn callers(name: &str, options: &SearchOptions) -> Result<Vec<CallSite>, TilthError> {
+    let bloom = crate::index::bloom::BloomFilterCache::new();
+    let index = crate::index::SymbolIndex::new();
+    let mut found = callers::find_callers(name, &options.scope, &bloom, &index)?;
+    callers::rank_callers(&mut found, &options.scope, options.context.as_deref());
+    Ok(found
+        .into_iter()
+        .map(|c| CallSite {
+            path: c.path,
+            line: c.line,
+            text: c.call_text,
+            caller: c.calling_function,
+            caller_range: c.caller_range,
+        })
+        .collect())
+}
+
+/// What part of a file to read. The default is tilth's smart view: small
+/// files whole, large ones as an outline.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    section: Option<String>,
+    full: bool,
+}
+
+impl ReadOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A line range (`"45-89"`) or markdown heading (`"## Install"`).
+    #[must_use]
+    pub fn section(mut self, section: impl Into<String>) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    /// The whole file, however large.
+    #[must_use]
+    pub fn full(mut self, full: bool) -> Self {
+        self.full = full;
+        self
+    }
+}
+
+/// `path` rendered as tilth shows it to agents: numbered lines, or an
+/// outline with line ranges.
+pub fn read(path: &Path, options: &ReadOptions) -> Result<String, TilthError> {
+    crate::read::read_file(
+        path,
+        options.section.as_deref(),
+        options.full,
+        cache(),
+        false,
+    )
+}
+
+/// Show files ending in `.ext` through `viewer`, ahead of tilth's own
+/// views, wherever this process reads them — [`read`] and an embedded MCP
+/// server alike. Binary files reach it too, with their raw
+/// [`FileView::bytes`], so it can render formats tilth can't. A viewer that
+/// returns `None` passes the file on; the latest registered for an
+/// extension is asked first.
+///
+/// ```no_run
+/// use tilth::api::{self, FileView, TilthError, Viewer};
+///
+/// struct Fixture;
+///
+/// impl Viewer for Fixture {
+///     fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+///         let fields = file.text().lines().filter(|l| l.contains('=')).count();
+///         Some(Ok(format!("{}\n\n{fields} fields", file.header("fixture"))))
+///     }
+/// }
+///
+/// api::register_viewer("fixture", std::sync::Arc::new(Fixture));
+/// ```
+pub fn register_viewer(ext: &str, viewer: std::sync::Arc<dyn Viewer>) {
+    crate::read::viewer::register(ext, viewer);
+}
+
+/// Show text files tilth detects as `file_type` through `viewer`, ahead of
+/// tilth's own views. Viewers registered for an extension are asked first,
+/// and only they see binary files.
+///
+/// ```no_run
+/// use tilth::api::{self, FileType, FileView, TilthError, Viewer};
+///
+/// struct Rows;
+///
+/// impl Viewer for Rows {
+///     fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+///         Some(Ok(format!("{}\n\n{} rows", file.header("rows"), file.lines())))
+///     }
+/// }
+///
+/// api::register_type_viewer(FileType::Tabular, std::sync::Arc::new(Rows));
+/// ```
+pub fn register_type_viewer(file_type: FileType, viewer: std::sync::Arc<dyn Viewer>) {
+    crate::read::viewer::register_type(file_type, viewer);
+}
+
+/// The skeleton of `scope` to `depth` directory levels: each directory's
+/// files with their line counts and top-level definitions.
+#[must_use]
+pub fn map(scope: &Path, depth: usize) -> String {
+    crate::map::generate(scope, depth, None, cache())
+}
+
+/// Outlines shared by every read and map in the process.
+fn cache() -> &'static OutlineCache {
+    static CACHE: OnceLock<OutlineCache> = OnceLock::new();
+    CACHE.get_or_init(OutlineCache::new)
+}
+
+/// A definition in a file's outline.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Everything before the body, for functions.
+    pub signature: Option<String>,
+    /// Members of classes, impls, traits and modules.
+    pub children: Vec<Symbol>,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    /// Interfaces, traits and protocols.
+    Interface,
+    /// A Rust `impl` block.
+    Impl,
+    TypeAlias,
+    Enum,
+    Constant,
+    Variable,
+    Property,
+    Module,
+    Export,
+    Test,
+}
+
+impl SymbolKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Class => "class",
+            Self::Struct => "struct",
+            Self::Interface => "interface",
+            Self::Impl => "impl",
+            Self::TypeAlias => "type_alias",
+            Self::Enum => "enum",
+            Self::Constant => "constant",
+            Self::Variable => "variable",
+            Self::Property => "property",
+            Self::Module => "module",
+            Self::Export => "export",
+            Self::Test => "test",
+        }
+    }
+}
+
+/// The definitions in `path`, in file order. Empty for files tilth has no
+/// grammar for.
+pub fn outline(path: &Path) -> Result<Vec<Symbol>, TilthError> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: crate::read::suggest_similar(path),
+        },
+        _ => TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })?;
+    let FileType::Code(lang) = crate::read::detect_file_type(path) else {
+        return Ok(Vec::new());
+    };
+    Ok(code::entries(&content, lang)
+        .map(|entries| symbols(&entries, lang))
+        .unwrap_or_default())
+}
+
+/// Imports are not definitions.
+fn symbols(entries: &[OutlineEntry], lang: Lang) -> Vec<Symbol> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let kind = match entry.kind {
+                OutlineKind::Import => return None,
+                _ if code::is_impl_block(entry, lang) => SymbolKind::Impl,
+                OutlineKind::Function => SymbolKind::Function,
+                OutlineKind::Method => SymbolKind::Method,
+                OutlineKind::Class => SymbolKind::Class,
+                OutlineKind::Struct => SymbolKind::Struct,
+                OutlineKind::Interface => SymbolKind::Interface,
+                OutlineKind::TypeAlias => SymbolKind::TypeAlias,
+                OutlineKind::Enum => SymbolKind::Enum,
+                OutlineKind::Constant | OutlineKind::ImmutableVariable => SymbolKind::Constant,
+                OutlineKind::Variable => SymbolKind::Variable,
+                OutlineKind::Property => SymbolKind::Property,
+                OutlineKind::Module => SymbolKind::Module,
+                OutlineKind::Export => SymbolKind::Export,
+                OutlineKind::TestSuite | OutlineKind::TestCase => SymbolKind::Test,
+            };
+            Some(Symbol {
+                name: entry.name.clone(),
+                kind,
+                start_line: entry.start_line,
+                end_line: entry.end_line,
+                signature: entry.signature.clone(),
+                children: symbols(&entry.children, lang),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_search_and_outline() {
+        let dir = std::env::temp_dir().join("tilth_test_api");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("lib.rs");
+        fs::write(
+            &path,
+            "use std::fmt;\n\n\
+             pub struct Config;\n\n\
+             impl Config {\n    pub fn load() -> Self {\n        Config\n    }\n}\n\n\
+             fn main() {\n    // TODO: args\n    Config::load();\n}\n",
+        )
+        .unwrap();
+
+        let options = SearchOptions::new(&dir);
+        let found = search("load", &options).unwrap();
+        assert_eq!(found.definitions, 1);
+        assert_eq!(found.matches[0].kind, MatchKind::Definition);
+        assert_eq!(found.matches[0].range, Some((6, 8)));
+        assert!(found.matches.iter().any(|m| m.line == 13));
+
+        let todo = search("TODO", &options.clone().kind(SearchKind::Content)).unwrap();
+        assert_eq!(todo.matches.len(), 1);
+        assert_eq!(todo.matches[0].kind, MatchKind::Usage);
+
+        let many = search_many(&["Config", "main"], &options).unwrap();
+        assert_eq!(many[0].query, "Config");
+        assert_eq!(many[1].definitions, 1);
+
+        let calls = callers("load", &options).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!((calls[0].line, calls[0].caller.as_str()), (13, "main"));
+        assert_eq!(calls[0].caller_range, Some((11, 14)));
+
+        let symbols = outline(&path).unwrap();
+        let kinds: Vec<(&str, SymbolKind)> =
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Config", SymbolKind::Struct),
+                ("impl Config", SymbolKind::Impl),
+                ("main", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(symbols[1].children[0].name, "load");
+
+        let read = read(&path, &ReadOptions::new().section("3-3")).unwrap();
+        assert!(read.contains("pub struct Config;"), "{read}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,222 @@
+//! Copies of files as they were before an edit, kept under
+//! `<root>/.tilth/backups/<relative path>@<millis>` where `<millis>` is the
+//! time of the backup since the Unix epoch. Nothing prunes them — they're
+//! for recovering from a run of edits gone wrong in files git doesn't track.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::TilthError;
+
+/// One stored copy of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// The backed-up file, relative to the root.
+    pub path: PathBuf,
+    /// Milliseconds since the Unix epoch when the copy was taken.
+    pub stamp: u64,
+    pub size: u64,
+}
+
+pub struct Backups {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl Backups {
+    #[must_use]
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dir: root.join(".tilth").join("backups"),
+        }
+    }
+
+    /// Store `content` as the current backup of `path`.
+    pub fn store(&self, path: &Path, content: &[u8]) -> Result<Backup, TilthError> {
+        let rel = self.relative(path);
+        let mut stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        while self.file(&rel, stamp).exists() {
+            stamp += 1;
+        }
+        let file = self.file(&rel, stamp);
+        let io_err = |e| TilthError::IoError {
+            path: file.clone(),
+            source: e,
+        };
+        if let Some(parent) = file.parent() {
+            if !self.dir.is_dir() {
+                fs::create_dir_all(&self.dir).map_err(io_err)?;
+                // Keep backups out of `git status`, like the outline cache.
+                if let Some(tilth_dir) = self.dir.parent() {
+                    let _ = fs::write(tilth_dir.join(".gitignore"), "*\n");
+                }
+            }
+            fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        fs::write(&file, content).map_err(io_err)?;
+        Ok(Backup {
+            path: rel,
+            stamp,
+            size: content.len() as u64,
+        })
+    }
+
+    /// Every backup, or those of one file — grouped by file, newest first.
+    #[must_use]
+    pub fn list(&self, path: Option<&Path>) -> Vec<Backup> {
+        let mut found = Vec::new();
+        collect(&self.dir, &self.dir, &mut found);
+        if let Some(path) = path {
+            let rel = self.relative(path);
+            found.retain(|b| b.path == rel);
+        }
+        found.sort_by(|a, b| a.path.cmp(&b.path).then(b.stamp.cmp(&a.stamp)));
+        found
+    }
+
+    /// Put back `path` as it was at `stamp`, or at its newest backup. The
+    /// content being replaced is backed up first, so a restore can be undone.
+    /// Refuses a `path` outside the root rather than writing beneath it.
+    pub fn restore(&self, path: &Path, stamp: Option<u64>) -> Result<Backup, TilthError> {
+        let root = crate::pathnorm::canonical(&self.root);
+        if !crate::pathnorm::starts_with(&crate::pathnorm::canonical(&self.root.join(path)), &root)
+        {
+            return Err(TilthError::OutOfScope {
+                path: path.to_path_buf(),
+                scope: self.root.clone(),
+            });
+        }
+        let rel = self.relative(path);
+        let backups = self.list(Some(path));
+        let chosen = match stamp {
+            Some(s) => backups.into_iter().find(|b| b.stamp == s),
+            None => backups.into_iter().next(),
+        }
+        .ok_or_else(|| TilthError::NotFound {
+            path: self.dir.join(&rel),
+            suggestion: stamp.map(|s| format!("no backup of {} at {s}", rel.display())),
+        })?;
+
+        let target = self.root.join(&rel);
+        let content = fs::read(self.file(&rel, chosen.stamp)).map_err(|e| TilthError::IoError {
+            path: self.file(&rel, chosen.stamp),
+            source: e,
+        })?;
+        if let Ok(current) = fs::read(&target) {
+            if current != content {
+                self.store(&target, &current)?;
+            }
+        }
+        fs::write(&target, &content).map_err(|e| TilthError::IoError {
+            path: target.clone(),
+            source: e,
+        })?;
+        Ok(chosen)
+    }
+
+    /// `path` relative to the root, with any leading `/` or `..` dropped so
+    /// the backup always lands inside the backups directory.
+    fn relative(&self, path: &Path) -> PathBuf {
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        let absolute = absolute.canonicalize().unwrap_or(absolute);
+        let root = self
+            .root
+            .canonicalize()
+            .unwrap_or_else(|_| self.root.clone());
+        absolute
+            .strip_prefix(&root)
+            .unwrap_or(&absolute)
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn file(&self, rel: &Path, stamp: u64) -> PathBuf {
+        let name = rel
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        self.dir.join(rel).with_file_name(format!("{name}@{stamp}"))
+    }
+}
+
+fn collect(base: &Path, dir: &Path, found: &mut Vec<Backup>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(base, &path, found);
+            continue;
+        }
+        let Some((name, stamp)) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.rsplit_once('@'))
+        else {
+            continue;
+        };
+        let (Ok(stamp), Ok(rel)) = (stamp.parse::<u64>(), path.strip_prefix(base)) else {
+            continue;
+        };
+        found.push(Backup {
+            path: rel.with_file_name(name),
+            stamp,
+            size: entry.metadata().map_or(0, |m| m.len()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_list_restore() {
+        let root = std::env::temp_dir().join("tilth_test_backups");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        let file = root.join("src/lib.rs");
+        fs::write(&file, "v1").unwrap();
+
+        let backups = Backups::new(&root);
+        let first = backups.store(&file, b"v1").unwrap();
+        assert_eq!(first.path, PathBuf::from("src/lib.rs"));
+        let second = backups.store(Path::new("src/lib.rs"), b"v2").unwrap();
+        assert!(second.stamp > first.stamp);
+        assert_eq!(
+            fs::read_to_string(root.join(".tilth/.gitignore")).unwrap(),
+            "*\n"
+        );
+
+        fs::write(&file, "v3").unwrap();
+        let listed = backups.list(Some(&file));
+        assert_eq!(listed, vec![second.clone(), first.clone()]);
+
+        // Newest first by default; the replaced content becomes a backup too
+        assert_eq!(backups.restore(&file, None).unwrap(), second);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v2");
+        assert_eq!(backups.list(None).len(), 3);
+
+        assert_eq!(backups.restore(&file, Some(first.stamp)).unwrap(), first);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v1");
+        assert!(backups.restore(&file, Some(1)).is_err());
+        assert!(matches!(
+            backups.restore(&std::env::temp_dir().join("elsewhere.rs"), None),
+            Err(TilthError::OutOfScope { .. })
+        ));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,202 @@
+//! `tilth bench <scope>`: time a fixed battery of operations on one repo so
+//! skip lists, caps and index settings can be compared before and after.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::cache::OutlineCache;
+use crate::error::TilthError;
+use crate::index::bloom::BloomFilterCache;
+use crate::index::SymbolIndex;
+use crate::session::Session;
+use crate::types::estimate_tokens;
+
+/// One finished step: what ran, how long it took, resident memory after it,
+/// and a short description of what it produced.
+struct Step {
+    name: &'static str,
+    elapsed: Duration,
+    rss_kb: Option<u64>,
+    result: String,
+}
+
+fn step(
+    name: &'static str,
+    run: impl FnOnce() -> Result<String, TilthError>,
+) -> Result<Step, TilthError> {
+    let started = Instant::now();
+    let result = run()?;
+    Ok(Step {
+        name,
+        elapsed: started.elapsed(),
+        rss_kb: proc_status_kb("VmRSS:"),
+        result,
+    })
+}
+
+/// Run the battery over `scope`. `query` is the symbol searched for; without
+/// one, the name with the most definitions in the index is used.
+pub fn run(scope: &Path, query: Option<&str>) -> Result<String, TilthError> {
+    if !scope.is_dir() {
+        return Err(TilthError::NotFound {
+            path: scope.to_path_buf(),
+            suggestion: None,
+        });
+    }
+
+    let mut steps = Vec::new();
+
+    let index = SymbolIndex::new();
+    steps.push(step("index build", || {
+        index.build(scope);
+        Ok(format!(
+            "{} files, {} symbols",
+            index.file_count(),
+            index.symbol_count()
+        ))
+    })?);
+
+    // The same build plus Bloom filters and outlines, on throwaway state —
+    // compare with the index build step for what the shared read saves
+    steps.push(step("warm pass", || {
+        let fresh = SymbolIndex::new();
+        crate::index::warm(
+            scope,
+            &fresh,
+            &BloomFilterCache::new(),
+            &OutlineCache::new(),
+        );
+        Ok(format!("{} files", fresh.file_count()))
+    })?);
+
+    let query = match query {
+        Some(q) => q.to_string(),
+        None => busiest_name(&index, scope).ok_or_else(|| TilthError::InvalidQuery {
+            query: scope.display().to_string(),
+            reason: "no symbols found to search for — pass --query".to_string(),
+        })?,
+    };
+
+    // Fresh caches for the cold run; the warm run reuses them as an MCP
+    // session would on a repeated query
+    let cache = OutlineCache::new();
+    let session = Session::new();
+    let bloom = BloomFilterCache::new();
+    let search = || {
+        crate::search::search_symbol_expanded(
+            &query, scope, &cache, &session, &index, &bloom, 2, None,
+        )
+        .map(|out| tokens(&out))
+    };
+    steps.push(step("symbol search (cold)", search)?);
+    steps.push(step("symbol search (warm)", search)?);
+    steps.push(step("content search", || {
+        crate::search::search_content_expanded(
+            &query,
+            scope,
+            &cache,
+            &session,
+            &bloom,
+            2,
+            None,
+            crate::search::Within::Anywhere,
+        )
+        .map(|out| tokens(&out))
+    })?);
+    steps.push(step("map", || {
+        Ok(tokens(&crate::map::generate(scope, 3, None, &cache)))
+    })?);
+
+    Ok(report(scope, &query, &steps, proc_status_kb("VmHWM:")))
+}
+
+fn report(scope: &Path, query: &str, steps: &[Step], peak_kb: Option<u64>) -> String {
+    let mut out = format!("# Bench: {} — query \"{query}\"\n", scope.display());
+    let _ = write!(out, "\n{:<22}{:>11}{:>10}  result", "step", "time", "rss");
+    for s in steps {
+        let _ = write!(
+            out,
+            "\n{:<22}{:>11}{:>10}  {}",
+            s.name,
+            format!("{:.1}ms", s.elapsed.as_secs_f64() * 1000.0),
+            mb(s.rss_kb),
+            s.result
+        );
+    }
+    let total: Duration = steps.iter().map(|s| s.elapsed).sum();
+    let _ = write!(
+        out,
+        "\n\ntotal {:.1}ms, peak rss {}",
+        total.as_secs_f64() * 1000.0,
+        mb(peak_kb)
+    );
+    out
+}
+
+/// Name defined in the most places under `scope` — a search that exercises
+/// ranking and expansion rather than a single hit.
+fn busiest_name(index: &SymbolIndex, scope: &Path) -> Option<String> {
+    index
+        .complete("", scope, usize::MAX)
+        .into_iter()
+        .max_by_key(|(_, defs)| defs.len())
+        .map(|(name, _)| name)
+}
+
+fn tokens(output: &str) -> String {
+    format!("~{} tokens", estimate_tokens(output.len() as u64))
+}
+
+fn mb(kb: Option<u64>) -> String {
+    kb.map_or_else(
+        || "n/a".to_string(),
+        |kb| format!("{}.{}MB", kb / 1024, kb % 1024 * 10 / 1024),
+    )
+}
+
+/// A `kB` field of `/proc/self/status` (`VmRSS:`, `VmHWM:`). `None` off Linux.
+fn proc_status_kb(field: &str) -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix(field))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_table() {
+        let steps = [
+            Step {
+                name: "index build",
+                elapsed: Duration::from_millis(120),
+                rss_kb: Some(20480),
+                result: "10 files, 42 symbols".to_string(),
+            },
+            Step {
+                name: "map",
+                elapsed: Duration::from_micros(2500),
+                rss_kb: None,
+                result: "~300 tokens".to_string(),
+            },
+        ];
+        let out = report(Path::new("/repo"), "new", &steps, Some(30720));
+        assert_eq!(
+            out,
+            "# Bench: /repo — query \"new\"\n\
+             \n\
+             step                         time       rss  result\n\
+             index build               120.0ms    20.0MB  10 files, 42 symbols\n\
+             map                         2.5ms       n/a  ~300 tokens\n\
+             \n\
+             total 122.5ms, peak rss 30.0MB"
+        );
+    }
+}
@@ -1,18 +1,29 @@
-use crate::types::estimate_tokens;
+use std::fmt::Write;
+
+use crate::format::token_count;
+use crate::tokens;
+use crate::types::FileType;
+
+/// Most omitted sections named in the truncation note.
+const LISTED_SECTIONS: usize = 8;
 
 /// Apply token budget to output. Works backwards from the cap:
 /// 1. Reserve 50 tokens for header
 /// 2. Truncate content at section boundaries to avoid broken output
 /// 3. Never exceed the budget
+///
+/// Truncated output ends with a note of the full size and of each section
+/// that was cut, so the caller can ask again with a budget that fits or a
+/// narrower scope.
 pub fn apply(output: &str, budget: u64) -> String {
-    let current = estimate_tokens(output.len() as u64);
+    let current = tokens::estimate(output.as_bytes(), FileType::Other);
     if current <= budget {
         return output.to_string();
     }
 
     let header_reserve = 50u64;
     let content_budget = budget.saturating_sub(header_reserve);
-    let max_bytes = (content_budget * 4) as usize; // inverse of estimate_tokens
+    let max_bytes = tokens::bytes_for(content_budget, output);
 
     // Find the first newline after the header (first line)
     let header_end = output.find('\n').unwrap_or(0);
@@ -31,13 +42,133 @@ pub fn apply(output: &str, budget: u64) -> String {
         .rfind("\n\n##")
         .or_else(|| truncated.rfind("\n\n"))
         .or_else(|| truncated.rfind('\n'))
-        .unwrap_or(max_bytes);
+        .unwrap_or(safe_max);
+
+    let (clean_body, rest) = body.split_at(cut_point);
+    let remaining_tokens = tokens::estimate(rest.as_bytes(), FileType::Other);
+    let mut out = format!(
+        "{header}{clean_body}\n\n... truncated ({remaining_tokens} tokens omitted, budget: {budget}; full response ~{})",
+        token_count(current)
+    );
+
+    let dropped = dropped_sections(clean_body, rest);
+    for (heading, tokens, partial) in dropped.iter().take(LISTED_SECTIONS) {
+        let shown = if *partial { "partly shown, " } else { "" };
+        let _ = write!(
+            out,
+            "\n  {heading} — {shown}{} omitted",
+            token_count(*tokens)
+        );
+    }
+    if dropped.len() > LISTED_SECTIONS {
+        let _ = write!(
+            out,
+            "\n  ... {} more sections",
+            dropped.len() - LISTED_SECTIONS
+        );
+    }
+    let _ = write!(
+        out,
+        "\nRe-request with budget ≥ {current} for all of it, or narrow the query or scope."
+    );
+    out
+}
+
+/// Sections of `rest` — the part of the output cut off after `kept` — with
+/// their size, at the coarsest heading level present: facets (`###`) in a
+/// faceted search, otherwise one entry per `##` match or file. Text before
+/// the first heading in `rest` belongs to the last section of `kept`, which
+/// is listed as partly shown.
+fn dropped_sections(kept: &str, rest: &str) -> Vec<(String, u64, bool)> {
+    let level = ["### ", "## "]
+        .into_iter()
+        .find(|marker| {
+            rest.lines().any(|l| l.starts_with(marker))
+                || kept.lines().any(|l| l.starts_with(marker))
+        })
+        .unwrap_or("## ");
+    let is_heading = |line: &str| line.starts_with(level);
+
+    let mut sections: Vec<(String, String, bool)> = Vec::new();
+    let mut open = kept
+        .lines()
+        .rev()
+        .find(|l| is_heading(l))
+        .map(|l| (heading(l), String::new(), true));
+    for line in rest.lines() {
+        if is_heading(line) {
+            sections.extend(open.take());
+            open = Some((heading(line), String::new(), false));
+        }
+        if let Some((_, text, _)) = &mut open {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    sections.extend(open);
 
-    let clean_body = &body[..cut_point];
+    sections
+        .into_iter()
+        .filter(|(_, text, _)| !text.trim().is_empty())
+        .map(|(name, text, partial)| {
+            (
+                name,
+                tokens::estimate(text.as_bytes(), FileType::Other),
+                partial,
+            )
+        })
+        .collect()
+}
+
+fn heading(line: &str) -> String {
+    line.trim_start_matches('#').trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facet(title: &str, hits: usize) -> String {
+        let mut out = format!("\n\n### {title} ({hits})");
+        for i in 0..hits {
+            let _ = write!(
+                out,
+                "\n\n## src/{title}{i}.rs:10 [usage]\n  10 │ let value = compute(input, options);"
+            );
+        }
+        out
+    }
+
+    #[test]
+    fn note_lists_omitted_facets_and_full_size() {
+        let output = format!(
+            "# Search: \"compute\" — 60 matches{}{}{}",
+            facet("Definitions", 2),
+            facet("Tests", 20),
+            facet("Usages", 40)
+        );
+        let full = tokens::estimate(output.as_bytes(), FileType::Other);
+        let out = apply(&output, 300);
 
-    let omitted_bytes = output.len() - header_end - cut_point;
-    let remaining_tokens = estimate_tokens(omitted_bytes as u64);
-    format!(
-        "{header}{clean_body}\n\n... truncated ({remaining_tokens} tokens omitted, budget: {budget})"
-    )
+        assert!(out.contains("### Definitions (2)"), "{out}");
+        assert!(
+            out.contains(&format!("full response ~{}", token_count(full))),
+            "{out}"
+        );
+        assert!(out.contains("\n  Tests (20) — partly shown, "), "{out}");
+        assert!(out.contains("\n  Usages (40) — "), "{out}");
+        assert!(!out.contains("Definitions (2) —"), "{out}");
+        assert!(
+            out.ends_with(&format!(
+                "budget ≥ {full} for all of it, or narrow the query or scope."
+            )),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn under_budget_is_untouched() {
+        let output = "# Search: \"x\" — 1 match\n\n## a.rs:1 [definition]";
+        assert_eq!(apply(output, 1000), output);
+    }
 }
@@ -1,10 +1,35 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 
+/// Hit/miss counters for a cache, reported by `tilth_session` `action: "perf"`.
+#[derive(Default)]
+pub struct HitStats {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl HitStats {
+    pub fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `(hits, lookups)` so far.
+    #[must_use]
+    pub fn snapshot(&self) -> (usize, usize) {
+        let hits = self.hits.load(Ordering::Relaxed);
+        (hits, hits + self.misses.load(Ordering::Relaxed))
+    }
+}
+
 /// Cached outline entry with insertion timestamp for TTL-based eviction.
 struct CacheEntry {
     outline: Arc<str>,
@@ -18,12 +43,25 @@ struct CacheEntry {
 /// one less indirection than `Arc<String>`.
 pub struct OutlineCache {
     entries: DashMap<(PathBuf, SystemTime), CacheEntry>,
+    stats: HitStats,
+    /// Directory outlines are also written to, so they outlive the process.
+    disk: Option<PathBuf>,
+    /// Fingerprint of the grammars and outline rules, stamped on stored
+    /// outlines.
+    config: u64,
+    /// Rendered map sections keyed by (directory, map depth), stamped with
+    /// the newest mtime under the directory when they were rendered.
+    subtrees: DashMap<(PathBuf, usize), (SystemTime, Arc<str>)>,
 }
 
 impl Default for OutlineCache {
     fn default() -> Self {
         Self {
             entries: DashMap::new(),
+            stats: HitStats::default(),
+            disk: None,
+            config: 0,
+            subtrees: DashMap::new(),
         }
     }
 }
@@ -34,6 +72,19 @@ impl OutlineCache {
         Self::default()
     }
 
+    /// A cache backed by `<root>/.tilth/outlines/`: a miss in memory checks
+    /// there before computing, and computed outlines are written back. A
+    /// restarted server then reuses outlines from earlier runs instead of
+    /// re-parsing every file it already saw.
+    #[must_use]
+    pub fn persistent(root: &Path) -> Self {
+        Self {
+            disk: Some(root.join(".tilth").join("outlines")),
+            config: crate::read::outline::config_fingerprint(),
+            ..Self::default()
+        }
+    }
+
     /// Get cached outline or compute and cache it. Accepts `&Path` (not `&PathBuf`).
     /// Uses `entry()` API to avoid TOCTOU race between get and insert.
     pub fn get_or_compute(
@@ -42,10 +93,18 @@ impl OutlineCache {
         mtime: SystemTime,
         compute: impl FnOnce() -> String,
     ) -> Arc<str> {
-        match self.entries.entry((path.to_path_buf(), mtime)) {
+        let entry = self.entries.entry((path.to_path_buf(), mtime));
+        self.stats.record(matches!(entry, Entry::Occupied(_)));
+        match entry {
             Entry::Occupied(e) => Arc::clone(&e.get().outline),
             Entry::Vacant(e) => {
-                let outline: Arc<str> = compute().into();
+                let outline: Arc<str> = if let Some(stored) = self.load(path, mtime) {
+                    stored.into()
+                } else {
+                    let computed = compute();
+                    self.store(path, mtime, &computed);
+                    computed.into()
+                };
                 e.insert(CacheEntry {
                     outline: Arc::clone(&outline),
                     inserted_at: Instant::now(),
@@ -55,9 +114,144 @@ impl OutlineCache {
         }
     }
 
+    #[must_use]
+    pub fn stats(&self) -> &HitStats {
+        &self.stats
+    }
+
+    /// Rendered map section for `dir`, if nothing under it has changed since
+    /// — `stamp` is the newest mtime among the directory and its contents.
+    #[must_use]
+    pub fn subtree(&self, dir: &Path, depth: usize, stamp: SystemTime) -> Option<Arc<str>> {
+        let entry = self.subtrees.get(&(dir.to_path_buf(), depth))?;
+        let (rendered_at, text) = &*entry;
+        (*rendered_at == stamp).then(|| Arc::clone(text))
+    }
+
+    pub fn store_subtree(&self, dir: &Path, depth: usize, stamp: SystemTime, text: Arc<str>) {
+        self.subtrees
+            .insert((dir.to_path_buf(), depth), (stamp, text));
+    }
+
     /// Evict entries that were cached more than `max_age` ago.
     pub fn prune(&self, max_age: Duration) {
         let cutoff = Instant::now().checked_sub(max_age).unwrap();
         self.entries.retain(|_, entry| entry.inserted_at > cutoff);
     }
+
+    /// One file per source path, named by a hash of the path. A newer mtime
+    /// overwrites the old outline rather than adding a file.
+    fn disk_file(&self, path: &Path) -> Option<PathBuf> {
+        let dir = self.disk.as_ref()?;
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        Some(dir.join(format!("{:016x}", hasher.finish())))
+    }
+
+    fn load(&self, path: &Path, mtime: SystemTime) -> Option<String> {
+        let file = self.disk_file(path)?;
+        let header = disk_header(path, mtime, self.config)?;
+        let stored = fs::read_to_string(file).ok()?;
+        stored.strip_prefix(&header).map(str::to_string)
+    }
+
+    /// Best effort — a read-only checkout just means no persistence.
+    fn store(&self, path: &Path, mtime: SystemTime, outline: &str) {
+        let (Some(file), Some(header)) =
+            (self.disk_file(path), disk_header(path, mtime, self.config))
+        else {
+            return;
+        };
+        let Some(dir) = file.parent() else {
+            return;
+        };
+        if !dir.is_dir() {
+            if fs::create_dir_all(dir).is_err() {
+                return;
+            }
+            // Keep the cache out of `git status`.
+            if let Some(tilth_dir) = dir.parent() {
+                let _ = fs::write(tilth_dir.join(".gitignore"), "*\n");
+            }
+        }
+        // Write then rename, so a concurrent server never reads half a file.
+        let tmp = file.with_extension(format!("{}.tmp", std::process::id()));
+        if fs::write(&tmp, format!("{header}{outline}")).is_ok() {
+            let _ = fs::rename(&tmp, &file);
+        }
+    }
+}
+
+/// First line of a stored outline. It must match exactly for the outline to
+/// be reused: a different source mtime, path (hash collision), tilth
+/// version (outline format changes) or `config` fingerprint (edited
+/// `tilth.toml` grammars or outline rules) all count as a miss. `None` for
+/// an unknown mtime, which can't be validated.
+fn disk_header(path: &Path, mtime: SystemTime, config: u64) -> Option<String> {
+    let nanos = mtime.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    if nanos == 0 {
+        return None;
+    }
+    Some(format!(
+        "tilth {} {config:016x} {nanos} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        path.display()
+    ))
+}
+
+/// File contents read during a single search call, so outline context,
+/// expansion, callee and sibling resolution share one read per file.
+/// Dropped with the call — nothing here outlives a request, so there is no
+/// mtime check. Unreadable files are remembered as `None`.
+#[derive(Default)]
+pub(crate) struct FileContents {
+    files: DashMap<PathBuf, Option<Arc<str>>>,
+}
+
+impl FileContents {
+    pub(crate) fn get(&self, path: &Path) -> Option<Arc<str>> {
+        self.files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| std::fs::read_to_string(path).ok().map(Arc::from))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persistent_outlines_survive_restart() {
+        let root = std::env::temp_dir().join("tilth_test_outline_persist");
+        let _ = fs::remove_dir_all(&root);
+        let source = root.join("lib.rs");
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let first = OutlineCache::persistent(&root);
+        first.get_or_compute(&source, mtime, || "fn a".to_string());
+
+        let restarted = OutlineCache::persistent(&root);
+        let outline = restarted.get_or_compute(&source, mtime, || unreachable!());
+        assert_eq!(&*outline, "fn a");
+
+        // A changed file is recomputed, and replaces the stored outline.
+        let newer = mtime + Duration::from_secs(1);
+        let outline = restarted.get_or_compute(&source, newer, || "fn b".to_string());
+        assert_eq!(&*outline, "fn b");
+        assert_eq!(
+            fs::read_dir(root.join(".tilth/outlines")).unwrap().count(),
+            1
+        );
+        assert_eq!(
+            fs::read_to_string(root.join(".tilth/.gitignore")).unwrap(),
+            "*\n"
+        );
+
+        // Other grammars or outline rules don't reuse it either.
+        let mut reconfigured = OutlineCache::persistent(&root);
+        reconfigured.config ^= 1;
+        let outline = reconfigured.get_or_compute(&source, newer, || "fn c".to_string());
+        assert_eq!(&*outline, "fn c");
+    }
 }
@@ -0,0 +1,424 @@
+//! What changed in the working tree since HEAD, per file and per symbol —
+//! a starting point for picking up work in progress without a full map.
+//!
+//! Files and line counts come from `git diff HEAD --numstat` plus untracked
+//! files. For code files, the outline of the HEAD version is compared with
+//! the outline on disk: definitions only on one side are added or removed,
+//! and definitions on both sides whose text differs are modified.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::TilthError;
+use crate::read::outline::code::{entries, kind_label};
+use crate::types::{FileType, Lang, OutlineEntry, OutlineKind};
+
+/// Symbols listed per file before the rest are counted.
+const MAX_SYMBOLS_PER_FILE: usize = 12;
+/// Files larger than this get line counts only.
+const MAX_OUTLINE_SIZE: usize = 500_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Added,
+    Modified,
+    Deleted,
+    Untracked,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+            Self::Untracked => "untracked",
+        }
+    }
+}
+
+/// A changed file: path relative to the scope, status, and lines added and
+/// removed (`None` for binary files).
+pub(crate) struct FileChange {
+    pub path: String,
+    status: Status,
+    lines: Option<(u64, u64)>,
+    /// Definitions that differ, for code files.
+    pub symbols: Vec<SymbolChange>,
+    /// Content on disk; empty for deleted files.
+    pub content: String,
+}
+
+/// One definition that differs between the base and the working tree.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SymbolChange {
+    /// `+` added, `-` removed, `~` modified.
+    pub mark: char,
+    /// `fn parse`, `fn new (in impl Config)`.
+    pub label: String,
+    /// The bare name, `new`; empty for `imports`.
+    pub name: String,
+    /// Lines in the working tree, or in the base for a removed definition.
+    pub lines: (u32, u32),
+}
+
+/// Summary of the working tree under `scope` against HEAD.
+pub fn changes(scope: &Path) -> Result<String, TilthError> {
+    let files = collect(scope, "HEAD")?;
+    if files.is_empty() {
+        return Ok(format!(
+            "# Changes: {} vs HEAD — working tree clean",
+            scope.display()
+        ));
+    }
+    let mut out = format!(
+        "# Changes: {} vs HEAD — {}",
+        scope.display(),
+        totals(&files)
+    );
+    render(&files, &mut out);
+    Ok(out)
+}
+
+/// `3 files (+40 -12)`.
+pub(crate) fn totals(files: &[FileChange]) -> String {
+    let (added, removed) = files
+        .iter()
+        .filter_map(|f| f.lines)
+        .fold((0, 0), |(a, r), (fa, fr)| (a + fa, r + fr));
+    format!("{} files (+{added} -{removed})", files.len())
+}
+
+/// One `## path [status] +A -R` section per file, listing its symbol changes.
+pub(crate) fn render(files: &[FileChange], out: &mut String) {
+    for file in files {
+        let counts = match file.lines {
+            Some((a, 0)) => format!("+{a}"),
+            Some((0, r)) => format!("-{r}"),
+            Some((a, r)) => format!("+{a} -{r}"),
+            None => "binary".to_string(),
+        };
+        let _ = write!(
+            out,
+            "\n\n## {} [{}] {counts}",
+            file.path,
+            file.status.label()
+        );
+        for change in file.symbols.iter().take(MAX_SYMBOLS_PER_FILE) {
+            let _ = write!(
+                out,
+                "\n  {} {}  L{}",
+                change.mark, change.label, change.lines.0
+            );
+        }
+        if file.symbols.len() > MAX_SYMBOLS_PER_FILE {
+            let _ = write!(
+                out,
+                "\n  ... {} more",
+                file.symbols.len() - MAX_SYMBOLS_PER_FILE
+            );
+        }
+    }
+}
+
+/// Files under `scope` that differ between `rev` and the working tree, with
+/// their symbol changes.
+pub(crate) fn collect(scope: &Path, rev: &str) -> Result<Vec<FileChange>, TilthError> {
+    let mut files = changed_files(scope, rev, crate::redact::visible())?;
+    for file in &mut files {
+        if file.status != Status::Deleted {
+            file.content = std::fs::read_to_string(scope.join(&file.path)).unwrap_or_default();
+        }
+        let FileType::Code(lang) = crate::read::detect_file_type(Path::new(&file.path)) else {
+            continue;
+        };
+        let old = match file.status {
+            Status::Modified | Status::Deleted => {
+                git(scope, &["show", &format!("{rev}:./{}", file.path)]).unwrap_or_default()
+            }
+            Status::Added | Status::Untracked => String::new(),
+        };
+        if old.len() > MAX_OUTLINE_SIZE || file.content.len() > MAX_OUTLINE_SIZE {
+            continue;
+        }
+        file.symbols = symbol_changes(&old, &file.content, lang);
+    }
+    Ok(files)
+}
+
+/// Tracked changes from `git diff <rev>`, then untracked files, sorted by
+/// path within each group. Paths `visible` rejects are left out.
+fn changed_files(
+    scope: &Path,
+    rev: &str,
+    visible: impl Fn(&Path) -> bool,
+) -> Result<Vec<FileChange>, TilthError> {
+    let shown = |path: &str| visible(&scope.join(path));
+    let diff = |format: &str| git(scope, &["diff", rev, "--no-renames", "--relative", format]);
+    let numstat = diff("--numstat")?;
+    let name_status = diff("--name-status")?;
+
+    let statuses: HashMap<&str, Status> = name_status
+        .lines()
+        .filter_map(|l| {
+            let (status, path) = l.split_once('\t')?;
+            let status = match status {
+                "A" => Status::Added,
+                "D" => Status::Deleted,
+                _ => Status::Modified,
+            };
+            Some((path, status))
+        })
+        .collect();
+
+    let mut files: Vec<FileChange> = numstat
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(3, '\t');
+            let added = parts.next()?;
+            let removed = parts.next()?;
+            let path = parts.next().filter(|p| shown(p))?;
+            Some(FileChange {
+                path: path.to_string(),
+                status: statuses.get(path).copied().unwrap_or(Status::Modified),
+                lines: added.parse().ok().zip(removed.parse().ok()),
+                symbols: Vec::new(),
+                content: String::new(),
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let untracked = git(scope, &["ls-files", "--others", "--exclude-standard"])?;
+    let mut untracked: Vec<FileChange> = untracked
+        .lines()
+        .filter(|p| !p.is_empty() && shown(p))
+        .map(|path| {
+            let lines = std::fs::read(scope.join(path)).ok().and_then(|buf| {
+                let text = std::str::from_utf8(&buf).ok()?;
+                Some((text.lines().count() as u64, 0))
+            });
+            FileChange {
+                path: path.to_string(),
+                status: Status::Untracked,
+                lines,
+                symbols: Vec::new(),
+                content: String::new(),
+            }
+        })
+        .collect();
+    untracked.sort_by(|a, b| a.path.cmp(&b.path));
+    files.extend(untracked);
+    Ok(files)
+}
+
+/// Definitions added, removed or modified between `old` and `new`, in the
+/// order they appear — removed ones last. Imports are reported once, as
+/// `imports`, if any changed.
+fn symbol_changes(old: &str, new: &str, lang: Lang) -> Vec<SymbolChange> {
+    let old_defs = definitions(old, lang);
+    let new_defs = definitions(new, lang);
+    let old_by_label: HashMap<&str, &Def> =
+        old_defs.iter().map(|d| (d.label.as_str(), d)).collect();
+    let new_labels: HashSet<&str> = new_defs.iter().map(|d| d.label.as_str()).collect();
+
+    let mut changes: Vec<SymbolChange> = new_defs
+        .iter()
+        .filter_map(|def| {
+            let mark = match old_by_label.get(def.label.as_str()) {
+                None => '+',
+                Some(old) if old.text != def.text => '~',
+                Some(_) => return None,
+            };
+            Some(def.change(mark))
+        })
+        .collect();
+    changes.extend(
+        old_defs
+            .iter()
+            .filter(|def| !new_labels.contains(def.label.as_str()))
+            .map(|def| def.change('-')),
+    );
+    changes
+}
+
+/// A definition in one version of a file.
+struct Def {
+    label: String,
+    name: String,
+    lines: (u32, u32),
+    text: String,
+}
+
+impl Def {
+    fn change(&self, mark: char) -> SymbolChange {
+        SymbolChange {
+            mark,
+            label: self.label.clone(),
+            name: self.name.clone(),
+            lines: self.lines,
+        }
+    }
+}
+
+/// Each definition in `content` and its direct children, plus one `imports`
+/// entry holding all import lines.
+fn definitions(content: &str, lang: Lang) -> Vec<Def> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+    let Some(top) = entries(content, lang) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let def = |e: &OutlineEntry, label: String| {
+        let start = (e.start_line as usize).saturating_sub(1).min(lines.len());
+        let end = (e.end_line as usize).clamp(start, lines.len());
+        Def {
+            label,
+            name: e.name.clone(),
+            lines: (e.start_line, e.end_line),
+            text: lines[start..end].join("\n"),
+        }
+    };
+
+    let mut defs = Vec::new();
+    let mut imports: Option<Def> = None;
+    for entry in &top {
+        if entry.kind == OutlineKind::Import {
+            let import = def(entry, "imports".to_string());
+            match &mut imports {
+                Some(all) => {
+                    all.text.push('\n');
+                    all.text.push_str(&import.text);
+                    all.lines.1 = import.lines.1;
+                }
+                None => {
+                    imports = Some(Def {
+                        name: String::new(),
+                        ..import
+                    });
+                }
+            }
+            continue;
+        }
+        defs.push(def(
+            entry,
+            format!("{} {}", kind_label(entry.kind, lang), entry.name),
+        ));
+        for child in &entry.children {
+            let label = format!(
+                "{} {} (in {})",
+                kind_label(child.kind, lang),
+                child.name,
+                entry.name
+            );
+            defs.push(def(child, label));
+        }
+    }
+    if let Some(imports) = imports {
+        defs.insert(0, imports);
+    }
+    defs
+}
+
+/// Run `git -C scope <args>` and return its stdout.
+pub(crate) fn git(scope: &Path, args: &[&str]) -> Result<String, TilthError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(scope)
+        .args(args)
+        .output()
+        .map_err(|e| TilthError::IoError {
+            path: scope.to_path_buf(),
+            source: e,
+        })?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(TilthError::InvalidQuery {
+            query: scope.display().to_string(),
+            reason: format!(
+                "git {}: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn outline_diff_marks_added_removed_and_modified() {
+        let old = "use std::fs;\n\nfn keep() {}\n\nfn edit() {\n    1\n}\n\nfn gone() {}\n";
+        let new = "use std::fs;\n\nfn keep() {}\n\nfn edit() {\n    2\n}\n\nfn fresh() {}\n";
+        let changes = symbol_changes(old, new, Lang::Rust);
+        let summary: Vec<(char, &str, u32)> = changes
+            .iter()
+            .map(|c| (c.mark, c.label.as_str(), c.lines.0))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ('~', "fn edit", 5),
+                ('+', "fn fresh", 9),
+                ('-', "fn gone", 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn summarizes_working_tree_against_head() {
+        let dir = std::env::temp_dir().join("tilth_test_changes");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "-q"]);
+        run(&["config", "user.email", "t@example.com"]);
+        run(&["config", "user.name", "t"]);
+        fs::write(dir.join("lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(dir.join("old.txt"), "x\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "init"]);
+
+        assert!(changes(&dir).unwrap().ends_with("working tree clean"));
+
+        fs::write(dir.join("lib.rs"), "fn a() {}\n\nfn b() {}\n").unwrap();
+        fs::remove_file(dir.join("old.txt")).unwrap();
+        fs::write(dir.join("new.md"), "# New\n").unwrap();
+        let out = changes(&dir).unwrap();
+        assert!(out.contains("— 3 files (+3 -1)"), "{out}");
+        assert!(
+            out.contains("## lib.rs [modified] +2\n  + fn b  L3"),
+            "{out}"
+        );
+        assert!(out.contains("## old.txt [deleted] -1"), "{out}");
+        assert!(out.contains("## new.md [untracked] +1"), "{out}");
+
+        // Redacted paths aren't listed, tracked or not
+        fs::write(dir.join("secret.rs"), "fn key() {}\n").unwrap();
+        let listed: Vec<String> = changed_files(&dir, "HEAD", |p| {
+            !p.ends_with("secret.rs") && !p.ends_with("lib.rs")
+        })
+        .unwrap()
+        .into_iter()
+        .map(|f| f.path)
+        .collect();
+        assert_eq!(listed, vec!["old.txt", "new.md"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
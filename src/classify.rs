@@ -6,12 +6,7 @@ use crate::types::QueryType;
 /// No regex engine — `matches!` compiles to a jump table.
 pub fn classify(query: &str, scope: &Path) -> QueryType {
     // 1. Glob — check first because globs can contain path separators.
-    //    But only if no spaces: real globs don't have spaces, content like "import { X }" does.
-    if !query.contains(' ')
-        && query
-            .bytes()
-            .any(|b| matches!(b, b'*' | b'?' | b'{' | b'['))
-    {
+    if is_glob(query) {
         return QueryType::Glob(query.into());
     }
 
@@ -58,6 +53,68 @@ pub fn classify(query: &str, scope: &Path) -> QueryType {
     QueryType::Content(query.into())
 }
 
+/// Glob-shaped: has a wildcard or brace/class and no spaces. Real globs
+/// don't have spaces; content like "import { X }" does. Parentheses and an
+/// empty `[]` aren't glob syntax, so `#[derive(Debug)]` and `operator[]` are code.
+pub(crate) fn is_glob(query: &str) -> bool {
+    !query.contains(' ')
+        && !query.contains('(')
+        && !query.contains("[]")
+        && query
+            .bytes()
+            .any(|b| matches!(b, b'*' | b'?' | b'{' | b'['))
+}
+
+/// Route for `tilth_search` `kind: "auto"`: `/re/` → regex, glob-shaped →
+/// files, an identifier or qualified name (or several, comma-separated) →
+/// symbol, anything with spaces, quotes or other punctuation → content.
+pub(crate) fn search_kind(query: &str) -> &'static str {
+    if query.len() > 2 && query.starts_with('/') && query.ends_with('/') {
+        "regex"
+    } else if is_glob(query) {
+        "files"
+    } else if query
+        .split(',')
+        .map(str::trim)
+        .all(|q| is_identifier(q) || crate::search::qualified::split(q).is_some())
+    {
+        "symbol"
+    } else {
+        "content"
+    }
+}
+
+/// The identifier a punctuated query is about, for symbol search: its
+/// longest identifier run, first on ties — `Vec` in `Vec<u8>`, `Serialize` in
+/// `#[derive(Serialize)]`, `operator` in `operator==`. None when the query is
+/// already an identifier, or has no identifier in it.
+pub(crate) fn identifier_in(query: &str) -> Option<&str> {
+    if is_identifier(query) {
+        return None;
+    }
+    let mut best: Option<&str> = None;
+    let mut start = None;
+    // A trailing separator closes the last run
+    let bytes = query.bytes().chain(std::iter::once(b' '));
+    for (i, b) in bytes.enumerate() {
+        let word = b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+        match (word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                start = None;
+                let run = &query[s..i];
+                if !run.starts_with(|c: char| c.is_ascii_digit())
+                    && best.is_none_or(|b| run.len() > b.len())
+                {
+                    best = Some(run);
+                }
+            }
+            _ => {}
+        }
+    }
+    best
+}
+
 /// Does this query look like a filename? Has an extension, or matches known extensionless names.
 fn looks_like_filename(query: &str) -> bool {
     if query.contains(' ') || query.contains('/') {
@@ -127,6 +184,18 @@ mod tests {
         assert!(matches!(classify("{a,b}.js", &scope), QueryType::Glob(_)));
     }
 
+    #[test]
+    fn auto_search_kind() {
+        assert_eq!(search_kind("handleAuth"), "symbol");
+        assert_eq!(search_kind("ServeHTTP, Next"), "symbol");
+        assert_eq!(search_kind("search::rank::sort"), "symbol");
+        assert_eq!(search_kind("TODO: fix this"), "content");
+        assert_eq!(search_kind("\"connection refused\""), "content");
+        assert_eq!(search_kind("404"), "content");
+        assert_eq!(search_kind("/fn \\w+_test/"), "regex");
+        assert_eq!(search_kind("src/**/handler*"), "files");
+    }
+
     #[test]
     fn identifiers() {
         let scope = PathBuf::from(".");
@@ -164,6 +233,19 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn punctuated_queries() {
+        assert_eq!(identifier_in("Vec<u8>"), Some("Vec"));
+        assert_eq!(identifier_in("#[derive(Serialize)]"), Some("Serialize"));
+        assert_eq!(identifier_in("operator=="), Some("operator"));
+        assert_eq!(identifier_in("HashMap<String, Vec<u8>>"), Some("HashMap"));
+        assert_eq!(identifier_in("handleAuth"), None);
+        assert_eq!(identifier_in("== 42"), None);
+        assert!(!is_glob("#[derive(Serialize)]"));
+        assert!(!is_glob("operator[]"));
+        assert!(is_glob("src/[ab]*.rs"));
+    }
+
     #[test]
     fn is_identifier_checks() {
         assert!(is_identifier("handleAuth"));
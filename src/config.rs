@@ -0,0 +1,341 @@
+//! Workspace settings, read from `tilth.toml` in the working directory:
+//! scope aliases for long monorepo paths, redaction rules (see
+//! [`crate::redact`]), extra tree-sitter grammars (see [`crate::grammars`])
+//! and outline rules (see [`crate::read::outline::rules`]).
+//!
+//! ```toml
+//! [aliases]
+//! server = "services/api/src"
+//!
+//! [redact]
+//! paths = ["secrets/**", "*.pem"]
+//! patterns = ['AKIA[0-9A-Z]{16}']
+//!
+//! [[grammars]]
+//! name = "zig"
+//! library = "grammars/libtree-sitter-zig.so"
+//! extensions = ["zig"]
+//! definitions = ["function_declaration", "variable_declaration"]
+//!
+//! [[outline]]
+//! language = "typescript"
+//! kind = "suite"
+//! query = '(call_expression function: (identifier) @_f (#eq? @_f "describe") arguments: (arguments (string) @name)) @entry'
+//! ```
+//!
+//! An alias stands for its path in any `scope`, alone or as the first
+//! component (`server/handlers`). A real path of the same name wins.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub(crate) const FILE: &str = "tilth.toml";
+
+/// The `[redact]` table: globs of paths left out of all output, and
+/// patterns masked wherever they appear.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Redaction {
+    pub paths: Vec<String>,
+    pub patterns: Vec<String>,
+}
+
+/// A `[[grammars]]` entry: a tree-sitter grammar built as a shared
+/// library, the extensions it parses, and the node kinds that count as
+/// definitions.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct GrammarSpec {
+    pub name: String,
+    pub library: PathBuf,
+    /// The exported language function; `tree_sitter_<name>` by default.
+    pub symbol: String,
+    pub extensions: Vec<String>,
+    pub definitions: Vec<String>,
+}
+
+/// An `[[outline]]` entry: a tree-sitter query whose matches are added to
+/// the outlines of one language's files.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct OutlineRule {
+    /// A built-in language (`typescript`, `c++`) or a `[[grammars]]` name.
+    pub language: String,
+    pub kind: String,
+    pub query: String,
+}
+
+/// Scope aliases from the working directory's `tilth.toml`, by name. Empty
+/// when there is no file, or it doesn't parse.
+#[must_use]
+pub fn aliases() -> BTreeMap<String, String> {
+    std::fs::read_to_string(FILE)
+        .map(|text| parse_aliases(&text))
+        .unwrap_or_default()
+}
+
+/// `scope` with a leading alias replaced by the path it stands for.
+#[must_use]
+pub fn expand_scope(scope: &str) -> PathBuf {
+    expand(scope, &aliases())
+}
+
+/// The working directory's redaction rules; none without a `tilth.toml`.
+/// Unlike aliases, a file that doesn't parse is an error — redaction
+/// shouldn't quietly switch off.
+pub(crate) fn redaction() -> Result<Redaction, String> {
+    match std::fs::read_to_string(FILE) {
+        Ok(text) => parse_redaction(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Redaction::default()),
+        Err(e) => Err(format!("{FILE}: {e}")),
+    }
+}
+
+fn parse_redaction(text: &str) -> Result<Redaction, String> {
+    let value = text
+        .parse::<toml::Value>()
+        .map_err(|e| format!("{FILE}: {e}"))?;
+    let Some(table) = value.get("redact") else {
+        return Ok(Redaction::default());
+    };
+    let list = |key: &str| -> Result<Vec<String>, String> {
+        let Some(items) = table.get(key) else {
+            return Ok(Vec::new());
+        };
+        items
+            .as_array()
+            .and_then(|items| {
+                items
+                    .iter()
+                    .map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .ok_or_else(|| format!("{FILE}: redact.{key} must be an array of strings"))
+    };
+    Ok(Redaction {
+        paths: list("paths")?,
+        patterns: list("patterns")?,
+    })
+}
+
+/// The working directory's extra grammars; none without a `tilth.toml`.
+pub(crate) fn grammars() -> Result<Vec<GrammarSpec>, String> {
+    match std::fs::read_to_string(FILE) {
+        Ok(text) => parse_grammars(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("{FILE}: {e}")),
+    }
+}
+
+fn parse_grammars(text: &str) -> Result<Vec<GrammarSpec>, String> {
+    let value = text
+        .parse::<toml::Value>()
+        .map_err(|e| format!("{FILE}: {e}"))?;
+    let Some(entries) = value.get("grammars") else {
+        return Ok(Vec::new());
+    };
+    let entries = entries
+        .as_array()
+        .ok_or_else(|| format!("{FILE}: grammars must be an array of tables ([[grammars]])"))?;
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let string = |key: &str| entry.get(key).and_then(toml::Value::as_str);
+            let name = string("name")
+                .ok_or_else(|| format!("{FILE}: grammars[{i}] needs a name"))?
+                .to_string();
+            let library = string("library")
+                .ok_or_else(|| format!("{FILE}: grammar {name} needs a library path"))?;
+            let list = |key: &str| -> Result<Vec<String>, String> {
+                entry
+                    .get(key)
+                    .and_then(toml::Value::as_array)
+                    .and_then(|items| {
+                        items
+                            .iter()
+                            .map(|item| item.as_str().map(str::to_string))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .filter(|items| !items.is_empty())
+                    .ok_or_else(|| {
+                        format!(
+                            "{FILE}: grammar {name}: {key} must be a non-empty array of strings"
+                        )
+                    })
+            };
+            Ok(GrammarSpec {
+                symbol: string("symbol").map_or_else(
+                    || format!("tree_sitter_{}", name.replace('-', "_")),
+                    str::to_string,
+                ),
+                library: PathBuf::from(library),
+                extensions: list("extensions")?
+                    .into_iter()
+                    .map(|e| e.trim_start_matches('.').to_string())
+                    .collect(),
+                definitions: list("definitions")?,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// The working directory's outline rules; none without a `tilth.toml`.
+pub(crate) fn outline_rules() -> Result<Vec<OutlineRule>, String> {
+    match std::fs::read_to_string(FILE) {
+        Ok(text) => parse_outline_rules(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("{FILE}: {e}")),
+    }
+}
+
+fn parse_outline_rules(text: &str) -> Result<Vec<OutlineRule>, String> {
+    let value = text
+        .parse::<toml::Value>()
+        .map_err(|e| format!("{FILE}: {e}"))?;
+    let Some(entries) = value.get("outline") else {
+        return Ok(Vec::new());
+    };
+    let entries = entries
+        .as_array()
+        .ok_or_else(|| format!("{FILE}: outline must be an array of tables ([[outline]])"))?;
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let string = |key: &str| {
+                entry
+                    .get(key)
+                    .and_then(toml::Value::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("{FILE}: outline[{i}] needs a {key} string"))
+            };
+            Ok(OutlineRule {
+                language: string("language")?,
+                kind: string("kind")?,
+                query: string("query")?,
+            })
+        })
+        .collect()
+}
+
+fn parse_aliases(text: &str) -> BTreeMap<String, String> {
+    let Ok(value) = text.parse::<toml::Value>() else {
+        return BTreeMap::new();
+    };
+    value
+        .get("aliases")
+        .and_then(toml::Value::as_table)
+        .map(|table| {
+            table
+                .iter()
+                .filter_map(|(name, path)| Some((name.clone(), path.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn expand(scope: &str, aliases: &BTreeMap<String, String>) -> PathBuf {
+    if aliases.is_empty() || Path::new(scope).exists() {
+        return PathBuf::from(scope);
+    }
+    let (head, rest) = scope
+        .split_once(['/', '\\'])
+        .map_or((scope, None), |(head, rest)| (head, Some(rest)));
+    match aliases.get(head) {
+        Some(target) => rest.map_or_else(|| PathBuf::from(target), |r| Path::new(target).join(r)),
+        None => PathBuf::from(scope),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_string_aliases_only() {
+        let aliases = parse_aliases(
+            "[aliases]\nserver = \"services/api/src\"\nweb = \"apps/web\"\nbad = 3\n\n[other]\nx = \"y\"\n",
+        );
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases["server"], "services/api/src");
+        assert!(parse_aliases("not toml [").is_empty());
+    }
+
+    #[test]
+    fn redaction_errors_instead_of_switching_off() {
+        let rules = parse_redaction(
+            "[redact]\npaths = [\"secrets/**\"]\npatterns = ['AKIA[0-9A-Z]{16}']\n",
+        )
+        .unwrap();
+        assert_eq!(rules.paths, vec!["secrets/**"]);
+        assert_eq!(rules.patterns, vec!["AKIA[0-9A-Z]{16}"]);
+        assert_eq!(parse_redaction("[aliases]\n"), Ok(Redaction::default()));
+        assert!(parse_redaction("[redact]\npaths = \"secrets\"\n").is_err());
+        assert!(parse_redaction("[redact\n").is_err());
+    }
+
+    #[test]
+    fn reads_grammar_entries() {
+        let grammars = parse_grammars(
+            "[[grammars]]\nname = \"zig\"\nlibrary = \"grammars/zig.so\"\nextensions = [\".zig\"]\ndefinitions = [\"function_declaration\"]\n\n[[grammars]]\nname = \"odin\"\nlibrary = \"odin.dylib\"\nsymbol = \"tree_sitter_odin_lang\"\nextensions = [\"odin\"]\ndefinitions = [\"procedure_declaration\", \"struct_declaration\"]\n",
+        )
+        .unwrap();
+        assert_eq!(grammars.len(), 2);
+        assert_eq!(
+            grammars[0],
+            GrammarSpec {
+                name: "zig".into(),
+                library: PathBuf::from("grammars/zig.so"),
+                symbol: "tree_sitter_zig".into(),
+                extensions: vec!["zig".into()],
+                definitions: vec!["function_declaration".into()],
+            }
+        );
+        assert_eq!(grammars[1].symbol, "tree_sitter_odin_lang");
+        assert_eq!(parse_grammars("[aliases]\n"), Ok(Vec::new()));
+        assert!(parse_grammars(
+            "[[grammars]]\nname = \"zig\"\nlibrary = \"z.so\"\nextensions = [\"zig\"]\n"
+        )
+        .is_err());
+        assert!(parse_grammars("[grammars]\nname = \"zig\"\n").is_err());
+    }
+
+    #[test]
+    fn reads_outline_rules() {
+        let rules = parse_outline_rules(
+            "[[outline]]\nlanguage = \"typescript\"\nkind = \"suite\"\nquery = '(call_expression) @entry'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            vec![OutlineRule {
+                language: "typescript".into(),
+                kind: "suite".into(),
+                query: "(call_expression) @entry".into(),
+            }]
+        );
+        assert!(parse_outline_rules("[[outline]]\nlanguage = \"go\"\n").is_err());
+        assert_eq!(parse_outline_rules(""), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn expands_a_leading_alias() {
+        let aliases = parse_aliases("[aliases]\nsrv_tilth_test = \"services/api/src\"\n");
+        assert_eq!(
+            expand("srv_tilth_test", &aliases),
+            PathBuf::from("services/api/src")
+        );
+        assert_eq!(
+            expand("srv_tilth_test/handlers", &aliases),
+            PathBuf::from("services/api/src/handlers")
+        );
+        assert_eq!(
+            expand("other/srv_tilth_test", &aliases),
+            PathBuf::from("other/srv_tilth_test")
+        );
+        // A real directory keeps its name
+        assert_eq!(
+            expand("src", &parse_aliases("[aliases]\nsrc = \"lib\"\n")),
+            PathBuf::from("src")
+        );
+    }
+}
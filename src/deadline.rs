@@ -0,0 +1,106 @@
+//! Per-call time limit for tool calls that walk the tree. File walks check
+//! it before visiting each entry and stop once it has passed, so a
+//! pathological query returns what it gathered so far instead of stalling.
+//!
+//! A deadline belongs to one call: it is armed on the calling thread, and
+//! walkers built there carry it to their worker threads. Concurrent calls,
+//! and walks nobody armed a deadline for, don't see each other's.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// The deadline of the call running on this thread, if it has one.
+    static CURRENT: RefCell<Option<Deadline>> = const { RefCell::new(None) };
+}
+
+/// When one call must stop, shared by every walk it starts.
+#[derive(Clone)]
+pub(crate) struct Deadline(Arc<State>);
+
+struct State {
+    /// `None` when the timeout is too large to represent.
+    at: Option<Instant>,
+    /// Set when a walk stopped early because the deadline passed.
+    expired: AtomicBool,
+}
+
+impl Deadline {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self(Arc::new(State {
+            at: Instant::now().checked_add(timeout),
+            expired: AtomicBool::new(false),
+        }))
+    }
+
+    /// The deadline of the call running on this thread.
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `f` with this as the calling thread's deadline.
+    pub(crate) fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        within(Some(self), f)
+    }
+
+    /// True once the deadline has passed. Callers stop early and the call
+    /// is reported as partial.
+    pub(crate) fn reached(&self) -> bool {
+        if self.0.at.is_none_or(|at| Instant::now() < at) {
+            return false;
+        }
+        self.0.expired.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Whether a walk was cut short by this deadline.
+    pub(crate) fn expired(&self) -> bool {
+        self.0.expired.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `f` with `deadline` as the calling thread's, restoring the previous
+/// one after — for handing a call's deadline to work on another thread.
+pub(crate) fn within<T>(deadline: Option<&Deadline>, f: impl FnOnce() -> T) -> T {
+    /// Puts the previous deadline back, even if `f` panics.
+    struct Restore(Option<Deadline>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|current| current.replace(deadline.cloned())));
+    f()
+}
+
+/// True once the calling thread's deadline has passed; never without one.
+pub(crate) fn reached() -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(Deadline::reached))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadlines_stay_with_their_call() {
+        let passed = Deadline::new(Duration::ZERO);
+        assert!(!reached());
+        passed.run(|| {
+            assert!(reached());
+            let open = Deadline::new(Duration::from_hours(1));
+            open.run(|| assert!(!reached()));
+            // Another thread's walk isn't cut short by this call's deadline
+            assert!(!std::thread::spawn(reached).join().unwrap());
+            let handed = Deadline::current();
+            let seen = std::thread::spawn(move || within(handed.as_ref(), reached));
+            assert!(seen.join().unwrap());
+        });
+        assert!(passed.expired());
+        assert!(!reached());
+    }
+}
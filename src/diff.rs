@@ -0,0 +1,372 @@
+//! Line diff between two files or two sections of files.
+//!
+//! Output is a unified diff whose right-hand lines carry hashline anchors
+//! (`<line>:<hash>|`), so a follow-up `tilth_edit` can target them directly.
+
+use std::fmt::Write;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::error::TilthError;
+use crate::format;
+use crate::read;
+
+/// Edit distances beyond this fall back to a whole-range replacement.
+/// Bounds the Myers trace to roughly `16 * MAX_EDIT_DISTANCE²` bytes.
+const MAX_EDIT_DISTANCE: usize = 1_000;
+
+/// One side of a comparison: a file plus an optional section address
+/// (`"45-89"` or `"## Heading"`, same syntax as `tilth_read`).
+pub struct DiffSide<'a> {
+    pub path: &'a Path,
+    pub section: Option<&'a str>,
+}
+
+/// A single step of the edit script. Indices are 0-based into the compared lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Diff `left` against `right` and render a unified diff with `context` lines
+/// of surrounding equal content per hunk.
+pub fn diff(left: &DiffSide, right: &DiffSide, context: usize) -> Result<String, TilthError> {
+    let (left_start, left_text) = load_side(left)?;
+    let (right_start, right_text) = load_side(right)?;
+    let a: Vec<&str> = left_text.lines().collect();
+    let b: Vec<&str> = right_text.lines().collect();
+
+    let ops = line_ops(&a, &b);
+    let added = ops.iter().filter(|op| matches!(op, Op::Insert(_))).count();
+    let removed = ops.iter().filter(|op| matches!(op, Op::Delete(_))).count();
+
+    let mut out = format!(
+        "# Diff: {} → {} (+{added} -{removed})",
+        side_label(left, left_start, a.len()),
+        side_label(right, right_start, b.len()),
+    );
+
+    if added == 0 && removed == 0 {
+        out.push_str("\n\n(identical)");
+        return Ok(out);
+    }
+
+    for hunk in hunks(&ops, context) {
+        render_hunk(
+            &ops[hunk],
+            &a,
+            &b,
+            left_start as usize,
+            right_start as usize,
+            &mut out,
+        );
+    }
+
+    Ok(out)
+}
+
+/// Read one side, applying its section if any. Returns `(first_line, text)`.
+fn load_side(side: &DiffSide) -> Result<(u32, String), TilthError> {
+    let buf = fs::read(side.path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: side.path.to_path_buf(),
+            suggestion: None,
+        },
+        std::io::ErrorKind::PermissionDenied => TilthError::PermissionDenied {
+            path: side.path.to_path_buf(),
+        },
+        _ => TilthError::IoError {
+            path: side.path.to_path_buf(),
+            source: e,
+        },
+    })?;
+
+    if read::binary::is_binary(&buf) {
+        return Err(TilthError::InvalidQuery {
+            query: side.path.display().to_string(),
+            reason: "binary file — cannot diff".into(),
+        });
+    }
+
+    match side.section {
+        Some(range) => {
+            let (start, _, bytes) = read::section_bytes(format::strip_bom(&buf), range)?;
+            Ok((start, String::from_utf8_lossy(bytes).into_owned()))
+        }
+        None => Ok((
+            1,
+            String::from_utf8_lossy(format::strip_bom(&buf)).into_owned(),
+        )),
+    }
+}
+
+fn side_label(side: &DiffSide, start: u32, lines: usize) -> String {
+    if side.section.is_some() {
+        let end = (start as usize + lines)
+            .saturating_sub(1)
+            .max(start as usize);
+        format!("{}:{start}-{end}", side.path.display())
+    } else {
+        side.path.display().to_string()
+    }
+}
+
+/// Compute the edit script between two line slices.
+/// Common prefix/suffix are trimmed before running Myers on the middle.
+fn line_ops(a: &[&str], b: &[&str]) -> Vec<Op> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let mid_a = &a[prefix..a.len() - suffix];
+    let mid_b = &b[prefix..b.len() - suffix];
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+
+    if let Some(mid) = myers(mid_a, mid_b) {
+        ops.extend(mid.into_iter().map(|op| match op {
+            Op::Equal(i, j) => Op::Equal(prefix + i, prefix + j),
+            Op::Delete(i) => Op::Delete(prefix + i),
+            Op::Insert(j) => Op::Insert(prefix + j),
+        }));
+    } else {
+        // Too different to align cheaply — show as a block replacement.
+        ops.extend((0..mid_a.len()).map(|i| Op::Delete(prefix + i)));
+        ops.extend((0..mid_b.len()).map(|j| Op::Insert(prefix + j)));
+    }
+
+    let a_tail = a.len() - suffix;
+    let b_tail = b.len() - suffix;
+    ops.extend((0..suffix).map(|k| Op::Equal(a_tail + k, b_tail + k)));
+    ops
+}
+
+/// Myers O(ND) shortest edit script.
+/// Returns `None` when the edit distance exceeds [`MAX_EDIT_DISTANCE`].
+#[allow(clippy::many_single_char_names)] // n, m, d, k, x, y as in the paper
+fn myers(a: &[&str], b: &[&str]) -> Option<Vec<Op>> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (a.len() + b.len()).min(MAX_EDIT_DISTANCE) as isize;
+    // v[k + offset] = furthest x reached on diagonal k
+    let offset = max + 1;
+    let mut v = vec![0isize; (2 * offset + 1) as usize];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max {
+        trace.push(v.clone());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m, offset));
+            }
+            k += 2;
+        }
+    }
+
+    None
+}
+
+/// Walk the Myers trace backwards from `(n, m)` to recover the edit script.
+#[allow(clippy::many_single_char_names)]
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize, offset: isize) -> Vec<Op> {
+    let mut ops = Vec::new();
+    let mut x = n;
+    let mut y = m;
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k =
+            if k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]) {
+                k + 1
+            } else {
+                k - 1
+            };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op::Equal(x as usize, y as usize));
+        }
+
+        if d > 0 {
+            if x == prev_x {
+                ops.push(Op::Insert(prev_y as usize));
+            } else {
+                ops.push(Op::Delete(prev_x as usize));
+            }
+        }
+
+        x = prev_x;
+        y = prev_y;
+    }
+
+    ops.reverse();
+    ops
+}
+
+/// Group ops into hunks: each change plus `context` equal ops either side,
+/// merged when two changes are separated by at most `2 * context` equal lines.
+fn hunks(ops: &[Op], context: usize) -> Vec<Range<usize>> {
+    let mut out: Vec<Range<usize>> = Vec::new();
+    for (i, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Equal(..)) {
+            continue;
+        }
+        let start = i.saturating_sub(context);
+        let end = (i + 1 + context).min(ops.len());
+        match out.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => out.push(start..end),
+        }
+    }
+    out
+}
+
+/// Render one hunk. Right-hand lines (context and additions) get hashline
+/// anchors; removed lines show their left-hand line number only.
+fn render_hunk(
+    ops: &[Op],
+    a: &[&str],
+    b: &[&str],
+    left_start: usize,
+    right_start: usize,
+    out: &mut String,
+) {
+    let left_first = ops.iter().find_map(|op| match *op {
+        Op::Equal(i, _) | Op::Delete(i) => Some(i),
+        Op::Insert(_) => None,
+    });
+    let right_first = ops.iter().find_map(|op| match *op {
+        Op::Equal(_, j) | Op::Insert(j) => Some(j),
+        Op::Delete(_) => None,
+    });
+    let left_count = ops.iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+    let right_count = ops.iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+
+    let _ = write!(
+        out,
+        "\n\n@@ -{},{left_count} +{},{right_count} @@",
+        left_first.map_or(left_start.saturating_sub(1), |i| left_start + i),
+        right_first.map_or(right_start.saturating_sub(1), |j| right_start + j),
+    );
+
+    for op in ops {
+        match *op {
+            Op::Equal(_, j) => {
+                let _ = write!(out, "\n {}|{}", anchor(b[j], right_start + j), b[j]);
+            }
+            Op::Delete(i) => {
+                let _ = write!(out, "\n-{}|{}", left_start + i, a[i]);
+            }
+            Op::Insert(j) => {
+                let _ = write!(out, "\n+{}|{}", anchor(b[j], right_start + j), b[j]);
+            }
+        }
+    }
+}
+
+fn anchor(line: &str, num: usize) -> String {
+    format!("{num}:{:03x}", format::line_hash(line.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(ops: &[Op], a: &[&str], b: &[&str]) -> (Vec<String>, Vec<String>) {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for op in ops {
+            match *op {
+                Op::Equal(i, j) => {
+                    left.push(a[i].to_string());
+                    right.push(b[j].to_string());
+                }
+                Op::Delete(i) => left.push(a[i].to_string()),
+                Op::Insert(j) => right.push(b[j].to_string()),
+            }
+        }
+        (left, right)
+    }
+
+    #[test]
+    fn identical_has_no_changes() {
+        let a = ["x", "y", "z"];
+        let ops = line_ops(&a, &a);
+        assert!(ops.iter().all(|op| matches!(op, Op::Equal(..))));
+        assert_eq!(ops.len(), 3);
+    }
+
+    #[test]
+    fn ops_reconstruct_both_sides() {
+        let a = ["a", "b", "c", "a", "b", "b", "a"];
+        let b = ["c", "b", "a", "b", "a", "c"];
+        let ops = line_ops(&a, &b);
+        let (left, right) = apply(&ops, &a, &b);
+        assert_eq!(left, a);
+        assert_eq!(right, b);
+        // Myers finds the minimal script: 5 edits for this classic example
+        let edits = ops.iter().filter(|op| !matches!(op, Op::Equal(..))).count();
+        assert_eq!(edits, 5);
+    }
+
+    #[test]
+    fn empty_sides() {
+        let ops = line_ops(&[], &["new"]);
+        assert_eq!(ops, vec![Op::Insert(0)]);
+        let ops = line_ops(&["old"], &[]);
+        assert_eq!(ops, vec![Op::Delete(0)]);
+    }
+
+    #[test]
+    fn hunks_merge_nearby_changes() {
+        let a = [
+            "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12",
+        ];
+        let b = [
+            "1", "X", "3", "4", "5", "6", "Y", "8", "9", "10", "11", "12",
+        ];
+        let ops = line_ops(&a, &b);
+        // Changes 4 lines apart merge with context 3 ...
+        assert_eq!(hunks(&ops, 3).len(), 1);
+        // ... but stay separate with context 1
+        assert_eq!(hunks(&ops, 1).len(), 2);
+    }
+
+    #[test]
+    fn rendered_lines_carry_right_anchors() {
+        let a = ["fn a() {", "    old();", "}"];
+        let b = ["fn a() {", "    new();", "}"];
+        let ops = line_ops(&a, &b);
+        let mut out = String::new();
+        for hunk in hunks(&ops, 3) {
+            render_hunk(&ops[hunk], &a, &b, 10, 20, &mut out);
+        }
+        let added = format!("+{}|    new();", anchor("    new();", 21));
+        assert!(out.contains("@@ -10,3 +20,3 @@"), "{out}");
+        assert!(out.contains("-11|    old();"), "{out}");
+        assert!(out.contains(&added), "{out}");
+    }
+}
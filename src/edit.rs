@@ -1,8 +1,11 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::error::TilthError;
 use crate::format;
+use crate::read::imports::is_import_line;
+use crate::read::{binary, detect_file_type, generated};
+use crate::types::{FileType, Lang};
 
 /// A single edit operation targeting a line range by hash anchors.
 #[derive(Debug, Clone)]
@@ -14,6 +17,17 @@ pub struct Edit {
     pub content: String,
 }
 
+/// A whole definition replaced by name. The range is resolved with
+/// tree-sitter when the edit is applied, so it survives line shifts that
+/// would break [`Edit`] anchors.
+#[derive(Debug, Clone)]
+pub struct SymbolEdit {
+    pub symbol: String,
+    /// [`format::body_hash`] of the definition as last seen.
+    pub hash: u16,
+    pub content: String,
+}
+
 /// Result of applying edits to a file.
 #[derive(Debug)]
 pub enum EditResult {
@@ -21,37 +35,30 @@ pub enum EditResult {
     Applied(String),
     /// One or more hashes didn't match current content.
     HashMismatch(String),
+    /// Nothing to change — the file already says what was asked. Says where.
+    Unchanged(String),
 }
 
 /// Apply a batch of edits to a file.
 ///
-/// 1. Read file into lines
+/// 1. Read file into lines, setting aside a byte order mark and each line's ending
 /// 2. Verify ALL hashes before applying ANY edit (fail-fast)
 /// 3. Sort edits by `start_line` descending (reverse preserves line numbers)
 /// 4. Splice replacements
-/// 5. Write file
+/// 5. Write file — untouched lines keep their endings, new lines take the
+///    file's usual one, and a byte order mark is written back
 /// 6. Return hashlined context around edit sites
 pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, TilthError> {
     if edits.is_empty() {
         return Ok(EditResult::Applied(String::new()));
     }
 
-    // Read file
-    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
-        std::io::ErrorKind::NotFound => TilthError::NotFound {
-            path: path.to_path_buf(),
-            suggestion: None,
-        },
-        std::io::ErrorKind::PermissionDenied => TilthError::PermissionDenied {
-            path: path.to_path_buf(),
-        },
-        _ => TilthError::IoError {
-            path: path.to_path_buf(),
-            source: e,
-        },
-    })?;
+    let content = read(path)?;
 
-    let lines: Vec<&str> = content.lines().collect();
+    let bom = content.starts_with('\u{feff}');
+    let body = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let split = split_endings(body);
+    let lines: Vec<&str> = split.iter().map(|&(text, _)| text).collect();
     let total = lines.len();
 
     // Phase 1: Verify all hashes
@@ -137,32 +144,46 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, TilthError
     let mut indices: Vec<usize> = (0..edits.len()).collect();
     indices.sort_by_key(|&i| std::cmp::Reverse(edits[i].start_line));
 
-    let mut owned: Vec<String> = lines.iter().map(|&s| s.to_string()).collect();
+    let mut owned: Vec<(String, &str)> = split
+        .iter()
+        .map(|&(text, ending)| (text.to_string(), ending))
+        .collect();
 
     for &idx in &indices {
         let edit = &edits[idx];
         let start_idx = edit.start_line - 1;
         let end_idx = edit.end_line; // exclusive end for inclusive range
 
-        let replacement: Vec<String> = if edit.content.is_empty() {
+        let replacement: Vec<(String, &str)> = if edit.content.is_empty() {
             vec![]
         } else {
-            edit.content.lines().map(String::from).collect()
+            edit.content
+                .lines()
+                .map(|line| (String::from(line), ""))
+                .collect()
         };
 
         owned.splice(start_idx..end_idx, replacement);
     }
 
-    // Phase 3: Write file, preserving original line ending style
-    let line_sep = if content.contains("\r\n") {
-        "\r\n"
-    } else {
-        "\n"
-    };
-    let has_trailing_newline = content.ends_with('\n');
-    let mut output = owned.join(line_sep);
-    if has_trailing_newline {
-        output.push_str(line_sep);
+    // Phase 3: Write file, preserving original line ending style. New lines
+    // take the file's majority ending; the last line ends as the file did.
+    let crlf = split.iter().filter(|&&(_, e)| e == "\r\n").count();
+    let lf = split.iter().filter(|&&(_, e)| e == "\n").count();
+    let line_sep = if crlf > lf { "\r\n" } else { "\n" };
+    let trailing = split.last().map_or("", |&(_, e)| e);
+    let last = owned.len().saturating_sub(1);
+    let mut output = String::with_capacity(content.len());
+    if bom {
+        output.push('\u{feff}');
+    }
+    for (i, (text, ending)) in owned.iter().enumerate() {
+        output.push_str(text);
+        output.push_str(match (i == last, ending.is_empty()) {
+            (true, _) if trailing.is_empty() => "",
+            (_, false) => ending,
+            (_, true) => line_sep,
+        });
     }
 
     fs::write(path, &output).map_err(|e| TilthError::IoError {
@@ -192,7 +213,11 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, TilthError
         let context_start = adjusted.saturating_sub(5);
         let context_end = (adjusted + new_count + 5).min(owned.len());
         if context_start < context_end {
-            let context_lines: String = owned[context_start..context_end].join("\n");
+            let context_lines: String = owned[context_start..context_end]
+                .iter()
+                .map(|(text, _)| text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
             let hashlined = format::hashlines(&context_lines, (context_start + 1) as u32);
             contexts.push(hashlined);
         }
@@ -202,3 +227,603 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, TilthError
 
     Ok(EditResult::Applied(contexts.join("\n---\n")))
 }
+
+/// Refuse files that reads show only as a header — binary files, lockfiles
+/// and files marked generated, whose edits belong in the source they're built
+/// from — and files with unresolved merge conflicts, which are shown instead.
+/// A file that doesn't exist yet passes — the edit reports it.
+pub fn check_editable(path: &Path) -> Result<(), TilthError> {
+    let Ok(bytes) = fs::read(path) else {
+        return Ok(());
+    };
+    let buf = format::strip_bom(&bytes);
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    let reason = if binary::is_binary(buf) {
+        "binary file (pass force: true to edit anyway)".to_string()
+    } else if generated::is_generated_by_name(name) {
+        "lockfile — update it with its package manager (pass force: true to edit anyway)"
+            .to_string()
+    } else if generated::is_generated_by_content(buf) {
+        "generated file — edit its source and regenerate (pass force: true to edit anyway)"
+            .to_string()
+    } else if let Some(conflicts) = std::str::from_utf8(buf)
+        .ok()
+        .and_then(|text| conflicts(path, text))
+    {
+        conflicts
+    } else {
+        return Ok(());
+    };
+    Err(TilthError::InvalidQuery {
+        query: path.display().to_string(),
+        reason,
+    })
+}
+
+/// Describe the unresolved conflict hunks in `content`, hashlined, and the
+/// merge, rebase or similar left in progress in the repository, if any.
+fn conflicts(path: &Path, content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    let mut open: Option<(usize, bool)> = None;
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("<<<<<<<") {
+            open = Some((i, false));
+        } else if line.starts_with("=======") {
+            if let Some((start, _)) = open {
+                open = Some((start, true));
+            }
+        } else if line.starts_with(">>>>>>>") {
+            if let Some((start, true)) = open {
+                hunks.push((start, i));
+            }
+            open = None;
+        }
+    }
+    if hunks.is_empty() {
+        return None;
+    }
+
+    let during = git_operation(path).map_or_else(String::new, |op| format!(", {op} in progress"));
+    let mut out = format!(
+        "unresolved merge conflict — {} hunk{}{during}; resolve it first, or pass force: true to edit through it:",
+        hunks.len(),
+        if hunks.len() == 1 { "" } else { "s" }
+    );
+    for &(start, end) in &hunks {
+        out.push_str("\n\n");
+        out.push_str(
+            format::hashlines(&lines[start..=end].join("\n"), start as u32 + 1).trim_end(),
+        );
+    }
+    Some(out)
+}
+
+/// The operation a repository was left in the middle of — `"merge"`,
+/// `"rebase"`, `"cherry-pick"` or `"revert"` — for the repository holding
+/// `path`, found by its `.git` directory (or a worktree's `.git` file).
+fn git_operation(path: &Path) -> Option<&'static str> {
+    let absolute = path.canonicalize().ok()?;
+    let dot_git = absolute
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join(".git"))
+        .find(|candidate| candidate.exists())?;
+    let git_dir = if dot_git.is_file() {
+        let pointer = fs::read_to_string(&dot_git).ok()?;
+        let target = PathBuf::from(pointer.strip_prefix("gitdir:")?.trim());
+        dot_git.parent()?.join(target)
+    } else {
+        dot_git
+    };
+    [
+        ("rebase-merge", "rebase"),
+        ("rebase-apply", "rebase"),
+        ("MERGE_HEAD", "merge"),
+        ("CHERRY_PICK_HEAD", "cherry-pick"),
+        ("REVERT_HEAD", "revert"),
+    ]
+    .into_iter()
+    .find(|(marker, _)| git_dir.join(marker).exists())
+    .map(|(_, op)| op)
+}
+
+/// Replace the definition of `edit.symbol` in `path`.
+///
+/// The file must hold exactly one definition of that name, and its current
+/// body must hash to `edit.hash` — otherwise nothing is written and the
+/// mismatch shows the current body with its hash. The replacement itself
+/// goes through [`apply_edits`], so line endings and a BOM are kept.
+pub fn apply_symbol_edit(path: &Path, edit: &SymbolEdit) -> Result<EditResult, TilthError> {
+    let content = read(path)?;
+    let body = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let lines: Vec<&str> = body.lines().collect();
+
+    let ranges = crate::search::symbol::definition_ranges(path, &edit.symbol, body);
+    let (start, end) = match ranges.as_slice() {
+        [(start, end)] if *start >= 1 && start <= end => {
+            (*start as usize, (*end as usize).min(lines.len()))
+        }
+        [] | [_] => {
+            return Err(TilthError::InvalidQuery {
+                query: edit.symbol.clone(),
+                reason: format!("no definition found in {}", path.display()),
+            })
+        }
+        _ => {
+            let at: Vec<String> = ranges.iter().map(|(s, e)| format!("{s}-{e}")).collect();
+            return Err(TilthError::InvalidQuery {
+                query: edit.symbol.clone(),
+                reason: format!(
+                    "{} definitions in {} (lines {}) — use line anchors",
+                    ranges.len(),
+                    path.display(),
+                    at.join(", ")
+                ),
+            });
+        }
+    };
+
+    let old = &lines[start - 1..end];
+    let actual = format::body_hash(old);
+    if actual != edit.hash {
+        return Ok(EditResult::HashMismatch(format!(
+            "Body hash mismatch for {} at lines {start}-{end} (expected {:03x}, got {actual:03x}):\n{}",
+            edit.symbol,
+            edit.hash,
+            format::hashlines(&old.join("\n"), start as u32)
+        )));
+    }
+
+    let anchored = Edit {
+        start_line: start,
+        start_hash: format::line_hash(old[0].as_bytes()),
+        end_line: end,
+        end_hash: format::line_hash(old[old.len() - 1].as_bytes()),
+        content: edit.content.clone(),
+    };
+    apply_edits(path, &[anchored])
+}
+
+/// Add an import statement to `path`, unless it's already there.
+///
+/// It goes into the group of top-level imports it shares the longest prefix
+/// with — `use crate::…` among the crate's own — in sorted position. A Go
+/// `import "x"` joins an existing `import ( … )` block. A file with no
+/// imports gets it after its leading comments and package clause.
+pub fn add_import(path: &Path, statement: &str) -> Result<EditResult, TilthError> {
+    let statement = statement.trim();
+    let invalid = |reason: String| TilthError::InvalidQuery {
+        query: statement.to_string(),
+        reason,
+    };
+    let FileType::Code(lang) = detect_file_type(path) else {
+        return Err(invalid(format!("{} is not a code file", path.display())));
+    };
+    if !is_import_line(statement, lang) {
+        return Err(invalid(format!("not a {lang} import statement")));
+    }
+
+    let content = read(path)?;
+    let body = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let lines: Vec<&str> = body.lines().collect();
+    if lines.is_empty() {
+        fs::write(path, format!("{content}{statement}\n")).map_err(|e| TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        return Ok(EditResult::Applied(format::hashlines(statement, 1)));
+    }
+
+    match import_site(&lines, statement, lang) {
+        ImportSite::Present(line) => Ok(EditResult::Unchanged(format!(
+            "already imported at line {line}: {statement}"
+        ))),
+        ImportSite::Splice { at, content } => {
+            let hash = format::line_hash(lines[at].as_bytes());
+            let edit = Edit {
+                start_line: at + 1,
+                start_hash: hash,
+                end_line: at + 1,
+                end_hash: hash,
+                content,
+            };
+            apply_edits(path, &[edit])
+        }
+    }
+}
+
+/// Where an import goes.
+#[derive(Debug, PartialEq)]
+enum ImportSite {
+    /// Already imported, at this 1-based line.
+    Present(usize),
+    /// Replace 0-based line `at` with `content` — the line plus the import.
+    Splice { at: usize, content: String },
+}
+
+fn import_site(lines: &[&str], statement: &str, lang: Lang) -> ImportSite {
+    if let Some(i) = lines.iter().position(|l| l.trim_end() == statement) {
+        return ImportSite::Present(i + 1);
+    }
+    let items = import_items(lines, lang);
+
+    // Go: a single import joins the parenthesized block, one spec per line
+    if lang == Lang::Go {
+        let spec = statement.strip_prefix("import ").map(str::trim);
+        let block = items.iter().find(|&&(s, _)| lines[s].trim() == "import (");
+        if let (Some(spec), Some(&(start, end))) = (spec, block) {
+            if let Some(i) = (start + 1..end).find(|&i| lines[i].trim() == spec) {
+                return ImportSite::Present(i + 1);
+            }
+            let at = (start + 1..end)
+                .find(|&i| !lines[i].trim().is_empty() && lines[i].trim() > spec)
+                .unwrap_or(end);
+            return before(lines, at, &format!("\t{spec}"));
+        }
+    }
+
+    // Runs of adjacent imports; blank lines separate groups
+    let mut groups: Vec<Vec<(usize, usize)>> = Vec::new();
+    for &item in &items {
+        match groups.last_mut() {
+            Some(group) if group.last().is_some_and(|&(_, end)| end + 1 == item.0) => {
+                group.push(item);
+            }
+            _ => groups.push(vec![item]),
+        }
+    }
+    let shared = |group: &[(usize, usize)]| {
+        group
+            .iter()
+            .map(|&(start, _)| {
+                lines[start]
+                    .bytes()
+                    .zip(statement.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            })
+            .max()
+            .unwrap_or(0)
+    };
+    let best = groups
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, group)| (shared(group), std::cmp::Reverse(i)))
+        .map(|(_, group)| group);
+    if let Some(group) = best {
+        return match group
+            .iter()
+            .find(|&&(s, _)| lines[s].trim_end() > statement)
+        {
+            Some(&(start, _)) => before(lines, start, statement),
+            None => after(lines, group[group.len() - 1].1, statement),
+        };
+    }
+
+    // No imports yet: after leading comments, docstring and package clause
+    let mut at = 0;
+    let mut in_docstring = false;
+    while at < lines.len() {
+        let trimmed = lines[at].trim();
+        let quotes =
+            lang == Lang::Python && (trimmed.starts_with("\"\"\"") || trimmed.starts_with("'''"));
+        if in_docstring {
+            in_docstring = !(trimmed.ends_with("\"\"\"") || trimmed.ends_with("'''"));
+        } else if quotes {
+            in_docstring =
+                trimmed.len() < 6 || !(trimmed.ends_with("\"\"\"") || trimmed.ends_with("'''"));
+        } else if !is_preamble(trimmed, lang) {
+            break;
+        }
+        at += 1;
+    }
+    if at == lines.len() {
+        let gap = if lines[at - 1].trim().is_empty() {
+            ""
+        } else {
+            "\n"
+        };
+        return after(lines, at - 1, &format!("{gap}{statement}"));
+    }
+    let gap = if at > 0 && !lines[at - 1].trim().is_empty() {
+        "\n"
+    } else {
+        ""
+    };
+    before(lines, at, &format!("{gap}{statement}\n"))
+}
+
+fn before(lines: &[&str], at: usize, text: &str) -> ImportSite {
+    ImportSite::Splice {
+        at,
+        content: format!("{text}\n{}", lines[at]),
+    }
+}
+
+fn after(lines: &[&str], at: usize, text: &str) -> ImportSite {
+    ImportSite::Splice {
+        at,
+        content: format!("{}\n{text}", lines[at]),
+    }
+}
+
+/// Top-level import statements as 0-based inclusive line ranges. A statement
+/// runs on until its brackets close — `use a::{\n b,\n};`, Go's `import (`.
+fn import_items(lines: &[&str], lang: Lang) -> Vec<(usize, usize)> {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].starts_with(char::is_whitespace) || !is_import_line(lines[i], lang) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut depth = 0i32;
+        loop {
+            depth += lines[i]
+                .chars()
+                .map(|c| match c {
+                    '{' | '(' | '[' => 1,
+                    '}' | ')' | ']' => -1,
+                    _ => 0,
+                })
+                .sum::<i32>();
+            if depth <= 0 || i + 1 == lines.len() {
+                break;
+            }
+            i += 1;
+        }
+        items.push((start, i));
+        i += 1;
+    }
+    items
+}
+
+/// Lines that stay above a file's first import.
+fn is_preamble(trimmed: &str, lang: Lang) -> bool {
+    trimmed.is_empty()
+        || trimmed.starts_with("//")
+        || trimmed.starts_with("/*")
+        || trimmed.starts_with('*')
+        || trimmed.starts_with("#!")
+        || (lang == Lang::Python && trimmed.starts_with('#'))
+        || (matches!(lang, Lang::Go | Lang::Java | Lang::Kotlin | Lang::Scala)
+            && trimmed.starts_with("package "))
+}
+
+fn read(path: &Path) -> Result<String, TilthError> {
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: None,
+        },
+        std::io::ErrorKind::PermissionDenied => TilthError::PermissionDenied {
+            path: path.to_path_buf(),
+        },
+        _ => TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })
+}
+
+/// Split `content` into lines, each with the ending that followed it —
+/// `"\r\n"`, `"\n"`, or `""` for a last line without one. Same lines as
+/// [`str::lines`].
+fn split_endings(content: &str) -> Vec<(&str, &str)> {
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if let Some(text) = line.strip_suffix("\r\n") {
+                (text, "\r\n")
+            } else if let Some(text) = line.strip_suffix('\n') {
+                (text, "\n")
+            } else {
+                (line, "")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor(line: usize, text: &str, content: &str) -> Edit {
+        let hash = format::line_hash(text.as_bytes());
+        Edit {
+            start_line: line,
+            start_hash: hash,
+            end_line: line,
+            end_hash: hash,
+            content: content.to_string(),
+        }
+    }
+
+    fn edit_file(name: &str, original: &[u8], edits: &[Edit]) -> Vec<u8> {
+        let dir = std::env::temp_dir().join("tilth_test_edit_endings");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join(name);
+        fs::write(&path, original).unwrap();
+        let result = apply_edits(&path, edits).unwrap();
+        assert!(matches!(result, EditResult::Applied(_)), "{result:?}");
+        let written = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        written
+    }
+
+    #[test]
+    fn crlf_file_stays_crlf() {
+        let out = edit_file(
+            "crlf.txt",
+            b"one\r\ntwo\r\nthree\r\n",
+            &[anchor(2, "two", "2a\n2b")],
+        );
+        assert_eq!(out, b"one\r\n2a\r\n2b\r\nthree\r\n");
+    }
+
+    #[test]
+    fn mixed_endings_untouched_lines_keep_theirs() {
+        let out = edit_file("mixed.txt", b"a\r\nb\nc\r\nd\r\ne", &[anchor(3, "c", "C")]);
+        assert_eq!(out, b"a\r\nb\nC\r\nd\r\ne");
+    }
+
+    #[test]
+    fn bom_is_kept_and_not_hashed() {
+        let out = edit_file(
+            "bom.txt",
+            b"\xEF\xBB\xBFfirst\r\nsecond\r\n",
+            &[anchor(1, "first", "FIRST")],
+        );
+        assert_eq!(out, b"\xEF\xBB\xBFFIRST\r\nsecond\r\n");
+    }
+
+    #[test]
+    fn symbol_edit_replaces_whole_definition() {
+        let dir = std::env::temp_dir().join("tilth_test_symbol_edit");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("lib.rs");
+        let original = "use std::fmt;\r\n\r\nfn greet() {\r\n    println!(\"hi\");\r\n}\r\n\r\nfn other() {}\r\n";
+        fs::write(&path, original).unwrap();
+
+        let stale = SymbolEdit {
+            symbol: "greet".into(),
+            hash: 0,
+            content: String::new(),
+        };
+        let result = apply_symbol_edit(&path, &stale).unwrap();
+        assert!(matches!(result, EditResult::HashMismatch(_)), "{result:?}");
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+
+        let edit = SymbolEdit {
+            symbol: "greet".into(),
+            hash: format::body_hash(&["fn greet() {", "    println!(\"hi\");", "}"]),
+            content: "fn greet(name: &str) {\n    println!(\"hi {name}\");\n}".into(),
+        };
+        let result = apply_symbol_edit(&path, &edit).unwrap();
+        assert!(matches!(result, EditResult::Applied(_)), "{result:?}");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "use std::fmt;\r\n\r\nfn greet(name: &str) {\r\n    println!(\"hi {name}\");\r\n}\r\n\r\nfn other() {}\r\n"
+        );
+
+        let missing = SymbolEdit {
+            symbol: "absent".into(),
+            ..edit
+        };
+        assert!(apply_symbol_edit(&path, &missing).is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    fn place(source: &str, statement: &str, lang: Lang) -> String {
+        let lines: Vec<&str> = source.lines().collect();
+        match import_site(&lines, statement, lang) {
+            ImportSite::Present(line) => format!("present at {line}"),
+            ImportSite::Splice { at, content } => {
+                let mut out: Vec<&str> = lines[..at].to_vec();
+                out.extend(content.lines());
+                out.extend(&lines[at + 1..]);
+                out.join("\n")
+            }
+        }
+    }
+
+    #[test]
+    fn import_joins_matching_group_sorted() {
+        let source = "use std::fs;\nuse std::path::Path;\n\nuse crate::error::TilthError;\nuse crate::types::Lang;\n\nfn main() {}";
+        assert_eq!(
+            place(source, "use crate::format;", Lang::Rust),
+            "use std::fs;\nuse std::path::Path;\n\nuse crate::error::TilthError;\nuse crate::format;\nuse crate::types::Lang;\n\nfn main() {}"
+        );
+        assert_eq!(
+            place(source, "use std::io;", Lang::Rust),
+            "use std::fs;\nuse std::io;\nuse std::path::Path;\n\nuse crate::error::TilthError;\nuse crate::types::Lang;\n\nfn main() {}"
+        );
+        assert_eq!(place(source, "use std::fs;", Lang::Rust), "present at 1");
+    }
+
+    #[test]
+    fn import_after_multiline_statement() {
+        let source = "use crate::a::{\n    B,\n    C,\n};\n\nfn f() {}";
+        assert_eq!(
+            place(source, "use crate::z;", Lang::Rust),
+            "use crate::a::{\n    B,\n    C,\n};\nuse crate::z;\n\nfn f() {}"
+        );
+    }
+
+    #[test]
+    fn go_import_joins_block() {
+        let source = "package main\n\nimport (\n\t\"fmt\"\n\t\"strings\"\n)\n\nfunc main() {}";
+        assert_eq!(
+            place(source, "import \"os\"", Lang::Go),
+            "package main\n\nimport (\n\t\"fmt\"\n\t\"os\"\n\t\"strings\"\n)\n\nfunc main() {}"
+        );
+        assert_eq!(place(source, "import \"fmt\"", Lang::Go), "present at 4");
+    }
+
+    #[test]
+    fn first_import_goes_below_preamble() {
+        assert_eq!(
+            place(
+                "\"\"\"Module doc.\n\nMore.\n\"\"\"\n\ndef f():\n    pass",
+                "import os",
+                Lang::Python
+            ),
+            "\"\"\"Module doc.\n\nMore.\n\"\"\"\n\nimport os\n\ndef f():\n    pass"
+        );
+        assert_eq!(
+            place("package main\nfunc main() {}", "import \"os\"", Lang::Go),
+            "package main\n\nimport \"os\"\n\nfunc main() {}"
+        );
+    }
+
+    #[test]
+    fn generated_and_binary_files_are_refused() {
+        let dir = std::env::temp_dir().join("tilth_test_check_editable");
+        let _ = fs::create_dir_all(&dir);
+        let cases: [(&str, &[u8], bool); 4] = [
+            ("Cargo.lock", b"version = 3\n", false),
+            ("gen.rs", b"// @generated by build.rs\nfn f() {}\n", false),
+            ("blob.bin", b"\x00\x01\x02", false),
+            ("main.rs", b"fn main() {}\n", true),
+        ];
+        for (name, content, editable) in cases {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            assert_eq!(check_editable(&path).is_ok(), editable, "{name}");
+            let _ = fs::remove_file(&path);
+        }
+        assert!(check_editable(&dir.join("missing.rs")).is_ok());
+    }
+
+    #[test]
+    fn conflicted_files_are_refused_with_hunks() {
+        let dir = std::env::temp_dir().join("tilth_test_conflicts");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("merge.rs");
+        fs::write(
+            &path,
+            "fn a() {}\n<<<<<<< HEAD\nfn b() {}\n=======\nfn b2() {}\n>>>>>>> topic\n",
+        )
+        .unwrap();
+        let err = check_editable(&path).unwrap_err().to_string();
+        assert!(err.contains("1 hunk"), "{err}");
+        assert!(err.contains("|<<<<<<< HEAD"), "{err}");
+        assert!(!err.contains("fn a()"), "{err}");
+
+        // Markers without a separator are just text
+        fs::write(&path, "<<<<<<< not a conflict\n").unwrap();
+        assert!(check_editable(&path).is_ok());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn split_endings_matches_lines() {
+        for content in ["a\r\nb\n", "a\n\nb", "", "x\r\n"] {
+            let split: Vec<&str> = split_endings(content).iter().map(|l| l.0).collect();
+            assert_eq!(split, content.lines().collect::<Vec<_>>(), "{content:?}");
+        }
+    }
+}
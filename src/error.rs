@@ -22,6 +22,47 @@ pub enum TilthError {
         path: PathBuf,
         reason: String,
     },
+    /// `path` lies outside the directory an operation is confined to.
+    OutOfScope {
+        path: PathBuf,
+        scope: PathBuf,
+    },
+    /// `path` matches a redaction rule in `tilth.toml`.
+    Redacted {
+        path: PathBuf,
+    },
+}
+
+/// Stable category of a failure, for clients that branch on the kind of
+/// error rather than its wording. [`ErrorCode::as_str`] is the wire form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    NotFound,
+    HashMismatch,
+    OutOfScope,
+    Timeout,
+    TooLarge,
+    InvalidQuery,
+    PermissionDenied,
+    Io,
+    Parse,
+}
+
+impl ErrorCode {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NotFound => "NOT_FOUND",
+            Self::HashMismatch => "HASH_MISMATCH",
+            Self::OutOfScope => "OUT_OF_SCOPE",
+            Self::Timeout => "TIMEOUT",
+            Self::TooLarge => "TOO_LARGE",
+            Self::InvalidQuery => "INVALID_QUERY",
+            Self::PermissionDenied => "PERMISSION_DENIED",
+            Self::Io => "IO_ERROR",
+            Self::Parse => "PARSE_ERROR",
+        }
+    }
 }
 
 impl std::fmt::Display for TilthError {
@@ -46,6 +87,12 @@ impl std::fmt::Display for TilthError {
             Self::ParseError { path, reason } => {
                 write!(f, "parse error in {}: {reason}", path.display())
             }
+            Self::OutOfScope { path, scope } => {
+                write!(f, "{} is outside {}", path.display(), scope.display())
+            }
+            Self::Redacted { path } => {
+                write!(f, "{} [redacted by tilth.toml]", path.display())
+            }
         }
     }
 }
@@ -58,8 +105,24 @@ impl TilthError {
     pub fn exit_code(&self) -> i32 {
         match self {
             Self::NotFound { .. } | Self::IoError { .. } => 2,
-            Self::InvalidQuery { .. } | Self::ParseError { .. } => 3,
-            Self::PermissionDenied { .. } => 4,
+            Self::InvalidQuery { .. } | Self::ParseError { .. } | Self::OutOfScope { .. } => 3,
+            Self::PermissionDenied { .. } | Self::Redacted { .. } => 4,
+        }
+    }
+
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotFound { .. } => ErrorCode::NotFound,
+            Self::PermissionDenied { .. } | Self::Redacted { .. } => ErrorCode::PermissionDenied,
+            Self::InvalidQuery { .. } => ErrorCode::InvalidQuery,
+            Self::IoError { source, .. } => match source.kind() {
+                std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+                std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+                _ => ErrorCode::Io,
+            },
+            Self::ParseError { .. } => ErrorCode::Parse,
+            Self::OutOfScope { .. } => ErrorCode::OutOfScope,
         }
     }
 }
@@ -1,23 +1,26 @@
-use std::fmt::Write;
+use std::borrow::Cow;
+use std::fmt::{self, Write};
 use std::path::Path;
 
-use crate::types::{estimate_tokens, ViewMode};
-
 /// Build the standard header line:
 /// `# path/to/file.ts (N lines, ~X.Xk tokens) [mode]`
-pub fn file_header(path: &Path, byte_len: u64, line_count: u32, mode: ViewMode) -> String {
-    let tokens = estimate_tokens(byte_len);
-    let token_str = if tokens >= 1000 {
-        format!("~{}.{}k tokens", tokens / 1000, (tokens % 1000) / 100)
-    } else {
-        format!("~{tokens} tokens")
-    };
+pub fn file_header(path: &Path, tokens: u64, line_count: u32, mode: impl fmt::Display) -> String {
     format!(
-        "# {} ({line_count} lines, {token_str}) [{mode}]",
-        path.display()
+        "# {} ({line_count} lines, ~{}) [{mode}]",
+        path.display(),
+        token_count(tokens)
     )
 }
 
+/// `N tokens` below a thousand, `X.Yk tokens` from there.
+pub fn token_count(tokens: u64) -> String {
+    if tokens >= 1000 {
+        format!("{}.{}k tokens", tokens / 1000, (tokens % 1000) / 100)
+    } else {
+        format!("{tokens} tokens")
+    }
+}
+
 /// Build header for binary files: `# path (binary, size, mime) [skipped]`
 pub fn binary_header(path: &Path, byte_len: u64, mime: &str) -> String {
     let size_str = format_size(byte_len);
@@ -68,6 +71,28 @@ pub fn number_lines(content: &str, start: u32) -> String {
     out
 }
 
+// ---------------------------------------------------------------------------
+// Line endings and byte order marks
+// ---------------------------------------------------------------------------
+
+/// UTF-8 byte order mark, as some Windows editors write it. Not content:
+/// hashes, outlines and heading matches see the text after it.
+pub(crate) const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// `buf` without a leading byte order mark.
+pub(crate) fn strip_bom(buf: &[u8]) -> &[u8] {
+    buf.strip_prefix(BOM).unwrap_or(buf)
+}
+
+/// `content` with `\r\n` line endings as `\n`. Borrowed when there are none.
+pub(crate) fn normalize_newlines(content: &str) -> Cow<'_, str> {
+    if content.contains("\r\n") {
+        Cow::Owned(content.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Hashline support (edit mode)
 // ---------------------------------------------------------------------------
@@ -83,6 +108,12 @@ pub(crate) fn line_hash(bytes: &[u8]) -> u16 {
     (h & 0xFFF) as u16
 }
 
+/// Hash of a whole definition, as [`line_hash`] over its lines joined with
+/// `\n`. A symbol-addressed edit checks it against the definition it finds.
+pub(crate) fn body_hash(lines: &[&str]) -> u16 {
+    line_hash(lines.join("\n").as_bytes())
+}
+
 /// Format lines with hashline anchors: `{line}:{hash}|{content}`
 /// Used in edit mode so the agent can reference lines by content hash.
 pub fn hashlines(content: &str, start: u32) -> String {
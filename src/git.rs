@@ -0,0 +1,108 @@
+//! Committing edited files with the `git` command line, for edits made with a
+//! `commit` message. Only the edited file goes into the commit — whatever
+//! else is staged or modified is left as it was.
+
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::TilthError;
+
+/// Commit the current content of `path` alone with `message`, returning the
+/// new commit's short hash. A file git doesn't track yet is added first.
+pub fn commit_file(path: &Path, message: &str) -> Result<String, TilthError> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let Some(name) = path.file_name() else {
+        return Err(TilthError::InvalidQuery {
+            query: path.display().to_string(),
+            reason: "not a file".into(),
+        });
+    };
+
+    let git = |args: &[&std::ffi::OsStr]| -> Result<String, TilthError> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map_err(|e| TilthError::IoError {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(TilthError::InvalidQuery {
+                query: message.to_string(),
+                reason: format!(
+                    "git {}: {}",
+                    args.first()
+                        .map_or(String::new(), |a| a.to_string_lossy().into_owned()),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            })
+        }
+    };
+
+    git(&["add".as_ref(), "--".as_ref(), name])?;
+    git(&[
+        "commit".as_ref(),
+        "--only".as_ref(),
+        "--quiet".as_ref(),
+        "-m".as_ref(),
+        message.as_ref(),
+        "--".as_ref(),
+        name,
+    ])?;
+    git(&["rev-parse".as_ref(), "--short".as_ref(), "HEAD".as_ref()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn commits_only_the_edited_file() {
+        let dir = std::env::temp_dir().join("tilth_test_git_commit");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let run = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(args)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !run(&["init", "--quiet"]) {
+            return; // no git on this machine
+        }
+        run(&["config", "user.name", "tilth"]);
+        run(&["config", "user.email", "tilth@example.com"]);
+        fs::write(dir.join("edited.rs"), "fn a() {}\n").unwrap();
+        fs::write(dir.join("other.rs"), "fn b() {}\n").unwrap();
+        run(&["add", "other.rs"]);
+
+        let hash = commit_file(&dir.join("edited.rs"), "Edit a").unwrap();
+        assert!(!hash.is_empty());
+        let show = Command::new("git")
+            .arg("-C")
+            .arg(&dir)
+            .args(["show", "--name-only", "--format=%s", "HEAD"])
+            .output()
+            .unwrap();
+        let show = String::from_utf8_lossy(&show.stdout);
+        assert!(show.starts_with("Edit a"), "{show}");
+        assert!(
+            show.contains("edited.rs") && !show.contains("other.rs"),
+            "{show}"
+        );
+
+        // Nothing changed since: git refuses an empty commit
+        assert!(commit_file(&dir.join("edited.rs"), "Again").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
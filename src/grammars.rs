@@ -0,0 +1,319 @@
+//! Extra tree-sitter grammars, per the `[[grammars]]` entries of
+//! `tilth.toml`: a grammar built as a shared library (`tree-sitter build`)
+//! parses the extensions it lists, and the node kinds under `definitions`
+//! become outline entries and symbol-search definitions — support for a
+//! language tilth doesn't ship, without rebuilding it.
+//!
+//! Loading a library runs its code, and `tilth.toml` comes with whatever
+//! repository is open, so nothing loads unless the process opted in with
+//! [`allow`] (`--load-grammars`). Grammars load once per process, from the
+//! `tilth.toml` present when a file of a listed extension is first read. One
+//! that isn't allowed or fails to load says why at the top of its files'
+//! outlines. Wasm grammars aren't supported.
+
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use tree_sitter::{Language, Node};
+use tree_sitter_language::LanguageFn;
+
+use crate::config::GrammarSpec;
+use crate::read::outline::buildfile::{self, BuildSymbol};
+
+/// Definitions nested deeper than this aren't looked for.
+const MAX_DEPTH: usize = 1;
+
+struct Loaded {
+    spec: &'static GrammarSpec,
+    language: Result<Language, String>,
+}
+
+/// Set by [`allow`]: whether grammar libraries may be loaded.
+static ALLOWED: AtomicBool = AtomicBool::new(false);
+static SPECS: OnceLock<Vec<GrammarSpec>> = OnceLock::new();
+static LOADED: OnceLock<Vec<Loaded>> = OnceLock::new();
+
+/// Lets this process load the grammar libraries `tilth.toml` lists. Call it
+/// before the first read; grammars don't load again once they have been
+/// looked for.
+pub fn allow() {
+    ALLOWED.store(true, Ordering::Relaxed);
+}
+
+/// A loaded grammar and the node kinds its definitions have.
+pub(crate) struct Grammar<'g> {
+    name: &'g str,
+    language: &'g Language,
+    definitions: &'g [String],
+}
+
+/// A definition the grammar found.
+struct Definition<'a> {
+    depth: usize,
+    name: Option<&'a str>,
+    kind: &'static str,
+    line: u32,
+    end_line: u32,
+}
+
+fn specs() -> &'static [GrammarSpec] {
+    SPECS.get_or_init(|| crate::config::grammars().unwrap_or_default())
+}
+
+fn loaded() -> &'static [Loaded] {
+    LOADED.get_or_init(|| {
+        specs()
+            .iter()
+            .map(|spec| Loaded {
+                spec,
+                language: load(spec),
+            })
+            .collect()
+    })
+}
+
+/// Feeds the configured grammars to `state`, for caches whose outlines
+/// depend on them: whether they may load, their specs and when each library
+/// was built. Loads nothing.
+pub(crate) fn fingerprint(state: &mut impl Hasher) {
+    ALLOWED.load(Ordering::Relaxed).hash(state);
+    for spec in specs() {
+        spec.hash(state);
+        library_path(spec)
+            .and_then(|path| std::fs::metadata(path)?.modified())
+            .ok()
+            .hash(state);
+    }
+}
+
+/// Where `spec`'s library is: its path, relative to the working directory.
+fn library_path(spec: &GrammarSpec) -> std::io::Result<PathBuf> {
+    Ok(std::env::current_dir()?.join(&spec.library))
+}
+
+/// The configured grammar for `path`'s extension, or why it didn't load.
+/// `None` when no grammar lists the extension.
+fn for_path(path: &Path) -> Option<Result<Grammar<'static>, &'static str>> {
+    let ext = path.extension()?.to_str()?;
+    let entry = loaded()
+        .iter()
+        .find(|l| l.spec.extensions.iter().any(|e| e == ext))?;
+    Some(match &entry.language {
+        Ok(language) => Ok(Grammar {
+            name: &entry.spec.name,
+            language,
+            definitions: &entry.spec.definitions,
+        }),
+        Err(e) => Err(e.as_str()),
+    })
+}
+
+fn load(spec: &GrammarSpec) -> Result<Language, String> {
+    let fail = |reason: String| format!("grammar {} didn't load: {reason}", spec.name);
+    if !ALLOWED.load(Ordering::Relaxed) {
+        return Err(fail(
+            "loading a grammar runs the library's code — start tilth with --load-grammars to allow it"
+                .into(),
+        ));
+    }
+    if spec.library.extension().is_some_and(|e| e == "wasm") {
+        return Err(fail(
+            "wasm grammars aren't supported — build a shared library with `tree-sitter build`"
+                .into(),
+        ));
+    }
+    let path = library_path(spec).map_err(|e| fail(e.to_string()))?;
+    // SAFETY: loading runs the library's initializers; it is one the
+    // workspace's own config names, and the process opted in with `allow`.
+    let library = unsafe { libloading::Library::new(&path) }
+        .map_err(|e| fail(format!("{}: {e}", path.display())))?;
+    // SAFETY: the symbol is a tree-sitter language function, which takes
+    // nothing and returns a pointer to static language data.
+    let language = unsafe {
+        let function = library
+            .get::<unsafe extern "C" fn() -> *const ()>(spec.symbol.as_bytes())
+            .map_err(|e| fail(format!("{}: {e}", spec.symbol)))?;
+        LanguageFn::from_raw(*function)
+    };
+    // The language data lives in the library: keep it loaded for good.
+    std::mem::forget(library);
+    let language = Language::new(language);
+    tree_sitter::Parser::new()
+        .set_language(&language)
+        .map_err(|e| fail(e.to_string()))?;
+    Ok(language)
+}
+
+/// The outline of a file a configured grammar parses; `None` for other
+/// files, `Err` with the reason when its grammar didn't load.
+pub(crate) fn outline(
+    path: &Path,
+    content: &str,
+    max_lines: usize,
+) -> Option<Result<String, String>> {
+    Some(match for_path(path)? {
+        Ok(grammar) => Ok(grammar.outline(content, max_lines)),
+        Err(e) => Err(e.to_string()),
+    })
+}
+
+/// The name and language of the configured grammar that parses `path`.
+pub(crate) fn language(path: &Path) -> Option<(&'static str, &'static Language)> {
+    let grammar = for_path(path)?.ok()?;
+    Some((grammar.name, grammar.language))
+}
+
+/// Definitions for symbol search and indexing, for files a configured
+/// grammar parses.
+pub(crate) fn symbols<'a>(path: &Path, content: &'a str) -> Option<Vec<BuildSymbol<'a>>> {
+    let grammar = for_path(path)?.ok()?;
+    Some(
+        grammar
+            .definitions(content)
+            .into_iter()
+            .filter_map(|d| {
+                Some(BuildSymbol {
+                    name: d.name?,
+                    kind: d.kind,
+                    line: d.line,
+                    end_line: d.end_line,
+                })
+            })
+            .collect(),
+    )
+}
+
+impl Grammar<'_> {
+    fn definitions<'a>(&self, content: &'a str) -> Vec<Definition<'a>> {
+        let mut found = Vec::new();
+        if let Some(tree) = crate::parser_pool::parse(content, self.language) {
+            self.walk(tree.root_node(), content, 0, &mut found);
+        }
+        found
+    }
+
+    fn walk<'a>(
+        &self,
+        node: Node,
+        content: &'a str,
+        depth: usize,
+        found: &mut Vec<Definition<'a>>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if self.definitions.iter().any(|k| k == child.kind()) {
+                found.push(Definition {
+                    depth,
+                    name: name(child, content),
+                    kind: child.kind(),
+                    line: child.start_position().row as u32 + 1,
+                    end_line: child.end_position().row as u32 + 1,
+                });
+                if depth < MAX_DEPTH {
+                    self.walk(child, content, depth + 1, found);
+                }
+            } else {
+                self.walk(child, content, depth, found);
+            }
+        }
+    }
+
+    fn outline(&self, content: &str, max_lines: usize) -> String {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut out = String::new();
+        for def in self.definitions(content).iter().take(max_lines) {
+            // Unnamed definitions — `describe("...")` calls and the like —
+            // show their first line.
+            let label = def.name.map_or_else(
+                || {
+                    lines
+                        .get(def.line as usize - 1)
+                        .map_or("", |l| l.trim())
+                        .to_string()
+                },
+                str::to_string,
+            );
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&buildfile::entry(
+                def.depth,
+                def.line,
+                def.end_line,
+                def.kind,
+                &label,
+            ));
+        }
+        out
+    }
+}
+
+/// A definition's name: its `name` field, else its first identifier.
+fn name<'a>(node: Node, content: &'a str) -> Option<&'a str> {
+    let named = node.child_by_field_name("name").or_else(|| {
+        let mut cursor = node.walk();
+        let first = node
+            .named_children(&mut cursor)
+            .find(|c| c.kind().ends_with("identifier") || c.kind() == "name");
+        first
+    })?;
+    content.get(named.byte_range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "struct Point {\n    x: i32,\n}\n\nimpl Point {\n    fn norm(&self) -> i32 {\n        self.x\n    }\n}\n";
+
+    fn with_grammar<T>(kinds: &[&str], f: impl FnOnce(&Grammar) -> T) -> T {
+        let language: Language = tree_sitter_rust::LANGUAGE.into();
+        let definitions: Vec<String> = kinds.iter().map(|k| (*k).to_string()).collect();
+        f(&Grammar {
+            name: "rust",
+            language: &language,
+            definitions: &definitions,
+        })
+    }
+
+    #[test]
+    fn listed_kinds_are_definitions() {
+        let found = with_grammar(&["struct_item", "function_item"], |g| {
+            g.definitions(SOURCE)
+                .into_iter()
+                .map(|d| (d.name, d.kind, d.line, d.end_line))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            found,
+            vec![
+                (Some("Point"), "struct_item", 1, 3),
+                (Some("norm"), "function_item", 6, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn outline_nests_one_level() {
+        let shown = with_grammar(&["impl_item", "function_item"], |g| g.outline(SOURCE, 100));
+        assert_eq!(
+            shown,
+            "[5-9]        impl_item Point\n  [6-8]        function_item norm"
+        );
+    }
+
+    #[test]
+    fn libraries_load_only_when_allowed() {
+        let spec = GrammarSpec {
+            name: "zig".into(),
+            library: PathBuf::from("grammars/libtree-sitter-zig.so"),
+            symbol: "tree_sitter_zig".into(),
+            extensions: vec!["zig".into()],
+            definitions: vec!["function_declaration".into()],
+        };
+        let refused = load(&spec).unwrap_err();
+        assert!(refused.contains("--load-grammars"), "{refused}");
+    }
+}
@@ -14,6 +14,9 @@ use std::time::SystemTime;
 
 use dashmap::DashMap;
 
+use crate::cache::HitStats;
+use crate::types::{FileType, Lang};
+
 // ---------------------------------------------------------------------------
 // BloomFilter
 // ---------------------------------------------------------------------------
@@ -137,6 +140,7 @@ fn hash_with_seed(item: &str, seed: u64) -> u64 {
 /// by mtime. Stale entries are automatically rebuilt on access.
 pub struct BloomFilterCache {
     filters: DashMap<PathBuf, (BloomFilter, SystemTime)>,
+    stats: HitStats,
 }
 
 impl Default for BloomFilterCache {
@@ -151,9 +155,16 @@ impl BloomFilterCache {
     pub fn new() -> Self {
         Self {
             filters: DashMap::new(),
+            stats: HitStats::default(),
         }
     }
 
+    /// Filter reuse: hits are checks answered by an already-built filter.
+    #[must_use]
+    pub fn stats(&self) -> &HitStats {
+        &self.stats
+    }
+
     /// Check if `symbol` might appear in the file at `path`.
     ///
     /// - If a cached filter exists with matching `mtime`, queries it directly.
@@ -167,16 +178,38 @@ impl BloomFilterCache {
         if let Some(entry) = self.filters.get(path) {
             let (ref filter, cached_mtime) = *entry;
             if cached_mtime == mtime {
+                self.stats.record(true);
                 return filter.contains(symbol);
             }
         }
 
         // Cache miss or stale: build and cache a new filter
+        self.stats.record(false);
         let filter = build_filter(content);
         let result = filter.contains(symbol);
         self.filters.insert(path.to_path_buf(), (filter, mtime));
         result
     }
+
+    /// Build the filter for `path` ahead of any check, unless one for this
+    /// `mtime` is already cached.
+    pub fn prime(&self, path: &Path, mtime: SystemTime, content: &str) {
+        if self.filters.get(path).is_some_and(|entry| entry.1 == mtime) {
+            return;
+        }
+        self.filters
+            .insert(path.to_path_buf(), (build_filter(content), mtime));
+    }
+
+    /// Drop the filters of `path` and of every file under it.
+    pub fn forget(&self, path: &Path) {
+        self.filters.retain(|file, _| !file.starts_with(path));
+    }
+
+    /// Whether a filter for `path` is cached, current or not.
+    pub(crate) fn has(&self, path: &Path) -> bool {
+        self.filters.contains_key(path)
+    }
 }
 
 /// Build a Bloom filter from file content by extracting all identifiers.
@@ -207,6 +240,94 @@ fn extract_identifiers(content: &str) -> impl Iterator<Item = &str> {
     IdentifierIter::new(content)
 }
 
+/// Comment and quote rules for the scanner. The Bloom filter always scans
+/// with `CFamily`; content search picks one per file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Syntax {
+    /// `//` and `/* */` comments; `"`, `'` and backtick strings.
+    CFamily,
+    /// As `CFamily`, except `'a` is a lifetime or label unless it closes as
+    /// a char literal.
+    Rust,
+    /// `#` line comments — Python, Ruby, shell, Make, YAML, TOML.
+    Hash,
+}
+
+impl Syntax {
+    /// Rules for `path` by language or extension. `None` for prose and data
+    /// formats without comments.
+    pub(crate) fn for_path(path: &Path) -> Option<Self> {
+        match crate::read::detect_file_type(path) {
+            FileType::Code(Lang::Rust) => Some(Self::Rust),
+            FileType::Code(Lang::Python | Lang::Ruby | Lang::Dockerfile | Lang::Make) => {
+                Some(Self::Hash)
+            }
+            FileType::Code(_) => Some(Self::CFamily),
+            _ => match path.extension().and_then(|e| e.to_str()) {
+                Some("sh" | "bash" | "zsh" | "yaml" | "yml" | "toml" | "pl" | "r") => {
+                    Some(Self::Hash)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// What the scanner makes of a byte: code, comment or string literal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Region {
+    Code,
+    Comment,
+    String,
+}
+
+/// The region of each byte offset in `offsets` (ascending) of `content`.
+/// Same approximations as the identifier scan: no nesting, no raw strings,
+/// no heredocs.
+pub(crate) fn regions(content: &str, syntax: Syntax, offsets: &[usize]) -> Vec<Region> {
+    let mut scan = IdentifierIter::with_syntax(content, syntax);
+    offsets
+        .iter()
+        .map(|&offset| {
+            while scan.pos < offset.min(scan.bytes.len()) {
+                let _ = scan.step();
+            }
+            match scan.state {
+                ScanState::Code => Region::Code,
+                ScanState::LineComment | ScanState::BlockComment => Region::Comment,
+                ScanState::StringDouble | ScanState::StringSingle | ScanState::StringBacktick => {
+                    Region::String
+                }
+            }
+        })
+        .collect()
+}
+
+/// String literals in `content` as `(offset of the opening quote, contents)`.
+/// Contents are raw source — escapes are left as written.
+pub(crate) fn string_literals(content: &str, syntax: Syntax) -> Vec<(usize, &str)> {
+    let mut scan = IdentifierIter::with_syntax(content, syntax);
+    let mut out = Vec::new();
+    let mut open = None;
+    while scan.pos < scan.bytes.len() {
+        let at = scan.pos;
+        let _ = scan.step();
+        let in_string = matches!(
+            scan.state,
+            ScanState::StringDouble | ScanState::StringSingle | ScanState::StringBacktick
+        );
+        match (open, in_string) {
+            (None, true) => open = Some(at),
+            (Some(start), false) => {
+                out.push((start, &content[start + 1..scan.pos - 1]));
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
 /// States for the identifier extraction state machine.
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum ScanState {
@@ -229,132 +350,170 @@ struct IdentifierIter<'a> {
     src: &'a str,
     pos: usize,
     state: ScanState,
+    syntax: Syntax,
 }
 
 impl<'a> IdentifierIter<'a> {
     fn new(content: &'a str) -> Self {
+        Self::with_syntax(content, Syntax::CFamily)
+    }
+
+    fn with_syntax(content: &'a str, syntax: Syntax) -> Self {
         Self {
             bytes: content.as_bytes(),
             src: content,
             pos: 0,
             state: ScanState::Code,
+            syntax,
         }
     }
-}
-
-impl<'a> Iterator for IdentifierIter<'a> {
-    type Item = &'a str;
 
-    fn next(&mut self) -> Option<&'a str> {
+    /// Advance past one byte, escape, comment marker or identifier. Returns
+    /// the start of the identifier when one was consumed (it ends at `pos`).
+    fn step(&mut self) -> Option<usize> {
         let bytes = self.bytes;
         let len = bytes.len();
-
-        while self.pos < len {
-            let i = self.pos;
-            let b = bytes[i];
-
-            match self.state {
-                ScanState::Code => {
-                    // Check for start of string literals
-                    if b == b'"' {
-                        self.state = ScanState::StringDouble;
-                        self.pos += 1;
-                        continue;
-                    }
-                    if b == b'\'' {
+        let i = self.pos;
+        let b = bytes[i];
+
+        match self.state {
+            ScanState::Code => {
+                // Check for start of string literals
+                if b == b'"' {
+                    self.state = ScanState::StringDouble;
+                    self.pos += 1;
+                    return None;
+                }
+                if b == b'\'' {
+                    if self.syntax != Syntax::Rust || is_char_literal(bytes, i) {
                         self.state = ScanState::StringSingle;
-                        self.pos += 1;
-                        continue;
                     }
-                    if b == b'`' {
-                        self.state = ScanState::StringBacktick;
+                    self.pos += 1;
+                    return None;
+                }
+                if b == b'`' {
+                    self.state = ScanState::StringBacktick;
+                    self.pos += 1;
+                    return None;
+                }
+
+                // Check for comments
+                if self.syntax == Syntax::Hash {
+                    if b == b'#' {
+                        self.state = ScanState::LineComment;
                         self.pos += 1;
-                        continue;
+                        return None;
                     }
-
-                    // Check for comments
-                    if b == b'/' && i + 1 < len {
-                        if bytes[i + 1] == b'/' {
-                            self.state = ScanState::LineComment;
-                            self.pos += 2;
-                            continue;
-                        }
-                        if bytes[i + 1] == b'*' {
-                            self.state = ScanState::BlockComment;
-                            self.pos += 2;
-                            continue;
-                        }
+                } else if b == b'/' && i + 1 < len {
+                    if bytes[i + 1] == b'/' {
+                        self.state = ScanState::LineComment;
+                        self.pos += 2;
+                        return None;
                     }
-
-                    // Check for start of identifier
-                    if is_ident_start(b) {
-                        let start = i;
-                        self.pos += 1;
-                        while self.pos < len && is_ident_continue(bytes[self.pos]) {
-                            self.pos += 1;
-                        }
-                        // Safety: identifiers are pure ASCII, so byte slicing is valid UTF-8
-                        return Some(&self.src[start..self.pos]);
+                    if bytes[i + 1] == b'*' {
+                        self.state = ScanState::BlockComment;
+                        self.pos += 2;
+                        return None;
                     }
-
-                    self.pos += 1;
                 }
 
-                ScanState::StringDouble => {
-                    if b == b'\\' && i + 1 < len {
-                        self.pos += 2; // skip escaped character
-                    } else if b == b'"' {
-                        self.state = ScanState::Code;
-                        self.pos += 1;
-                    } else {
+                // Check for start of identifier
+                if is_ident_start(b) {
+                    self.pos += 1;
+                    while self.pos < len && is_ident_continue(bytes[self.pos]) {
                         self.pos += 1;
                     }
+                    return Some(i);
                 }
 
-                ScanState::StringSingle => {
-                    if b == b'\\' && i + 1 < len {
-                        self.pos += 2; // skip escaped character
-                    } else if b == b'\'' {
-                        self.state = ScanState::Code;
-                        self.pos += 1;
-                    } else {
-                        self.pos += 1;
-                    }
+                self.pos += 1;
+            }
+
+            ScanState::StringDouble => {
+                if b == b'\\' && i + 1 < len {
+                    self.pos += 2; // skip escaped character
+                } else if b == b'"' {
+                    self.state = ScanState::Code;
+                    self.pos += 1;
+                } else {
+                    self.pos += 1;
                 }
+            }
 
-                ScanState::StringBacktick => {
-                    if b == b'\\' && i + 1 < len {
-                        self.pos += 2;
-                    } else if b == b'`' {
-                        self.state = ScanState::Code;
-                        self.pos += 1;
-                    } else {
-                        self.pos += 1;
-                    }
+            ScanState::StringSingle => {
+                if b == b'\\' && i + 1 < len {
+                    self.pos += 2; // skip escaped character
+                } else if b == b'\'' {
+                    self.state = ScanState::Code;
+                    self.pos += 1;
+                } else {
+                    self.pos += 1;
                 }
+            }
 
-                ScanState::LineComment => {
-                    if b == b'\n' {
-                        self.state = ScanState::Code;
-                    }
+            ScanState::StringBacktick => {
+                if b == b'\\' && i + 1 < len {
+                    self.pos += 2;
+                } else if b == b'`' {
+                    self.state = ScanState::Code;
+                    self.pos += 1;
+                } else {
                     self.pos += 1;
                 }
+            }
+
+            ScanState::LineComment => {
+                if b == b'\n' {
+                    self.state = ScanState::Code;
+                }
+                self.pos += 1;
+            }
 
-                ScanState::BlockComment => {
-                    if b == b'*' && i + 1 < len && bytes[i + 1] == b'/' {
-                        self.state = ScanState::Code;
-                        self.pos += 2;
-                    } else {
-                        self.pos += 1;
-                    }
+            ScanState::BlockComment => {
+                if b == b'*' && i + 1 < len && bytes[i + 1] == b'/' {
+                    self.state = ScanState::Code;
+                    self.pos += 2;
+                } else {
+                    self.pos += 1;
                 }
             }
         }
+        None
+    }
+}
+
+impl<'a> Iterator for IdentifierIter<'a> {
+    type Item = &'a str;
 
+    fn next(&mut self) -> Option<&'a str> {
+        while self.pos < self.bytes.len() {
+            if let Some(start) = self.step() {
+                // Safety: identifiers are pure ASCII, so byte slicing is valid UTF-8
+                return Some(&self.src[start..self.pos]);
+            }
+        }
         None
     }
 }
 
+/// Rust `'` at `i` opens a char literal (`'x'`, `'\n'`, `'é'`) rather than a
+/// lifetime or loop label (`'a`, `'outer:`).
+fn is_char_literal(bytes: &[u8], i: usize) -> bool {
+    match bytes.get(i + 1) {
+        Some(b'\\') => true,
+        Some(&b) => {
+            let width = match b {
+                0..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            bytes.get(i + 1 + width) == Some(&b'\'')
+        }
+        None => false,
+    }
+}
+
 #[inline]
 fn is_ident_start(b: u8) -> bool {
     b.is_ascii_alphabetic() || b == b'_'
@@ -473,6 +632,41 @@ mod tests {
         assert!(!idents.contains(&"also_fake"));
     }
 
+    #[test]
+    fn test_regions_at_offsets() {
+        // One char per occurrence of `needle`: c(ode), #(comment), s(tring)
+        let at = |src: &str, syntax: Syntax, needle: &str| -> String {
+            let offsets: Vec<usize> = src.match_indices(needle).map(|(i, _)| i).collect();
+            regions(src, syntax, &offsets)
+                .into_iter()
+                .map(|r| match r {
+                    Region::Code => 'c',
+                    Region::Comment => '#',
+                    Region::String => 's',
+                })
+                .collect()
+        };
+
+        let rust =
+            "fn retry<'a>(x: &'a str) { // retry later\n    let c = 'r'; retry(\"retry\"); }";
+        assert_eq!(at(rust, Syntax::Rust, "retry"), "c#cs");
+        let js = "retry('a'); /* retry */ retry(`retry`)";
+        assert_eq!(at(js, Syntax::CFamily, "retry"), "c#cs");
+        let python = "x = a // retry  # retry\nretry('retry')";
+        assert_eq!(at(python, Syntax::Hash, "retry"), "c#cs");
+    }
+
+    #[test]
+    fn test_string_literals() {
+        let code = "t(\"home.title\"); // \"not this\"\nlet c = 'x'; f(`a ${b}`, \"esc\\\"q\");";
+        let literals: Vec<&str> = string_literals(code, Syntax::CFamily)
+            .into_iter()
+            .map(|(_, s)| s)
+            .collect();
+        assert_eq!(literals, vec!["home.title", "x", "a ${b}", "esc\\\"q"]);
+        assert_eq!(string_literals(code, Syntax::CFamily)[0].0, 2);
+    }
+
     #[test]
     fn test_identifier_extraction_underscores_and_numbers() {
         let code = "_private __dunder var_123 _0 a1b2c3";
@@ -1,4 +1,9 @@
 pub mod bloom;
+mod paths;
 pub mod symbol;
+mod warm;
+mod watch;
 
-pub use symbol::SymbolIndex;
+pub use symbol::{IndexConfig, SymbolIndex};
+pub use warm::warm;
+pub use watch::{watch, Watcher};
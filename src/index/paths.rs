@@ -0,0 +1,72 @@
+//! Interned file paths for the symbol index.
+//!
+//! Every indexed file gets a dense `u32` id on first sight; locations store
+//! the id instead of their own `PathBuf`. A re-indexed file keeps its id, and
+//! the id of an evicted file is never handed out again, so a stale id can only
+//! miss, never name the wrong file.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use dashmap::DashMap;
+
+/// Id of an interned path, an index into the table.
+pub(crate) type PathId = u32;
+
+/// Two-way map between file paths and [`PathId`]s, with each file's mtime
+/// when it was last indexed.
+#[derive(Default)]
+pub(crate) struct PathTable {
+    ids: DashMap<Arc<Path>, PathId>,
+    files: DashMap<PathId, (Arc<Path>, SystemTime)>,
+    next: AtomicU32,
+}
+
+impl PathTable {
+    /// Id for `path`, allocating one on first sight; records `mtime` either way.
+    pub(crate) fn intern(&self, path: &Path, mtime: SystemTime) -> PathId {
+        if let Some(id) = self.ids.get(path).map(|id| *id) {
+            if let Some(mut file) = self.files.get_mut(&id) {
+                file.1 = mtime;
+            }
+            return id;
+        }
+        let path: Arc<Path> = Arc::from(path);
+        let id = *self
+            .ids
+            .entry(Arc::clone(&path))
+            .or_insert_with(|| self.next.fetch_add(1, Ordering::Relaxed));
+        self.files.insert(id, (path, mtime));
+        id
+    }
+
+    /// Id of `path` if it has been indexed.
+    pub(crate) fn id(&self, path: &Path) -> Option<PathId> {
+        self.ids.get(path).map(|id| *id)
+    }
+
+    /// Path and last-indexed mtime for `id`.
+    pub(crate) fn get(&self, id: PathId) -> Option<(Arc<Path>, SystemTime)> {
+        self.files.get(&id).map(|file| file.clone())
+    }
+
+    /// Whether any indexed file lies under `scope`.
+    pub(crate) fn any_under(&self, scope: &Path) -> bool {
+        self.files
+            .iter()
+            .any(|file| crate::pathnorm::starts_with(&file.0, scope))
+    }
+
+    /// Forget `id` and its path.
+    pub(crate) fn remove(&self, id: PathId) {
+        if let Some((_, (path, _))) = self.files.remove(&id) {
+            self.ids.remove(&path);
+        }
+    }
+
+    pub(crate) fn file_count(&self) -> usize {
+        self.files.len()
+    }
+}
@@ -3,24 +3,67 @@
 //! Instead of walking the entire tree on every symbol query, `SymbolIndex::build()`
 //! parses all code files in scope using tree-sitter and stores (`symbol_name` -> locations)
 //! in a concurrent `DashMap`. Subsequent lookups are O(1) hash lookups plus a filter.
+//!
+//! File paths are interned (see [`super::paths`]): a location is a path id,
+//! a line and a kind, and the `PathBuf` is only rebuilt for lookup results.
+//!
+//! The index is sharded by directory: a build puts each top-level directory
+//! of its scope in a shard of its own. Lookups scoped to a package only read
+//! that package's shard, and [`SymbolIndex::evict`] drops shards wholesale.
+//!
+//! Definitions are always indexed. Usages — every identifier in the file — are
+//! opt-in through [`IndexConfig::usage_budget`], since they outnumber
+//! definitions by an order of magnitude or more.
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::SystemTime;
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 
+use super::paths::{PathId, PathTable};
+use crate::cache::HitStats;
+use crate::pathnorm;
 use crate::read::detect_file_type;
 use crate::read::outline::code::outline_language;
+use crate::read::outline::{buildfile, structured};
+use crate::search::symbol::heuristic_definition_name;
 use crate::search::treesitter::{extract_definition_name, DEFINITION_KINDS};
-use crate::types::FileType;
+use crate::types::{FileType, Lang};
 
 /// Maximum file size to index (500 KB). Matches the limit in symbol search.
 const MAX_FILE_SIZE: u64 = 500_000;
 
-/// Per-file extraction result: (path, mtime, extracted symbols).
-type FileSymbols = (PathBuf, SystemTime, Vec<(Arc<str>, u32, bool)>);
+/// One extracted symbol: (name, line, `is_definition`, kind label).
+type ExtractedSymbol = (Arc<str>, u32, bool, &'static str);
+
+/// Per-file extraction result: (path, mtime, extracted symbols, parsed cleanly).
+type FileSymbols = (PathBuf, SystemTime, Vec<ExtractedSymbol>, bool);
+
+/// Kind label for usage locations.
+const USAGE_KIND: &str = "ref";
+
+/// What an index build records beyond definitions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IndexConfig {
+    /// Record identifier occurrences (usages) too, up to this many across the
+    /// index. Past the budget, usage lookups return `None` and callers walk
+    /// the files as they would without an index. 0 — the default — records
+    /// definitions only.
+    pub usage_budget: usize,
+}
+
+/// A location as stored in the index, its file an id into the path table.
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    file: PathId,
+    line: u32,
+    is_definition: bool,
+    kind: &'static str,
+}
 
 /// A location where a symbol appears in the codebase.
 #[derive(Clone, Debug)]
@@ -28,19 +71,45 @@ pub struct SymbolLocation {
     pub path: PathBuf,
     pub line: u32,
     pub is_definition: bool,
+    /// Short kind label: `fn`, `struct`, `class`, `target`, ...
+    pub kind: &'static str,
     pub mtime: SystemTime,
 }
 
+/// The part of the index under one directory — a top-level directory of the
+/// scope a build ran over, or that scope itself for the files directly in it.
+#[derive(Default)]
+struct Shard {
+    /// `symbol_name` -> list of locations
+    symbols: DashMap<Arc<str>, Vec<Entry>>,
+}
+
 /// Pre-computed symbol-to-file index for O(1) lookups.
 ///
 /// Uses `DashMap` for lock-free concurrent reads and writes.
 /// Keys are `Arc<str>` for memory-efficient string interning — many lookups
 /// against the same symbol names benefit from shared allocations.
+///
+/// Locations are split into shards by directory. A scoped lookup only visits
+/// the shards that overlap its scope, and only checks paths in the shards
+/// that reach outside it; shards are built and evicted independently.
 pub struct SymbolIndex {
-    /// `symbol_name` -> list of locations
-    symbols: DashMap<Arc<str>, Vec<SymbolLocation>>,
-    /// file -> mtime when last indexed
-    indexed_files: DashMap<PathBuf, SystemTime>,
+    /// shard root -> shard
+    shards: DashMap<Arc<Path>, Arc<Shard>>,
+    /// file -> root of the shard holding its locations
+    file_shards: DashMap<PathId, Arc<Path>>,
+    /// Indexed files, with their mtime when last indexed
+    files: PathTable,
+    /// Files tree-sitter couldn't parse cleanly
+    unparsed: DashSet<PathId>,
+    /// Lookups that found at least one location
+    lookups: HitStats,
+    builds: AtomicUsize,
+    config: IndexConfig,
+    /// Usage locations recorded so far, against `config.usage_budget`
+    usages: AtomicUsize,
+    /// Set once a usage had to be dropped for the budget
+    usages_truncated: AtomicBool,
 }
 
 impl Default for SymbolIndex {
@@ -50,12 +119,25 @@ impl Default for SymbolIndex {
 }
 
 impl SymbolIndex {
-    /// Create an empty symbol index.
+    /// Create an empty symbol index that records definitions only.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_config(IndexConfig::default())
+    }
+
+    /// Create an empty symbol index with the given settings.
+    #[must_use]
+    pub fn with_config(config: IndexConfig) -> Self {
         Self {
-            symbols: DashMap::new(),
-            indexed_files: DashMap::new(),
+            shards: DashMap::new(),
+            file_shards: DashMap::new(),
+            files: PathTable::default(),
+            unparsed: DashSet::new(),
+            lookups: HitStats::default(),
+            builds: AtomicUsize::new(0),
+            config,
+            usages: AtomicUsize::new(0),
+            usages_truncated: AtomicBool::new(false),
         }
     }
 
@@ -63,15 +145,26 @@ impl SymbolIndex {
     ///
     /// Uses `ignore::WalkBuilder` with the same directory filtering as search
     /// (skipping `.git`, `node_modules`, `target`, etc.) and processes files
-    /// in parallel via rayon for speed.
+    /// in parallel via rayon for speed. Files already indexed and unchanged
+    /// since are skipped, so building an overlapping scope again only reads
+    /// what is new or modified.
     pub fn build(&self, scope: &Path) {
+        self.build_with(scope, |_, _, _| {});
+    }
+
+    /// [`build`](Self::build), handing each file read to `visit` as well,
+    /// so other per-file caches can fill from the same read.
+    pub(crate) fn build_with(&self, scope: &Path, visit: impl Fn(&Path, SystemTime, &str) + Sync) {
         use ignore::WalkBuilder;
         use rayon::prelude::*;
 
+        self.builds.fetch_add(1, Ordering::Relaxed);
+
         // Collect file paths first, then process in parallel with rayon.
         // We use WalkBuilder for directory filtering but rayon for parallelism
         // because rayon gives us better work-stealing than ignore's parallel walker
         // for CPU-bound tree-sitter parsing.
+        let visible = crate::redact::visible();
         let files: Vec<PathBuf> = WalkBuilder::new(scope)
             .hidden(false)
             .git_ignore(false)
@@ -79,10 +172,13 @@ impl SymbolIndex {
             .git_exclude(false)
             .ignore(false)
             .parents(false)
-            .filter_entry(|entry| {
+            .filter_entry(move |entry| {
+                if !visible(entry.path()) {
+                    return false;
+                }
                 if entry.file_type().is_some_and(|ft| ft.is_dir()) {
                     if let Some(name) = entry.file_name().to_str() {
-                        return !crate::search::SKIP_DIRS.contains(&name);
+                        return !crate::search::is_skipped_dir(name);
                     }
                 }
                 true
@@ -94,22 +190,26 @@ impl SymbolIndex {
                     return None;
                 }
                 let path = entry.into_path();
-                // Only index code files that have tree-sitter grammars
-                if let FileType::Code(lang) = detect_file_type(&path) {
-                    if outline_language(lang).is_some() {
-                        // Skip oversized files
-                        if let Ok(meta) = fs::metadata(&path) {
-                            if meta.len() <= MAX_FILE_SIZE {
-                                return Some(path);
-                            }
-                        }
-                    }
+                if !indexable(&path) {
+                    return None;
                 }
-                None
+                // Skip oversized files, and files indexed since they last changed
+                let meta = fs::metadata(&path).ok()?;
+                if meta.len() > MAX_FILE_SIZE {
+                    return None;
+                }
+                let mtime = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                let current = self
+                    .files
+                    .id(&path)
+                    .and_then(|id| self.files.get(id))
+                    .is_some_and(|(_, indexed)| indexed == mtime);
+                (!current).then_some(path)
             })
             .collect();
 
         // Process files in parallel with rayon
+        let with_usages = self.records_usages();
         let results: Vec<FileSymbols> = files
             .par_iter()
             .filter_map(|path| {
@@ -117,39 +217,232 @@ impl SymbolIndex {
                 let mtime = fs::metadata(path)
                     .and_then(|m| m.modified())
                     .unwrap_or(SystemTime::UNIX_EPOCH);
-                let symbols = extract_symbols(path, &content);
+                visit(path, mtime, &content);
+                let (symbols, parsed) = extract_symbols(path, &content, with_usages);
                 if symbols.is_empty() {
                     // Still record the file as indexed even if no symbols found
-                    Some((path.clone(), mtime, Vec::new()))
+                    Some((path.clone(), mtime, Vec::new(), parsed))
                 } else {
-                    Some((path.clone(), mtime, symbols))
+                    Some((path.clone(), mtime, symbols, parsed))
                 }
             })
             .collect();
 
-        // Insert results into the DashMaps
-        for (path, mtime, symbols) in results {
-            self.indexed_files.insert(path.clone(), mtime);
-            for (name, line, is_def) in symbols {
-                let loc = SymbolLocation {
-                    path: path.clone(),
-                    line,
-                    is_definition: is_def,
-                    mtime,
-                };
-                self.symbols.entry(name).or_default().push(loc);
+        // Insert results into the shards
+        for (path, mtime, symbols, parsed) in results {
+            let root = self
+                .remove_file(&path)
+                .unwrap_or_else(|| top_level_root(scope, &path));
+            let file = self.files.intern(&path, mtime);
+            self.insert(&root, file, symbols, parsed);
+        }
+    }
+
+    /// Add one file's symbols to the shard at `root`, dropping usages once
+    /// the budget is spent.
+    fn insert(&self, root: &Arc<Path>, file: PathId, symbols: Vec<ExtractedSymbol>, parsed: bool) {
+        let shard = Arc::clone(self.shards.entry(Arc::clone(root)).or_default().value());
+        self.file_shards.insert(file, Arc::clone(root));
+        if parsed {
+            self.unparsed.remove(&file);
+        } else {
+            self.unparsed.insert(file);
+        }
+        for (name, line, is_def, kind) in symbols {
+            if !is_def && !self.take_usage_slot() {
+                continue;
             }
+            let entry = Entry {
+                file,
+                line,
+                is_definition: is_def,
+                kind,
+            };
+            shard.symbols.entry(name).or_default().push(entry);
+        }
+    }
+
+    /// Drop an indexed file's locations from its shard, handing its usages
+    /// back to the budget. Returns the shard's root, for re-inserting the
+    /// file in the same place; None when the file isn't indexed.
+    fn remove_file(&self, path: &Path) -> Option<Arc<Path>> {
+        let file = self.files.id(path)?;
+        let root = self.file_shards.get(&file).map(|r| Arc::clone(r.value()))?;
+        if let Some(shard) = self.shards.get(&root) {
+            let mut freed = 0;
+            shard.symbols.iter_mut().for_each(|mut entry| {
+                entry.value_mut().retain(|e| {
+                    let stale = e.file == file;
+                    freed += usize::from(stale && !e.is_definition);
+                    !stale
+                });
+            });
+            self.usages.fetch_sub(freed, Ordering::Relaxed);
+        }
+        Some(root)
+    }
+
+    /// Whether a built shard holds `path`, so keeping it current is the
+    /// index's business. Files elsewhere wait for a build of their scope.
+    pub(crate) fn covers(&self, path: &Path) -> bool {
+        self.shards
+            .iter()
+            .any(|shard| pathnorm::starts_with(path, shard.key()))
+    }
+
+    /// Drop `path` from the index — a deleted file, or every indexed file
+    /// under a deleted directory. Returns how many files were dropped.
+    pub fn forget(&self, path: &Path) -> usize {
+        let files: Vec<PathBuf> = self
+            .file_shards
+            .iter()
+            .filter_map(|entry| self.files.get(*entry.key()))
+            .map(|(file, _)| file.to_path_buf())
+            .filter(|file| pathnorm::starts_with(file, path))
+            .collect();
+        for file in &files {
+            self.remove_file(file);
+            if let Some(id) = self.files.id(file) {
+                self.file_shards.remove(&id);
+                self.unparsed.remove(&id);
+                self.files.remove(id);
+            }
+        }
+        files.len()
+    }
+
+    /// Shards overlapping `scope`, each with whether it lies wholly inside
+    /// it. Entries of the other shards need a path check.
+    fn shards_for(&self, scope: &Path) -> Vec<(Arc<Shard>, bool)> {
+        self.shards
+            .iter()
+            .filter_map(|shard| {
+                let root = shard.key();
+                if pathnorm::starts_with(root, scope) {
+                    Some((Arc::clone(shard.value()), true))
+                } else if pathnorm::starts_with(scope, root) {
+                    Some((Arc::clone(shard.value()), false))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Whether a stored location lies in `scope`. `whole` skips the path
+    /// lookup for shards already known to be inside it.
+    fn in_scope(&self, entry: &Entry, scope: &Path, whole: bool) -> bool {
+        whole
+            || self
+                .files
+                .get(entry.file)
+                .is_some_and(|(path, _)| pathnorm::starts_with(&path, scope))
+    }
+
+    /// Count one usage against the budget. False, and the index marked
+    /// truncated, when the budget is already spent.
+    fn take_usage_slot(&self) -> bool {
+        if self.usages.fetch_add(1, Ordering::Relaxed) < self.config.usage_budget {
+            return true;
+        }
+        self.usages.fetch_sub(1, Ordering::Relaxed);
+        self.usages_truncated.store(true, Ordering::Relaxed);
+        false
+    }
+
+    /// Whether builds record usages: configured, and not yet over budget.
+    #[must_use]
+    pub fn records_usages(&self) -> bool {
+        self.config.usage_budget > 0 && !self.usages_truncated.load(Ordering::Relaxed)
+    }
+
+    /// The stored locations of `name` in `scope` that `keep` accepts, as
+    /// full locations. None when no shard in scope has the name.
+    fn locations(
+        &self,
+        name: &str,
+        scope: &Path,
+        keep: impl Fn(&Entry) -> bool,
+    ) -> Option<Vec<SymbolLocation>> {
+        let key: Arc<str> = Arc::from(name);
+        let mut found = None;
+        for (shard, whole) in self.shards_for(scope) {
+            let Some(entries) = shard.symbols.get(&key) else {
+                continue;
+            };
+            found.get_or_insert_with(Vec::new).extend(
+                entries
+                    .iter()
+                    .filter(|e| keep(e) && self.in_scope(e, scope, whole))
+                    .filter_map(|e| self.resolve(e)),
+            );
         }
+        found
+    }
+
+    /// Rebuild the public form of a stored location.
+    fn resolve(&self, entry: &Entry) -> Option<SymbolLocation> {
+        let (path, mtime) = self.files.get(entry.file)?;
+        Some(SymbolLocation {
+            path: path.to_path_buf(),
+            line: entry.line,
+            is_definition: entry.is_definition,
+            kind: entry.kind,
+            mtime,
+        })
     }
 
     /// Check if the index has been built for the given scope.
     ///
-    /// Simple heuristic: returns true if any indexed file path starts with `scope`.
+    /// Simple heuristic: returns true if a shard lies inside `scope`, or
+    /// failing that, if any indexed file path starts with `scope`.
     #[must_use]
     pub fn is_built(&self, scope: &Path) -> bool {
-        self.indexed_files
+        self.shards
+            .iter()
+            .any(|shard| pathnorm::starts_with(shard.key(), scope))
+            || self.files.any_under(scope)
+    }
+
+    /// Drop the shards inside `scope`, and their files, so memory goes back
+    /// and the next [`build`](Self::build) of the scope reads them afresh.
+    /// Returns how many files were dropped.
+    pub fn evict(&self, scope: &Path) -> usize {
+        let roots: Vec<Arc<Path>> = self
+            .shards
             .iter()
-            .any(|entry| entry.key().starts_with(scope))
+            .filter(|shard| pathnorm::starts_with(shard.key(), scope))
+            .map(|shard| Arc::clone(shard.key()))
+            .collect();
+        let mut dropped = 0;
+        for root in roots {
+            let Some((_, shard)) = self.shards.remove(&root) else {
+                continue;
+            };
+            let mut files = HashSet::new();
+            let mut freed = 0;
+            for entry in &shard.symbols {
+                for e in entry.value() {
+                    files.insert(e.file);
+                    freed += usize::from(!e.is_definition);
+                }
+            }
+            self.usages.fetch_sub(freed, Ordering::Relaxed);
+            // Files with no symbols are only known to `file_shards`
+            self.file_shards.retain(|file, r| {
+                let here = *r == root;
+                if here {
+                    files.insert(*file);
+                }
+                !here
+            });
+            for file in &files {
+                self.files.remove(*file);
+                self.unparsed.remove(file);
+            }
+            dropped += files.len();
+        }
+        dropped
     }
 
     /// Look up all locations of a symbol within `scope`.
@@ -159,15 +452,28 @@ impl SymbolIndex {
     /// callers can check `mtime` against the current file if freshness matters.
     #[must_use]
     pub fn lookup(&self, name: &str, scope: &Path) -> Vec<SymbolLocation> {
-        let key: Arc<str> = Arc::from(name);
-        let Some(locations) = self.symbols.get(&key) else {
-            return Vec::new();
-        };
-        locations
-            .iter()
-            .filter(|loc| loc.path.starts_with(scope))
-            .cloned()
-            .collect()
+        let found = self.locations(name, scope, |_| true).unwrap_or_default();
+        self.lookups.record(!found.is_empty());
+        found
+    }
+
+    /// Look up usage locations of a symbol within `scope` — every line the
+    /// name appears on as an identifier, outside its definitions.
+    ///
+    /// `None` when the index can't answer: usages aren't configured, `scope`
+    /// isn't built, or the budget ran out and some were never recorded.
+    /// Callers then fall back to walking the files. `Some(empty)` is a real
+    /// answer — no usages.
+    #[must_use]
+    pub fn lookup_usages(&self, name: &str, scope: &Path) -> Option<Vec<SymbolLocation>> {
+        if !self.records_usages() || !self.is_built(scope) {
+            return None;
+        }
+        let found = self
+            .locations(name, scope, |e| !e.is_definition)
+            .unwrap_or_default();
+        self.lookups.record(!found.is_empty());
+        Some(found)
     }
 
     /// Look up only definition locations of a symbol within `scope`.
@@ -175,85 +481,278 @@ impl SymbolIndex {
     /// Same as `lookup` but filters to `is_definition == true`.
     #[must_use]
     pub fn lookup_definitions(&self, name: &str, scope: &Path) -> Vec<SymbolLocation> {
-        let key: Arc<str> = Arc::from(name);
-        let Some(locations) = self.symbols.get(&key) else {
-            return Vec::new();
-        };
-        locations
-            .iter()
-            .filter(|loc| loc.is_definition && loc.path.starts_with(scope))
-            .cloned()
+        let found = self
+            .locations(name, scope, |e| e.is_definition)
+            .unwrap_or_default();
+        self.lookups.record(!found.is_empty());
+        found
+    }
+
+    /// Defined names close to `query`, for "did you mean" hints on empty searches.
+    ///
+    /// Names equal modulo case and `_`/`-` rank first (`FormatSearchResult` for
+    /// `format_search_result`), then small edit distances. Only names with a
+    /// definition inside `scope` are considered.
+    #[must_use]
+    pub fn suggest(&self, query: &str, scope: &Path, limit: usize) -> Vec<String> {
+        let wanted = normalize_name(query);
+        let max_dist = (wanted.len() / 4).clamp(1, 3);
+
+        let mut scored: Vec<(usize, String)> = Vec::new();
+        let mut seen: HashSet<Arc<str>> = HashSet::new();
+        for (shard, whole) in self.shards_for(scope) {
+            for entry in &shard.symbols {
+                let name = entry.key();
+                if &**name == query
+                    || seen.contains(name)
+                    || !entry
+                        .value()
+                        .iter()
+                        .any(|e| e.is_definition && self.in_scope(e, scope, whole))
+                {
+                    continue;
+                }
+                seen.insert(Arc::clone(name));
+                let norm = normalize_name(name);
+                if norm.len().abs_diff(wanted.len()) > max_dist {
+                    continue;
+                }
+                let dist = crate::read::edit_distance(&wanted, &norm);
+                if dist <= max_dist {
+                    scored.push((dist, name.to_string()));
+                }
+            }
+        }
+
+        scored.sort();
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Defined names in `scope` matching `pattern` as a prefix or substring,
+    /// with their definition sites. Best first: case-sensitive prefix, then
+    /// case-insensitive prefix, then substring; shorter names first within a tier.
+    #[must_use]
+    pub fn complete(
+        &self,
+        pattern: &str,
+        scope: &Path,
+        limit: usize,
+    ) -> Vec<(String, Vec<SymbolLocation>)> {
+        let lower = pattern.to_lowercase();
+        // A name defined in several shards gathers its sites from each
+        let mut by_name: HashMap<Arc<str>, (u8, Vec<SymbolLocation>)> = HashMap::new();
+        for (shard, whole) in self.shards_for(scope) {
+            for entry in &shard.symbols {
+                let name = entry.key();
+                let name_lower = name.to_lowercase();
+                let tier = if name.starts_with(pattern) {
+                    0
+                } else if name_lower.starts_with(&lower) {
+                    1
+                } else if name_lower.contains(&lower) {
+                    2
+                } else {
+                    continue;
+                };
+                let defs = entry
+                    .value()
+                    .iter()
+                    .filter(|e| e.is_definition && self.in_scope(e, scope, whole))
+                    .filter_map(|e| self.resolve(e));
+                by_name
+                    .entry(Arc::clone(name))
+                    .or_insert_with(|| (tier, Vec::new()))
+                    .1
+                    .extend(defs);
+            }
+        }
+
+        let mut found: Vec<(u8, String, Vec<SymbolLocation>)> = by_name
+            .into_iter()
+            .filter(|(_, (_, defs))| !defs.is_empty())
+            .map(|(name, (tier, mut defs))| {
+                defs.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+                (tier, name.to_string(), defs)
+            })
+            .collect();
+        found.sort_by(|a, b| (a.0, a.1.len(), &a.1).cmp(&(b.0, b.1.len(), &b.1)));
+        found
+            .into_iter()
+            .take(limit)
+            .map(|(_, name, defs)| (name, defs))
             .collect()
     }
 
     /// Index a single file, updating the symbol maps.
     ///
     /// Used for incremental updates when a file changes.
-    /// Removes old entries for this file before inserting new ones.
+    /// Removes old entries for this file before inserting new ones. A file
+    /// new to the index joins the deepest shard holding it, or starts one
+    /// for its directory.
     pub fn index_file(&self, path: &Path, content: &str) {
         let mtime = fs::metadata(path)
             .and_then(|m| m.modified())
             .unwrap_or(SystemTime::UNIX_EPOCH);
 
-        // Remove old entries for this file from all symbol lists
-        let old_mtime = self.indexed_files.get(path).map(|r| *r.value());
-        if old_mtime.is_some() {
-            self.symbols.iter_mut().for_each(|mut entry| {
-                entry.value_mut().retain(|loc| loc.path != path);
-            });
-        }
+        let root = self.remove_file(path).unwrap_or_else(|| {
+            self.shards
+                .iter()
+                .map(|shard| Arc::clone(shard.key()))
+                .filter(|root| pathnorm::starts_with(path, root))
+                .max_by_key(|root| root.components().count())
+                .unwrap_or_else(|| Arc::from(path.parent().unwrap_or(path)))
+        });
 
         // Extract and insert new symbols
-        let symbols = extract_symbols(path, content);
-        self.indexed_files.insert(path.to_path_buf(), mtime);
-
-        for (name, line, is_def) in symbols {
-            let loc = SymbolLocation {
-                path: path.to_path_buf(),
-                line,
-                is_definition: is_def,
-                mtime,
-            };
-            self.symbols.entry(name).or_default().push(loc);
-        }
+        let (symbols, parsed) = extract_symbols(path, content, self.records_usages());
+        let file = self.files.intern(path, mtime);
+        self.insert(&root, file, symbols, parsed);
     }
 
     /// Number of unique symbol names in the index.
     #[must_use]
     pub fn symbol_count(&self) -> usize {
-        self.symbols.len()
+        let mut names: HashSet<Arc<str>> = HashSet::new();
+        for shard in &self.shards {
+            names.extend(shard.symbols.iter().map(|entry| Arc::clone(entry.key())));
+        }
+        names.len()
+    }
+
+    /// Number of usage locations recorded.
+    #[must_use]
+    pub fn usage_count(&self) -> usize {
+        self.usages.load(Ordering::Relaxed)
     }
 
     /// Number of indexed files.
     #[must_use]
     pub fn file_count(&self) -> usize {
-        self.indexed_files.len()
+        self.files.file_count()
+    }
+
+    /// Indexed files in `scope` that tree-sitter couldn't parse cleanly,
+    /// sorted. Their definitions were recovered by keyword heuristic.
+    #[must_use]
+    pub fn unparsed(&self, scope: &Path) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self
+            .unparsed
+            .iter()
+            .filter_map(|file| self.files.get(*file))
+            .filter(|(path, _)| pathnorm::starts_with(path, scope))
+            .map(|(path, _)| path.to_path_buf())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Number of indexed files tree-sitter couldn't parse cleanly.
+    #[must_use]
+    pub fn unparsed_count(&self) -> usize {
+        self.unparsed.len()
+    }
+
+    /// Number of shards — directories indexed on their own.
+    #[must_use]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Lookups answered with at least one location.
+    #[must_use]
+    pub fn lookup_stats(&self) -> &HitStats {
+        &self.lookups
+    }
+
+    /// Full builds run so far.
+    #[must_use]
+    pub fn build_count(&self) -> usize {
+        self.builds.load(Ordering::Relaxed)
+    }
+}
+
+/// Root of the shard a file found by building `scope` belongs to: the
+/// top-level directory of `scope` it sits under, or `scope` itself.
+fn top_level_root(scope: &Path, path: &Path) -> Arc<Path> {
+    let mut rest = path.strip_prefix(scope).unwrap_or(path).components();
+    match (rest.next(), rest.next()) {
+        (Some(dir), Some(_)) => Arc::from(scope.join(dir)),
+        _ => Arc::from(scope),
     }
 }
 
+/// Whether builds index `path`: code with a tree-sitter grammar or line
+/// scanner, files a `tilth.toml` grammar parses, and JSON/YAML for `OpenAPI`
+/// operations and Kubernetes resources.
+pub(crate) fn indexable(path: &Path) -> bool {
+    match detect_file_type(path) {
+        FileType::Code(lang) => {
+            outline_language(lang).is_some()
+                || matches!(lang, Lang::Dockerfile | Lang::Make)
+                || crate::grammars::language(path).is_some()
+        }
+        FileType::StructuredData => matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("json" | "yaml" | "yml")
+        ),
+        _ => crate::grammars::language(path).is_some(),
+    }
+}
+
+/// Lowercase with `_` and `-` removed, so naming conventions compare equal.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
 /// Extract all symbol definitions from a file using tree-sitter.
 ///
-/// Returns a list of `(name, line_number, is_definition)` tuples.
+/// Returns a list of `(name, line_number, is_definition, kind)` tuples.
 /// Line numbers are 1-based (matching the convention used in search results).
 ///
-/// Only extracts definitions (function, struct, trait, class, etc.) --
-/// not usages. This keeps the index focused and compact.
-fn extract_symbols(path: &Path, content: &str) -> Vec<(Arc<str>, u32, bool)> {
+/// Definitions (function, struct, trait, class, etc.) always; with `usages`,
+/// also every other identifier in a tree-sitter file, once per name and line.
+///
+/// The flag is false when tree-sitter failed on the file or its tree has
+/// errors; keyword-led definition lines then fill in what the tree missed.
+fn extract_symbols(path: &Path, content: &str, usages: bool) -> (Vec<ExtractedSymbol>, bool) {
+    if let Some(plugin_symbols) = crate::grammars::symbols(path, content) {
+        let symbols = plugin_symbols
+            .into_iter()
+            .map(|sym| (Arc::from(sym.name), sym.line, true, sym.kind))
+            .collect();
+        return (symbols, true);
+    }
+
     let FileType::Code(lang) = detect_file_type(path) else {
-        return Vec::new();
+        let symbols = structured::symbols(path, content)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|sym| (Arc::from(sym.name), sym.line, true, sym.kind))
+            .collect();
+        return (symbols, true);
     };
 
+    if let Some(build_symbols) = buildfile::symbols(lang, content) {
+        let symbols = build_symbols
+            .into_iter()
+            .map(|sym| (Arc::from(sym.name), sym.line, true, sym.kind))
+            .collect();
+        return (symbols, true);
+    }
+
     let Some(ts_lang) = outline_language(lang) else {
-        return Vec::new();
+        return (Vec::new(), true);
     };
 
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-
-    let Some(tree) = parser.parse(content, None) else {
-        return Vec::new();
+    let Some(tree) = crate::parser_pool::parse(content, &ts_lang) else {
+        return (heuristic_definitions(content, &[]), false);
     };
 
     let lines: Vec<&str> = content.lines().collect();
@@ -261,7 +760,83 @@ fn extract_symbols(path: &Path, content: &str) -> Vec<(Arc<str>, u32, bool)> {
 
     walk_definitions(tree.root_node(), &lines, &mut symbols, 0);
 
-    symbols
+    let parsed = !tree.root_node().has_error();
+    if !parsed {
+        let recovered = heuristic_definitions(content, &symbols);
+        symbols.extend(recovered);
+    }
+
+    if usages {
+        let mut refs = Vec::new();
+        walk_identifiers(tree.root_node(), content.as_bytes(), &mut refs);
+        refs.sort_unstable();
+        refs.dedup();
+        refs.retain(|(name, line)| {
+            !symbols
+                .iter()
+                .any(|(def, def_line, ..)| def == name && def_line == line)
+        });
+        symbols.extend(
+            refs.into_iter()
+                .map(|(name, line)| (name, line, false, USAGE_KIND)),
+        );
+    }
+
+    (symbols, parsed)
+}
+
+/// Definitions found by keyword on lines where `known` has none.
+fn heuristic_definitions(content: &str, known: &[ExtractedSymbol]) -> Vec<ExtractedSymbol> {
+    content
+        .lines()
+        .zip(1..)
+        .filter_map(|(text, line)| {
+            let name = heuristic_definition_name(text)?;
+            let known = known
+                .iter()
+                .any(|(def, def_line, ..)| *def_line == line && &**def == name);
+            (!known).then(|| (Arc::from(name), line, true, "def"))
+        })
+        .collect()
+}
+
+/// Collect every identifier leaf under `node` as `(name, line)`.
+fn walk_identifiers(node: tree_sitter::Node, source: &[u8], out: &mut Vec<(Arc<str>, u32)>) {
+    if node.child_count() == 0 {
+        if node.kind().ends_with("identifier") {
+            if let Ok(text) = node.utf8_text(source) {
+                out.push((Arc::from(text), node.start_position().row as u32 + 1));
+            }
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk_identifiers(child, source, out);
+    }
+}
+
+/// Short label for a definition node kind, shown by symbol completion.
+fn kind_label(node_kind: &str) -> &'static str {
+    match node_kind {
+        "function_declaration"
+        | "function_definition"
+        | "function_item"
+        | "function_signature_item" => "fn",
+        "method_definition" | "method_declaration" => "method",
+        "class_declaration" | "class_definition" => "class",
+        "struct_item" => "struct",
+        "interface_declaration" => "interface",
+        "trait_item" => "trait",
+        "type_alias_declaration" | "type_item" | "type_declaration" => "type",
+        "enum_item" | "enum_declaration" => "enum",
+        "const_item" | "static_item" => "const",
+        "lexical_declaration" | "variable_declaration" => "var",
+        "impl_item" => "impl",
+        "mod_item" => "mod",
+        "export_statement" => "export",
+        _ => "def",
+    }
 }
 
 /// Recursively walk tree-sitter AST nodes to find all definitions.
@@ -273,7 +848,7 @@ fn extract_symbols(path: &Path, content: &str) -> Vec<(Arc<str>, u32, bool)> {
 fn walk_definitions(
     node: tree_sitter::Node,
     lines: &[&str],
-    symbols: &mut Vec<(Arc<str>, u32, bool)>,
+    symbols: &mut Vec<ExtractedSymbol>,
     depth: usize,
 ) {
     if depth > 3 {
@@ -285,7 +860,7 @@ fn walk_definitions(
     if DEFINITION_KINDS.contains(&kind) {
         if let Some(name) = extract_definition_name(node, lines) {
             let line = node.start_position().row as u32 + 1;
-            symbols.push((Arc::from(name.as_str()), line, true));
+            symbols.push((Arc::from(name.as_str()), line, true, kind_label(kind)));
         }
 
         // For impl blocks in Rust, also index the trait name and type name
@@ -293,11 +868,11 @@ fn walk_definitions(
         if kind == "impl_item" {
             if let Some(trait_name) = crate::search::treesitter::extract_impl_trait(node, lines) {
                 let line = node.start_position().row as u32 + 1;
-                symbols.push((Arc::from(trait_name.as_str()), line, true));
+                symbols.push((Arc::from(trait_name.as_str()), line, true, "impl"));
             }
             if let Some(type_name) = crate::search::treesitter::extract_impl_type(node, lines) {
                 let line = node.start_position().row as u32 + 1;
-                symbols.push((Arc::from(type_name.as_str()), line, true));
+                symbols.push((Arc::from(type_name.as_str()), line, true, "impl"));
             }
         }
 
@@ -306,7 +881,7 @@ fn walk_definitions(
             let interfaces = crate::search::treesitter::extract_implemented_interfaces(node, lines);
             for iface in interfaces {
                 let line = node.start_position().row as u32 + 1;
-                symbols.push((Arc::from(iface.as_str()), line, true));
+                symbols.push((Arc::from(iface.as_str()), line, true, "impl"));
             }
         }
     }
@@ -359,8 +934,8 @@ impl MyTrait for Foo {
         let mut f = fs::File::create(&path).unwrap();
         f.write_all(content.as_bytes()).unwrap();
 
-        let symbols = extract_symbols(&path, content);
-        let names: Vec<&str> = symbols.iter().map(|(n, _, _)| n.as_ref()).collect();
+        let (symbols, _) = extract_symbols(&path, content, false);
+        let names: Vec<&str> = symbols.iter().map(|(n, ..)| n.as_ref()).collect();
 
         assert!(names.contains(&"Foo"), "should find struct Foo: {names:?}");
         assert!(names.contains(&"baz"), "should find fn baz: {names:?}");
@@ -374,7 +949,7 @@ impl MyTrait for Foo {
         );
 
         // All extracted symbols should be definitions
-        assert!(symbols.iter().all(|(_, _, is_def)| *is_def));
+        assert!(symbols.iter().all(|(_, _, is_def, _)| *is_def));
 
         let _ = fs::remove_file(&path);
     }
@@ -434,6 +1009,160 @@ impl MyTrait for Foo {
         let _ = fs::remove_file(&path);
     }
 
+    #[test]
+    fn test_usages_within_budget() {
+        let content = "fn target() {}\nfn caller() {\n    let x = target();\n    target();\n}\n";
+        let dir = std::env::temp_dir().join("tilth_test_usages");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("test.rs");
+        fs::write(&path, content).unwrap();
+
+        // Definitions only by default: usage lookups defer to a file walk
+        let index = SymbolIndex::new();
+        index.index_file(&path, content);
+        assert!(index.lookup_usages("target", &dir).is_none());
+
+        let index = SymbolIndex::with_config(IndexConfig { usage_budget: 100 });
+        index.index_file(&path, content);
+        let usages = index.lookup_usages("target", &dir).unwrap();
+        let lines: Vec<u32> = usages.iter().map(|loc| loc.line).collect();
+        assert_eq!(lines, vec![3, 4], "no usage at the definition");
+        assert_eq!(index.lookup_definitions("target", &dir).len(), 1);
+
+        // Re-indexing the file hands its usages back to the budget
+        let before = index.usage_count();
+        index.index_file(&path, content);
+        assert_eq!(index.usage_count(), before);
+
+        // Over budget: the index can no longer say what isn't used
+        let index = SymbolIndex::with_config(IndexConfig { usage_budget: 1 });
+        index.index_file(&path, content);
+        assert!(index.lookup_usages("target", &dir).is_none());
+        assert_eq!(index.lookup_definitions("target", &dir).len(), 1);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_shards_by_top_level_dir() {
+        let root = std::env::temp_dir().join("tilth_test_shards");
+        let _ = fs::remove_dir_all(&root);
+        for (dir, name) in [("api", "serve"), ("core", "parse")] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(
+                root.join(dir).join("lib.rs"),
+                format!("pub fn {name}() {{}}\npub fn shared() {{}}"),
+            )
+            .unwrap();
+        }
+        fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+
+        let index = SymbolIndex::new();
+        index.build(&root);
+        assert_eq!(index.shard_count(), 3, "api, core and the root itself");
+        assert_eq!(index.lookup_definitions("shared", &root).len(), 2);
+        assert_eq!(
+            index.lookup_definitions("shared", &root.join("api")).len(),
+            1
+        );
+        assert!(index.lookup("serve", &root.join("core")).is_empty());
+        assert_eq!(index.complete("shared", &root, 5)[0].1.len(), 2);
+
+        // An unchanged tree is not re-read, so nothing is duplicated
+        index.build(&root);
+        assert_eq!(index.lookup_definitions("shared", &root).len(), 2);
+
+        // Evicting one package leaves the others answering
+        assert_eq!(index.evict(&root.join("api")), 1);
+        assert!(index.lookup("serve", &root).is_empty());
+        assert_eq!(index.lookup_definitions("parse", &root).len(), 1);
+        assert!(!index.is_built(&root.join("api")));
+        index.build(&root.join("api"));
+        assert_eq!(index.lookup_definitions("serve", &root).len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_unparsed_files_tracked() {
+        let dir = std::env::temp_dir().join("tilth_test_unparsed");
+        let _ = fs::create_dir_all(&dir);
+        let good = dir.join("good.rs");
+        let bad = dir.join("bad.rs");
+        fs::write(&good, "pub fn fine() {}").unwrap();
+        fs::write(&bad, "pub fn kept() {}\n\nfn broken( {\n").unwrap();
+
+        let index = SymbolIndex::new();
+        index.build(&dir);
+        assert_eq!(index.unparsed(&dir), vec![bad.clone()]);
+        assert_eq!(index.unparsed_count(), 1);
+        assert_eq!(index.lookup_definitions("broken", &dir).len(), 1);
+        assert_eq!(index.lookup_definitions("kept", &dir).len(), 1);
+
+        // Fixing the file clears it
+        let fixed = "pub fn kept() {}\n\nfn broken() {}\n";
+        fs::write(&bad, fixed).unwrap();
+        index.index_file(&bad, fixed);
+        assert!(index.unparsed(&dir).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_suggest_similar_names() {
+        let content = "pub fn format_search_result() {}\npub fn format_search_header() {}\npub fn unrelated() {}";
+        let dir = std::env::temp_dir().join("tilth_test_suggest");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("test.rs");
+        fs::write(&path, content).unwrap();
+
+        let index = SymbolIndex::new();
+        index.index_file(&path, content);
+
+        assert_eq!(
+            index.suggest("FormatSearchResult", &dir, 3),
+            vec!["format_search_result"]
+        );
+        assert_eq!(
+            index.suggest("format_search_heder", &dir, 3),
+            vec!["format_search_header"]
+        );
+        assert!(index
+            .suggest("format_search_result", Path::new("/nonexistent"), 3)
+            .is_empty());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_complete_ranks_prefix_first() {
+        let content = "pub fn format_entry() {}\npub struct Formatter;\npub fn reformat() {}\npub fn other() {}";
+        let dir = std::env::temp_dir().join("tilth_test_complete");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("test.rs");
+        fs::write(&path, content).unwrap();
+
+        let index = SymbolIndex::new();
+        index.index_file(&path, content);
+
+        let completions = index.complete("format", &dir, 10);
+        let found: Vec<(&str, &str)> = completions
+            .iter()
+            .map(|(name, defs)| (name.as_str(), defs[0].kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("format_entry", "fn"),
+                ("Formatter", "struct"),
+                ("reformat", "fn")
+            ]
+        );
+        assert_eq!(index.complete("format", &dir, 1).len(), 1);
+
+        let _ = fs::remove_file(&path);
+    }
+
     #[test]
     fn test_extract_symbols_typescript() {
         let content = r#"
@@ -457,8 +1186,8 @@ interface Printable {
         let path = dir.join("test.ts");
         fs::write(&path, content).unwrap();
 
-        let symbols = extract_symbols(&path, content);
-        let names: Vec<&str> = symbols.iter().map(|(n, _, _)| n.as_ref()).collect();
+        let (symbols, _) = extract_symbols(&path, content, false);
+        let names: Vec<&str> = symbols.iter().map(|(n, ..)| n.as_ref()).collect();
 
         assert!(
             names.contains(&"greet"),
@@ -491,8 +1220,8 @@ class MyClass:
         let path = dir.join("test.py");
         fs::write(&path, content).unwrap();
 
-        let symbols = extract_symbols(&path, content);
-        let names: Vec<&str> = symbols.iter().map(|(n, _, _)| n.as_ref()).collect();
+        let (symbols, _) = extract_symbols(&path, content, false);
+        let names: Vec<&str> = symbols.iter().map(|(n, ..)| n.as_ref()).collect();
 
         assert!(names.contains(&"hello"), "should find def hello: {names:?}");
         assert!(
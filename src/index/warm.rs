@@ -0,0 +1,26 @@
+//! Cold-start warm-up: one walk over a scope that fills the symbol index,
+//! the Bloom filters and the outline cache from a single read per file,
+//! instead of each reading every file again on first use.
+
+use std::path::Path;
+
+use super::bloom::BloomFilterCache;
+use super::SymbolIndex;
+use crate::cache::OutlineCache;
+use crate::read::{detect_file_type, outline};
+use crate::types::FileType;
+
+/// Build `index` for `scope`, priming `bloom` and `cache` for each file read
+/// on the way. Files the index already has unchanged are skipped, as by
+/// [`SymbolIndex::build`].
+pub fn warm(scope: &Path, index: &SymbolIndex, bloom: &BloomFilterCache, cache: &OutlineCache) {
+    index.build_with(scope, |path, mtime, content| {
+        bloom.prime(path, mtime, content);
+        let file_type = detect_file_type(path);
+        if matches!(file_type, FileType::Code(_)) {
+            cache.get_or_compute(path, mtime, || {
+                outline::generate(path, file_type, content.as_bytes(), false)
+            });
+        }
+    });
+}
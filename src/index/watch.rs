@@ -0,0 +1,126 @@
+//! Filesystem watching for the MCP server: a file created or changed under
+//! a built shard is indexed again and its Bloom filter rebuilt as soon as it
+//! is written, and a deleted one — or everything under a deleted directory —
+//! leaves both, instead of waiting for a lookup to notice the stale mtime.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+use super::bloom::BloomFilterCache;
+use super::symbol::indexable;
+use super::SymbolIndex;
+
+/// Watches a directory tree until dropped.
+pub struct Watcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watch `root` recursively, keeping `index` and `bloom` current with every
+/// change under it. Redacted paths and skipped directories (`.git`,
+/// `node_modules`, `target`, …) are ignored.
+///
+/// # Errors
+/// When the platform watcher can't be set up, e.g. past the inotify limit.
+pub fn watch(
+    root: &Path,
+    index: Arc<SymbolIndex>,
+    bloom: Arc<BloomFilterCache>,
+) -> notify::Result<Watcher> {
+    let visible = crate::redact::visible();
+    let base = root.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+        for path in &event.paths {
+            if !skipped(path.strip_prefix(&base).unwrap_or(path)) && visible(path) {
+                update(path, &index, &bloom);
+            }
+        }
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+    Ok(Watcher { _watcher: watcher })
+}
+
+/// Bring `path`'s index entries and Bloom filter in line with the disk.
+fn update(path: &Path, index: &SymbolIndex, bloom: &BloomFilterCache) {
+    if !path.exists() {
+        index.forget(path);
+        bloom.forget(path);
+        return;
+    }
+    if !path.is_file() || (!index.covers(path) && !bloom.has(path)) {
+        return;
+    }
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    if index.covers(path) && indexable(path) {
+        index.index_file(path, &content);
+    }
+    let mtime = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    bloom.prime(path, mtime, &content);
+}
+
+/// Whether `path`, relative to the watched root, is inside a directory
+/// walks skip.
+fn skipped(path: &Path) -> bool {
+    path.parent().is_some_and(|dir| {
+        dir.components()
+            .filter_map(|c| c.as_os_str().to_str())
+            .any(crate::search::is_skipped_dir)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_and_deletes_reach_the_index() {
+        let dir = std::env::temp_dir().join(format!("tilth_watch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+        fs::write(&file, "pub fn before() {}\n").unwrap();
+
+        let index = SymbolIndex::new();
+        let bloom = BloomFilterCache::new();
+        index.build(&dir);
+        assert_eq!(index.lookup("before", &dir).len(), 1);
+
+        fs::write(&file, "pub fn after() {}\n").unwrap();
+        update(&file, &index, &bloom);
+        assert!(index.lookup("before", &dir).is_empty());
+        assert_eq!(index.lookup("after", &dir).len(), 1);
+        assert!(bloom.has(&file));
+
+        fs::remove_file(&file).unwrap();
+        update(&file, &index, &bloom);
+        assert!(index.lookup("after", &dir).is_empty());
+        assert_eq!(index.file_count(), 0);
+        assert!(!bloom.has(&file));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn skips_ignored_directories() {
+        assert!(skipped(Path::new("node_modules/pkg/index.js")));
+        assert!(skipped(Path::new(".git/HEAD")));
+        assert!(!skipped(Path::new("src/main.rs")));
+        // A skipped directory is itself an event path, not inside one
+        assert!(!skipped(Path::new("target")));
+    }
+}
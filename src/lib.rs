@@ -13,23 +13,44 @@
     clippy::missing_panics_doc,        // same
 )]
 
+pub(crate) mod analyze;
+pub mod api;
+pub(crate) mod backup;
+pub mod bench;
 pub(crate) mod budget;
 pub mod cache;
+pub(crate) mod changes;
 pub(crate) mod classify;
+pub mod config;
+pub(crate) mod deadline;
+pub(crate) mod diff;
 pub(crate) mod edit;
 pub mod error;
 pub(crate) mod format;
+pub(crate) mod git;
+pub mod grammars;
 pub mod index;
 pub mod install;
 pub mod map;
 pub mod mcp;
+pub mod owners;
+pub(crate) mod parser_pool;
+pub(crate) mod pathnorm;
 pub(crate) mod read;
+pub(crate) mod redact;
+pub(crate) mod review;
 pub(crate) mod search;
 pub(crate) mod session;
+pub mod sourcemap;
+pub(crate) mod tokens;
+pub(crate) mod trace;
 pub(crate) mod types;
+pub(crate) mod verbosity;
 
 use std::path::Path;
 
+pub use types::{Match, SearchResult, TraitMethod};
+
 use cache::OutlineCache;
 use classify::classify;
 use error::TilthError;
@@ -58,6 +79,20 @@ pub fn run_full(
     run_inner(query, scope, section, budget_tokens, true, cache)
 }
 
+/// Symbol search for each of `queries` under `scope`, as structured results
+/// in query order. One walk of the tree serves every query, and each file is
+/// read and parsed once — for tools that need many lookups per run.
+pub fn search_many(queries: &[&str], scope: &Path) -> Result<Vec<SearchResult>, TilthError> {
+    search::symbol::search_many(queries, scope)
+}
+
+/// Self-contained snippet around `line` (1-based) of `path`: the enclosing
+/// function, the members of its type it uses, and the type definitions it
+/// names from the same file.
+pub fn snippet(path: &Path, line: u32) -> Result<String, TilthError> {
+    read::snippet::snippet(path, line)
+}
+
 fn run_inner(
     query: &str,
     scope: &Path,
@@ -66,27 +101,65 @@ fn run_inner(
     full: bool,
     cache: &OutlineCache,
 ) -> Result<String, TilthError> {
+    let (output, _) = route(query, scope, section, full, cache, false)?;
+
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b)),
+        None => Ok(output),
+    }
+}
+
+/// Classify `query` and hand it to the subsystem that fits. Returns the
+/// output and a short account of the route taken — what `tilth_lookup`
+/// puts in its header.
+pub(crate) fn route(
+    query: &str,
+    scope: &Path,
+    section: Option<&str>,
+    full: bool,
+    cache: &OutlineCache,
+    edit_mode: bool,
+) -> Result<(String, String), TilthError> {
     let query_type = classify(query, scope);
 
-    let output = match query_type {
-        QueryType::FilePath(path) => read::read_file(&path, section, full, cache, false)?,
+    let routed = match query_type {
+        QueryType::FilePath(path) => (
+            read::read_file(&path, section, full, cache, edit_mode)?,
+            "read — an existing path".to_string(),
+        ),
 
-        QueryType::Glob(pattern) => search::search_glob(&pattern, scope, cache)?,
+        QueryType::Glob(pattern) => (
+            search::search_glob(&pattern, scope, cache)?,
+            "files — glob characters".to_string(),
+        ),
 
-        QueryType::Symbol(name) => search::search_symbol(&name, scope, cache)?,
+        QueryType::Symbol(name) => (
+            search::search_symbol(&name, scope, cache)?,
+            "symbol search — an identifier".to_string(),
+        ),
 
-        QueryType::Content(text) => search::search_content(&text, scope, cache)?,
+        QueryType::Content(text) => (
+            search::search_content(&text, scope, cache)?,
+            "content search — not a path, glob or identifier".to_string(),
+        ),
 
         QueryType::Fallthrough(text) => {
             // Path-like query that didn't resolve. Try symbol, then content.
             // Use structured total_found check, not string matching.
             let sym_result = search::search_symbol_raw(&text, scope)?;
             if sym_result.total_found > 0 {
-                search::format_symbol_result(&sym_result, cache)?
+                (
+                    search::format_symbol_result(&sym_result, cache)?,
+                    "symbol search — looks like a path, but no such file".to_string(),
+                )
             } else {
                 let content_result = search::search_content_raw(&text, scope)?;
                 if content_result.total_found > 0 {
-                    search::format_content_result(&content_result, cache)?
+                    (
+                        search::format_content_result(&content_result, cache)?,
+                        "content search — looks like a path, but no such file or symbol"
+                            .to_string(),
+                    )
                 } else {
                     let resolved = scope.join(&text);
                     return Err(TilthError::NotFound {
@@ -97,9 +170,5 @@ fn run_inner(
             }
         }
     };
-
-    match budget_tokens {
-        Some(b) => Ok(budget::apply(&output, b)),
-        None => Ok(output),
-    }
+    Ok(routed)
 }
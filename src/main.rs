@@ -20,7 +20,8 @@ struct Cli {
     #[arg(long, default_value = ".")]
     scope: PathBuf,
 
-    /// Line range or markdown heading (e.g. "45-89" or "## Architecture"). Bypasses smart view.
+    /// Line range, markdown heading or XML element path (e.g. "45-89", "## Architecture" or
+    /// "project/dependencies"). Bypasses smart view.
     #[arg(long)]
     section: Option<String>,
 
@@ -44,10 +45,48 @@ struct Cli {
     #[arg(long)]
     edit: bool,
 
+    /// With --mcp: also index identifier usages, up to N locations, so callers
+    /// lookups read only the files that mention the name. Costs memory.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    index_usages: usize,
+
+    /// With --mcp: resolve relative paths and the default scope against DIR,
+    /// not the directory the MCP host launched the server in.
+    #[arg(long, value_name = "DIR")]
+    workspace: Option<PathBuf>,
+
+    /// With --mcp: once responses this session add up to N tokens, switch
+    /// tools to terse output — no expansion, capped facets and responses.
+    #[arg(long, value_name = "N")]
+    session_budget: Option<u64>,
+
+    /// With --mcp: append every file path and line range the session shows
+    /// to FILE, one JSON object per line, for auditing what code was exposed.
+    #[arg(long, value_name = "FILE")]
+    audit_log: Option<PathBuf>,
+
+    /// Load the shared-library grammars tilth.toml lists. Off by default:
+    /// loading a library runs its code, and tilth.toml comes with the repo.
+    #[arg(long)]
+    load_grammars: bool,
+
+    /// With --mcp: don't watch the workspace for file changes. The index is
+    /// then refreshed only when a lookup finds a file changed.
+    #[arg(long)]
+    no_watch: bool,
+
     /// Generate a structural codebase map.
     #[arg(long)]
     map: bool,
 
+    /// Annotate search results and map entries with their CODEOWNERS owners.
+    #[arg(long)]
+    owners: bool,
+
+    /// Add the original source location to matches in built files with a source map.
+    #[arg(long)]
+    sourcemaps: bool,
+
     /// Print shell completions for the given shell.
     #[arg(long, value_name = "SHELL")]
     completions: Option<Shell>,
@@ -65,10 +104,23 @@ enum Command {
         #[arg(long)]
         edit: bool,
     },
+    /// Time index build, symbol search (cold and warm), content search and
+    /// map on a directory, with memory use after each step.
+    Bench {
+        /// Directory to benchmark.
+        scope: PathBuf,
+
+        /// Symbol to search for. Defaults to the most widely defined name.
+        #[arg(long)]
+        query: Option<String>,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
+    if cli.load_grammars {
+        tilth::grammars::allow();
+    }
 
     // Shell completions
     if let Some(shell) = cli.completions {
@@ -85,13 +137,33 @@ fn main() {
                     process::exit(1);
                 }
             }
+            Command::Bench { scope, query } => {
+                let scope = scope.canonicalize().unwrap_or(scope);
+                match tilth::bench::run(&scope, query.as_deref()) {
+                    Ok(output) => println!("{output}"),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        process::exit(e.exit_code());
+                    }
+                }
+            }
         }
         return;
     }
 
     // MCP mode: JSON-RPC server
     if cli.mcp {
-        if let Err(e) = tilth::mcp::run(cli.edit) {
+        let options = tilth::mcp::ServerOptions {
+            edit_mode: cli.edit,
+            index: tilth::index::IndexConfig {
+                usage_budget: cli.index_usages,
+            },
+            workspace: cli.workspace,
+            session_budget: cli.session_budget,
+            audit_log: cli.audit_log,
+            watch: !cli.no_watch,
+        };
+        if let Err(e) = tilth::mcp::run(options) {
             eprintln!("mcp error: {e}");
             process::exit(1);
         }
@@ -99,11 +171,14 @@ fn main() {
     }
 
     let is_tty = io::stdout().is_terminal();
+    let _owners = tilth::owners::Annotate::set(cli.owners);
+    let _sourcemaps = tilth::sourcemap::Translate::set(cli.sourcemaps);
 
     // Map mode
     if cli.map {
-        let cache = tilth::cache::OutlineCache::new();
-        let scope = cli.scope.canonicalize().unwrap_or(cli.scope);
+        let scope = tilth::config::expand_scope(&cli.scope.to_string_lossy());
+        let scope = scope.canonicalize().unwrap_or(scope);
+        let cache = tilth::cache::OutlineCache::persistent(&scope);
         let output = tilth::map::generate(&scope, 3, cli.budget, &cache);
         emit_output(&output, is_tty);
         return;
@@ -118,7 +193,8 @@ fn main() {
     };
 
     let cache = tilth::cache::OutlineCache::new();
-    let scope = cli.scope.canonicalize().unwrap_or(cli.scope);
+    let scope = tilth::config::expand_scope(&cli.scope.to_string_lossy());
+    let scope = scope.canonicalize().unwrap_or(scope);
 
     // When piped (not a TTY), force full output — scripts expect raw content
     let full = cli.full || !is_tty;
@@ -1,94 +1,477 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 use ignore::WalkBuilder;
+use rayon::prelude::*;
 
 use crate::cache::OutlineCache;
+use crate::deadline::Deadline;
 use crate::read::{detect_file_type, outline};
-use crate::types::{estimate_tokens, FileType};
+use crate::tokens;
+use crate::types::FileType;
 
 /// Generate a structural codebase map.
 /// Code files show symbol names from outline cache.
 /// Non-code files show name + token estimate.
+/// Outlines are collected in parallel. With a `budget`, each top-level
+/// directory gets a share of it proportional to its size.
+///
+/// Each top-level section is cached in `cache` against the newest mtime
+/// under it, so a repeated map only re-renders directories that changed.
 #[must_use]
 pub fn generate(scope: &Path, depth: usize, budget: Option<u64>, cache: &OutlineCache) -> String {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    // Newest mtime per top-level section; directories count too, so an
+    // added or removed file shows up even though it has no mtime of its own.
+    let mut stamps: BTreeMap<PathBuf, SystemTime> = BTreeMap::new();
+
+    for entry in walker(scope, Some(depth + 1)).flatten() {
+        let path = entry.path();
+        let rel = path.strip_prefix(scope).unwrap_or(path);
+        let is_file = entry.file_type().is_some_and(|ft| ft.is_file());
+
+        let mtime = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let stamp = stamps.entry(section_key(rel, is_file)).or_insert(mtime);
+        *stamp = (*stamp).max(mtime);
+
+        // Skip if deeper than requested
+        if is_file && rel.components().count().saturating_sub(1) <= depth {
+            paths.push(entry.into_path());
+        }
+    }
+
+    // Owner annotations aren't part of the cached text
+    let cached = !crate::owners::enabled();
+    let mut sections: BTreeMap<PathBuf, Arc<str>> = BTreeMap::new();
+    for (key, &stamp) in stamps.iter().filter(|_| cached) {
+        if let Some(text) = cache.subtree(&scope.join(key), depth, stamp) {
+            sections.insert(key.clone(), text);
+        }
+    }
+
+    // Outlining dominates on large repos; rayon keeps walk order on collect.
+    let entries: Vec<(PathBuf, FileEntry)> = paths
+        .par_iter()
+        .filter_map(|path| {
+            let rel = path.strip_prefix(scope).unwrap_or(path);
+            if sections.contains_key(&section_key(rel, true)) {
+                return None;
+            }
+            let parent = rel.parent().unwrap_or(Path::new("")).to_path_buf();
+            Some((parent, file_entry(path, rel, cache)))
+        })
+        .collect();
+
     let mut tree: BTreeMap<PathBuf, Vec<FileEntry>> = BTreeMap::new();
+    for (parent, entry) in entries {
+        tree.entry(parent).or_default().push(entry);
+    }
+
+    for (key, &stamp) in &stamps {
+        if sections.contains_key(key) {
+            continue;
+        }
+        let text: Arc<str> = render_section(&tree, key, scope).into();
+        if cached {
+            cache.store_subtree(&scope.join(key), depth, stamp, Arc::clone(&text));
+        }
+        sections.insert(key.clone(), text);
+    }
+    let sections: Vec<&str> = sections
+        .values()
+        .map(|s| &**s)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut out = format!("# Map: {} (depth {})\n", scope.display(), depth);
+    let header = RepoStats::collect(scope).format();
+    if !header.is_empty() {
+        let _ = writeln!(out, "{header}");
+    }
+    let aliases = crate::config::aliases();
+    if !aliases.is_empty() {
+        let listed: Vec<String> = aliases
+            .iter()
+            .map(|(name, path)| format!("{name} → {path}"))
+            .collect();
+        let _ = writeln!(out, "> Scope aliases: {}", listed.join(", "));
+    }
+
+    let Some(b) = budget else {
+        out.push_str(&sections.concat());
+        return out;
+    };
+    let full = tokens::estimate(out.as_bytes(), FileType::Other)
+        + sections
+            .iter()
+            .map(|s| tokens::estimate(s.as_bytes(), FileType::Other))
+            .sum::<u64>();
+    if full <= b {
+        out.push_str(&sections.concat());
+        return out;
+    }
+    // Room for the note below
+    let available = b
+        .saturating_sub(tokens::estimate(out.as_bytes(), FileType::Other))
+        .saturating_sub(40);
+    out.push_str(&fit_sections(&sections, available));
+    let _ = write!(
+        out,
+        "\n... map trimmed to budget {b}: full map ~{}. Re-request with budget ≥ {full}, a lower depth, or a subdirectory as scope.",
+        crate::format::token_count(full)
+    );
+    crate::budget::apply(&out, b)
+}
+
+/// Which top-level section `rel` belongs to: its first component, or the
+/// empty path for the scope root's own files (and the root itself).
+fn section_key(rel: &Path, is_file: bool) -> PathBuf {
+    let mut components = rel.components();
+    match (components.next(), components.next()) {
+        (Some(top), Some(_)) => PathBuf::from(top.as_os_str()),
+        (Some(top), None) if !is_file => PathBuf::from(top.as_os_str()),
+        _ => PathBuf::new(),
+    }
+}
+
+fn file_entry(path: &Path, rel: &Path, cache: &OutlineCache) -> FileEntry {
+    let name = rel
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
 
-    let walker = WalkBuilder::new(scope)
+    let meta = std::fs::metadata(path).ok();
+    let byte_len = meta.as_ref().map_or(0, std::fs::Metadata::len);
+    let file_type = detect_file_type(path);
+    let tokens = tokens::estimate_len(byte_len, file_type);
+    let symbols = match file_type {
+        FileType::Code(_) => {
+            let mtime = meta
+                .and_then(|m| m.modified().ok())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+
+            let outline_str = cache.get_or_compute(path, mtime, || {
+                let buf = std::fs::read(path).unwrap_or_default();
+                outline::generate(path, file_type, &buf, true)
+            });
+
+            Some(extract_symbol_names(&outline_str))
+        }
+        _ => None,
+    };
+
+    FileEntry {
+        name,
+        symbols,
+        tokens,
+    }
+}
+
+/// One top-level section of the tree: the root's own files for the empty
+/// key, otherwise a top-level directory and everything under it. Empty when
+/// the directory holds no files directly.
+fn render_section(tree: &BTreeMap<PathBuf, Vec<FileEntry>>, key: &Path, scope: &Path) -> String {
+    let mut out = String::new();
+    if key.as_os_str().is_empty() {
+        if let Some(entries) = tree.get(key) {
+            format_files(entries, "", scope, &mut out);
+        }
+    } else if tree.contains_key(key) {
+        format_subdir(tree, key, 0, scope, &mut out);
+    }
+    out
+}
+
+/// Share `budget` tokens between sections in proportion to their full size,
+/// smallest first, each getting at least an even split of what's left — so
+/// small directories show whole and the big ones divide the remainder. A
+/// section over its share keeps its leading lines plus a count of the rest:
+/// a huge first directory can't push later ones out of the map.
+fn fit_sections(sections: &[&str], budget: u64) -> String {
+    let sizes: Vec<u64> = sections
+        .iter()
+        .map(|s| tokens::estimate(s.as_bytes(), FileType::Other))
+        .collect();
+    let mut unshared: u64 = sizes.iter().sum();
+    if unshared <= budget {
+        return sections.concat();
+    }
+
+    let mut order: Vec<usize> = (0..sections.len()).collect();
+    order.sort_by_key(|&i| sizes[i]);
+    let mut shares = vec![0; sections.len()];
+    let mut left = budget;
+    for (done, &i) in order.iter().enumerate() {
+        let even = left / (order.len() - done) as u64;
+        let proportional = left * sizes[i] / unshared.max(1);
+        shares[i] = sizes[i].min(even.max(proportional));
+        left -= shares[i];
+        unshared -= sizes[i];
+    }
+
+    let mut out = String::new();
+    for (section, share) in sections.iter().zip(shares) {
+        let max_bytes = tokens::bytes_for(share, section);
+        if section.len() <= max_bytes {
+            out.push_str(section);
+            continue;
+        }
+
+        // Room for the "... N more lines" marker
+        let max_bytes = max_bytes.saturating_sub(24);
+        let mut lines = section.lines();
+        let first = lines.next().unwrap_or("");
+        let mut kept = format!("{first}\n");
+        let mut omitted = 0;
+        for line in lines {
+            if omitted == 0 && kept.len() + line.len() < max_bytes {
+                kept.push_str(line);
+                kept.push('\n');
+            } else {
+                omitted += 1;
+            }
+        }
+        if omitted > 0 {
+            let indent = first.len() - first.trim_start().len();
+            let indent = if first.ends_with('/') {
+                indent + 2
+            } else {
+                indent
+            };
+            let _ = writeln!(kept, "{}... {omitted} more lines", " ".repeat(indent));
+        }
+        out.push_str(&kept);
+    }
+    out
+}
+
+fn walker(scope: &Path, max_depth: Option<usize>) -> ignore::Walk {
+    let visible = crate::redact::visible();
+    let deadline = crate::deadline::Deadline::current();
+    WalkBuilder::new(scope)
         .hidden(false)
         .git_ignore(false)
         .git_global(false)
         .git_exclude(false)
         .ignore(false)
         .parents(false)
-        .filter_entry(|entry| {
+        .filter_entry(move |entry| {
+            if deadline.as_ref().is_some_and(Deadline::reached) || !visible(entry.path()) {
+                return false;
+            }
             if entry.file_type().is_some_and(|ft| ft.is_dir()) {
                 if let Some(name) = entry.file_name().to_str() {
-                    return !crate::search::SKIP_DIRS.contains(&name);
+                    return !crate::search::is_skipped_dir(name);
                 }
             }
             true
         })
-        .max_depth(Some(depth + 1))
-        .build();
+        .max_depth(max_depth)
+        .build()
+}
 
-    for entry in walker.flatten() {
-        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
-            continue;
+/// Package manifests — one per buildable unit.
+const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+    "setup.py",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "build.sbt",
+    "Gemfile",
+    "Package.swift",
+];
+
+/// Conventional entry-point file names, reported when found near the root.
+const ENTRY_FILES: &[&str] = &[
+    "main.rs",
+    "main.go",
+    "__main__.py",
+    "main.py",
+    "manage.py",
+    "index.js",
+    "index.ts",
+    "Main.java",
+    "Program.cs",
+    "main.c",
+    "main.cpp",
+    "main.swift",
+];
+
+/// CI config locations, relative to the scope root.
+const CI_PATHS: &[&str] = &[
+    ".github/workflows",
+    ".gitlab-ci.yml",
+    ".circleci",
+    ".travis.yml",
+    "azure-pipelines.yml",
+    "Jenkinsfile",
+    ".buildkite",
+];
+
+/// Whole-repo orientation summary shown above the map tree.
+#[derive(Default)]
+struct RepoStats {
+    lang_lines: HashMap<String, u64>,
+    dir_lines: HashMap<String, u64>,
+    packages: usize,
+    test_files: usize,
+    entry_points: Vec<String>,
+    ci: Vec<String>,
+    docs: Vec<String>,
+}
+
+impl RepoStats {
+    fn collect(scope: &Path) -> Self {
+        let mut stats = RepoStats::default();
+
+        for entry in walker(scope, None).flatten() {
+            if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                continue;
+            }
+            let path = entry.path();
+            let rel = path.strip_prefix(scope).unwrap_or(path);
+            let name = rel.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+            if MANIFESTS.contains(&name) || name.ends_with(".csproj") {
+                stats.packages += 1;
+            }
+
+            let FileType::Code(lang) = detect_file_type(path) else {
+                continue;
+            };
+
+            let lines = std::fs::read(path)
+                .map_or(0, |buf| memchr::memchr_iter(b'\n', &buf).count() as u64);
+            *stats.lang_lines.entry(lang.to_string()).or_insert(0) += lines;
+
+            let mut components = rel.components();
+            if let (Some(top), Some(_)) = (components.next(), components.next()) {
+                let top = top.as_os_str().to_string_lossy().into_owned();
+                *stats.dir_lines.entry(top).or_insert(0) += lines;
+            }
+
+            if is_test_path(rel) {
+                stats.test_files += 1;
+            }
+
+            if ENTRY_FILES.contains(&name) && rel.components().count() <= 3 {
+                stats.entry_points.push(rel.display().to_string());
+            }
         }
 
-        let path = entry.path();
-        let rel = path.strip_prefix(scope).unwrap_or(path);
+        stats.ci = CI_PATHS
+            .iter()
+            .filter(|p| scope.join(p).exists())
+            .map(|p| (*p).to_string())
+            .collect();
 
-        // Skip if deeper than requested
-        let file_depth = rel.components().count().saturating_sub(1);
-        if file_depth > depth {
-            continue;
+        for doc in ["README.md", "README.rst", "README", "docs", "doc"] {
+            if scope.join(doc).exists() {
+                stats.docs.push(doc.to_string());
+            }
         }
 
-        let parent = rel.parent().unwrap_or(Path::new("")).to_path_buf();
-        let name = rel
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
+        stats.entry_points.sort();
+        stats
+    }
 
-        let meta = std::fs::metadata(path).ok();
-        let byte_len = meta.as_ref().map_or(0, std::fs::Metadata::len);
-        let tokens = estimate_tokens(byte_len);
+    fn format(&self) -> String {
+        let mut out = String::new();
 
-        let file_type = detect_file_type(path);
-        let symbols = match file_type {
-            FileType::Code(_) => {
-                let mtime = meta
-                    .and_then(|m| m.modified().ok())
-                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+        let total: u64 = self.lang_lines.values().sum();
+        if total > 0 {
+            let mut langs: Vec<_> = self.lang_lines.iter().collect();
+            langs.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let parts: Vec<String> = langs
+                .iter()
+                .take(6)
+                .map(|(lang, lines)| {
+                    format!(
+                        "{lang} {} ({}%)",
+                        short_count(**lines),
+                        **lines * 100 / total
+                    )
+                })
+                .collect();
+            let _ = writeln!(out, "Languages: {}", parts.join(", "));
+        }
 
-                let outline_str = cache.get_or_compute(path, mtime, || {
-                    let content = std::fs::read_to_string(path).unwrap_or_default();
-                    let buf = content.as_bytes();
-                    outline::generate(path, file_type, &content, buf, true)
-                });
+        if !self.dir_lines.is_empty() {
+            let mut dirs: Vec<_> = self.dir_lines.iter().collect();
+            dirs.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let parts: Vec<String> = dirs
+                .iter()
+                .take(5)
+                .map(|(dir, lines)| format!("{dir}/ {}", short_count(**lines)))
+                .collect();
+            let _ = writeln!(out, "Largest dirs: {}", parts.join(", "));
+        }
 
-                Some(extract_symbol_names(&outline_str))
+        let list_or_none = |items: &[String]| {
+            if items.is_empty() {
+                "none".to_string()
+            } else {
+                items.join(", ")
             }
-            _ => None,
         };
+        let _ = writeln!(
+            out,
+            "Packages: {} | Test files: {} | CI: {} | Docs: {}",
+            self.packages,
+            self.test_files,
+            list_or_none(&self.ci),
+            list_or_none(&self.docs),
+        );
+
+        if !self.entry_points.is_empty() {
+            let shown: Vec<&str> = self
+                .entry_points
+                .iter()
+                .take(8)
+                .map(String::as_str)
+                .collect();
+            let _ = writeln!(out, "Entry points: {}", shown.join(", "));
+        }
 
-        tree.entry(parent).or_default().push(FileEntry {
-            name,
-            symbols,
-            tokens,
-        });
+        out
     }
+}
 
-    let mut out = format!("# Map: {} (depth {})\n", scope.display(), depth);
-    format_tree(&tree, Path::new(""), 0, &mut out);
+/// Test files by the common naming conventions across languages.
+pub(crate) fn is_test_path(rel: &Path) -> bool {
+    let in_test_dir = rel.components().any(|c| {
+        matches!(
+            c.as_os_str().to_str(),
+            Some("test" | "tests" | "spec" | "__tests__")
+        )
+    });
+    let name = rel.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    in_test_dir
+        || name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.contains("_spec.")
+}
 
-    match budget {
-        Some(b) => crate::budget::apply(&out, b),
-        None => out,
+/// `12345` → `12.3k`.
+fn short_count(n: u64) -> String {
+    if n >= 1000 {
+        format!("{}.{}k", n / 1000, n % 1000 / 100)
+    } else {
+        n.to_string()
     }
 }
 
@@ -153,46 +536,132 @@ fn extract_name_from_sig(sig: &str) -> String {
         .collect()
 }
 
-fn format_tree(
-    tree: &BTreeMap<PathBuf, Vec<FileEntry>>,
-    dir: &Path,
-    indent: usize,
-    out: &mut String,
-) {
-    // Collect subdirectories that have entries
+/// Directories directly under `dir` that have entries, sorted.
+fn subdirs<'a>(tree: &'a BTreeMap<PathBuf, Vec<FileEntry>>, dir: &Path) -> Vec<&'a PathBuf> {
     let mut subdirs: Vec<&PathBuf> = tree
         .keys()
         .filter(|k| k.parent() == Some(dir) && *k != dir)
         .collect();
     subdirs.sort();
+    subdirs
+}
 
-    let prefix = "  ".repeat(indent);
-
-    // Show files in this directory
-    if let Some(files) = tree.get(dir) {
-        for f in files {
-            if let Some(ref symbols) = f.symbols {
-                if symbols.is_empty() {
-                    let _ = writeln!(out, "{prefix}{} (~{} tokens)", f.name, f.tokens);
-                } else {
-                    let syms = symbols.join(", ");
-                    let truncated = if syms.len() > 80 {
-                        format!("{}...", crate::types::truncate_str(&syms, 77))
-                    } else {
-                        syms
-                    };
-                    let _ = writeln!(out, "{prefix}{}: {truncated}", f.name);
-                }
+/// The files of directory `dir` (absolute), each with its CODEOWNERS owners
+/// when they differ from the directory's.
+fn format_files(files: &[FileEntry], prefix: &str, dir: &Path, out: &mut String) {
+    let dir_owners = crate::owners::codeowners(dir, true);
+    for f in files {
+        let owners = crate::owners::codeowners(&dir.join(&f.name), false)
+            .filter(|o| Some(o) != dir_owners.as_ref())
+            .map_or_else(String::new, |o| owners_suffix(&o));
+        if let Some(ref symbols) = f.symbols {
+            if symbols.is_empty() {
+                let _ = writeln!(out, "{prefix}{} (~{} tokens){owners}", f.name, f.tokens);
             } else {
-                let _ = writeln!(out, "{prefix}{} (~{} tokens)", f.name, f.tokens);
+                let syms = symbols.join(", ");
+                let truncated = if syms.len() > 80 {
+                    format!("{}...", crate::types::truncate_str(&syms, 77))
+                } else {
+                    syms
+                };
+                let _ = writeln!(out, "{prefix}{}: {truncated}{owners}", f.name);
             }
+        } else {
+            let _ = writeln!(out, "{prefix}{} (~{} tokens){owners}", f.name, f.tokens);
         }
     }
+}
+
+/// `  [owners: @a @b]`, or `  [unowned]` for a rule that names no one.
+fn owners_suffix(owners: &[String]) -> String {
+    if owners.is_empty() {
+        "  [unowned]".to_string()
+    } else {
+        format!("  [owners: {}]", owners.join(" "))
+    }
+}
+
+/// `dir/` at `indent`, then its files and subdirectories one level deeper.
+fn format_subdir(
+    tree: &BTreeMap<PathBuf, Vec<FileEntry>>,
+    dir: &Path,
+    indent: usize,
+    scope: &Path,
+    out: &mut String,
+) {
+    let prefix = "  ".repeat(indent);
+    let dir_name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("?");
+    let owners = crate::owners::codeowners(&scope.join(dir), true)
+        .map_or_else(String::new, |o| owners_suffix(&o));
+    let _ = writeln!(out, "{prefix}{dir_name}/{owners}");
+
+    if let Some(files) = tree.get(dir) {
+        format_files(files, &"  ".repeat(indent + 1), &scope.join(dir), out);
+    }
+    for subdir in subdirs(tree, dir) {
+        format_subdir(tree, subdir, indent + 1, scope, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_count_rounds_down_to_tenths() {
+        assert_eq!(short_count(999), "999");
+        assert_eq!(short_count(12_345), "12.3k");
+        assert_eq!(short_count(1_000), "1.0k");
+    }
+
+    #[test]
+    fn test_paths_detected() {
+        assert!(is_test_path(Path::new("tests/integration.rs")));
+        assert!(is_test_path(Path::new("src/foo_test.go")));
+        assert!(is_test_path(Path::new("web/app.spec.ts")));
+        assert!(is_test_path(Path::new("pkg/test_utils.py")));
+        assert!(!is_test_path(Path::new("src/contest.rs")));
+    }
+
+    #[test]
+    fn header_lists_languages_by_share() {
+        let mut stats = RepoStats::default();
+        stats.lang_lines.insert("Rust".into(), 3000);
+        stats.lang_lines.insert("Python".into(), 1000);
+        let header = stats.format();
+        assert!(
+            header.starts_with("Languages: Rust 3.0k (75%), Python 1.0k (25%)"),
+            "{header}"
+        );
+        assert!(header.contains("CI: none"), "{header}");
+    }
+
+    #[test]
+    fn sections_follow_top_level_dirs() {
+        assert_eq!(section_key(Path::new("README.md"), true), Path::new(""));
+        assert_eq!(section_key(Path::new(""), false), Path::new(""));
+        assert_eq!(section_key(Path::new("src"), false), Path::new("src"));
+        assert_eq!(section_key(Path::new("src/a/b.rs"), true), Path::new("src"));
+    }
+
+    #[test]
+    fn budget_is_shared_across_top_level_dirs() {
+        let big: String = std::iter::once("big/\n".to_string())
+            .chain((0..200).map(|i| format!("  file{i:03}.rs: run, stop\n")))
+            .collect();
+        let late = "zeta/\n  last.rs: only\n";
+        let sections = ["README.md (~40 tokens)\n", &big, late];
+
+        let fitted = fit_sections(&sections, 300);
+        assert!(fitted.starts_with("README.md"), "{fitted}");
+        assert!(fitted.contains("big/\n  file000.rs"), "{fitted}");
+        assert!(fitted.contains("more lines\n"), "{fitted}");
+        assert!(fitted.ends_with(late), "{fitted}");
+        assert!(
+            tokens::estimate(fitted.as_bytes(), FileType::Other) <= 300,
+            "{fitted}"
+        );
 
-    // Recurse into subdirectories
-    for subdir in subdirs {
-        let dir_name = subdir.file_name().and_then(|n| n.to_str()).unwrap_or("?");
-        let _ = writeln!(out, "{prefix}{dir_name}/");
-        format_tree(tree, subdir, indent + 1, out);
+        assert_eq!(fit_sections(&sections, 100_000), sections.concat());
     }
 }
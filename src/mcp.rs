@@ -1,15 +1,18 @@
+use std::borrow::Cow;
 use std::fmt::Write as _;
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::cache::OutlineCache;
+use crate::error::{ErrorCode, TilthError};
 use crate::index::bloom::BloomFilterCache;
-use crate::index::SymbolIndex;
-use crate::session::Session;
+use crate::index::{IndexConfig, SymbolIndex};
+use crate::session::{Crumb, PerfCounters, Session};
+use crate::verbosity::Verbosity;
 
 // Sent to the LLM via the MCP `instructions` field during initialization.
 // Keeps the strategic guidance from AGENTS.md available to any host.
@@ -18,7 +21,15 @@ tilth — code intelligence MCP server. Replaces grep, cat, find, ls with AST-aw
 \n\
 tilth_search: Find symbol definitions, usages, and callers. Replaces grep/rg for code navigation.\n\
   Comma-separated symbols for multi-symbol lookup (max 5).\n\
-  kind: \"symbol\" (default) | \"content\" (strings/comments) | \"callers\" (call sites)\n\
+  kind: \"symbol\" (default) | \"content\" (strings/comments) | \"strings\" (string literals only) | \"callers\" (call sites) | \"graph\" (transitive callers/callees as a tree, DOT or JSON) | \"complete\" (names by prefix/substring) | \"describe\" (definitions matching a plain-language description; embeddings builds only) | \"docs\" (markdown and doc comments) | \"error\" (where a pasted error message is raised) | \"log\" (logging calls that write a JSON or key=value log line) | \"feature\" (Cargo.toml files that enable a Cargo feature, e.g. serde/derive) | \"files\" (glob) | \"auto\" (picked from the query)\n\
+  Glob-shaped queries (`*.rs`, `src/**/handler*`) return matching files.\n\
+  Punctuated queries (`Vec<u8>`, `#[derive(Serialize)]`) return exact-text matches, then the identifier inside as a symbol.\n\
+  Qualified names (`rank::sort`, `com.foo.Bar#method`) search the last segment, kept where the path or enclosing type matches the rest.\n\
+  in_comments: \"exclude\" | \"only\" — content/regex hits outside or inside comments and strings.\n\
+  scopes: [\"app/src\", {\"path\": \"shared\", \"weight\": 2}] → one ranked result across directories, matches shared by weight.\n\
+  owners: true → each match header ends with its CODEOWNERS owners (or top committer, marked (git)).\n\
+  sourcemaps: true → matches in built files with a source map also name the original source line.\n\
+  max_definitions, max_usages, max_total (default 10): how many matches come back.\n\
   expand (default 2): inline full source for top matches.\n\
   context: path to file being edited — boosts nearby results.\n\
   Output per match:\n\
@@ -29,12 +40,18 @@ tilth_search: Find symbol definitions, usages, and callers. Replaces grep/rg for
       <name>  <path>:<start>-<end>  <signature>\n\
     ── siblings ──\n\
       <name>  <path>:<start>-<end>  <signature>\n\
+  Rust trait methods are tagged [default in trait <T>] or [override in impl <T> for <X>] instead of [definition].\n\
   Re-expanding a previously shown definition returns [shown earlier].\n\
+  Each match header carries a breadcrumb like [S12.3] — pass it as tilth_read path (reads the match), tilth_edit path, or search context instead of repeating path:line.\n\
 \n\
 tilth_read: Read file content with smart outlining. Replaces cat/head/tail.\n\
   Small files → full content. Large files → structural outline.\n\
-  section: \"<start>-<end>\" or \"<heading text>\"\n\
+  section: \"<start>-<end>\", \"<line>:<from>-<to>\" (byte columns of one long line), \"<heading text>\" or an XML element path \"project/dependencies\" (\"dependency[2]\" for the second)\n\
   paths: read multiple files in one call.\n\
+  symbols: true → every definition as JSON records, no outline formatting.\n\
+  snippet: <line> → the enclosing function plus the same-file types and members it uses, nothing else.\n\
+  query: \"field=value\" or text → the rows of a JSON array of objects that match (shown as a table; its first rows lead the outline).\n\
+    On a lockfile: a package name → its locked versions and what requires it (the lockfile's read is a summary: direct deps, duplicates).\n\
   Output:\n\
     <line_number> │ <content>                  ← full/section mode\n\
     [<start>-<end>]  <symbol name>             ← outline mode\n\
@@ -42,6 +59,23 @@ tilth_read: Read file content with smart outlining. Replaces cat/head/tail.\n\
 tilth_files: Find files by glob pattern. Replaces find/ls.\n\
   Output: <path>  (~<token_count> tokens). Respects .gitignore.\n\
 \n\
+tilth_lookup: Any string — path, glob, symbol or sentence — routed to read, files, symbol or content search. Header: `# Lookup: \"<query>\" → <route> — <why>`.\n\
+\n\
+tilth_diff: Compare two files, or two sections of one file. Unified diff.\n\
+  a, b (default: a), a_section, b_section (same syntax as tilth_read section).\n\
+\n\
+tilth_changes: Working tree vs HEAD — changed files with line counts, and the symbols added (+), removed (-) or modified (~) in each. Start here when picking up work in progress.\n\
+  review: true (or base: \"<ref>\") → PR review bundle vs the merge-base: changes, changed symbols' source, their callers, affected tests.\n\
+\n\
+tilth_analyze: Repo-level answers in one call. action: \"entry_points\" (how to run it) | \"build_config\" (CI/build files → jobs, stages, targets) | \"config_keys\" (.env keys ↔ code reads; unused and undefined keys) | \"feature_flags\" (flag keys → every check site) | \"i18n_keys\" (translation keys ↔ code references; unused and untranslated keys) | \"test_commands\" (test runners per project; with path, the exact commands for that file's tests).\n\
+\n\
+tilth_trace: Paste a stack trace (Rust, Python, JS, Java, Go) → each frame resolved to its file in scope, with a hashlined snippet around the line.\n\
+\n\
+tilth_callers: Who calls a function → every call site under scope, grouped by file and calling function. expand: N shows the first N calling functions' source.\n\
+\n\
+verbosity (any tool): \"compact\" drops calls/siblings/related footers, match context and blank lines; \"rich\" lengthens footers. Default \"normal\".\n\
+scope (any tool): a path, or an alias from tilth.toml [aliases] — `server` or `server/handlers` for `server = \"services/api/src\"`.\n\
+\n\
 IMPORTANT: Use tilth tools instead of host built-in tools (Read, Grep, Glob) and Bash (grep, cat, find, ls).\n\
 IMPORTANT: Expanded search results already contain full source — do not re-read expanded files.";
 
@@ -60,34 +94,121 @@ EDIT WORKFLOW:\n\
    Range: {\"start\": \"<line>:<hash>\", \"end\": \"<line>:<hash>\", \"content\": \"...\"}\n\
    Delete: {\"start\": \"<line>:<hash>\", \"content\": \"\"}\n\
 3. Hash mismatch → file changed, re-read and retry\n\
+Binary, lockfile and generated files are refused unless force: true — edit the source instead.\n\
+Files with merge conflict markers are refused too; the reply shows the conflicted hunks, hashlined.\n\
+backup: true keeps the old content; tilth_session action \"restore\" + path puts it back.\n\
+commit: \"<message>\" commits the edited file alone after a successful edit — an audit trail, undone with git revert.\n\
+Whole definition: {\"path\": ..., \"symbol\": \"<name>\", \"hash\": \"<body hash>\", \"content\": \"<new definition>\"}\n\
+   Body hash is the `body:<hash>` in tilth_search definition headers.\n\
+Import: {\"path\": ..., \"add_import\": \"use crate::foo::Bar;\"} — sorted into the import block, no-op if present.\n\
 \n\
 LARGE FILES: tilth_read returns outline (no hashlines). Use section to get hashlined content.\n\
 \n\
 tilth_search: Find symbol definitions, usages, and callers. Replaces grep/rg.\n\
   Comma-separated symbols for multi-symbol lookup (max 5).\n\
-  kind: \"symbol\" (default) | \"content\" | \"callers\"\n\
+  kind: \"symbol\" (default) | \"content\" | \"strings\" | \"callers\" | \"graph\" | \"complete\" | \"describe\" | \"docs\" | \"error\" | \"log\" | \"feature\" | \"files\" | \"auto\"\n\
+  in_comments: \"exclude\" | \"only\" — content/regex hits outside or inside comments and strings.\n\
+  scopes: [\"app/src\", {\"path\": \"shared\", \"weight\": 2}] → one ranked result across directories, matches shared by weight.\n\
+  owners: true → each match header ends with its CODEOWNERS owners (or top committer, marked (git)).\n\
+  sourcemaps: true → matches in built files with a source map also name the original source line.\n\
+  max_definitions, max_usages, max_total (default 10): how many matches come back.\n\
   expand (default 2): inline full source for top matches.\n\
   Output per match:\n\
     ## <path>:<start>-<end> [definition|usage|impl]\n\
     <expanded source block>\n\
     ── calls ──\n\
       <name>  <path>:<start>-<end>  <signature>\n\
+  Trait methods: [default in trait <T>] | [override in impl <T> for <X>].\n\
   Re-expanding a shown definition returns [shown earlier].\n\
+  Expanded lines are hashlined (`<line>:<hash>|<content>`) — pass them to tilth_edit without a read.\n\
+  Match headers carry a breadcrumb like [S12.3] — usable as tilth_read/tilth_edit path or search context.\n\
 \n\
 tilth_read: Read files. Replaces cat/head/tail.\n\
-  section: \"<start>-<end>\" or \"<heading text>\". paths: multiple files in one call.\n\
+  section: \"<start>-<end>\", \"<line>:<from>-<to>\" (byte columns), \"<heading text>\" or XML element path \"project/dependencies\". paths: multiple files in one call.\n\
+  symbols: true → every definition as JSON records. snippet: <line> → enclosing function + the types it uses.\n\
+  query: \"field=value\" or text → matching rows of a JSON array of objects, as a table; on a lockfile, a package name → its versions.\n\
 \n\
 tilth_files: Find files by glob. Replaces find/ls.\n\
 \n\
+tilth_lookup: Any string routed to read, files, symbol or content search; the header says which.\n\
+\n\
+tilth_diff: Compare two files or sections. `+` and context lines carry b's anchors — usable by tilth_edit.\n\
+\n\
+tilth_changes: Working tree vs HEAD — changed files and the symbols added, removed or modified in each. review: true or base → PR review bundle.\n\
+\n\
+tilth_trace: Stack trace → each frame's file in scope with a hashlined snippet — anchors usable by tilth_edit.\n\
+\n\
+tilth_callers: Call sites of a function, grouped by file and caller. expand: N → hashlined caller bodies.\n\
+\n\
+verbosity (any tool): \"compact\" | \"normal\" | \"rich\" — how much footer and context detail comes back.\n\
+scope (any tool): a path, or a tilth.toml alias (`server`, `server/handlers`).\n\
+\n\
 IMPORTANT: Expanded search results already contain full source — do not re-read expanded files.";
 
-/// MCP server over stdio. When `edit_mode` is true, exposes `tilth_edit` and
-/// switches `tilth_read` to hashline output format.
-pub fn run(edit_mode: bool) -> io::Result<()> {
-    let cache = OutlineCache::new();
-    let session = Session::new();
-    let symbol_index = Arc::new(SymbolIndex::new());
+/// How [`run`] serves a session.
+#[derive(Debug, Default)]
+pub struct ServerOptions {
+    /// Expose `tilth_edit` and switch `tilth_read` to hashline output.
+    pub edit_mode: bool,
+    /// What the session's symbol index records.
+    pub index: IndexConfig,
+    /// Becomes the working directory before anything else runs, so relative
+    /// paths, the default `"."` scope, the outline cache and edit backups all
+    /// resolve against it rather than wherever the host happened to launch
+    /// the server.
+    pub workspace: Option<PathBuf>,
+    /// Tokens the session sends before tools turn terse (see
+    /// [`Session::over_budget`]).
+    pub session_budget: Option<u64>,
+    /// File every path a response discloses is appended to, as a JSON line
+    /// (see [`Session::disclose`]).
+    pub audit_log: Option<PathBuf>,
+    /// Update the index and Bloom filters as workspace files change (see
+    /// [`crate::index::watch`]).
+    pub watch: bool,
+}
+
+/// MCP server over stdio, configured by `options`.
+pub fn run(options: ServerOptions) -> io::Result<()> {
+    let ServerOptions {
+        edit_mode,
+        index,
+        workspace,
+        session_budget,
+        audit_log,
+        watch,
+    } = options;
+    // Opened before the workspace change, so a relative path means what it said
+    let audit_log = audit_log
+        .map(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("audit log {}: {e}", path.display())))
+        })
+        .transpose()?;
+    if let Some(dir) = workspace {
+        std::env::set_current_dir(&dir)
+            .map_err(|e| io::Error::new(e.kind(), format!("workspace {}: {e}", dir.display())))?;
+    }
+    let cache = std::env::current_dir().map_or_else(
+        |_| OutlineCache::new(),
+        |cwd| OutlineCache::persistent(&cwd),
+    );
+    let session = Session::with_edit_mode(edit_mode)
+        .with_token_budget(session_budget)
+        .with_audit_log(audit_log);
+    let symbol_index = Arc::new(SymbolIndex::with_config(index));
     let bloom_cache = Arc::new(BloomFilterCache::new());
+    // Held for the session. Without it — not asked for, or the platform
+    // refused — lookups still catch changed files by mtime.
+    let _watcher = watch
+        .then(|| {
+            let root = crate::pathnorm::canonical(&std::env::current_dir().ok()?);
+            crate::index::watch(&root, Arc::clone(&symbol_index), Arc::clone(&bloom_cache)).ok()
+        })
+        .flatten();
     let stdin = io::stdin();
     let stdout = io::stdout();
     let mut stdout = stdout.lock();
@@ -153,6 +274,51 @@ struct JsonRpcError {
     message: String,
 }
 
+/// A failed tool call: what the agent reads, and a stable code for clients
+/// that branch on the kind of failure. Bad arguments are `INVALID_QUERY`.
+#[derive(Debug)]
+pub(crate) struct ToolError {
+    code: ErrorCode,
+    message: String,
+}
+
+impl ToolError {
+    fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn io(e: &io::Error) -> Self {
+        Self::new(ErrorCode::Io, e.to_string())
+    }
+}
+
+impl From<TilthError> for ToolError {
+    fn from(e: TilthError) -> Self {
+        Self::new(e.code(), e.to_string())
+    }
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::InvalidQuery, message)
+    }
+}
+
+impl From<&str> for ToolError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::InvalidQuery, message)
+    }
+}
+
+impl std::fmt::Display for ToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 fn handle_request(
     req: &JsonRpcRequest,
     cache: &OutlineCache,
@@ -163,11 +329,21 @@ fn handle_request(
 ) -> JsonRpcResponse {
     match req.method.as_str() {
         "initialize" => {
-            let instructions = if edit_mode {
+            let mut instructions = if edit_mode {
                 EDIT_MODE_INSTRUCTIONS
             } else {
                 SERVER_INSTRUCTIONS
-            };
+            }
+            .to_string();
+            // The workspace's own aliases, so they can be used from the start
+            let aliases = crate::config::aliases();
+            if !aliases.is_empty() {
+                let listed: Vec<String> = aliases
+                    .iter()
+                    .map(|(name, path)| format!("{name} → {path}"))
+                    .collect();
+                let _ = write!(instructions, "\n\nScope aliases: {}", listed.join(", "));
+            }
             JsonRpcResponse {
                 jsonrpc: "2.0",
                 id: req.id.clone(),
@@ -230,16 +406,148 @@ pub(crate) fn dispatch_tool(
     index: &Arc<SymbolIndex>,
     bloom: &Arc<BloomFilterCache>,
     edit_mode: bool,
-) -> Result<String, String> {
-    match tool {
+) -> Result<String, ToolError> {
+    session.next_turn();
+    let redaction = crate::redact::rules().map_err(|e| {
+        ToolError::new(
+            ErrorCode::Parse,
+            format!("{e} — no tool runs until the redaction rules load"),
+        )
+    })?;
+    if let Some(path) = redacted_arg(args, &redaction) {
+        return Err(TilthError::Redacted { path }.into());
+    }
+    let started = std::time::Instant::now();
+    let terse = tool != "tilth_session" && session.over_budget();
+    let verbosity = match args.get("verbosity").and_then(|v| v.as_str()) {
+        None => Verbosity::Normal,
+        Some(s) => Verbosity::parse(s)
+            .ok_or_else(|| format!("unknown verbosity: {s}. Use: compact, normal, rich"))?,
+    };
+    let _verbosity = crate::verbosity::Level::set(verbosity);
+    let result = match tool {
         "tilth_read" => tool_read(args, cache, session, edit_mode),
-        "tilth_search" => tool_search(args, cache, session, index, bloom),
+        "tilth_search" => with_timeout(args, || tool_search(args, cache, session, index, bloom)),
         "tilth_files" => tool_files(args, cache),
+        "tilth_lookup" => with_timeout(args, || tool_lookup(args, cache, session, edit_mode)),
+        "tilth_diff" => tool_diff(args, session),
+        "tilth_changes" => with_timeout(args, || tool_changes(args, index, bloom)),
+        "tilth_analyze" => with_timeout(args, || tool_analyze(args)),
+        "tilth_trace" => with_timeout(args, || tool_trace(args)),
+        "tilth_callers" => with_timeout(args, || tool_callers(args, cache, session, index, bloom)),
         "tilth_map" => Err("tilth_map is disabled — use tilth_search instead".into()),
-        "tilth_session" => tool_session(args, session),
+        "tilth_session" => tool_session(args, session, cache, index, bloom, edit_mode),
         "tilth_edit" if edit_mode => tool_edit(args, session),
-        _ => Err(format!("unknown tool: {tool}")),
+        _ => return Err(format!("unknown tool: {tool}").into()),
+    };
+    session.record_call(tool, started.elapsed());
+    // These show code from anywhere under their scope: audited at that level
+    if result.is_ok()
+        && matches!(
+            tool,
+            "tilth_search"
+                | "tilth_files"
+                | "tilth_lookup"
+                | "tilth_changes"
+                | "tilth_analyze"
+                | "tilth_trace"
+                | "tilth_callers"
+        )
+    {
+        match resolve_scopes(args) {
+            Ok(Some(scopes)) => {
+                for scope in &scopes {
+                    session.disclose(&scope.path, "scope", None);
+                }
+            }
+            _ => session.disclose(&resolve_scope(args), "scope", None),
+        }
     }
+    result.map(|output| {
+        let output = verbosity.shape(output);
+        let output = match redaction.mask(&output) {
+            Cow::Borrowed(_) => output,
+            Cow::Owned(masked) => masked,
+        };
+        let output = if terse {
+            terse_output(output, tool, args, session)
+        } else {
+            output
+        };
+        session.record_output(&output);
+        output
+    })
+}
+
+/// A path argument — `path`, `paths`, `a` or `b` — the redaction rules hide.
+fn redacted_arg(args: &Value, rules: &crate::redact::Rules) -> Option<PathBuf> {
+    let named = ["path", "a", "b"]
+        .into_iter()
+        .filter_map(|key| args.get(key)?.as_str());
+    let listed = args
+        .get("paths")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    named
+        .chain(listed)
+        .map(PathBuf::from)
+        .find(|path| rules.hides(path))
+}
+
+/// Response cap, in tokens, once the session is over its budget — unless the
+/// call passes its own `budget`. Reads apply it themselves, before dedupe
+/// records what they sent.
+const TERSE_RESPONSE_TOKENS: u64 = 1_500;
+
+/// `output` cut down for a session over its token budget, with a note saying
+/// why.
+fn terse_output(output: String, tool: &str, args: &Value, session: &Session) -> String {
+    let mut output = if tool == "tilth_read" || args.get("budget").is_some() {
+        output
+    } else {
+        crate::budget::apply(&output, TERSE_RESPONSE_TOKENS)
+    };
+    let _ = write!(
+        output,
+        "\n\n> Session over its token budget ({}): search expansion is off, facets and responses are capped. tilth_session action \"reset\" clears the count.",
+        session.token_usage()
+    );
+    output
+}
+
+/// Run a tool under the optional `timeout_ms` argument. File walks stop
+/// once it passes; the output gathered so far is returned with a marker.
+fn with_timeout(
+    args: &Value,
+    run: impl FnOnce() -> Result<String, ToolError>,
+) -> Result<String, ToolError> {
+    let Some(ms) = args.get("timeout_ms").and_then(serde_json::Value::as_u64) else {
+        return run();
+    };
+    let deadline = crate::deadline::Deadline::new(std::time::Duration::from_millis(ms));
+    let mut output = deadline.run(run).map_err(|e| {
+        // Nothing to show because the walk was cut short, not because there's nothing
+        if deadline.expired() {
+            ToolError::new(
+                ErrorCode::Timeout,
+                format!(
+                    "{} (stopped after {ms}ms — narrow `scope` or raise `timeout_ms`)",
+                    e.message
+                ),
+            )
+        } else {
+            e
+        }
+    })?;
+    if deadline.expired() {
+        let _ = write!(
+            output,
+            "\n\n> partial due to timeout: stopped after {ms}ms, only files reached by then are included. Narrow `scope` or raise `timeout_ms` for complete results."
+        );
+    }
+    Ok(output)
 }
 
 fn tool_read(
@@ -247,29 +555,56 @@ fn tool_read(
     cache: &OutlineCache,
     session: &Session,
     edit_mode: bool,
-) -> Result<String, String> {
-    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+) -> Result<String, ToolError> {
+    // Over the session budget the response is capped anyway; cap it here, so
+    // dedupe only records reads the client gets whole
+    let budget = args
+        .get("budget")
+        .and_then(serde_json::Value::as_u64)
+        .or_else(|| session.over_budget().then_some(TERSE_RESPONSE_TOKENS));
+    let dedupe = args
+        .get("dedupe")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
 
     // Multi-file batch read (capped at 20 to bound I/O)
     if let Some(paths_arr) = args.get("paths").and_then(|v| v.as_array()) {
         if paths_arr.len() > 20 {
-            return Err(format!(
-                "batch read limited to 20 files (got {})",
-                paths_arr.len()
+            return Err(ToolError::new(
+                ErrorCode::TooLarge,
+                format!("batch read limited to 20 files (got {})", paths_arr.len()),
             ));
         }
         let mut results = Vec::with_capacity(paths_arr.len());
+        // Reads sent as content, by key and position in `results`
+        let mut sent = Vec::new();
         for p in paths_arr {
             let path_str = p.as_str().ok_or("paths must be an array of strings")?;
-            let path = PathBuf::from(path_str);
+            let (path, section) = match breadcrumb(path_str, session)? {
+                Some(crumb) => (crumb.path.clone(), Some(crumb_section(&crumb))),
+                None => (PathBuf::from(path_str), None),
+            };
+            let section = section.as_deref();
             session.record_read(&path);
-            match crate::read::read_file(&path, None, false, cache, edit_mode) {
-                Ok(output) => results.push(output),
+            match crate::read::read_file(&path, section, false, cache, edit_mode) {
+                Ok(output) => {
+                    disclose_read(session, &path, section, false);
+                    let key = read_key(&path, section);
+                    if let Some(notice) = dedupe_read(session, &key, &path, &output, dedupe) {
+                        results.push(notice);
+                    } else {
+                        sent.push((key, results.len()));
+                        results.push(output);
+                    }
+                }
                 Err(e) => results.push(format!("# {} — error: {}", path.display(), e)),
             }
         }
-        let combined = results.join("\n\n");
-        return Ok(apply_budget(combined, budget));
+        let response = apply_budget(results.join("\n\n"), budget);
+        for (key, i) in sent {
+            record_sent(session, &key, &results[i], &response);
+        }
+        return Ok(response);
     }
 
     // Single file read
@@ -277,32 +612,124 @@ fn tool_read(
         .get("path")
         .and_then(|v| v.as_str())
         .ok_or("missing required parameter: path (or use paths for batch read)")?;
-    let path = PathBuf::from(path_str);
-    let section = args.get("section").and_then(|v| v.as_str());
+    let crumb = breadcrumb(path_str, session)?;
+    let path = crumb
+        .as_ref()
+        .map_or_else(|| PathBuf::from(path_str), |c| c.path.clone());
+
+    if let Some(query) = args.get("query").and_then(|v| v.as_str()) {
+        session.record_read(&path);
+        let lockfile = crate::read::generated::is_generated_by_name(
+            path.file_name().and_then(|n| n.to_str()).unwrap_or(""),
+        );
+        let output = if lockfile {
+            crate::read::lockfile::package(&path, query)?
+        } else {
+            crate::read::records::records(&path, query)?
+        };
+        session.disclose(&path, "records", Some(query.to_string()));
+        return Ok(apply_budget(output, budget));
+    }
+
+    if args
+        .get("symbols")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+    {
+        session.record_read(&path);
+        let output = crate::read::symbols::file_symbols(&path)?;
+        session.disclose(&path, "symbols", None);
+        return Ok(apply_budget(output, budget));
+    }
+
+    if let Some(line) = args.get("snippet").and_then(serde_json::Value::as_u64) {
+        session.record_read(&path);
+        let output = crate::read::snippet::snippet(&path, line as u32)?;
+        session.disclose(&path, "snippet", Some(line.to_string()));
+        return Ok(apply_budget(output, budget));
+    }
+
     let full = args
         .get("full")
         .and_then(serde_json::Value::as_bool)
         .unwrap_or(false);
+    // A breadcrumb reads the match itself unless told otherwise
+    let crumb_section = crumb.filter(|_| !full).map(|c| crumb_section(&c));
+    let section = args
+        .get("section")
+        .and_then(|v| v.as_str())
+        .or(crumb_section.as_deref());
 
     session.record_read(&path);
-    let mut output = crate::read::read_file(&path, section, full, cache, edit_mode)
-        .map_err(|e| e.to_string())?;
+    let output = crate::read::read_file(&path, section, full, cache, edit_mode)?;
+    disclose_read(session, &path, section, full);
+    let key = read_key(&path, section);
+    let notice = dedupe_read(session, &key, &path, &output, dedupe);
+    let mut shown = notice.clone().unwrap_or_else(|| output.clone());
 
     // Append related-file hint for outlined code files (not section reads, not batch).
-    if section.is_none() && crate::read::would_outline(&path) {
+    if section.is_none()
+        && crate::verbosity::current().footers()
+        && crate::read::would_outline(&path)
+    {
         let related = crate::read::imports::resolve_related_files(&path);
         if !related.is_empty() {
-            output.push_str("\n\n> Related: ");
+            shown.push_str("\n\n> Related: ");
             for (i, p) in related.iter().enumerate() {
                 if i > 0 {
-                    output.push_str(", ");
+                    shown.push_str(", ");
                 }
-                let _ = write!(output, "{}", p.display());
+                let _ = write!(shown, "{}", p.display());
             }
         }
     }
 
-    Ok(apply_budget(output, budget))
+    let response = apply_budget(shown, budget);
+    if notice.is_none() {
+        record_sent(session, &key, &output, &response);
+    }
+    Ok(response)
+}
+
+/// Add a read to the audit trail: the section, else the whole file or its
+/// outline.
+fn disclose_read(session: &Session, path: &Path, section: Option<&str>, full: bool) {
+    let kind = match section {
+        Some(_) => "section",
+        None if !full && crate::read::would_outline(path) => "outline",
+        None => "read",
+    };
+    session.disclose(path, kind, section.map(str::to_string));
+}
+
+/// What dedupe tracks a read by: its canonical path and section.
+fn read_key(path: &Path, section: Option<&str>) -> String {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    format!("{}#{}", canonical.display(), section.unwrap_or(""))
+}
+
+/// With `dedupe`, the one-line notice that replaces `output` when identical
+/// content was already sent in full this session.
+fn dedupe_read(
+    session: &Session,
+    key: &str,
+    path: &Path,
+    output: &str,
+    dedupe: bool,
+) -> Option<String> {
+    let turn = session.was_sent(key, output).filter(|_| dedupe)?;
+    Some(format!(
+        "# {} — unchanged since turn {turn}, earlier output and anchors still valid",
+        path.display()
+    ))
+}
+
+/// Record `read` as sent if `response` carries it whole. A read the budget
+/// cut stays unrecorded, so dedupe never vouches for content only seen in part.
+fn record_sent(session: &Session, key: &str, read: &str, response: &str) {
+    if response.contains(read) {
+        session.record_sent(key, read);
+    }
 }
 
 fn tool_search(
@@ -311,7 +738,7 @@ fn tool_search(
     session: &Session,
     index: &Arc<SymbolIndex>,
     bloom: &Arc<BloomFilterCache>,
-) -> Result<String, String> {
+) -> Result<String, ToolError> {
     let query = args
         .get("query")
         .and_then(|v| v.as_str())
@@ -325,12 +752,102 @@ fn tool_search(
         .get("expand")
         .and_then(serde_json::Value::as_u64)
         .unwrap_or(2) as usize;
-    let context_path = args
-        .get("context")
-        .and_then(|v| v.as_str())
-        .map(PathBuf::from);
+    let context_path = match args.get("context").and_then(|v| v.as_str()) {
+        Some(c) => Some(match breadcrumb(c, session)? {
+            Some(crumb) => crumb.path,
+            None => crate::pathnorm::canonical(Path::new(c)),
+        }),
+        None => None,
+    };
     let context = context_path.as_deref();
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let _owners = crate::owners::Annotate::set(
+        args.get("owners")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+    );
+    let _sourcemaps = crate::sourcemap::Translate::set(
+        args.get("sourcemaps")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+    );
+    let _limits = crate::search::limits::Scoped::set(search_limits(args)?);
+    let in_comments = match args.get("in_comments").and_then(|v| v.as_str()) {
+        None => crate::search::Within::Anywhere,
+        Some(s) => crate::search::Within::from_in_comments(s)
+            .ok_or_else(|| format!("unknown in_comments: {s}. Use: include, exclude, only"))?,
+    };
+
+    let auto = kind == "auto";
+    let (kind, query) = match kind {
+        "auto" => match crate::classify::search_kind(query) {
+            "regex" => ("regex", &query[1..query.len() - 1]),
+            routed => (routed, query),
+        },
+        _ => (kind, query),
+    };
+    // The header says where `auto` sent the query.
+    let routed = |output: String| {
+        if !auto {
+            return output;
+        }
+        match output.split_once('\n') {
+            Some((head, rest)) => format!("{head} [auto: {kind}]\n{rest}"),
+            None => format!("{output} [auto: {kind}]"),
+        }
+    };
+
+    if let Some(scopes) = resolve_scopes(args)? {
+        if !matches!(kind, "symbol" | "content" | "strings" | "regex") {
+            return Err(format!(
+                "scopes works with kind symbol, content, strings and regex, not {kind}"
+            )
+            .into());
+        }
+        if kind == "symbol" && (query.contains(',') || crate::classify::is_glob(query)) {
+            return Err("scopes takes one symbol name per search".into());
+        }
+        let search = |scope: &Path| -> Result<crate::types::SearchResult, TilthError> {
+            match kind {
+                "symbol" => match crate::search::qualified::try_search(query, scope, context)? {
+                    Some(result) => Ok(result),
+                    None => crate::search::symbol::search(query, scope, context),
+                },
+                "content" => {
+                    let (pattern, is_regex) = crate::search::parse_pattern(query);
+                    crate::search::content::search(pattern, scope, is_regex, context, in_comments)
+                }
+                "strings" => crate::search::content::search(
+                    query,
+                    scope,
+                    false,
+                    context,
+                    crate::search::Within::Strings,
+                ),
+                _ => crate::search::content::search(query, scope, true, context, in_comments),
+            }
+        };
+        session.record_search(query);
+        let output = crate::search::scopes::search_expanded(
+            query, &scopes, search, cache, session, index, bloom, expand,
+        )?;
+        return Ok(apply_budget(routed(output), budget));
+    }
+
+    // Symbol search can't match `*.rs` or `src/**/handler*` — answer with
+    // the files view instead of an empty result.
+    if kind == "files" || (kind == "symbol" && crate::classify::is_glob(query)) {
+        let output = crate::search::search_glob(query, &scope, cache)?;
+        let output = if kind == "files" {
+            output
+        } else {
+            format!(
+                "{output}\n\n> Query looks like a glob, so matching files are shown. \
+                 Use `kind: \"content\"` to search for it as text."
+            )
+        };
+        return Ok(apply_budget(routed(output), budget));
+    }
 
     let output = match kind {
         "symbol" => {
@@ -356,41 +873,148 @@ fn tool_search(
                     )
                 }
                 _ => {
-                    return Err(format!(
-                        "multi-symbol search limited to 5 queries (got {})",
-                        queries.len()
+                    return Err(ToolError::new(
+                        ErrorCode::TooLarge,
+                        format!(
+                            "multi-symbol search limited to 5 queries (got {})",
+                            queries.len()
+                        ),
                     ))
                 }
             }
         }
         "content" => {
             session.record_search(query);
-            crate::search::search_content_expanded(query, &scope, cache, session, expand, context)
+            crate::search::search_content_expanded(
+                query,
+                &scope,
+                cache,
+                session,
+                bloom,
+                expand,
+                context,
+                in_comments,
+            )
         }
         "regex" => {
             session.record_search(query);
-            let result = crate::search::content::search(query, &scope, true, context)
-                .map_err(|e| e.to_string())?;
+            let result =
+                crate::search::content::search(query, &scope, true, context, in_comments)?;
             crate::search::format_content_result(&result, cache)
         }
+        "strings" => {
+            session.record_search(query);
+            crate::search::search_content_expanded(
+                query,
+                &scope,
+                cache,
+                session,
+                bloom,
+                expand,
+                context,
+                crate::search::Within::Strings,
+            )
+        }
         "callers" => {
             session.record_search(query);
             crate::search::callers::search_callers_expanded(
-                query, &scope, cache, session, bloom, expand, context,
+                query, &scope, cache, session, bloom, index, expand, context,
             )
         }
+        "graph" => {
+            let roots: Vec<&str> = query
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .collect();
+            if roots.is_empty() {
+                return Err("missing required parameter: query".into());
+            }
+            let direction = match args.get("direction").and_then(|v| v.as_str()) {
+                None => crate::search::graph::Direction::Both,
+                Some(s) => crate::search::graph::Direction::parse(s).ok_or_else(|| {
+                    format!("unknown direction: {s}. Use: callees, callers, both")
+                })?,
+            };
+            let depth = args
+                .get("depth")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(3)
+                .clamp(1, 6) as u32;
+            for root in &roots {
+                session.record_search(root);
+            }
+            let graph = crate::search::graph::build_call_graph(
+                &scope, &roots, direction, depth, cache, bloom, index,
+            )?;
+            match args.get("format").and_then(|v| v.as_str()).unwrap_or("tree") {
+                "tree" => Ok(graph.tree(&scope)),
+                "dot" => Ok(graph.dot(&scope)),
+                "json" => Ok(graph.json(&scope)),
+                other => {
+                    return Err(format!("unknown format: {other}. Use: tree, dot, json").into())
+                }
+            }
+        }
+        "complete" => {
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(20) as usize;
+            Ok(crate::search::complete_symbols(query, &scope, index, limit))
+        }
+        "describe" => {
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(10) as usize;
+            session.record_search(query);
+            crate::search::describe::search(query, &scope, limit)
+        }
+        "docs" => {
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(10) as usize;
+            session.record_search(query);
+            crate::search::docs::search(query, &scope, limit)
+        }
+        "error" => {
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(10) as usize;
+            session.record_search(query);
+            crate::search::errors::search(query, &scope, limit)
+        }
+        "log" => {
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(10) as usize;
+            session.record_search(query);
+            crate::search::logs::search(query, &scope, limit)
+        }
+        "feature" => {
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(20) as usize;
+            session.record_search(query);
+            crate::search::features::search(query, &scope, limit)
+        }
         _ => {
             return Err(format!(
-                "unknown search kind: {kind}. Use: symbol, content, regex, callers"
-            ))
+                "unknown search kind: {kind}. Use: symbol, content, strings, regex, callers, graph, complete, describe, docs, error, log, feature, files, auto"
+            )
+            .into())
         }
-    }
-    .map_err(|e| e.to_string())?;
+    }?;
 
-    Ok(apply_budget(output, budget))
+    Ok(apply_budget(routed(output), budget))
 }
 
-fn tool_files(args: &Value, cache: &OutlineCache) -> Result<String, String> {
+fn tool_files(args: &Value, cache: &OutlineCache) -> Result<String, ToolError> {
     let pattern = args
         .get("pattern")
         .and_then(|v| v.as_str())
@@ -398,13 +1022,168 @@ fn tool_files(args: &Value, cache: &OutlineCache) -> Result<String, String> {
     let scope = resolve_scope(args);
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
 
-    let output = crate::search::search_glob(pattern, &scope, cache).map_err(|e| e.to_string())?;
+    let output = crate::search::search_glob(pattern, &scope, cache)?;
+
+    Ok(apply_budget(output, budget))
+}
+
+/// Any string — path, glob, symbol or sentence — sent where the CLI would
+/// send it, with the route taken in the header.
+fn tool_lookup(
+    args: &Value,
+    cache: &OutlineCache,
+    session: &Session,
+    edit_mode: bool,
+) -> Result<String, ToolError> {
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("missing required parameter: query")?;
+    let scope = resolve_scope(args);
+    let section = args.get("section").and_then(|v| v.as_str());
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+
+    let (output, why) = crate::route(query, &scope, section, false, cache, edit_mode)?;
+    let path = scope.join(query);
+    if path.is_file() {
+        session.record_read(&path);
+    } else {
+        session.record_search(query);
+    }
+    let output = format!("# Lookup: \"{query}\" → {why}\n\n{output}");
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_diff(args: &Value, session: &Session) -> Result<String, ToolError> {
+    let a = args
+        .get("a")
+        .and_then(|v| v.as_str())
+        .ok_or("missing required parameter: a")?;
+    let b = args.get("b").and_then(|v| v.as_str()).unwrap_or(a);
+    let a_path = PathBuf::from(a);
+    let b_path = PathBuf::from(b);
+    let context = args
+        .get("context_lines")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(3) as usize;
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+
+    let left = crate::diff::DiffSide {
+        path: &a_path,
+        section: args.get("a_section").and_then(|v| v.as_str()),
+    };
+    let right = crate::diff::DiffSide {
+        path: &b_path,
+        section: args.get("b_section").and_then(|v| v.as_str()),
+    };
+
+    session.record_read(&a_path);
+    session.record_read(&b_path);
+    let output = crate::diff::diff(&left, &right, context)?;
+    for side in [&left, &right] {
+        session.disclose(side.path, "diff", side.section.map(str::to_string));
+    }
+
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_changes(
+    args: &Value,
+    index: &SymbolIndex,
+    bloom: &BloomFilterCache,
+) -> Result<String, ToolError> {
+    let scope = resolve_scope(args);
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let base = args.get("base").and_then(|v| v.as_str());
+    let review = base.is_some()
+        || args
+            .get("review")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+    let output = if review {
+        crate::review::review(&scope, base, bloom, index)?
+    } else {
+        crate::changes::changes(&scope)?
+    };
+
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_analyze(args: &Value) -> Result<String, ToolError> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or("missing required parameter: action")?;
+    let scope = resolve_scope(args);
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+
+    let path = args.get("path").and_then(|v| v.as_str()).map(Path::new);
+
+    let output = crate::analyze::run(action, &scope, path)?;
+
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_trace(args: &Value) -> Result<String, ToolError> {
+    let trace = args
+        .get("trace")
+        .and_then(|v| v.as_str())
+        .ok_or("missing required parameter: trace")?;
+    let scope = resolve_scope(args);
+    let context = args
+        .get("context_lines")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(3) as u32;
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+
+    let output = crate::trace::trace(trace, &scope, context)?;
+
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_callers(
+    args: &Value,
+    cache: &OutlineCache,
+    session: &Session,
+    index: &SymbolIndex,
+    bloom: &BloomFilterCache,
+) -> Result<String, ToolError> {
+    let symbol = args
+        .get("symbol")
+        .and_then(|v| v.as_str())
+        .ok_or("missing required parameter: symbol")?;
+    let scope = resolve_scope(args);
+    let expand = args
+        .get("expand")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0) as usize;
+    let context_path = match args.get("context").and_then(|v| v.as_str()) {
+        Some(c) => Some(match breadcrumb(c, session)? {
+            Some(crumb) => crumb.path,
+            None => crate::pathnorm::canonical(Path::new(c)),
+        }),
+        None => None,
+    };
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+
+    session.record_search(symbol);
+    let output = crate::search::callers::callers_by_file(
+        symbol,
+        &scope,
+        cache,
+        session,
+        bloom,
+        index,
+        expand,
+        context_path.as_deref(),
+    )?;
 
     Ok(apply_budget(output, budget))
 }
 
 #[allow(dead_code)] // Map disabled in v0.3.2 — kept for potential re-enable
-fn tool_map(args: &Value, cache: &OutlineCache, session: &Session) -> Result<String, String> {
+fn tool_map(args: &Value, cache: &OutlineCache, session: &Session) -> Result<String, ToolError> {
     let scope = resolve_scope(args);
     let depth = args
         .get("depth")
@@ -412,11 +1191,26 @@ fn tool_map(args: &Value, cache: &OutlineCache, session: &Session) -> Result<Str
         .unwrap_or(3) as usize;
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
 
+    let _owners = crate::owners::Annotate::set(
+        args.get("owners")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+    );
+
     session.record_map();
-    Ok(crate::map::generate(&scope, depth, budget, cache))
+    with_timeout(args, || {
+        Ok(crate::map::generate(&scope, depth, budget, cache))
+    })
 }
 
-fn tool_session(args: &Value, session: &Session) -> Result<String, String> {
+fn tool_session(
+    args: &Value,
+    session: &Session,
+    cache: &OutlineCache,
+    index: &SymbolIndex,
+    bloom: &BloomFilterCache,
+    edit_mode: bool,
+) -> Result<String, ToolError> {
     let action = args
         .get("action")
         .and_then(|v| v.as_str())
@@ -426,16 +1220,136 @@ fn tool_session(args: &Value, session: &Session) -> Result<String, String> {
             session.reset();
             Ok("Session reset.".to_string())
         }
+        "backups" => {
+            let root = std::env::current_dir().map_err(|e| ToolError::io(&e))?;
+            let path = args.get("path").and_then(|v| v.as_str()).map(Path::new);
+            let backups = crate::backup::Backups::new(&root).list(path);
+            if backups.is_empty() {
+                return Ok("No backups. Pass backup: true to tilth_edit to keep one.".to_string());
+            }
+            let mut out = format!("# Backups — {}\n", backups.len());
+            for b in &backups {
+                let _ = writeln!(out, "{}@{}  {} bytes", b.path.display(), b.stamp, b.size);
+            }
+            Ok(out)
+        }
+        "restore" if !edit_mode => Err("restore writes files — needs edit mode (--edit)".into()),
+        "restore" => {
+            let root = std::env::current_dir().map_err(|e| ToolError::io(&e))?;
+            let path = args
+                .get("path")
+                .and_then(|v| v.as_str())
+                .ok_or("restore: missing required parameter: path")?;
+            let stamp = args.get("backup").and_then(Value::as_u64);
+            let restored = crate::backup::Backups::new(&root).restore(Path::new(path), stamp)?;
+            session.record_read(&root.join(&restored.path));
+            Ok(format!(
+                "Restored {} from backup {}. The replaced content was backed up first.",
+                restored.path.display(),
+                restored.stamp
+            ))
+        }
+        "audit" => Ok(session.audit()),
+        "perf" => Ok(session.perf(&PerfCounters {
+            outline_cache: cache.stats().snapshot(),
+            bloom_cache: bloom.stats().snapshot(),
+            index_lookups: index.lookup_stats().snapshot(),
+            index_builds: index.build_count(),
+            index_unparsed: index.unparsed_count(),
+            walked_files: crate::search::walked_files(),
+        })),
         _ => Ok(session.summary()),
     }
 }
 
-fn tool_edit(args: &Value, session: &Session) -> Result<String, String> {
+fn tool_edit(args: &Value, session: &Session) -> Result<String, ToolError> {
     let path_str = args
         .get("path")
         .and_then(|v| v.as_str())
         .ok_or("missing required parameter: path")?;
-    let path = PathBuf::from(path_str);
+    let path = match breadcrumb(path_str, session)? {
+        Some(crumb) => crumb.path,
+        None => PathBuf::from(path_str),
+    };
+
+    let force = args.get("force").and_then(Value::as_bool).unwrap_or(false);
+    if !force {
+        crate::edit::check_editable(&path)?;
+    }
+
+    let backup = args.get("backup").and_then(Value::as_bool).unwrap_or(false);
+    let original = if backup {
+        std::fs::read(&path).ok()
+    } else {
+        None
+    };
+
+    session.record_read(&path);
+    let (result, stale) = apply_edit_args(args, &path)?;
+    session.disclose(&path, "edit", None);
+    match result {
+        crate::edit::EditResult::Applied(mut output) => {
+            if let Some(original) = original {
+                let root = std::env::current_dir().map_err(|e| ToolError::io(&e))?;
+                let saved = crate::backup::Backups::new(&root).store(&path, &original)?;
+                let _ = write!(
+                    output,
+                    "\n[backup: {}@{} — restore with tilth_session action \"restore\"]",
+                    saved.path.display(),
+                    saved.stamp
+                );
+            }
+            if let Some(message) = args.get("commit").and_then(|v| v.as_str()) {
+                // The edit stands either way; say whether the commit did
+                match crate::git::commit_file(&path, message) {
+                    Ok(hash) => {
+                        let _ = write!(output, "\n[committed {hash}: {message}]");
+                    }
+                    Err(e) => {
+                        let _ = write!(output, "\n[not committed — {e}]");
+                    }
+                }
+            }
+            Ok(output)
+        }
+        crate::edit::EditResult::HashMismatch(msg) => Err(ToolError::new(
+            ErrorCode::HashMismatch,
+            format!("hash mismatch — {stale}:\n\n{msg}"),
+        )),
+        crate::edit::EditResult::Unchanged(msg) => Ok(msg),
+    }
+}
+
+/// Run the edit `args` describe: a symbol rewrite, an import, or a batch of
+/// anchored edits. Also says what a hash mismatch means for that kind.
+fn apply_edit_args(
+    args: &Value,
+    path: &Path,
+) -> Result<(crate::edit::EditResult, &'static str), ToolError> {
+    if let Some(symbol) = args.get("symbol").and_then(|v| v.as_str()) {
+        let hash_str = args
+            .get("hash")
+            .and_then(|v| v.as_str())
+            .ok_or("symbol edit: missing 'hash' (the body hash shown by tilth_search)")?;
+        let hash = u16::from_str_radix(hash_str.trim(), 16)
+            .map_err(|_| format!("symbol edit: invalid hash '{hash_str}'"))?;
+        let content = args
+            .get("content")
+            .and_then(|v| v.as_str())
+            .ok_or("symbol edit: missing 'content'")?;
+        let edit = crate::edit::SymbolEdit {
+            symbol: symbol.to_string(),
+            hash,
+            content: content.to_string(),
+        };
+        let result = crate::edit::apply_symbol_edit(path, &edit)?;
+        return Ok((result, "definition changed since last read"));
+    }
+
+    if let Some(statement) = args.get("add_import").and_then(|v| v.as_str()) {
+        let result = crate::edit::add_import(path, statement)?;
+        return Ok((result, "file changed while editing"));
+    }
 
     let edits_val = args
         .get("edits")
@@ -472,24 +1386,110 @@ fn tool_edit(args: &Value, session: &Session) -> Result<String, String> {
         });
     }
 
-    session.record_read(&path);
+    let result = crate::edit::apply_edits(path, &edits)?;
+    Ok((result, "file changed since last read"))
+}
 
-    match crate::edit::apply_edits(&path, &edits).map_err(|e| e.to_string())? {
-        crate::edit::EditResult::Applied(output) => Ok(output),
-        crate::edit::EditResult::HashMismatch(msg) => Err(format!(
-            "hash mismatch — file changed since last read:\n\n{msg}"
-        )),
+/// The match a breadcrumb like `S12.3` (or `[S12.3]`) names, when `raw` is
+/// one rather than a path. An id this session never showed is an error.
+fn breadcrumb(raw: &str, session: &Session) -> Result<Option<Crumb>, ToolError> {
+    let id = raw.trim_start_matches('[').trim_end_matches(']');
+    if crate::session::parse_breadcrumb(id).is_none() || Path::new(raw).exists() {
+        return Ok(None);
     }
+    session.crumb(id).map(Some).ok_or_else(|| {
+        ToolError::new(
+            ErrorCode::NotFound,
+            format!("unknown breadcrumb {raw} — use an id shown on this session's search results, like [S3.1]"),
+        )
+    })
 }
 
-/// Canonicalize scope path, falling back to the raw path if canonicalization fails.
+/// The lines a breadcrumb reads: a definition's body, else the match ±10.
+fn crumb_section(crumb: &Crumb) -> String {
+    match crumb.range {
+        Some((start, end)) => format!("{start}-{end}"),
+        None => format!(
+            "{}-{}",
+            crumb.line.saturating_sub(10).max(1),
+            crumb.line + 10
+        ),
+    }
+}
+
+/// Canonical scope path — the same spelling the walker and the index use for
+/// the files beneath it, even when the scope is reached through a symlink.
 fn resolve_scope(args: &Value) -> PathBuf {
-    let raw: PathBuf = args
-        .get("scope")
-        .and_then(|v| v.as_str())
-        .unwrap_or(".")
-        .into();
-    raw.canonicalize().unwrap_or(raw)
+    let raw = args.get("scope").and_then(|v| v.as_str()).unwrap_or(".");
+    crate::pathnorm::canonical(&crate::config::expand_scope(raw))
+}
+
+/// Most matches one search may show, whatever `max_total` asks for.
+const MAX_TOTAL_MATCHES: usize = 200;
+
+/// `max_definitions`, `max_usages` and `max_total`, over the defaults for
+/// the ones not given.
+fn search_limits(args: &Value) -> Result<crate::search::limits::Limits, ToolError> {
+    let mut limits = crate::search::limits::Limits::default();
+    for (name, slot) in [
+        ("max_definitions", &mut limits.definitions),
+        ("max_usages", &mut limits.usages),
+        ("max_total", &mut limits.total),
+    ] {
+        if let Some(value) = args.get(name) {
+            let n = value
+                .as_u64()
+                .ok_or_else(|| format!("{name} must be a non-negative integer"))?;
+            *slot = usize::try_from(n).unwrap_or(usize::MAX);
+        }
+    }
+    if limits.total > MAX_TOTAL_MATCHES {
+        return Err(ToolError::new(
+            ErrorCode::TooLarge,
+            format!(
+                "max_total is at most {MAX_TOTAL_MATCHES} (got {})",
+                limits.total
+            ),
+        ));
+    }
+    Ok(limits)
+}
+
+/// `scopes` — paths, or `{path, weight}` objects — resolved like `scope`.
+/// `None` when absent.
+fn resolve_scopes(args: &Value) -> Result<Option<Vec<crate::search::scopes::Scope>>, ToolError> {
+    let Some(list) = args.get("scopes") else {
+        return Ok(None);
+    };
+    let list = list
+        .as_array()
+        .filter(|l| !l.is_empty())
+        .ok_or("scopes must be a non-empty array")?;
+    if args.get("scope").is_some() {
+        return Err("pass scope or scopes, not both".into());
+    }
+    list.iter()
+        .map(|item| {
+            let (path, weight) = match item {
+                Value::String(path) => (path.as_str(), 1.0),
+                Value::Object(o) => (
+                    o.get("path")
+                        .and_then(Value::as_str)
+                        .ok_or("each scopes object needs a path")?,
+                    o.get("weight").and_then(Value::as_f64).unwrap_or(1.0),
+                ),
+                _ => return Err("scopes entries are paths or {path, weight} objects".into()),
+            };
+            if !(weight.is_finite() && weight > 0.0) {
+                return Err(format!("scope weight must be a positive number: {path}").into());
+            }
+            Ok(crate::search::scopes::Scope {
+                path: crate::pathnorm::canonical(&crate::config::expand_scope(path)),
+                weight,
+            })
+        })
+        .collect::<Result<_, ToolError>>()
+        .map(Some)
 }
 
 fn apply_budget(output: String, budget: Option<u64>) -> String {
@@ -535,8 +1535,14 @@ fn handle_tool_call(
             result: Some(serde_json::json!({
                 "content": [{
                     "type": "text",
-                    "text": e
+                    "text": e.message
                 }],
+                "structuredContent": {
+                    "error": {
+                        "code": e.code.as_str(),
+                        "message": e.message
+                    }
+                },
                 "isError": true
             })),
             error: None,
@@ -577,13 +1583,81 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     },
                     "scope": {
                         "type": "string",
-                        "description": "Directory to search within. Default: current directory."
+                        "description": "Directory to search within, or a scope alias from tilth.toml. Default: current directory."
+                    },
+                    "scopes": {
+                        "type": "array",
+                        "items": {
+                            "oneOf": [
+                                { "type": "string" },
+                                {
+                                    "type": "object",
+                                    "required": ["path"],
+                                    "properties": {
+                                        "path": { "type": "string" },
+                                        "weight": { "type": "number", "default": 1 }
+                                    }
+                                }
+                            ]
+                        },
+                        "description": "Several directories to search in one call instead of scope, e.g. [\"app/src\", {\"path\": \"shared/proto\", \"weight\": 2}]. Each is searched and ranked on its own; the shown matches are shared out by weight, and a directory with fewer matches leaves its share to the others. For kind: symbol (one name), content, strings and regex."
                     },
                     "kind": {
                         "type": "string",
-                        "enum": ["symbol", "content", "regex", "callers"],
+                        "enum": ["symbol", "content", "strings", "regex", "callers", "graph", "complete", "describe", "docs", "error", "log", "feature", "files", "auto"],
                         "default": "symbol",
-                        "description": "Search type. symbol: structural definitions + usages. content: literal text. regex: regex pattern. callers: find all call sites of a symbol."
+                        "description": "Search type. symbol: structural definitions + usages. content: literal text. strings: literal text inside string literals only — messages, log templates, i18n keys. regex: regex pattern. callers: find all call sites of a symbol. graph: the call graph around one or more comma-separated functions — what they call and what calls them, transitively to depth; see direction, depth and format. complete: symbol names matching query as a prefix or substring, with kind and definition file. describe: definitions ranked by how well their name and doc comment match a plain-language description (\"function that collapses blank lines\") — needs a build with the embeddings feature. docs: markdown sections and doc comments only, ranked by the query's words in headings and just below them — for \"where is the retry policy documented\". error: query is a runtime error message as printed; numbers, paths and quoted values are ignored and the string literals that could have produced it are ranked by similarity, raise sites first. log: query is one structured log line (JSON or key=value); logging calls in scope are ranked by how many of its fields they write, then by message similarity. feature: a Cargo feature, 'derive' or 'serde/derive'; every Cargo.toml in scope that defines or enables it — dependency features (workspace inheritance resolved), [workspace.dependencies] and [features] entries. files: files matching query as a glob (same as tilth_files) — glob-shaped symbol queries are routed here automatically. auto: pick from the query — identifiers → symbol, /re/ → regex, globs → files, anything else → content; the header names the choice."
+                    },
+                    "direction": {
+                        "type": "string",
+                        "enum": ["both", "callees", "callers"],
+                        "default": "both",
+                        "description": "For kind: graph — follow what the roots call (callees), what calls them (callers), or both."
+                    },
+                    "depth": {
+                        "type": "number",
+                        "default": 3,
+                        "description": "For kind: graph — hops followed from the roots each way (1-6)."
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["tree", "dot", "json"],
+                        "default": "tree",
+                        "description": "For kind: graph — an indented tree, a Graphviz digraph, or JSON {roots, nodes, edges, truncated} with edges from caller to callee."
+                    },
+                    "owners": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Append each match's owners to its header — from CODEOWNERS, else the most frequent recent committer (marked (git)) — to know who to ask or route a change to."
+                    },
+                    "sourcemaps": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "For matches in built output (dist/app.js) with a source map, also give the original source file and line the code came from, as [source: src/app.ts:42] on the match header."
+                    },
+                    "in_comments": {
+                        "type": "string",
+                        "enum": ["include", "exclude", "only"],
+                        "default": "include",
+                        "description": "For kind: content and regex — exclude: drop hits inside comments and string literals, to find real code uses of a word. only: keep just those hits."
+                    },
+                    "limit": {
+                        "type": "number",
+                        "default": 20,
+                        "description": "Max names returned by kind: complete (default 20) or results by kind: describe, docs, error and log (default 10)."
+                    },
+                    "max_definitions": {
+                        "type": "number",
+                        "description": "Most definitions shown. Default: no cap beyond max_total."
+                    },
+                    "max_usages": {
+                        "type": "number",
+                        "description": "Most usages shown — content and regex matches count as usages. 0 for definitions only. Default: no cap beyond max_total."
+                    },
+                    "max_total": {
+                        "type": "number",
+                        "default": 10,
+                        "description": "Most matches shown in all (up to 200). The footer says how many more definitions and usages were found."
                     },
                     "expand": {
                         "type": "number",
@@ -592,11 +1666,15 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     },
                     "context": {
                         "type": "string",
-                        "description": "Path to the file the agent is currently editing. Boosts ranking of matches in the same directory or package."
+                        "description": "Path to the file the agent is currently editing, or a match breadcrumb like S12.3. Boosts ranking of matches in the same directory or package."
+                    },
+                    "timeout_ms": {
+                        "type": "number",
+                        "description": "Stop walking files after this many milliseconds and return what was found so far, marked as partial."
                     },
                     "budget": {
                         "type": "number",
-                        "description": "Max tokens in response."
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
                     }
                 }
             }
@@ -609,25 +1687,43 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                 "properties": {
                     "path": {
                         "type": "string",
-                        "description": "Absolute or relative file path to read."
+                        "description": "Absolute or relative file path to read, or a search match breadcrumb like S12.3 (reads that match's lines unless section or full is given)."
                     },
                     "paths": {
                         "type": "array",
                         "items": { "type": "string" },
-                        "description": "Multiple file paths to read in one call. Each file gets independent smart handling. Saves round-trips vs multiple single reads."
+                        "description": "Multiple file paths (or match breadcrumbs) to read in one call. Each file gets independent smart handling. Saves round-trips vs multiple single reads."
                     },
                     "section": {
                         "type": "string",
-                        "description": "Line range e.g. '45-89', or heading e.g. '## Architecture'. Bypasses smart view."
+                        "description": "Line range e.g. '45-89', byte columns of one line e.g. '1:0-4000' (for files that are one long line), heading e.g. '## Architecture', or XML element path e.g. 'project/dependencies' ('dependency[2]' for the second of several). Bypasses smart view."
                     },
                     "full": {
                         "type": "boolean",
                         "default": false,
                         "description": "Force full content output, bypass smart outlining."
                     },
+                    "symbols": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Return every definition in the file as JSON records (name, kind, start_line, end_line, signature, visibility, cfg, decorators, parent) instead of content or outline. Single path only."
+                    },
+                    "snippet": {
+                        "type": "number",
+                        "description": "A line number: return only the function enclosing it, the members of its own type it uses, and the type definitions it names from the same file — a self-contained snippet. Single path only."
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "For a JSON file that is an array of objects: the rows matching 'field=value' (one field, ignoring case) or containing the text in any field, as a table with the fields as columns. For a lockfile (Cargo.lock, package-lock.json, yarn.lock, poetry.lock): a package name — its locked versions and what requires it. Single path only."
+                    },
+                    "dedupe": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "If this exact content was already returned this session, reply with a short 'unchanged since turn N' notice instead of repeating it."
+                    },
                     "budget": {
                         "type": "number",
-                        "description": "Max tokens in response."
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
                     }
                 }
             }
@@ -645,11 +1741,203 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     },
                     "scope": {
                         "type": "string",
-                        "description": "Directory to search within. Default: current directory."
+                        "description": "Directory to search within, or a scope alias from tilth.toml. Default: current directory."
                     },
                     "budget": {
                         "type": "number",
-                        "description": "Max tokens in response."
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "tilth_lookup",
+            "description": "Look up anything when you're not sure which tool fits: a path reads the file, a glob lists files, an identifier searches symbols, anything else searches text. A path-like string that doesn't exist falls back to symbol then text search, and a miss comes back with did-you-mean suggestions. The header says which route was taken and why.",
+            "inputSchema": {
+                "type": "object",
+                "required": ["query"],
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "A path, glob, symbol name or free text."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory to resolve and search within. Default: current directory."
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "When the query is a file: line range or markdown heading, as in tilth_read."
+                    },
+                    "timeout_ms": {
+                        "type": "number",
+                        "description": "Stop walking files after this many milliseconds and return what was found so far, marked as partial."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "tilth_diff",
+            "description": "Compare two files, or two sections of the same file, as a unified diff. Context and added lines carry hashline anchors (line:hash) from the right-hand side; removed lines show their left-hand line number.",
+            "inputSchema": {
+                "type": "object",
+                "required": ["a"],
+                "properties": {
+                    "a": {
+                        "type": "string",
+                        "description": "Left-hand (old) file path."
+                    },
+                    "b": {
+                        "type": "string",
+                        "description": "Right-hand (new) file path. Default: same as a — combine with sections to compare two parts of one file."
+                    },
+                    "a_section": {
+                        "type": "string",
+                        "description": "Restrict a to a line range e.g. '45-89', or heading e.g. '## Architecture'."
+                    },
+                    "b_section": {
+                        "type": "string",
+                        "description": "Restrict b to a line range or heading."
+                    },
+                    "context_lines": {
+                        "type": "number",
+                        "default": 3,
+                        "description": "Unchanged lines shown around each change."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "tilth_changes",
+            "description": "Summarize uncommitted work: every file changed since HEAD (modified, added, deleted, untracked) with lines added and removed, and for code files the definitions added (+), removed (-) or modified (~), found by comparing the HEAD outline with the one on disk. A task-relevant starting point when resuming work, instead of a full map.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory inside a git repository to summarize. Default: current directory."
+                    },
+                    "review": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Review bundle for the current branch instead: changes since its merge-base with `base`, committed or not, plus the source of each changed definition, call sites of modified and removed ones, and affected tests."
+                    },
+                    "base": {
+                        "type": "string",
+                        "description": "Ref the branch is reviewed against; implies review. Default: origin/HEAD, else main or master."
+                    },
+                    "timeout_ms": {
+                        "type": "number",
+                        "description": "Stop walking files after this many milliseconds and return what was found so far, marked as partial."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "tilth_analyze",
+            "description": "Repo-level analyses that answer an orientation question in one call. entry_points: binaries, package scripts, main functions and make targets, with locations — answers 'how do I run this?'. build_config: CI workflows, Dockerfiles, Makefiles, justfiles and compose files outlined to their jobs, stages and targets. config_keys: keys defined in .env files cross-referenced with the code that reads them (env::var, process.env, os.getenv, ...), listing keys never read and reads never defined. feature_flags: every flag key checked through LaunchDarkly, Unleash, Statsig, GrowthBook, Split, Flipper or a homegrown is_enabled(\"x\"), with all call sites — use before removing a flag. i18n_keys: keys from translation files (JSON/YAML under locales/, i18n/ and similar, gettext .po) cross-referenced with the string literals code passes to t(), $t(), gettext(), _() and friends, listing keys never referenced and references with no translation. test_commands: test runners detected from manifests (cargo, go, pytest/unittest, jest/vitest/mocha, maven/gradle, rspec, sbt) with the command for each suite; with path, the narrowest commands that run the tests for that file (e.g. `cargo test -p foo module::`, `pytest tests/test_x.py::TestY`).",
+            "inputSchema": {
+                "type": "object",
+                "required": ["action"],
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": crate::analyze::ACTIONS,
+                        "description": "Analysis to run."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory to analyze. Default: current directory."
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "test_commands only: file whose tests to run, relative to scope."
+                    },
+                    "timeout_ms": {
+                        "type": "number",
+                        "description": "Stop walking files after this many milliseconds and return what was found so far, marked as partial."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "tilth_trace",
+            "description": "Resolve a pasted stack trace to code. Recognizes Rust panics and backtraces, Python tracebacks, JS (V8, Firefox and Safari), Java/Kotlin and Go frames, finds each frame's file in scope — by path, or by the longest matching path suffix for traces from another machine or CI — and returns a hashlined snippet around each frame's line. Runtime and dependency frames (site-packages, node_modules, the Rust and Go standard libraries) are listed as outside scope.",
+            "inputSchema": {
+                "type": "object",
+                "required": ["trace"],
+                "properties": {
+                    "trace": {
+                        "type": "string",
+                        "description": "The stack trace, as printed — extra log lines around it are ignored."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory the trace's code lives in. Default: current directory."
+                    },
+                    "context_lines": {
+                        "type": "number",
+                        "default": 3,
+                        "description": "Lines shown either side of each frame's line."
+                    },
+                    "timeout_ms": {
+                        "type": "number",
+                        "description": "Stop walking files after this many milliseconds and return what was found so far, marked as partial."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "tilth_callers",
+            "description": "Who calls this? Every call site of a function or method under scope, found by tree-sitter, grouped by file and then by the function making the call, with that function's line range. Use expand to see the calling functions' source with each call marked.",
+            "inputSchema": {
+                "type": "object",
+                "required": ["symbol"],
+                "properties": {
+                    "symbol": {
+                        "type": "string",
+                        "description": "Name of the function or method whose callers to find, e.g. parse_args."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory to search within, or a scope alias from tilth.toml. Default: current directory."
+                    },
+                    "expand": {
+                        "type": "number",
+                        "default": 0,
+                        "description": "Number of calling functions, best-ranked first, to show in full with their call sites marked."
+                    },
+                    "context": {
+                        "type": "string",
+                        "description": "Path to the file the agent is currently editing, or a match breadcrumb like S12.3. Its callers rank first."
+                    },
+                    "timeout_ms": {
+                        "type": "number",
+                        "description": "Stop walking files after this many milliseconds and return what was found so far, marked as partial."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response. Over it, the response is cut at a section boundary and ends with its full size and the size of each omitted section."
                     }
                 }
             }
@@ -665,14 +1953,42 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
     if edit_mode {
         tools.push(serde_json::json!({
             "name": "tilth_edit",
-            "description": "Apply edits to a file using hashline anchors from tilth_read. Each edit targets a line range by line:hash anchors. Edits are verified against content hashes and rejected if the file has changed since the last read.",
+            "description": "Apply edits to a file using hashline anchors from tilth_read. Each edit targets a line range by line:hash anchors. Edits are verified against content hashes and rejected if the file has changed since the last read. To rewrite a whole definition, pass symbol + hash + content instead of edits; to add an import, pass add_import.",
             "inputSchema": {
                 "type": "object",
-                "required": ["path", "edits"],
+                "required": ["path"],
                 "properties": {
                     "path": {
                         "type": "string",
-                        "description": "Absolute or relative file path to edit."
+                        "description": "Absolute or relative file path to edit, or a search match breadcrumb like S12.3 for its file."
+                    },
+                    "symbol": {
+                        "type": "string",
+                        "description": "Replace this definition in full. Located by tree-sitter when applied; requires hash and content."
+                    },
+                    "hash": {
+                        "type": "string",
+                        "description": "With symbol: the definition's body hash, shown as 'body:<hash>' in tilth_search headers."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "With symbol: the new definition."
+                    },
+                    "backup": {
+                        "type": "boolean",
+                        "description": "Keep the pre-edit content in .tilth/backups/<path>@<millis>. List with tilth_session action 'backups'; restore with action 'restore' + path (+ backup stamp). Default: false."
+                    },
+                    "commit": {
+                        "type": "string",
+                        "description": "Commit message. After a successful edit, commits the edited file alone to the current branch with git — other staged or modified files are left out."
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Edit even if the file is binary, a lockfile, marked generated, or has unresolved merge conflicts. Default: false."
+                    },
+                    "add_import": {
+                        "type": "string",
+                        "description": "Import statement to add (e.g. 'use crate::foo::Bar;', 'import os'). Inserted sorted into the matching import group; a no-op if already present."
                     },
                     "edits": {
                         "type": "array",
@@ -701,6 +2017,22 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
         }));
     }
 
+    // One knob for how much surrounding detail every tool returns
+    for tool in &mut tools {
+        if let Some(props) = tool
+            .pointer_mut("/inputSchema/properties")
+            .and_then(Value::as_object_mut)
+        {
+            props.insert(
+                "verbosity".into(),
+                serde_json::json!({
+                    "type": "string",
+                    "enum": ["compact", "normal", "rich"],
+                    "description": "compact: no calls/siblings/related footers, bare match headers, no blank lines. rich: longer footers and outline context for every match. Default normal."
+                }),
+            );
+        }
+    }
     tools
 }
 
@@ -0,0 +1,258 @@
+//! Who owns a file, for the `owners` option: search match headers and map
+//! entries then name the team or person to route a question or change to.
+//!
+//! Owners come from the repository's CODEOWNERS (`.github/`, the root, or
+//! `docs/`), with GitHub's rules: gitignore-style patterns, last match wins,
+//! a pattern with no owners leaves the path unowned. Search results also
+//! fall back to git history for files CODEOWNERS doesn't cover — whoever
+//! made most of the last 100 commits touching the file.
+//!
+//! Like verbosity, the option is process-wide for the call in progress.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::SystemTime;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+/// Where GitHub looks for CODEOWNERS, in its order of precedence.
+const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+/// Commits read for the git fallback.
+const HISTORY_DEPTH: &str = "100";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns owner annotations on for one call; off again on drop.
+pub struct Annotate(());
+
+impl Annotate {
+    #[must_use]
+    pub fn set(enabled: bool) -> Self {
+        ENABLED.store(enabled, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Drop for Annotate {
+    fn drop(&mut self) {
+        ENABLED.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Whether the call in progress asked for owners.
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// ` [owners: @org/team @someone]` for `path`, from CODEOWNERS or else git
+/// history, or ` [unowned]` when CODEOWNERS says so. Empty when owners are
+/// off or unknown.
+pub(crate) fn annotation(path: &Path) -> String {
+    if !enabled() {
+        return String::new();
+    }
+    let Some(root) = repo_root(path) else {
+        return String::new();
+    };
+    let rel = relative(path, &root);
+    if let Some(rules) = rules(&root) {
+        if let Some(owners) = rules.owners(&rel) {
+            return if owners.is_empty() {
+                " [unowned]".to_string()
+            } else {
+                format!(" [owners: {}]", owners.join(" "))
+            };
+        }
+    }
+    top_committer(&root, &rel).map_or_else(String::new, |name| format!(" [owners: {name} (git)]"))
+}
+
+/// CODEOWNERS owners of `path`, a file or — with `is_dir` — everything in a
+/// directory, for the map. No git fallback: a map covers too many files.
+pub(crate) fn codeowners(path: &Path, is_dir: bool) -> Option<Vec<String>> {
+    if !enabled() {
+        return None;
+    }
+    let root = repo_root(path)?;
+    let mut rel = relative(path, &root);
+    if is_dir {
+        // What a rule says about a file directly inside, named so that only
+        // patterns covering the whole directory match it
+        rel.push_str(if rel.is_empty() { "\u{0}" } else { "/\u{0}" });
+    }
+    rules(&root)?.owners(&rel).map(<[String]>::to_vec)
+}
+
+/// Parsed CODEOWNERS rules, in file order.
+struct Rules {
+    rules: Vec<(GlobMatcher, Vec<String>)>,
+}
+
+impl Rules {
+    fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let glob = glob_for(fields.next()?)?;
+                let matcher = GlobBuilder::new(&glob)
+                    .literal_separator(true)
+                    .build()
+                    .ok()?
+                    .compile_matcher();
+                let owners = fields
+                    .take_while(|f| !f.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some((matcher, owners))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners from the last rule matching `rel`; `Some(&[])` if that rule
+    /// names none.
+    fn owners(&self, rel: &str) -> Option<&[String]> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| matcher.is_match(rel))
+            .map(|(_, owners)| owners.as_slice())
+    }
+}
+
+/// A CODEOWNERS pattern as a glob over root-relative paths. Patterns with
+/// a leading or inner `/` are anchored at the root, others match at any
+/// depth, and a pattern naming a directory covers everything under it.
+fn glob_for(pattern: &str) -> Option<String> {
+    let (anchored, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (pattern.trim_end_matches('/').contains('/'), pattern),
+    };
+    let dir_only = rest.ends_with('/');
+    let rest = rest.trim_end_matches('/');
+    if rest.is_empty() {
+        return None;
+    }
+    let base = if anchored {
+        rest.to_string()
+    } else {
+        format!("**/{rest}")
+    };
+    Some(if dir_only {
+        format!("{base}/**")
+    } else {
+        format!("{{{base},{base}/**}}")
+    })
+}
+
+/// The nearest directory at or above `path` holding `.git` or a CODEOWNERS.
+fn repo_root(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    path.ancestors()
+        .find(|dir| {
+            dir.join(".git").exists() || LOCATIONS.iter().any(|loc| dir.join(loc).is_file())
+        })
+        .map(Path::to_path_buf)
+}
+
+fn relative(path: &Path, root: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    crate::pathnorm::relative(&path, root)
+}
+
+/// Rules per CODEOWNERS file, re-parsed when it changes.
+type RuleCache = HashMap<PathBuf, (SystemTime, Arc<Rules>)>;
+
+fn rules(root: &Path) -> Option<Arc<Rules>> {
+    static CACHE: OnceLock<Mutex<RuleCache>> = OnceLock::new();
+    let file = LOCATIONS
+        .iter()
+        .map(|loc| root.join(loc))
+        .find(|p| p.is_file())?;
+    let mtime = std::fs::metadata(&file).and_then(|m| m.modified()).ok()?;
+    let mut cache = CACHE
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((stamp, rules)) = cache.get(&file) {
+        if *stamp == mtime {
+            return Some(Arc::clone(rules));
+        }
+    }
+    let rules = Arc::new(Rules::parse(&std::fs::read_to_string(&file).ok()?));
+    cache.insert(file, (mtime, Arc::clone(&rules)));
+    Some(rules)
+}
+
+/// The author of most recent commits to `rel`, remembered per file.
+fn top_committer(root: &Path, rel: &str) -> Option<String> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, Option<String>>>> = OnceLock::new();
+    let key = root.join(rel);
+    let cache = CACHE.get_or_init(Mutex::default);
+    if let Some(name) = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return name.clone();
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "-n", HISTORY_DEPTH, "--format=%an", "--", rel])
+        .output()
+        .ok()
+        .filter(|o| o.status.success());
+    let name = output.and_then(|o| {
+        let log = String::from_utf8_lossy(&o.stdout).into_owned();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for author in log.lines().filter(|l| !l.is_empty()) {
+            *counts.entry(author).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, _)| name.to_string())
+    });
+    cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(key, name.clone());
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let rules = Rules::parse(
+            "# default\n\
+             *            @org/core\n\
+             *.md         @org/docs\n\
+             /src/api/    @org/api @lead  # comment\n\
+             vendor       \n\
+             docs/*.rs    @org/examples\n",
+        );
+        let owners = |p: &str| rules.owners(p).map(|o| o.join(" "));
+        assert_eq!(owners("src/main.rs").as_deref(), Some("@org/core"));
+        assert_eq!(owners("README.md").as_deref(), Some("@org/docs"));
+        assert_eq!(
+            owners("src/api/v1/routes.rs").as_deref(),
+            Some("@org/api @lead")
+        );
+        assert_eq!(owners("lib/api/x.rs").as_deref(), Some("@org/core"));
+        assert_eq!(owners("third/vendor/x.c").as_deref(), Some(""));
+        assert_eq!(owners("docs/ex.rs").as_deref(), Some("@org/examples"));
+        assert_eq!(owners("docs/deep/ex.rs").as_deref(), Some("@org/core"));
+        assert_eq!(owners("src/api/\u{0}").as_deref(), Some("@org/api @lead"));
+    }
+}
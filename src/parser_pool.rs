@@ -0,0 +1,59 @@
+//! Tree-sitter parsers kept per thread, one per grammar. Outline, symbol,
+//! caller, callee and sibling extraction all parse through here, so a batch
+//! over thousands of files (index build, map, multi-file search) allocates a
+//! parser once per rayon worker rather than once per file.
+
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use tree_sitter::{Language, Parser, Tree};
+
+thread_local! {
+    static PARSERS: RefCell<HashMap<Language, Parser>> = RefCell::new(HashMap::new());
+}
+
+/// Parse `content` with this thread's parser for `language`. `None` when the
+/// grammar can't be loaded or parsing fails.
+pub(crate) fn parse(content: &str, language: &Language) -> Option<Tree> {
+    PARSERS.with_borrow_mut(|parsers| {
+        let parser = match parsers.entry(language.clone()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let mut parser = Parser::new();
+                parser.set_language(language).ok()?;
+                e.insert(parser)
+            }
+        };
+        let tree = parser.parse(content, None);
+        if tree.is_none() {
+            // A failed parse can leave state behind that the next call would
+            // try to resume from.
+            parser.reset();
+        }
+        tree
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_parser_per_language() {
+        let rust: Language = tree_sitter_rust::LANGUAGE.into();
+        let python: Language = tree_sitter_python::LANGUAGE.into();
+
+        let first = parse("fn a() {}", &rust).unwrap();
+        let second = parse("struct B;", &rust).unwrap();
+        let third = parse("def c(): pass", &python).unwrap();
+
+        assert_eq!(first.root_node().child(0).unwrap().kind(), "function_item");
+        assert_eq!(second.root_node().child(0).unwrap().kind(), "struct_item");
+        assert_eq!(
+            third.root_node().child(0).unwrap().kind(),
+            "function_definition"
+        );
+        assert_eq!(PARSERS.with_borrow(HashMap::len), 2);
+    }
+}
@@ -0,0 +1,175 @@
+//! Path comparison and rendering that hold up on Windows.
+//!
+//! `Path::starts_with` compares components exactly, so the same directory
+//! spelled `C:\repo`, `c:\repo` and `\\?\C:\repo` (what `canonicalize`
+//! returns there) counts as three. These helpers fall back to comparing a
+//! normalized spelling — forward slashes, no verbatim prefix, upper-case
+//! drive letter — and render paths that way, so output reads the same on
+//! every platform. The normalization is plain string work, so it behaves
+//! identically (and is tested) on any host.
+//!
+//! Symlinks are the other way one directory gets two names. Scopes, context
+//! files and indexed paths all go through [`canonical`], so a workspace
+//! reached through a link compares equal to the paths walked beneath it.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// `path` spelled with `/` separators, without a `\\?\` verbatim prefix, a
+/// UNC share as `//server/share`, and the drive letter upper-cased.
+pub(crate) fn normalize(path: &str) -> Cow<'_, str> {
+    if !path.contains('\\') && !has_drive(path) {
+        return Cow::Borrowed(path);
+    }
+    let rest = if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        Cow::Owned(format!(r"\\{unc}"))
+    } else {
+        Cow::Borrowed(path.strip_prefix(r"\\?\").unwrap_or(path))
+    };
+    let mut out = rest.replace('\\', "/");
+    if has_drive(&out) {
+        out[..1].make_ascii_uppercase();
+    }
+    Cow::Owned(out)
+}
+
+/// Whether `path` lies under `prefix`, however either is spelled.
+pub(crate) fn starts_with(path: &Path, prefix: &Path) -> bool {
+    path.starts_with(prefix) || strip(path, prefix).is_some()
+}
+
+/// `path` relative to `scope` for output, `/`-separated; the whole path,
+/// normalized, when it isn't under `scope`.
+pub(crate) fn relative(path: &Path, scope: &Path) -> String {
+    if let Ok(rel) = path.strip_prefix(scope) {
+        return normalize(&rel.to_string_lossy()).into_owned();
+    }
+    strip(path, scope).unwrap_or_else(|| display(path))
+}
+
+/// `path` for output, normalized.
+pub(crate) fn display(path: &Path) -> String {
+    normalize(&path.to_string_lossy()).into_owned()
+}
+
+/// `path` with symlinks resolved and made absolute. A path that doesn't
+/// exist yet — a file about to be created — resolves its nearest existing
+/// ancestor and keeps the rest as given; with no ancestor, `path` itself.
+pub(crate) fn canonical(path: &Path) -> PathBuf {
+    if let Ok(resolved) = path.canonicalize() {
+        return resolved;
+    }
+    let mut missing = Vec::new();
+    let mut existing = path;
+    while let Some(parent) = existing.parent() {
+        if let Some(name) = existing.file_name() {
+            missing.push(name);
+        }
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(mut resolved) = parent.canonicalize() {
+            resolved.extend(missing.iter().rev());
+            return resolved;
+        }
+        existing = parent;
+    }
+    path.to_path_buf()
+}
+
+/// The normalized remainder of `path` after `prefix`, if `prefix` covers
+/// whole components of it.
+fn strip(path: &Path, prefix: &Path) -> Option<String> {
+    let (path, prefix) = (path.to_string_lossy(), prefix.to_string_lossy());
+    let (path, prefix) = (normalize(&path), normalize(&prefix));
+    let trimmed = prefix.trim_end_matches('/');
+    let rest = path.strip_prefix(trimmed)?;
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        // A root — `/`, `C:/` or a bare `C:`
+        if trimmed.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        return Some(rest.trim_start_matches('/').to_string());
+    }
+    match rest.strip_prefix('/') {
+        Some(rest) => Some(rest.to_string()),
+        None => rest.is_empty().then(String::new),
+    }
+}
+
+/// `C:` or `c:` at the start.
+fn has_drive(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_windows_spellings() {
+        assert_eq!(normalize(r"C:\repo\src\lib.rs"), "C:/repo/src/lib.rs");
+        assert_eq!(normalize(r"c:\repo"), "C:/repo");
+        assert_eq!(normalize(r"\\?\C:\repo\src"), "C:/repo/src");
+        assert_eq!(normalize(r"\\?\UNC\server\share\src"), "//server/share/src");
+        assert_eq!(normalize(r"\\server\share\src"), "//server/share/src");
+        assert_eq!(normalize("/home/me/repo"), "/home/me/repo");
+    }
+
+    #[test]
+    fn prefix_matching_across_spellings() {
+        let scope = Path::new(r"\\?\C:\repo");
+        assert!(starts_with(Path::new(r"c:\repo\src\lib.rs"), scope));
+        assert!(starts_with(Path::new("C:/repo"), scope));
+        assert!(!starts_with(Path::new(r"C:\repository\lib.rs"), scope));
+        assert!(starts_with(
+            Path::new(r"\\server\share\src\a.rs"),
+            Path::new(r"\\?\UNC\server\share"),
+        ));
+        assert!(starts_with(Path::new("/repo/src"), Path::new("/repo/")));
+        assert!(!starts_with(Path::new("/repos"), Path::new("/repo")));
+        assert!(!starts_with(Path::new("src/lib.rs"), Path::new("/")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn canonical_sees_through_symlinked_root() {
+        let base = std::env::temp_dir().join("tilth_test_canonical");
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(base.join("real/src")).unwrap();
+        std::os::unix::fs::symlink(base.join("real"), base.join("link")).unwrap();
+        let real = base.join("real").canonicalize().unwrap();
+
+        assert_eq!(canonical(&base.join("link")), real);
+        assert_eq!(canonical(&base.join("link/src")), real.join("src"));
+        // Not created yet: the existing part resolves, the rest is kept
+        assert_eq!(
+            canonical(&base.join("link/src/new.rs")),
+            real.join("src/new.rs")
+        );
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn relative_output_uses_forward_slashes() {
+        assert_eq!(
+            relative(Path::new(r"C:\repo\src\lib.rs"), Path::new(r"\\?\c:\repo")),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            relative(Path::new(r"C:\lib.rs"), Path::new(r"C:\")),
+            "lib.rs"
+        );
+        assert_eq!(
+            relative(Path::new(r"D:\other\x.rs"), Path::new(r"C:\repo")),
+            "D:/other/x.rs"
+        );
+        assert_eq!(
+            relative(Path::new("/repo/src/lib.rs"), Path::new("/repo")),
+            "src/lib.rs"
+        );
+    }
+}
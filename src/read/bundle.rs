@@ -0,0 +1,229 @@
+//! Minified bundles: `dist/app.min.js` and the like. An outline of one is a
+//! single giant line, so reads report the bundle as generated instead, with
+//! its source map and which of the sources it was built from exist here —
+//! those are what to read and search.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lines at least this long count toward a file being minified.
+const MINIFIED_LINE_BYTES: usize = 1_000;
+/// How far from the end a `sourceMappingURL` comment is looked for.
+const MAP_COMMENT_WINDOW: usize = 1_024;
+/// Source files listed in a read's report.
+const LISTED_SOURCES: usize = 8;
+
+/// Whether `buf` is minified — most of its bytes on very long lines.
+pub(crate) fn is_minified(buf: &[u8]) -> bool {
+    let long: usize = buf
+        .split(|&b| b == b'\n')
+        .map(<[u8]>::len)
+        .filter(|&n| n >= MINIFIED_LINE_BYTES)
+        .sum();
+    long > 0 && long * 2 > buf.len()
+}
+
+/// The report a read gives for `path` when it is a minified bundle: its
+/// source map and the sources found in the tree. `None` for other files.
+pub(crate) fn describe(path: &Path, buf: &[u8]) -> Option<String> {
+    if !is_named_min(path) && !is_minified(buf) {
+        return None;
+    }
+
+    let mut out = String::from(
+        "> Minified bundle — not outlined. Read a range with section (\"1:0-4000\" for byte columns of one line), or the whole of it with full: true.",
+    );
+    match map_reference(path, buf) {
+        Some(MapRef::Inline) => out.push_str("\n> Source map: inline (data: URL)."),
+        Some(MapRef::File(map)) if map.is_file() => {
+            let (found, total) = sources(&map);
+            let _ = write!(
+                out,
+                "\n> Source map: {} — {total} sources, {} in this tree",
+                shown(&map),
+                found.len()
+            );
+            if found.is_empty() {
+                out.push('.');
+            } else {
+                out.push(':');
+                for source in found.iter().take(LISTED_SOURCES) {
+                    let _ = write!(out, "\n  {}", shown(source));
+                }
+                if found.len() > LISTED_SOURCES {
+                    let _ = write!(out, "\n  ... and {} more", found.len() - LISTED_SOURCES);
+                }
+                if let Some(dir) = common_dir(&found) {
+                    let _ = write!(
+                        out,
+                        "\n> Search the sources instead: scope=\"{}\"",
+                        shown(&dir)
+                    );
+                }
+            }
+        }
+        Some(MapRef::File(map)) => {
+            let _ = write!(out, "\n> Source map: {} (missing).", shown(&map));
+        }
+        None => {}
+    }
+    Some(out)
+}
+
+/// Whether the file is named as a minified bundle, `app.min.js`.
+pub(crate) fn is_named_min(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| {
+            [".min.js", ".min.mjs", ".min.cjs", ".min.css"]
+                .iter()
+                .any(|ext| name.ends_with(ext))
+        })
+}
+
+pub(crate) enum MapRef {
+    Inline,
+    File(PathBuf),
+}
+
+/// The source map `path` points to with a trailing `sourceMappingURL`
+/// comment, or else a `<name>.map` beside it.
+pub(crate) fn map_reference(path: &Path, buf: &[u8]) -> Option<MapRef> {
+    let tail = &buf[buf.len().saturating_sub(MAP_COMMENT_WINDOW)..];
+    let tail = String::from_utf8_lossy(tail);
+    if let Some(at) = tail.rfind("sourceMappingURL=") {
+        let url = tail[at + "sourceMappingURL=".len()..]
+            .split(|c: char| c.is_whitespace() || c == '*')
+            .next()
+            .unwrap_or("");
+        if url.starts_with("data:") {
+            return Some(MapRef::Inline);
+        }
+        if !url.is_empty() && !url.contains("://") {
+            let url = url.split(['?', '#']).next().unwrap_or(url);
+            return Some(MapRef::File(path.parent()?.join(url)));
+        }
+    }
+    let mut adjacent = path.as_os_str().to_owned();
+    adjacent.push(".map");
+    let adjacent = PathBuf::from(adjacent);
+    adjacent.is_file().then_some(MapRef::File(adjacent))
+}
+
+/// The map's sources that exist here, and how many it lists.
+fn sources(map: &Path) -> (Vec<PathBuf>, usize) {
+    let Some(json) = fs::read_to_string(map)
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+    else {
+        return (Vec::new(), 0);
+    };
+    let root = json
+        .get("sourceRoot")
+        .and_then(|r| r.as_str())
+        .unwrap_or("");
+    let listed: Vec<&str> = json
+        .get("sources")
+        .and_then(|s| s.as_array())
+        .map(|s| s.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let dir = map.parent().unwrap_or(Path::new("."));
+    let mut found: Vec<PathBuf> = listed
+        .iter()
+        .filter_map(|source| resolve_source(dir, root, source))
+        .collect();
+    found.sort();
+    found.dedup();
+    (found, listed.len())
+}
+
+/// Where a source-map entry lives in this tree: relative to the map and its
+/// `sourceRoot`, or — for bundler URLs like `webpack:///./src/app.ts` — to
+/// the map's directory or one of its parents.
+pub(crate) fn resolve_source(map_dir: &Path, root: &str, source: &str) -> Option<PathBuf> {
+    if let Some((_, rest)) = source.split_once("://") {
+        // Drop the bundler's namespace, `webpack://<name>/./src/x.ts`
+        let rest = rest.split_once('/').map_or(rest, |(_, path)| path);
+        let rest = rest.trim_start_matches('/');
+        return map_dir
+            .ancestors()
+            .take(5)
+            .map(|dir| dir.join(rest))
+            .find(|p| p.is_file())
+            .map(|p| crate::pathnorm::canonical(&p));
+    }
+    let path = map_dir.join(root).join(source);
+    path.is_file().then(|| crate::pathnorm::canonical(&path))
+}
+
+/// The deepest directory holding all of `paths`.
+fn common_dir(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut dir = paths.first()?.parent()?.to_path_buf();
+    for path in &paths[1..] {
+        while !path.starts_with(&dir) {
+            if !dir.pop() {
+                return None;
+            }
+        }
+    }
+    Some(dir)
+}
+
+/// `path` relative to the working directory when under it.
+fn shown(path: &Path) -> String {
+    let path = crate::pathnorm::canonical(path);
+    match std::env::current_dir() {
+        Ok(cwd) if crate::pathnorm::starts_with(&path, &crate::pathnorm::canonical(&cwd)) => {
+            crate::pathnorm::relative(&path, &crate::pathnorm::canonical(&cwd))
+        }
+        _ => crate::pathnorm::display(&path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minified_files_are_mostly_long_lines() {
+        let bundle = format!("/* license */\n{}\n", "var a=1;".repeat(500));
+        assert!(is_minified(bundle.as_bytes()));
+        assert!(!is_minified(b"fn main() {}\n"));
+    }
+
+    #[test]
+    fn bundle_report_lists_sources_in_the_tree() {
+        let dir = std::env::temp_dir().join("tilth_test_bundle");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("dist")).unwrap();
+        fs::create_dir_all(dir.join("src/util")).unwrap();
+        fs::write(dir.join("src/app.ts"), "export const a = 1;\n").unwrap();
+        fs::write(dir.join("src/util/fmt.ts"), "export const f = 1;\n").unwrap();
+        fs::write(
+            dir.join("dist/app.js.map"),
+            r#"{"version":3,"sources":["webpack:///./src/app.ts","../src/util/fmt.ts","webpack:///webpack/bootstrap"],"mappings":""}"#,
+        )
+        .unwrap();
+        let js = format!(
+            "{}\n//# sourceMappingURL=app.js.map\n",
+            "var a=1;".repeat(500)
+        );
+        let path = dir.join("dist/app.js");
+        fs::write(&path, &js).unwrap();
+
+        let report = describe(&path, js.as_bytes()).unwrap();
+        assert!(
+            report.contains("app.js.map — 3 sources, 2 in this tree:"),
+            "{report}"
+        );
+        assert!(report.contains("src/app.ts"), "{report}");
+        assert!(report.contains("src/util/fmt.ts"), "{report}");
+        assert!(
+            report.contains("Search the sources instead: scope=\""),
+            "{report}"
+        );
+        assert!(describe(&dir.join("src/app.ts"), b"export const a = 1;\n").is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -49,7 +49,7 @@ pub fn resolve_related_files_with_content(file_path: &Path, content: &str) -> Ve
     results
 }
 
-fn is_import_line(line: &str, lang: Lang) -> bool {
+pub(crate) fn is_import_line(line: &str, lang: Lang) -> bool {
     let trimmed = line.trim_start();
     match lang {
         Lang::Rust => trimmed.starts_with("use "),
@@ -0,0 +1,557 @@
+//! Lockfiles summed up rather than hidden: how many packages are resolved,
+//! the project's direct dependencies at their locked versions, and the
+//! packages locked at several versions at once. A read with `query` looks
+//! up one package — its versions, where each is in the file, and what
+//! requires it.
+//!
+//! `Cargo.lock` and `poetry.lock`, `package-lock.json` (v1–v3) and
+//! `yarn.lock` (classic and berry) are read; other lockfiles keep the bare
+//! generated header.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::suggest_similar;
+use crate::error::TilthError;
+use crate::format;
+use crate::tokens;
+use crate::types::{FileType, ViewMode};
+
+/// Direct dependencies listed in the summary.
+const MAX_DIRECT: usize = 40;
+/// Packages with several versions listed in the summary.
+const MAX_DUPLICATES: usize = 30;
+/// Dependents named in a lookup.
+const MAX_DEPENDENTS: usize = 30;
+
+/// One resolved package. `line` is where its entry starts, when known.
+pub(crate) struct Package {
+    pub name: String,
+    pub version: String,
+    pub line: Option<u32>,
+    /// Names of the packages it requires.
+    pub deps: Vec<String>,
+}
+
+pub(crate) struct Lock {
+    pub packages: Vec<Package>,
+    /// The project's own dependencies, when the lockfile (or, for yarn, the
+    /// `package.json` beside it) says which they are.
+    pub direct: Vec<String>,
+}
+
+/// The packages locked in `content`, by the file name of `path`. `None`
+/// for other files and lockfiles that don't parse.
+pub(crate) fn parse(path: &Path, content: &str) -> Option<Lock> {
+    let lock = match path.file_name()?.to_str()? {
+        "Cargo.lock" => toml_lock(content, true),
+        "poetry.lock" => toml_lock(content, false),
+        "package-lock.json" => npm_lock(content)?,
+        "yarn.lock" => yarn_lock(content, path),
+        _ => return None,
+    };
+    (!lock.packages.is_empty()).then_some(lock)
+}
+
+/// The package count, the direct dependencies at their locked versions,
+/// and the packages locked at more than one version.
+pub(crate) fn summary(lock: &Lock) -> String {
+    let versions = by_name(lock);
+    let duplicates: Vec<(&str, &Vec<&Package>)> = {
+        let mut found: Vec<(&str, &Vec<&Package>)> = versions
+            .iter()
+            .filter(|(_, packages)| packages.len() > 1)
+            .map(|(name, packages)| (*name, packages))
+            .collect();
+        found.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+        found
+    };
+    let mut out = format!(
+        "{} packages ({} names), {} locked at several versions",
+        lock.packages.len(),
+        versions.len(),
+        duplicates.len()
+    );
+
+    if !lock.direct.is_empty() {
+        let _ = write!(out, "\n\ndirect dependencies ({}):", lock.direct.len());
+        for name in lock.direct.iter().take(MAX_DIRECT) {
+            let locked = versions.get(name.as_str()).map_or_else(
+                || "(not locked)".to_string(),
+                |packages| {
+                    packages
+                        .iter()
+                        .map(|p| at(p.line, &p.version))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            );
+            let _ = write!(out, "\n  {name} {locked}");
+        }
+        if lock.direct.len() > MAX_DIRECT {
+            let _ = write!(out, "\n  ... {} more", lock.direct.len() - MAX_DIRECT);
+        }
+    }
+
+    if !duplicates.is_empty() {
+        out.push_str("\n\nseveral versions:");
+        for (name, packages) in duplicates.iter().take(MAX_DUPLICATES) {
+            let listed: Vec<String> = packages.iter().map(|p| at(p.line, &p.version)).collect();
+            let _ = write!(out, "\n  {name} {}", listed.join(", "));
+        }
+        if duplicates.len() > MAX_DUPLICATES {
+            let _ = write!(out, "\n  ... {} more", duplicates.len() - MAX_DUPLICATES);
+        }
+    }
+    out.push_str(
+        "\n\n> Look up one package with query: \"<name>\" — its versions and what requires it.",
+    );
+    out
+}
+
+/// Every locked version of the package named `query`, and the packages
+/// that require it. Names compare ignoring case.
+pub(crate) fn lookup(lock: &Lock, query: &str) -> Result<String, String> {
+    let query = query.trim();
+    let found: Vec<&Package> = lock
+        .packages
+        .iter()
+        .filter(|p| p.name.eq_ignore_ascii_case(query))
+        .collect();
+    let Some(first) = found.first() else {
+        let lower = query.to_lowercase();
+        let mut similar: Vec<&str> = lock
+            .packages
+            .iter()
+            .map(|p| p.name.as_str())
+            .filter(|n| n.to_lowercase().contains(&lower))
+            .collect();
+        similar.sort_unstable();
+        similar.dedup();
+        similar.truncate(10);
+        return Err(if similar.is_empty() {
+            format!("no package named \"{query}\" is locked")
+        } else {
+            format!(
+                "no package named \"{query}\" is locked — similar: {}",
+                similar.join(", ")
+            )
+        });
+    };
+    let name = first.name.as_str();
+    let mut dependents: Vec<String> = lock
+        .packages
+        .iter()
+        .filter(|p| p.deps.iter().any(|d| d == name))
+        .map(|p| format!("{} {}", p.name, p.version))
+        .collect();
+    dependents.sort();
+    dependents.dedup();
+
+    let mut out = format!(
+        "{name} — {} version{}",
+        found.len(),
+        if found.len() == 1 { "" } else { "s" }
+    );
+    if lock.direct.iter().any(|d| d == name) {
+        out.push_str(", a direct dependency");
+    }
+    for package in &found {
+        let _ = write!(out, "\n  {}", at(package.line, &package.version));
+    }
+    if dependents.is_empty() {
+        out.push_str("\n\nrequired by: nothing else in the lockfile");
+    } else {
+        let _ = write!(out, "\n\nrequired by ({}):", dependents.len());
+        for dependent in dependents.iter().take(MAX_DEPENDENTS) {
+            let _ = write!(out, "\n  {dependent}");
+        }
+        if dependents.len() > MAX_DEPENDENTS {
+            let _ = write!(out, "\n  ... {} more", dependents.len() - MAX_DEPENDENTS);
+        }
+    }
+    Ok(out)
+}
+
+/// [`lookup`] on the lockfile at `path`, under its file header.
+pub fn package(path: &Path, query: &str) -> Result<String, TilthError> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: suggest_similar(path),
+        },
+        _ => TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })?;
+    let lock = parse(path, &content).ok_or_else(|| TilthError::ParseError {
+        path: path.to_path_buf(),
+        reason: "no packages found — lookups read Cargo.lock, poetry.lock, package-lock.json and yarn.lock".into(),
+    })?;
+    let found = lookup(&lock, query).map_err(|reason| TilthError::InvalidQuery {
+        query: query.to_string(),
+        reason,
+    })?;
+    let tokens = tokens::estimate(content.as_bytes(), FileType::Other);
+    let lines = content.lines().count() as u32;
+    let header = format::file_header(path, tokens, lines, ViewMode::Generated);
+    Ok(format!("{header}\n\n{found}"))
+}
+
+fn by_name(lock: &Lock) -> HashMap<&str, Vec<&Package>> {
+    let mut versions: HashMap<&str, Vec<&Package>> = HashMap::new();
+    for package in &lock.packages {
+        let entry = versions.entry(package.name.as_str()).or_default();
+        if !entry.iter().any(|p| p.version == package.version) {
+            entry.push(package);
+        }
+    }
+    versions
+}
+
+/// `1.0.86 [120]`, or the bare version when the line isn't known.
+fn at(line: Option<u32>, version: &str) -> String {
+    match line {
+        Some(line) => format!("{version} [{line}]"),
+        None => version.to_string(),
+    }
+}
+
+/// `Cargo.lock` and `poetry.lock`: `[[package]]` tables with `name`,
+/// `version` and, for Cargo, a `dependencies` array of `"name"` or
+/// `"name version"`. Cargo's own workspace members have no `source`; their
+/// dependencies are the direct ones.
+fn toml_lock(content: &str, cargo: bool) -> Lock {
+    struct Entry {
+        package: Package,
+        local: bool,
+    }
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut in_deps = false;
+    let mut in_table = false;
+    for (i, line) in content.lines().enumerate() {
+        let t = line.trim();
+        if t == "[[package]]" {
+            entries.push(Entry {
+                package: Package {
+                    name: String::new(),
+                    version: String::new(),
+                    line: Some((i + 1) as u32),
+                    deps: Vec::new(),
+                },
+                local: true,
+            });
+            in_deps = false;
+            in_table = true;
+            continue;
+        }
+        if t.starts_with('[') {
+            // poetry's `[package.dependencies]` still belongs to the package
+            in_table = t == "[package.dependencies]";
+            in_deps = in_table;
+            continue;
+        }
+        let Some(entry) = entries.last_mut().filter(|_| in_table) else {
+            continue;
+        };
+        if in_deps {
+            if t == "]" {
+                in_deps = false;
+                continue;
+            }
+            let dep = t.trim_end_matches(',').trim_matches('"');
+            let dep = dep
+                .split([' ', '='])
+                .next()
+                .unwrap_or(dep)
+                .trim_matches('"');
+            if !dep.is_empty() {
+                entry.package.deps.push(dep.to_string());
+            }
+        } else if let Some(name) = toml_string(t, "name") {
+            entry.package.name = name.to_string();
+        } else if let Some(version) = toml_string(t, "version") {
+            entry.package.version = version.to_string();
+        } else if toml_string(t, "source").is_some() {
+            entry.local = false;
+        } else if t.starts_with("dependencies") && t.ends_with('[') {
+            in_deps = true;
+        }
+    }
+    entries.retain(|e| !e.package.name.is_empty());
+
+    let mut direct: Vec<String> = entries
+        .iter()
+        .filter(|e| cargo && e.local)
+        .flat_map(|e| e.package.deps.iter().cloned())
+        .collect();
+    let locals: Vec<&str> = entries
+        .iter()
+        .filter(|e| e.local)
+        .map(|e| e.package.name.as_str())
+        .collect();
+    direct.retain(|d| !locals.contains(&d.as_str()));
+    direct.sort();
+    direct.dedup();
+    Lock {
+        packages: entries.into_iter().map(|e| e.package).collect(),
+        direct,
+    }
+}
+
+/// The string value of `key = "…"`.
+fn toml_string<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let value = line
+        .strip_prefix(key)?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim();
+    value.strip_prefix('"')?.strip_suffix('"')
+}
+
+/// `package-lock.json`: the `packages` map of v2/v3, keyed by
+/// `node_modules/…` path, or the nested `dependencies` of v1.
+fn npm_lock(content: &str) -> Option<Lock> {
+    let value: Value = serde_json::from_str(content).ok()?;
+    let keys = |v: &Value, field: &str| -> Vec<String> {
+        v.get(field)
+            .and_then(Value::as_object)
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+
+    let mut packages = Vec::new();
+    let mut direct = Vec::new();
+    if let Some(entries) = value.get("packages").and_then(Value::as_object) {
+        // Where each entry starts: `"node_modules/x": {`
+        let lines: HashMap<&str, u32> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let key = line.trim().strip_prefix('"')?.split('"').next()?;
+                key.contains("node_modules/")
+                    .then_some((key, (i + 1) as u32))
+            })
+            .collect();
+        for (key, entry) in entries {
+            if key.is_empty() {
+                for field in ["dependencies", "devDependencies", "optionalDependencies"] {
+                    direct.extend(keys(entry, field));
+                }
+                continue;
+            }
+            let name = entry
+                .get("name")
+                .and_then(Value::as_str)
+                .or_else(|| key.rsplit("node_modules/").next())
+                .unwrap_or(key);
+            let Some(version) = entry.get("version").and_then(Value::as_str) else {
+                continue;
+            };
+            packages.push(Package {
+                name: name.to_string(),
+                version: version.to_string(),
+                line: lines.get(key.as_str()).copied(),
+                deps: keys(entry, "dependencies"),
+            });
+        }
+    } else if let Some(deps) = value.get("dependencies").and_then(Value::as_object) {
+        fn walk(deps: &serde_json::Map<String, Value>, out: &mut Vec<Package>) {
+            for (name, entry) in deps {
+                if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                    out.push(Package {
+                        name: name.clone(),
+                        version: version.to_string(),
+                        line: None,
+                        deps: entry
+                            .get("requires")
+                            .and_then(Value::as_object)
+                            .map(|m| m.keys().cloned().collect())
+                            .unwrap_or_default(),
+                    });
+                }
+                if let Some(nested) = entry.get("dependencies").and_then(Value::as_object) {
+                    walk(nested, out);
+                }
+            }
+        }
+        walk(deps, &mut packages);
+    }
+    direct.sort();
+    direct.dedup();
+    Some(Lock { packages, direct })
+}
+
+/// `yarn.lock`: unindented `spec, spec:` lines open an entry, an indented
+/// `version` line and `dependencies:` block fill it. Direct dependencies
+/// come from the `package.json` beside it.
+fn yarn_lock(content: &str, path: &Path) -> Lock {
+    let mut packages: Vec<Package> = Vec::new();
+    let mut in_deps = false;
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let t = line.trim();
+        if indent == 0 {
+            in_deps = false;
+            let spec = t.trim_end_matches(':');
+            let first = spec.split(", ").next().unwrap_or(spec).trim_matches('"');
+            // `@scope/name@^1.0.0` or `name@npm:^1.0.0`
+            let name = match first.rfind('@') {
+                Some(at) if at > 0 => &first[..at],
+                _ => first,
+            };
+            packages.push(Package {
+                name: name.to_string(),
+                version: String::new(),
+                line: Some((i + 1) as u32),
+                deps: Vec::new(),
+            });
+            continue;
+        }
+        let Some(package) = packages.last_mut() else {
+            continue;
+        };
+        if indent <= 2 {
+            in_deps = t == "dependencies:";
+            if let Some(version) = t.strip_prefix("version") {
+                package.version = version
+                    .trim_start_matches(':')
+                    .trim()
+                    .trim_matches('"')
+                    .to_string();
+            }
+        } else if in_deps {
+            let dep = match t.strip_prefix('"') {
+                Some(quoted) => quoted.split('"').next().unwrap_or(quoted),
+                None => t.split([' ', ':']).next().unwrap_or(t),
+            };
+            package.deps.push(dep.to_string());
+        }
+    }
+    // berry's `__metadata:` entry has a version too
+    packages.retain(|p| !p.version.is_empty() && p.name != "__metadata");
+
+    let mut direct = Vec::new();
+    if let Some(manifest) = fs::read_to_string(path.with_file_name("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+    {
+        for field in ["dependencies", "devDependencies", "optionalDependencies"] {
+            if let Some(deps) = manifest.get(field).and_then(Value::as_object) {
+                direct.extend(deps.keys().cloned());
+            }
+        }
+    }
+    direct.sort();
+    direct.dedup();
+    Lock { packages, direct }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_LOCK: &str = r#"# This file is automatically @generated by Cargo.
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "syn 2.0.66",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.66"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    #[test]
+    fn summarizes_cargo_lock() {
+        let lock = parse(Path::new("Cargo.lock"), CARGO_LOCK).unwrap();
+        assert_eq!(lock.packages.len(), 4);
+        assert_eq!(lock.direct, vec!["serde", "syn"]);
+        let shown = summary(&lock);
+        assert!(
+            shown.starts_with("4 packages (3 names), 1 locked at several versions"),
+            "{shown}"
+        );
+        assert!(shown.contains("\n  serde 1.0.200 [12]\n"), "{shown}");
+        assert!(
+            shown.contains("several versions:\n  syn 1.0.109 [20], 2.0.66 [25]"),
+            "{shown}"
+        );
+
+        let found = lookup(&lock, "SYN").unwrap();
+        assert!(
+            found.starts_with("syn — 2 versions, a direct dependency"),
+            "{found}"
+        );
+        assert!(
+            found.contains("required by (2):\n  app 0.1.0\n  serde 1.0.200"),
+            "{found}"
+        );
+        let err = lookup(&lock, "ser").unwrap_err();
+        assert!(err.contains("similar: serde"), "{err}");
+    }
+
+    #[test]
+    fn reads_npm_and_yarn_entries() {
+        let npm = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "lodash": "^4.17.0" } },
+    "node_modules/lodash": { "version": "4.17.21" },
+    "node_modules/a/node_modules/lodash": { "version": "3.10.1" },
+    "node_modules/a": { "version": "1.0.0", "dependencies": { "lodash": "^3" } }
+  }
+}"#;
+        let lock = parse(Path::new("package-lock.json"), npm).unwrap();
+        assert_eq!(lock.direct, vec!["lodash"]);
+        let found = lookup(&lock, "lodash").unwrap();
+        assert!(
+            found.contains("4.17.21 [5]") && found.contains("3.10.1 [6]"),
+            "{found}"
+        );
+        assert!(found.contains("required by (1):\n  a 1.0.0"), "{found}");
+
+        let yarn = "# yarn lockfile v1\n\n\"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":\n  version \"7.24.0\"\n  dependencies:\n    debug \"^4.1.0\"\n\ndebug@^4.1.0:\n  version \"4.3.4\"\n";
+        let lock = parse(Path::new("/nonexistent/yarn.lock"), yarn).unwrap();
+        let names: Vec<(&str, &str, Option<u32>)> = lock
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.version.as_str(), p.line))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("@babel/core", "7.24.0", Some(3)),
+                ("debug", "4.3.4", Some(8))
+            ]
+        );
+        assert_eq!(lock.packages[0].deps, vec!["debug"]);
+    }
+}
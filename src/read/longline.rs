@@ -0,0 +1,277 @@
+//! Files that are one enormous line — JSON blobs, minified CSS, data dumps.
+//! Line numbers, hashes and expansion windows all see a single unusable
+//! line, so these are addressed by byte column instead: a read outlines the
+//! line as column ranges, and a `section` of `"<line>:<from>-<to>"` shows
+//! those bytes wrapped into rows, each labelled with where it starts.
+
+use std::fmt::Write;
+
+/// Lines at most; more than this and the file reads by line as usual.
+const MAX_LINES: usize = 3;
+/// Bytes per row of a column section.
+const ROW_BYTES: usize = 160;
+/// Bytes per outline entry when the line has no structure to split on.
+const CHUNK_BYTES: usize = 4_000;
+/// Outline entries listed; the rest are summed up as one range.
+const MAX_ENTRIES: usize = 50;
+/// Characters of each entry shown in the outline.
+const PREVIEW_CHARS: usize = 60;
+
+/// A `section` addressing bytes of one line: `"1:0-4000"` → `(1, 0, 4000)`,
+/// the end exclusive.
+pub(crate) fn parse_columns(section: &str) -> Option<(usize, usize, usize)> {
+    let (line, range) = section.split_once(':')?;
+    let (from, to) = range.split_once('-')?;
+    let line: usize = line.trim().parse().ok()?;
+    let (from, to): (usize, usize) = (from.trim().parse().ok()?, to.trim().parse().ok()?);
+    (line > 0 && to > from).then_some((line, from, to))
+}
+
+/// The column outline of `buf` when it is a few very long lines: each
+/// top-level JSON member or element, CSS rule, or else fixed-size chunk of
+/// the longest line, as `[line:from-to]  preview`. `None` for other files.
+pub(crate) fn outline(buf: &[u8]) -> Option<String> {
+    let lines: Vec<&[u8]> = buf
+        .strip_suffix(b"\n")
+        .unwrap_or(buf)
+        .split(|&b| b == b'\n')
+        .collect();
+    if lines.len() > MAX_LINES || !super::bundle::is_minified(buf) {
+        return None;
+    }
+    let (index, line) = lines.iter().enumerate().max_by_key(|(_, l)| l.len())?;
+    let number = index + 1;
+    let (entries, what) = if let Some(entries) = json_entries(line) {
+        (entries, "top-level JSON entries")
+    } else if let Some(entries) = css_entries(line) {
+        (entries, "rules")
+    } else {
+        (chunks(line), "chunks")
+    };
+
+    let mut out = format!(
+        "> Line {number} is {} bytes — {} {what}, by byte column. Read one with section: \"{number}:<from>-<to>\".\n",
+        line.len(),
+        entries.len()
+    );
+    for &(from, to) in entries.iter().take(MAX_ENTRIES) {
+        let _ = writeln!(out, "[{number}:{from}-{to}]  {}", preview(&line[from..to]));
+    }
+    if entries.len() > MAX_ENTRIES {
+        let from = entries[MAX_ENTRIES].0;
+        let to = entries.last().map_or(from, |e| e.1);
+        let _ = writeln!(
+            out,
+            "[{number}:{from}-{to}]  ... {} more",
+            entries.len() - MAX_ENTRIES
+        );
+    }
+    Some(out)
+}
+
+/// Bytes `from..to` of 1-based `line` in `buf`, as rows of about
+/// [`ROW_BYTES`] labelled `line:column`, and how many bytes that is. The
+/// range is clamped to the line and widened to whole characters.
+pub(crate) fn columns(
+    buf: &[u8],
+    line: usize,
+    from: usize,
+    to: usize,
+) -> Result<(String, usize), String> {
+    let lines: Vec<&[u8]> = buf.split(|&b| b == b'\n').collect();
+    let Some(text) = lines.get(line - 1) else {
+        return Err(format!(
+            "line {line} out of bounds (file has {} lines)",
+            lines.len()
+        ));
+    };
+    let text = String::from_utf8_lossy(text);
+    if from >= text.len() {
+        return Err(format!(
+            "column {from} out of bounds (line {line} has {} bytes)",
+            text.len()
+        ));
+    }
+    let from = floor_char(&text, from);
+    let to = ceil_char(&text, to.min(text.len()));
+
+    let width = to.max(1).ilog10() as usize + 1;
+    let mut out = String::new();
+    let mut at = from;
+    while at < to {
+        let end = ceil_char(&text, (at + ROW_BYTES).min(to));
+        let _ = writeln!(out, "{line}:{at:<width$} │ {}", &text[at..end]);
+        at = end;
+    }
+    Ok((out, to - from))
+}
+
+/// Each top-level member of a JSON object, or element of an array, as a
+/// byte range. `None` unless the line opens with `{` or `[`.
+fn json_entries(line: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let open = line.iter().position(|b| !b.is_ascii_whitespace())?;
+    if !matches!(line[open], b'{' | b'[') {
+        return None;
+    }
+    let mut entries = Vec::new();
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    let mut start = open + 1;
+    for (i, &b) in line.iter().enumerate().skip(open) {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    push_trimmed(&mut entries, line, start, i);
+                    break;
+                }
+            }
+            b',' if depth == 1 => {
+                push_trimmed(&mut entries, line, start, i);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    (!entries.is_empty()).then_some(entries)
+}
+
+/// Each top-level `selector { … }` rule as a byte range. `None` when the
+/// line has none.
+fn css_entries(line: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let mut entries = Vec::new();
+    let (mut depth, mut quote) = (0usize, None);
+    let mut start = 0;
+    for (i, &b) in line.iter().enumerate() {
+        if let Some(q) = quote {
+            if b == q {
+                quote = None;
+            }
+            continue;
+        }
+        match b {
+            b'"' | b'\'' => quote = Some(b),
+            b'{' => depth += 1,
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    push_trimmed(&mut entries, line, start, i + 1);
+                    start = i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    (!entries.is_empty()).then_some(entries)
+}
+
+/// The line in [`CHUNK_BYTES`] pieces, split on character boundaries.
+fn chunks(line: &[u8]) -> Vec<(usize, usize)> {
+    let mut entries = Vec::new();
+    let mut at = 0;
+    while at < line.len() {
+        let mut end = (at + CHUNK_BYTES).min(line.len());
+        while end < line.len() && (line[end] & 0b1100_0000) == 0b1000_0000 {
+            end += 1;
+        }
+        entries.push((at, end));
+        at = end;
+    }
+    entries
+}
+
+fn push_trimmed(entries: &mut Vec<(usize, usize)>, line: &[u8], from: usize, to: usize) {
+    let piece = &line[from..to];
+    let Some(first) = piece.iter().position(|b| !b.is_ascii_whitespace()) else {
+        return;
+    };
+    let last = piece
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .unwrap_or(first);
+    entries.push((from + first, from + last + 1));
+}
+
+/// The first [`PREVIEW_CHARS`] of `bytes`, whitespace collapsed.
+fn preview(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let mut words = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((cut, _)) = words.char_indices().nth(PREVIEW_CHARS) {
+        words.truncate(cut);
+        words.push('…');
+    }
+    words
+}
+
+fn floor_char(text: &str, mut at: usize) -> usize {
+    while !text.is_char_boundary(at) {
+        at -= 1;
+    }
+    at
+}
+
+fn ceil_char(text: &str, mut at: usize) -> usize {
+    while !text.is_char_boundary(at) {
+        at += 1;
+    }
+    at
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlines_json_members_by_column() {
+        let blob = format!(
+            "{{\"name\": \"fixture\", \"rows\": [{}], \"meta\": {{\"a\": \"}}\"}}}}\n",
+            vec!["{\"id\": 1, \"v\": \"x\"}"; 100].join(",")
+        );
+        let shown = outline(blob.as_bytes()).unwrap();
+        assert!(shown.contains("3 top-level JSON entries"), "{shown}");
+        assert!(shown.contains("[1:1-18]  \"name\": \"fixture\""), "{shown}");
+        let rows = shown.lines().nth(2).unwrap();
+        assert!(
+            rows.starts_with("[1:20-") && rows.contains("\"rows\": [{"),
+            "{shown}"
+        );
+        assert!(outline(b"{\"a\": 1}\n").is_none());
+
+        let css = "a{color:red}".repeat(200);
+        let shown = outline(css.as_bytes()).unwrap();
+        assert!(shown.contains("200 rules"), "{shown}");
+        assert!(shown.contains("[1:12-24]  a{color:red}"), "{shown}");
+        assert!(shown.contains("... 150 more"), "{shown}");
+    }
+
+    #[test]
+    fn column_sections_wrap_into_labelled_rows() {
+        assert_eq!(parse_columns("1:0-4000"), Some((1, 0, 4000)));
+        assert_eq!(parse_columns("45-89"), None);
+        assert_eq!(parse_columns("1:10-5"), None);
+
+        let line = "x".repeat(400);
+        let (rows, bytes) = columns(line.as_bytes(), 1, 100, 450).unwrap();
+        assert_eq!(bytes, 300);
+        let labels: Vec<&str> = rows
+            .lines()
+            .map(|r| r.split(" │").next().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["1:100", "1:260"]);
+        assert!(columns(line.as_bytes(), 2, 0, 10).is_err());
+        assert!(columns(line.as_bytes(), 1, 400, 410).is_err());
+        // A range inside a character widens to take all of it
+        let (rows, _) = columns("aé".as_bytes(), 1, 2, 3).unwrap();
+        assert!(rows.ends_with("│ é\n"), "{rows}");
+    }
+}
@@ -1,22 +1,31 @@
 pub mod binary;
+pub mod bundle;
 pub mod generated;
 pub mod imports;
+pub mod lockfile;
+pub mod longline;
 pub mod outline;
+pub mod records;
+pub mod snippet;
+pub mod symbols;
+pub mod viewer;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use memmap2::Mmap;
 
 use crate::cache::OutlineCache;
 use crate::error::TilthError;
 use crate::format;
-use crate::types::{estimate_tokens, FileType, Lang, ViewMode};
+use crate::tokens;
+use crate::types::{FileType, Lang, ViewMode};
 
 pub(crate) const TOKEN_THRESHOLD: u64 = 3_500;
-const FILE_SIZE_CAP: u64 = 500_000; // 500KB
 
-/// Main entry point for read mode. Routes through the decision tree.
+/// Main entry point for read mode. Sections, directories, empty and binary
+/// files are handled here — binary ones after the viewers registered for
+/// their extension pass; any other file goes to the [`viewer`]s.
 pub fn read_file(
     path: &Path,
     section: Option<&str>,
@@ -24,6 +33,11 @@ pub fn read_file(
     cache: &OutlineCache,
     edit_mode: bool,
 ) -> Result<String, TilthError> {
+    if crate::redact::hides(path) {
+        return Err(TilthError::Redacted {
+            path: path.to_path_buf(),
+        });
+    }
     let meta = match fs::metadata(path) {
         Ok(m) => m,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
@@ -71,64 +85,48 @@ pub fn read_file(
         path: path.to_path_buf(),
         source: e,
     })?;
-    let buf = &mmap[..];
+    let buf = format::strip_bom(&mmap[..]);
 
+    let file_type = detect_file_type(path);
+    let file = viewer::FileView {
+        path,
+        buf,
+        file_type,
+        byte_len,
+        tokens: tokens::estimate(buf, file_type),
+        lines: memchr::memchr_iter(b'\n', buf).count() as u32 + 1,
+        mtime: meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH),
+        full,
+        edit_mode,
+        cache,
+    };
+    // Viewers registered for the extension may know a binary format
+    if let Some(shown) = viewer::view_by_extension(&file) {
+        return shown;
+    }
     if binary::is_binary(buf) {
         let mime = mime_from_ext(path);
         return Ok(format::binary_header(path, byte_len, mime));
     }
-
-    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-    // Generated
-    if generated::is_generated_by_name(name) || generated::is_generated_by_content(buf) {
-        let line_count = memchr::memchr_iter(b'\n', buf).count() as u32 + 1;
-        return Ok(format::file_header(
-            path,
-            byte_len,
-            line_count,
-            ViewMode::Generated,
-        ));
-    }
-
-    let tokens = estimate_tokens(byte_len);
-    let content = String::from_utf8_lossy(buf);
-    let line_count = memchr::memchr_iter(b'\n', buf).count() as u32 + 1;
-
-    // Full mode or small file → return full content (skip smart view)
-    if full || tokens <= TOKEN_THRESHOLD {
-        let header = format::file_header(path, byte_len, line_count, ViewMode::Full);
-        if edit_mode {
-            let numbered = format::hashlines(&content, 1);
-            return Ok(format!("{header}\n\n{numbered}"));
-        }
-        return Ok(format!("{header}\n\n{content}"));
-    }
-
-    // Large file → smart view by file type
-    let file_type = detect_file_type(path);
-    let mtime = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-
-    let capped = byte_len > FILE_SIZE_CAP;
-
-    let outline = cache.get_or_compute(path, mtime, || {
-        outline::generate(path, file_type, &content, buf, capped)
-    });
-
-    let mode = match file_type {
-        FileType::StructuredData => ViewMode::Keys,
-        _ => ViewMode::Outline,
-    };
-    let header = format::file_header(path, byte_len, line_count, mode);
-    Ok(format!("{header}\n\n{outline}"))
+    viewer::view(&file)
 }
 
 /// Would this file produce an outline (rather than full content) in default read mode?
 /// Used by the MCP layer to decide whether to append related-file hints.
 pub fn would_outline(path: &Path) -> bool {
-    std::fs::metadata(path)
-        .map(|m| !m.is_dir() && estimate_tokens(m.len()) > TOKEN_THRESHOLD)
-        .unwrap_or(false)
+    let Ok(meta) = std::fs::metadata(path) else {
+        return false;
+    };
+    if meta.is_dir() {
+        return false;
+    }
+    let file_type = detect_file_type(path);
+    if tokens::estimate_len(meta.len(), file_type) > TOKEN_THRESHOLD {
+        return true;
+    }
+    // Dense content can cross the threshold below its byte-length estimate
+    meta.len() > TOKEN_THRESHOLD
+        && fs::read(path).is_ok_and(|buf| tokens::estimate(&buf, file_type) > TOKEN_THRESHOLD)
 }
 
 /// Resolve a heading address to a line range in a markdown file.
@@ -239,19 +237,56 @@ fn read_section(path: &Path, range: &str, edit_mode: bool) -> Result<String, Til
         path: path.to_path_buf(),
         source: e,
     })?;
-    let buf = &mmap[..];
+    let buf = format::strip_bom(&mmap[..]);
+
+    if let Some((line, from, to)) = longline::parse_columns(range) {
+        let (rows, bytes) =
+            longline::columns(buf, line, from, to).map_err(|reason| TilthError::InvalidQuery {
+                query: range.to_string(),
+                reason,
+            })?;
+        let tokens = tokens::estimate_len(bytes as u64, detect_file_type(path));
+        let header = format::file_header(path, tokens, 1, ViewMode::Section);
+        return Ok(format!("{header}\n\n{rows}"));
+    }
+
+    let (start, line_count, bytes) = section_bytes(buf, range)?;
+    let selected = String::from_utf8_lossy(bytes);
+    let tokens = tokens::estimate(selected.as_bytes(), detect_file_type(path));
+    let header = format::file_header(path, tokens, line_count, ViewMode::Section);
+    let formatted = if edit_mode {
+        format::hashlines(&selected, start)
+    } else {
+        format::number_lines(&selected, start)
+    };
+    Ok(format!("{header}\n\n{formatted}"))
+}
 
+/// Resolve a section address (line range, markdown heading or XML element
+/// path) against a file buffer.
+/// Returns `(first_line, line_count, selected_bytes)` with `first_line` 1-indexed.
+pub(crate) fn section_bytes<'a>(
+    buf: &'a [u8],
+    range: &str,
+) -> Result<(u32, u32, &'a [u8]), TilthError> {
     // Check if this is a heading-based address (markdown)
     let (start, end) = if range.starts_with('#') {
         resolve_heading(buf, range).ok_or_else(|| TilthError::InvalidQuery {
             query: range.to_string(),
             reason: "heading not found in file".into(),
         })?
-    } else {
-        parse_range(range).ok_or_else(|| TilthError::InvalidQuery {
+    } else if let Some(lines) = parse_range(range) {
+        lines
+    } else if let Some(element) = outline::xml::section(&String::from_utf8_lossy(buf), range) {
+        element.map_err(|reason| TilthError::InvalidQuery {
             query: range.to_string(),
-            reason: "expected format: \"start-end\" (e.g. \"45-89\") or heading (e.g. \"## Architecture\")".into(),
+            reason,
         })?
+    } else {
+        return Err(TilthError::InvalidQuery {
+            query: range.to_string(),
+            reason: "expected format: \"start-end\" (e.g. \"45-89\"), \"line:from-to\" byte columns (e.g. \"1:0-4000\"), heading (e.g. \"## Architecture\") or XML element path (e.g. \"project/dependencies\")".into(),
+        });
     };
 
     // Find line offsets using memchr — no full-file Vec<&str> allocation
@@ -278,16 +313,7 @@ fn read_section(path: &Path, range: &str, edit_mode: bool) -> Result<String, Til
         buf.len()
     };
 
-    let selected = String::from_utf8_lossy(&buf[start_byte..end_byte]);
-    let byte_len = selected.len() as u64;
-    let line_count = (e - s) as u32;
-    let header = format::file_header(path, byte_len, line_count, ViewMode::Section);
-    let formatted = if edit_mode {
-        format::hashlines(&selected, start as u32)
-    } else {
-        format::number_lines(&selected, start as u32)
-    };
-    Ok(format!("{header}\n\n{formatted}"))
+    Ok(((s + 1) as u32, (e - s) as u32, &buf[start_byte..end_byte]))
 }
 
 /// Parse "45-89" into (45, 89). 1-indexed.
@@ -309,7 +335,11 @@ fn list_directory(path: &Path) -> Result<String, TilthError> {
         source: e,
     })?;
 
-    let mut items: Vec<_> = read_dir.filter_map(std::result::Result::ok).collect();
+    let visible = crate::redact::visible();
+    let mut items: Vec<_> = read_dir
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| visible(&entry.path()))
+        .collect();
     items.sort_by_key(std::fs::DirEntry::file_name);
 
     for entry in &items {
@@ -323,7 +353,7 @@ fn list_directory(path: &Path) -> Result<String, TilthError> {
             Some(t) if t.is_symlink() => " →".to_string(),
             _ => match meta {
                 Some(m) => {
-                    let tokens = estimate_tokens(m.len());
+                    let tokens = tokens::estimate_len(m.len(), detect_file_type(&entry.path()));
                     format!("  ({tokens} tokens)")
                 }
                 None => String::new(),
@@ -381,17 +411,71 @@ pub fn suggest_similar_file(scope: &Path, query: &str) -> Option<String> {
     suggest_similar(&resolved)
 }
 
-/// Suggest a similar file name from the parent directory (edit distance).
-fn suggest_similar(path: &Path) -> Option<String> {
+/// What to offer for a `path` that doesn't exist: a similarly named file
+/// beside it; the path with a misspelled directory corrected; the nearest
+/// directory that does exist; or, for a glob, the files it matches.
+pub(crate) fn suggest_similar(path: &Path) -> Option<String> {
+    if crate::classify::is_glob(&path.to_string_lossy()) {
+        return Some(suggest_glob(path));
+    }
     let parent = path.parent()?;
     let name = path.file_name()?.to_str()?;
-    let entries = fs::read_dir(parent).ok()?;
+    if parent.as_os_str().is_empty() || parent.is_dir() {
+        return similar_entry(parent, name, false);
+    }
+
+    // A directory on the way is missing: correct the first one that is
+    let mut base = parent;
+    while !(base.as_os_str().is_empty() || base.is_dir()) {
+        base = base.parent()?;
+    }
+    let rest = parent.strip_prefix(base).ok()?;
+    let mut rest = rest.components();
+    let missing = rest.next()?.as_os_str().to_str()?;
+    match similar_entry(base, missing, true) {
+        Some(fixed) => {
+            let corrected = base.join(&fixed).join(rest.as_path()).join(name);
+            if corrected.exists() {
+                return Some(corrected.display().to_string());
+            }
+            let dir = corrected.parent()?;
+            if dir.is_dir() {
+                if let Some(file) = similar_entry(dir, name, false) {
+                    return Some(dir.join(file).display().to_string());
+                }
+            }
+            Some(format!(
+                "{}/ (similar directory)",
+                base.join(fixed).display()
+            ))
+        }
+        None if base.as_os_str().is_empty() => None,
+        None => Some(format!("{}/ (nearest existing directory)", base.display())),
+    }
+}
+
+/// The entry of `dir` closest to `name` by edit distance, within 3 edits.
+/// With `dirs_only`, only subdirectories count.
+fn similar_entry(dir: &Path, name: &str, dirs_only: bool) -> Option<String> {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let entries = fs::read_dir(dir).ok()?;
 
     let mut best: Option<(usize, String)> = None;
     for entry in entries.flatten() {
+        if dirs_only && !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
         let candidate = entry.file_name();
         let candidate = candidate.to_string_lossy();
-        let dist = edit_distance(name, &candidate);
+        let dist = if candidate.eq_ignore_ascii_case(name) {
+            0
+        } else {
+            edit_distance(name, &candidate)
+        };
         if dist <= 3 {
             match &best {
                 Some((d, _)) if dist < *d => best = Some((dist, candidate.into_owned())),
@@ -403,8 +487,57 @@ fn suggest_similar(path: &Path) -> Option<String> {
     best.map(|(_, name)| name)
 }
 
-/// Simple Levenshtein distance — only used on short file names.
-fn edit_distance(a: &str, b: &str) -> usize {
+/// Most files named when a glob was passed as a path.
+const GLOB_SUGGESTIONS: usize = 5;
+
+/// A glob passed where a path belongs: the files it matches, as `tilth_files`
+/// would find them, searched from the literal directories in front of it.
+fn suggest_glob(path: &Path) -> String {
+    let mut base = PathBuf::new();
+    let mut pattern = Vec::new();
+    for component in path.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if pattern.is_empty() && !crate::classify::is_glob(&part) {
+            base.push(component);
+        } else {
+            pattern.push(part.into_owned());
+        }
+    }
+    let pattern = pattern.join("/");
+    let scope = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    };
+
+    let found = crate::search::glob::search(&pattern, &scope).ok();
+    let Some(found) = found.filter(|f| !f.files.is_empty()) else {
+        return format!(
+            "tilth_files — `{pattern}` is a glob, and nothing in {} matches it",
+            scope.display()
+        );
+    };
+    let mut names: Vec<String> = found
+        .files
+        .iter()
+        .map(|f| f.path.display().to_string())
+        .collect();
+    names.sort();
+    names.truncate(GLOB_SUGGESTIONS);
+    let more = found.total_found.saturating_sub(names.len());
+    let more = if more > 0 {
+        format!(" (+{more} more)")
+    } else {
+        String::new()
+    };
+    format!(
+        "{}{more} — `{pattern}` is a glob; tilth_files lists matches",
+        names.join(", ")
+    )
+}
+
+/// Simple Levenshtein distance — only used on short names (files, symbols).
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
     let a = a.as_bytes();
     let b = b.as_bytes();
     let mut prev: Vec<usize> = (0..=b.len()).collect();
@@ -447,6 +580,42 @@ fn mime_from_ext(path: &Path) -> &'static str {
 mod tests {
     use super::*;
 
+    #[test]
+    fn suggests_directories_and_glob_matches() {
+        let dir = std::env::temp_dir().join("tilth_test_suggest_paths");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/handlers")).unwrap();
+        fs::write(dir.join("src/handlers/auth.rs"), "").unwrap();
+        fs::write(dir.join("src/handlers/user.rs"), "").unwrap();
+
+        // Misspelled directory, file beside it exists
+        assert_eq!(
+            suggest_similar(&dir.join("src/handler/auth.rs")),
+            Some(dir.join("src/handlers/auth.rs").display().to_string())
+        );
+        // Both misspelled
+        assert_eq!(
+            suggest_similar(&dir.join("src/handler/auht.rs")),
+            Some(dir.join("src/handlers/auth.rs").display().to_string())
+        );
+        // Nothing close: the deepest directory that exists
+        assert_eq!(
+            suggest_similar(&dir.join("src/models/post/mod.rs")),
+            Some(format!(
+                "{}/ (nearest existing directory)",
+                dir.join("src").display()
+            ))
+        );
+
+        let glob = suggest_similar(&dir.join("src/handlers/*.rs")).unwrap();
+        assert!(
+            glob.starts_with(&dir.join("src/handlers/auth.rs").display().to_string()),
+            "{glob}"
+        );
+        assert!(glob.contains("user.rs — `*.rs` is a glob"), "{glob}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn heading_found() {
         let input = b"# Title\nSome content\n## Section\nSection content\n";
@@ -500,6 +669,13 @@ mod tests {
         assert_eq!(result, Some((1, 4)));
     }
 
+    #[test]
+    fn heading_in_crlf_file_with_bom() {
+        let input = format::strip_bom(b"\xEF\xBB\xBF# Title\r\ntext\r\n## Next\r\nmore\r\n");
+        assert_eq!(resolve_heading(input, "# Title"), Some((1, 4)));
+        assert_eq!(resolve_heading(input, "## Next"), Some((3, 4)));
+    }
+
     #[test]
     fn no_hashes() {
         let input = b"# Heading\ntext\n";
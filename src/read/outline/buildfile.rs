@@ -0,0 +1,390 @@
+//! Dockerfile and Makefile outlines. No grammar ships for either, but both
+//! are line-oriented enough that a scanner recovers the structure that matters:
+//! build stages and their instructions, make targets with prerequisites.
+
+use crate::types::{truncate_str, Lang};
+
+/// One Dockerfile instruction, spanning its `\` continuation and heredoc lines.
+pub(crate) struct Instruction<'a> {
+    pub keyword: String,
+    pub args: &'a str,
+    pub line: u32,
+    pub end_line: u32,
+}
+
+/// An explicit make rule target. `end_line` covers the recipe.
+pub(crate) struct MakeTarget<'a> {
+    pub name: &'a str,
+    pub prereqs: &'a str,
+    pub line: u32,
+    pub end_line: u32,
+}
+
+/// A named definition found by a line scanner rather than tree-sitter:
+/// Dockerfile stage, make target, `OpenAPI` operation or Kubernetes resource.
+pub(crate) struct BuildSymbol<'a> {
+    pub name: &'a str,
+    /// `stage`, `target`, `operation`, `schema` or `resource`.
+    pub kind: &'static str,
+    pub line: u32,
+    pub end_line: u32,
+}
+
+/// Definitions for symbol search and indexing. `None` for languages this module doesn't handle.
+pub(crate) fn symbols(lang: Lang, content: &str) -> Option<Vec<BuildSymbol<'_>>> {
+    match lang {
+        Lang::Dockerfile => Some(
+            dockerfile_stages(&dockerfile_instructions(content))
+                .into_iter()
+                .filter_map(|stage| {
+                    Some(BuildSymbol {
+                        name: stage_name(stage.first()?.args)?,
+                        kind: "stage",
+                        line: stage.first()?.line,
+                        end_line: stage.last()?.end_line,
+                    })
+                })
+                .collect(),
+        ),
+        Lang::Make => Some(
+            make_targets(content)
+                .into_iter()
+                .map(|t| BuildSymbol {
+                    name: t.name,
+                    kind: "target",
+                    line: t.line,
+                    end_line: t.end_line,
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+pub(crate) fn dockerfile_instructions(content: &str) -> Vec<Instruction<'_>> {
+    let mut out: Vec<Instruction> = Vec::new();
+    let mut continued = false;
+    let mut heredoc: Option<&str> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let n = (i + 1) as u32;
+        let trimmed = line.trim();
+
+        if let Some(terminator) = heredoc {
+            if let Some(last) = out.last_mut() {
+                last.end_line = n;
+            }
+            if trimmed == terminator {
+                heredoc = None;
+            }
+            continue;
+        }
+        if continued {
+            if let Some(last) = out.last_mut() {
+                last.end_line = n;
+            }
+            continued = trimmed.ends_with('\\') || trimmed.starts_with('#');
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, args) = trimmed
+            .split_once(char::is_whitespace)
+            .unwrap_or((trimmed, ""));
+        let args = args.trim_end_matches('\\').trim();
+        heredoc = heredoc_terminator(args);
+        continued = trimmed.ends_with('\\');
+        out.push(Instruction {
+            keyword: keyword.to_ascii_uppercase(),
+            args,
+            line: n,
+            end_line: n,
+        });
+    }
+    out
+}
+
+/// `RUN <<EOF` / `COPY <<-"EOF" /x` → `EOF`.
+fn heredoc_terminator(args: &str) -> Option<&str> {
+    let rest = &args[args.find("<<")? + 2..];
+    let rest = rest.strip_prefix('-').unwrap_or(rest);
+    let rest = rest.trim_start_matches(['"', '\'']);
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+/// Split instructions into stages, each starting at a `FROM`.
+/// Instructions before the first `FROM` (global `ARG`s) form no stage.
+fn dockerfile_stages<'i, 'a>(instructions: &'i [Instruction<'a>]) -> Vec<&'i [Instruction<'a>]> {
+    let starts: Vec<usize> = instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, ins)| ins.keyword == "FROM")
+        .map(|(i, _)| i)
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(k, &s)| {
+            let e = starts.get(k + 1).copied().unwrap_or(instructions.len());
+            &instructions[s..e]
+        })
+        .collect()
+}
+
+/// `rust:1.80 AS builder` → `builder`.
+fn stage_name(from_args: &str) -> Option<&str> {
+    let mut words = from_args.split_whitespace();
+    while let Some(word) = words.next() {
+        if word.eq_ignore_ascii_case("as") {
+            return words.next();
+        }
+    }
+    None
+}
+
+/// Stages (`FROM`) at the top level with their instructions beneath,
+/// consecutive runs of the same instruction collapsed to `RUN ×3`.
+pub fn dockerfile_outline(content: &str, max_lines: usize) -> String {
+    let instructions = dockerfile_instructions(content);
+    let mut out = Vec::new();
+
+    // Global ARGs before the first FROM
+    for ins in instructions.iter().take_while(|ins| ins.keyword != "FROM") {
+        out.push(entry(0, ins.line, ins.end_line, &ins.keyword, ins.args));
+    }
+
+    for stage in dockerfile_stages(&instructions) {
+        let (from, body) = stage.split_first().expect("stage starts with FROM");
+        let end = stage.last().map_or(from.end_line, |ins| ins.end_line);
+        out.push(entry(0, from.line, end, "FROM", from.args));
+
+        for group in body.chunk_by(|a, b| a.keyword == b.keyword) {
+            let first = &group[0];
+            let last = &group[group.len() - 1];
+            let label = if group.len() > 1 {
+                format!("×{}", group.len())
+            } else {
+                first.args.to_string()
+            };
+            out.push(entry(1, first.line, last.end_line, &first.keyword, &label));
+        }
+    }
+
+    out.truncate(max_lines);
+    out.join("\n")
+}
+
+/// Explicit targets of a Makefile. Skips special targets (`.PHONY`),
+/// pattern rules, and variable assignments (`X := a:b`).
+pub(crate) fn make_targets(content: &str) -> Vec<MakeTarget<'_>> {
+    let mut out: Vec<MakeTarget> = Vec::new();
+    let mut recipe_of: Option<usize> = None; // index in `out` of the first target of the current rule
+
+    for (i, line) in content.lines().enumerate() {
+        let n = (i + 1) as u32;
+        if line.starts_with('\t') {
+            if let Some(first) = recipe_of {
+                for t in &mut out[first..] {
+                    t.end_line = n;
+                }
+            }
+            continue;
+        }
+        if !line.trim().is_empty() && !line.starts_with('#') {
+            recipe_of = None;
+        }
+        if line.starts_with([' ', '#', '.']) {
+            continue;
+        }
+        let Some(colon) = line.find(':') else {
+            continue;
+        };
+        let (head, rest) = line.split_at(colon);
+        if rest.starts_with(":=") || rest.starts_with("::=") || head.contains(['=', '$', '%']) {
+            continue;
+        }
+
+        let prereqs = rest
+            .trim_start_matches(':')
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim();
+        let first = out.len();
+        for name in head.split_whitespace() {
+            out.push(MakeTarget {
+                name,
+                prereqs,
+                line: n,
+                end_line: n,
+            });
+        }
+        if out.len() > first {
+            recipe_of = Some(first);
+        }
+    }
+    out
+}
+
+/// `CC := gcc`, `export PATH += x`, `override X ?= y` → the variable name.
+fn make_variable(line: &str) -> Option<&str> {
+    if line.starts_with(['\t', ' ', '#']) {
+        return None;
+    }
+    let eq = line.find('=')?;
+    let head = line[..eq].trim_end_matches([':', '?', '+', '!']).trim();
+    let head = head
+        .strip_prefix("export ")
+        .or_else(|| head.strip_prefix("override "))
+        .unwrap_or(head)
+        .trim();
+    let valid = !head.is_empty()
+        && head
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    valid.then_some(head)
+}
+
+/// Variables, includes and targets (with prerequisites) in file order.
+pub fn make_outline(content: &str, max_lines: usize) -> String {
+    let targets = make_targets(content);
+    let mut out: Vec<(u32, String)> = targets
+        .iter()
+        .map(|t| {
+            let name = if t.prereqs.is_empty() {
+                t.name.to_string()
+            } else {
+                format!("{}: {}", t.name, t.prereqs)
+            };
+            (t.line, entry(0, t.line, t.end_line, "target", &name))
+        })
+        .collect();
+
+    for (i, line) in content.lines().enumerate() {
+        let n = (i + 1) as u32;
+        if let Some(path) = line
+            .strip_prefix("include ")
+            .or_else(|| line.strip_prefix("-include "))
+        {
+            out.push((n, entry(0, n, n, "include", path.trim())));
+        } else if let Some(var) = make_variable(line) {
+            out.push((n, entry(0, n, n, "var", var)));
+        }
+    }
+
+    out.sort_by_key(|(line, _)| *line);
+    out.into_iter()
+        .take(max_lines)
+        .map(|(_, s)| s)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Same layout as code outline entries: `[start-end]  label name`.
+pub(crate) fn entry(indent: usize, start: u32, end: u32, label: &str, name: &str) -> String {
+    let prefix = "  ".repeat(indent);
+    let range = if start == end {
+        format!("[{start}]")
+    } else {
+        format!("[{start}-{end}]")
+    };
+    let name = if name.len() > 60 {
+        format!("{}...", truncate_str(name, 57))
+    } else {
+        name.to_string()
+    };
+    format!("{prefix}{range:<12} {label} {name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKERFILE: &str = "\
+ARG RUST_VERSION=1.80
+FROM rust:${RUST_VERSION} AS builder
+WORKDIR /app
+COPY Cargo.toml .
+COPY src src
+RUN apt-get update && \\
+    apt-get install -y pkg-config
+RUN cargo build --release
+
+FROM debian:slim AS runtime
+COPY --from=builder /app/target/release/app /usr/local/bin/
+RUN <<EOF
+useradd app
+EOF
+EXPOSE 8080
+ENTRYPOINT [\"app\"]
+";
+
+    #[test]
+    fn dockerfile_stages_and_groups() {
+        let outline = dockerfile_outline(DOCKERFILE, 100);
+        let lines: Vec<&str> = outline.lines().collect();
+        assert_eq!(lines[0], "[1]          ARG RUST_VERSION=1.80");
+        assert_eq!(
+            lines[1],
+            "[2-8]        FROM rust:${RUST_VERSION} AS builder"
+        );
+        assert_eq!(lines[3], "  [4-5]        COPY ×2");
+        assert_eq!(lines[4], "  [6-8]        RUN ×2");
+        assert_eq!(lines[5], "[10-16]      FROM debian:slim AS runtime");
+        // Heredoc body is part of the RUN, not separate instructions
+        assert_eq!(lines[7], "  [12-14]      RUN <<EOF");
+        assert_eq!(lines.len(), 10);
+    }
+
+    #[test]
+    fn dockerfile_symbols_are_stage_names() {
+        let syms = symbols(Lang::Dockerfile, DOCKERFILE).unwrap();
+        let names: Vec<(&str, u32, u32)> =
+            syms.iter().map(|s| (s.name, s.line, s.end_line)).collect();
+        assert_eq!(names, vec![("builder", 2, 8), ("runtime", 10, 16)]);
+    }
+
+    const MAKEFILE: &str = "\
+include common.mk
+CC := gcc
+export PATH += ./bin
+
+.PHONY: build test
+build: deps
+\tcargo build
+\tstrip target/app
+
+test:
+\tcargo test
+%.o: %.c
+\t$(CC) -c $<
+";
+
+    #[test]
+    fn make_targets_cover_recipes() {
+        let targets = make_targets(MAKEFILE);
+        let found: Vec<(&str, &str, u32, u32)> = targets
+            .iter()
+            .map(|t| (t.name, t.prereqs, t.line, t.end_line))
+            .collect();
+        assert_eq!(found, vec![("build", "deps", 6, 8), ("test", "", 10, 11)]);
+    }
+
+    #[test]
+    fn make_outline_in_file_order() {
+        let outline = make_outline(MAKEFILE, 100);
+        assert_eq!(
+            outline,
+            "[1]          include common.mk\n\
+             [2]          var CC\n\
+             [3]          var PATH\n\
+             [6-8]        target build: deps\n\
+             [10-11]      target test"
+        );
+    }
+}
@@ -0,0 +1,489 @@
+//! `Cargo.toml` as a manifest rather than a TOML tree: the package, its
+//! features, and each dependency with version, source and enabled features.
+//! `workspace = true` fields and dependencies are resolved against the
+//! nearest workspace root above, so the view shows what the crate actually
+//! builds with.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use toml::Value;
+
+use crate::types::truncate_str;
+
+/// Dependency tables, in the order they are shown.
+pub(crate) const DEP_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+/// `[package]` fields shown after the name and version.
+const PACKAGE_FIELDS: [&str; 5] = [
+    "edition",
+    "rust-version",
+    "license",
+    "publish",
+    "description",
+];
+/// Target tables listed by name.
+const TARGETS: [&str; 5] = ["lib", "bin", "example", "test", "bench"];
+
+/// A dependency as the crate builds it, after workspace inheritance.
+pub(crate) struct Dep {
+    pub name: String,
+    /// The table it is declared in, e.g. `dependencies` or
+    /// `target.cfg(unix).dependencies`.
+    pub table: String,
+    pub version: Option<String>,
+    /// `path ../core` or `git https://…`.
+    pub source: Option<String>,
+    /// The crate it renames, from `package = "…"`.
+    pub package: Option<String>,
+    pub features: Vec<String>,
+    pub optional: bool,
+    pub default_features: bool,
+    /// Declared `workspace = true`.
+    pub inherited: bool,
+}
+
+/// Where each `key` of each `[table]` is written, quotes removed:
+/// `("dependencies", "serde", 14)`. A `[dependencies.serde]` header counts
+/// as the key `serde` of `dependencies`.
+pub(crate) struct KeyLines(Vec<(String, String, u32)>);
+
+impl KeyLines {
+    pub(crate) fn scan(content: &str) -> Self {
+        let mut found = Vec::new();
+        let mut table = String::new();
+        for (i, line) in content.lines().enumerate() {
+            let n = (i + 1) as u32;
+            let t = line.trim();
+            if let Some(header) = t.strip_prefix('[') {
+                let header = header.trim_start_matches('[');
+                let header = header.split(']').next().unwrap_or(header);
+                table = unquote(header);
+                let (parent, key) = table.rsplit_once('.').unwrap_or(("", &table));
+                found.push((parent.to_string(), key.to_string(), n));
+                continue;
+            }
+            let Some(key) = t.split(['=', '.']).next().map(str::trim) else {
+                continue;
+            };
+            if !key.is_empty() && !key.starts_with('#') && t.len() > key.len() {
+                found.push((table.clone(), unquote(key), n));
+            }
+        }
+        Self(found)
+    }
+
+    /// The line of `key` in `table`.
+    pub(crate) fn get(&self, table: &str, key: &str) -> Option<u32> {
+        let table = unquote(table);
+        self.0
+            .iter()
+            .find(|(t, k, _)| *t == table && k == key)
+            .map(|&(_, _, n)| n)
+    }
+
+    /// The line of the `[table]` header.
+    fn header(&self, table: &str) -> Option<u32> {
+        let (parent, key) = table.rsplit_once('.').unwrap_or(("", table));
+        self.get(parent, key)
+    }
+}
+
+fn unquote(text: &str) -> String {
+    text.replace(['"', '\''], "").trim().to_string()
+}
+
+/// The `[workspace]` table of the nearest `Cargo.toml` at or above the
+/// directory of `manifest` that has one.
+pub(crate) fn workspace_of(manifest: &Path) -> Option<Value> {
+    let dir = crate::pathnorm::canonical(manifest.parent().unwrap_or(Path::new(".")));
+    dir.ancestors().find_map(|d| {
+        let value: Value = std::fs::read_to_string(d.join("Cargo.toml"))
+            .ok()?
+            .parse()
+            .ok()?;
+        value.get("workspace").cloned()
+    })
+}
+
+/// Every dependency of `manifest`, target-specific ones included, with
+/// `workspace = true` entries filled in from `workspace`.
+pub(crate) fn dependencies(manifest: &Value, workspace: Option<&Value>) -> Vec<Dep> {
+    let mut tables: Vec<(String, &toml::Table)> = Vec::new();
+    for table in DEP_TABLES {
+        if let Some(deps) = manifest.get(table).and_then(Value::as_table) {
+            tables.push((table.to_string(), deps));
+        }
+    }
+    if let Some(targets) = manifest.get("target").and_then(Value::as_table) {
+        for (cfg, target) in targets {
+            for table in DEP_TABLES {
+                if let Some(deps) = target.get(table).and_then(Value::as_table) {
+                    tables.push((format!("target.{cfg}.{table}"), deps));
+                }
+            }
+        }
+    }
+    let shared = workspace
+        .and_then(|w| w.get("dependencies"))
+        .and_then(Value::as_table);
+
+    let mut deps = Vec::new();
+    for (table, entries) in tables {
+        for (name, spec) in entries {
+            let mut dep = Dep {
+                name: name.clone(),
+                table: table.clone(),
+                version: None,
+                source: None,
+                package: None,
+                features: Vec::new(),
+                optional: false,
+                default_features: true,
+                inherited: false,
+            };
+            if spec.get("workspace").and_then(Value::as_bool) == Some(true) {
+                dep.inherited = true;
+                if let Some(base) = shared.and_then(|s| s.get(name)) {
+                    apply(&mut dep, base);
+                }
+            }
+            apply(&mut dep, spec);
+            deps.push(dep);
+        }
+    }
+    deps
+}
+
+/// Fill `dep` from one spec: `"1.0"` or `{ version, path, features, … }`.
+/// Features add up, as Cargo merges them over the workspace's.
+fn apply(dep: &mut Dep, spec: &Value) {
+    let str_of = |key: &str| spec.get(key).and_then(Value::as_str).map(str::to_string);
+    if let Some(version) = spec.as_str() {
+        dep.version = Some(version.to_string());
+        return;
+    }
+    if let Some(version) = str_of("version") {
+        dep.version = Some(version);
+    }
+    if let Some(path) = str_of("path") {
+        dep.source = Some(format!("path {path}"));
+    } else if let Some(git) = str_of("git") {
+        dep.source = Some(format!("git {git}"));
+    }
+    if let Some(package) = str_of("package") {
+        dep.package = Some(package);
+    }
+    for feature in spec
+        .get("features")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        if !dep.features.iter().any(|f| f == feature) {
+            dep.features.push(feature.to_string());
+        }
+    }
+    if let Some(optional) = spec.get("optional").and_then(Value::as_bool) {
+        dep.optional = optional;
+    }
+    if let Some(default) = spec
+        .get("default-features")
+        .or_else(|| spec.get("default_features"))
+        .and_then(Value::as_bool)
+    {
+        dep.default_features = default;
+    }
+}
+
+/// The manifest view of a `Cargo.toml`, or `None` when `content` doesn't
+/// parse or has neither `[package]` nor `[workspace]`.
+pub fn outline(path: &Path, content: &str, max_lines: usize) -> Option<String> {
+    let manifest: Value = content.parse().ok()?;
+    if manifest.get("package").is_none() && manifest.get("workspace").is_none() {
+        return None;
+    }
+    let workspace = workspace_of(path);
+    let mut out = render(&manifest, content, workspace.as_ref());
+    out.truncate(max_lines);
+    Some(out.join("\n"))
+}
+
+/// The manifest view of a small `Cargo.toml` read in full, when it inherits
+/// from a workspace — the one thing its text doesn't show. `None` otherwise.
+pub(crate) fn resolved(path: &Path, content: &str) -> Option<String> {
+    if !content.contains("workspace = true") && !content.contains("workspace=true") {
+        return None;
+    }
+    let manifest: Value = content.parse().ok()?;
+    let workspace = workspace_of(path)?;
+    Some(render(&manifest, content, Some(&workspace)).join("\n"))
+}
+
+fn render(manifest: &Value, content: &str, workspace: Option<&Value>) -> Vec<String> {
+    let lines = KeyLines::scan(content);
+    let at = |n: Option<u32>| n.map_or_else(String::new, |n| format!("[{n}] "));
+    let mut out = Vec::new();
+
+    if let Some(package) = manifest.get("package") {
+        let shared = workspace.and_then(|w| w.get("package"));
+        // A `field.workspace = true` takes the workspace's value
+        let field = |key: &str| -> Option<(String, bool)> {
+            let value = package.get(key)?;
+            if value.get("workspace").and_then(Value::as_bool) == Some(true) {
+                let value = shared.and_then(|s| s.get(key))?;
+                return Some((plain(value), true));
+            }
+            Some((plain(value), false))
+        };
+        let mut head = format!("{}[package]", at(lines.header("package")));
+        for key in ["name", "version"] {
+            if let Some((value, inherited)) = field(key) {
+                let _ = write!(
+                    head,
+                    " {value}{}",
+                    if inherited { " (workspace)" } else { "" }
+                );
+            }
+        }
+        out.push(head);
+        for key in PACKAGE_FIELDS {
+            if let Some((value, inherited)) = field(key) {
+                out.push(format!(
+                    "  {key}: {}{}",
+                    truncate_str(&value, 80),
+                    if inherited { " (workspace)" } else { "" }
+                ));
+            }
+        }
+    }
+
+    if let Some(features) = manifest.get("features").and_then(Value::as_table) {
+        out.push(format!(
+            "{}[features] {}",
+            at(lines.header("features")),
+            features.len()
+        ));
+        let mut entries: Vec<(Option<u32>, String)> = features
+            .iter()
+            .map(|(name, enables)| {
+                let enables: Vec<String> = enables
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(plain)
+                    .collect();
+                let line = lines.get("features", name);
+                (
+                    line,
+                    format!(
+                        "  {}{name} = {}",
+                        at(line),
+                        truncate_str(&enables.join(", "), 100)
+                    ),
+                )
+            })
+            .collect();
+        entries.sort_by_key(|(line, _)| *line);
+        out.extend(entries.into_iter().map(|(_, entry)| entry));
+    }
+
+    let deps = dependencies(manifest, workspace);
+    let mut tables: Vec<&str> = Vec::new();
+    for dep in &deps {
+        if !tables.contains(&dep.table.as_str()) {
+            tables.push(&dep.table);
+        }
+    }
+    for table in tables {
+        let mut group: Vec<&Dep> = deps.iter().filter(|d| d.table == table).collect();
+        group.sort_by_key(|d| lines.get(table, &d.name));
+        out.push(format!(
+            "{}[{table}] {}",
+            at(lines.header(table)),
+            group.len()
+        ));
+        for dep in group {
+            let mut entry = format!("  {}{}", at(lines.get(table, &dep.name)), dep.name);
+            if let Some(package) = &dep.package {
+                let _ = write!(entry, " → {package}");
+            }
+            if let Some(version) = &dep.version {
+                let _ = write!(entry, " {version}");
+            }
+            if let Some(source) = &dep.source {
+                let _ = write!(entry, " ({source})");
+            }
+            if !dep.features.is_empty() {
+                let _ = write!(entry, " +{}", dep.features.join(" +"));
+            }
+            if !dep.default_features {
+                entry.push_str(" no-default");
+            }
+            if dep.optional {
+                entry.push_str(" optional");
+            }
+            if dep.inherited {
+                entry.push_str(" (workspace)");
+            }
+            out.push(entry);
+        }
+    }
+
+    for target in TARGETS {
+        let named = |t: &Value| {
+            let name = t.get("name").and_then(Value::as_str).unwrap_or(target);
+            match t.get("path").and_then(Value::as_str) {
+                Some(path) => format!("{name} ({path})"),
+                None => name.to_string(),
+            }
+        };
+        match manifest.get(target) {
+            Some(Value::Array(items)) => {
+                let listed: Vec<String> = items.iter().map(named).collect();
+                out.push(format!(
+                    "[[{target}]] {}",
+                    truncate_str(&listed.join(", "), 100)
+                ));
+            }
+            Some(table @ Value::Table(_)) => out.push(format!("[{target}] {}", named(table))),
+            _ => {}
+        }
+    }
+
+    if let Some(ws) = manifest.get("workspace") {
+        let members: Vec<String> = ws
+            .get("members")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(plain)
+            .collect();
+        out.push(format!(
+            "{}[workspace] members: {}",
+            at(lines.header("workspace")),
+            truncate_str(&members.join(", "), 100)
+        ));
+        if let Some(shared) = ws.get("dependencies").and_then(Value::as_table) {
+            out.push(format!("  dependencies: {} shared", shared.len()));
+        }
+        if let Some(shared) = ws.get("package").and_then(Value::as_table) {
+            let keys: Vec<&str> = shared.keys().map(String::as_str).collect();
+            out.push(format!("  package: {}", keys.join(", ")));
+        }
+    }
+
+    let known = ["package", "features", "target", "workspace"];
+    let others: Vec<&str> = manifest
+        .as_table()
+        .into_iter()
+        .flatten()
+        .map(|(key, _)| key.as_str())
+        .filter(|key| !known.contains(key) && !DEP_TABLES.contains(key) && !TARGETS.contains(key))
+        .collect();
+    if !others.is_empty() {
+        out.push(format!("other: {}", others.join(", ")));
+    }
+    out
+}
+
+/// A value as text: strings without quotes, the rest as TOML.
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKSPACE: &str = r#"
+[package]
+version = "0.3.0"
+edition = "2021"
+
+[dependencies]
+serde = { version = "1.0", features = ["std"] }
+tokio = "1"
+"#;
+
+    const MEMBER: &str = r#"[package]
+name = "core"
+version.workspace = true
+edition = "2018"
+
+[features]
+default = ["json"]
+json = ["dep:serde_json", "serde/derive"]
+
+[dependencies]
+serde = { workspace = true, features = ["derive"] }
+serde_json = { version = "1", optional = true }
+util = { path = "../util" }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[dev-dependencies.tokio]
+workspace = true
+features = ["macros"]
+
+[profile.release]
+lto = true
+"#;
+
+    #[test]
+    fn resolves_workspace_inheritance() {
+        let manifest: Value = MEMBER.parse().unwrap();
+        let workspace: Value = WORKSPACE.parse().unwrap();
+        let deps = dependencies(&manifest, Some(&workspace));
+        let serde = deps.iter().find(|d| d.name == "serde").unwrap();
+        assert_eq!(serde.version.as_deref(), Some("1.0"));
+        assert_eq!(serde.features, vec!["std", "derive"]);
+        assert!(serde.inherited);
+        let tokio = deps.iter().find(|d| d.name == "tokio").unwrap();
+        assert_eq!(tokio.table, "dev-dependencies");
+        assert_eq!(tokio.version.as_deref(), Some("1"));
+        let libc = deps.iter().find(|d| d.name == "libc").unwrap();
+        assert_eq!(libc.table, "target.cfg(unix).dependencies");
+
+        let lines = KeyLines::scan(MEMBER);
+        assert_eq!(lines.get("dependencies", "serde"), Some(11));
+        assert_eq!(lines.get("dev-dependencies", "tokio"), Some(18));
+        assert_eq!(
+            lines.get("target.'cfg(unix)'.dependencies", "libc"),
+            Some(16)
+        );
+    }
+
+    #[test]
+    fn renders_the_manifest() {
+        let manifest: Value = MEMBER.parse().unwrap();
+        let workspace: Value = WORKSPACE.parse().unwrap();
+        let out = render(&manifest, MEMBER, Some(&workspace)).join("\n");
+        assert!(
+            out.starts_with("[1] [package] core 0.3.0 (workspace)\n  edition: 2018"),
+            "{out}"
+        );
+        assert!(
+            out.contains("  [7] default = json\n  [8] json = dep:serde_json, serde/derive"),
+            "{out}"
+        );
+        assert!(
+            out.contains("[10] [dependencies] 3\n  [11] serde 1.0 +std +derive (workspace)"),
+            "{out}"
+        );
+        assert!(
+            out.contains("  [12] serde_json 1 optional\n  [13] util (path ../util)"),
+            "{out}"
+        );
+        assert!(
+            out.contains("[15] [target.cfg(unix).dependencies] 1"),
+            "{out}"
+        );
+        assert!(out.contains("  [18] tokio 1 +macros (workspace)"), "{out}");
+        assert!(out.ends_with("other: profile"), "{out}");
+    }
+}
@@ -1,28 +1,31 @@
+use std::fmt::Write;
+
 use crate::types::{Lang, OutlineEntry, OutlineKind};
 
 /// Generate a code outline using tree-sitter. Walks top-level AST nodes,
 /// emitting signatures without bodies.
 pub fn outline(content: &str, lang: Lang, max_lines: usize) -> String {
-    let Some(language) = outline_language(lang) else {
-        return fallback_outline(content, max_lines);
-    };
-
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&language).is_err() {
-        return fallback_outline(content, max_lines);
-    }
-
-    let Some(tree) = parser.parse(content, None) else {
-        return fallback_outline(content, max_lines);
+    let Some(entries) = entries(content, lang) else {
+        return match lang {
+            Lang::Dockerfile => super::buildfile::dockerfile_outline(content, max_lines),
+            Lang::Make => super::buildfile::make_outline(content, max_lines),
+            _ => fallback_outline(content, max_lines),
+        };
     };
 
-    let root = tree.root_node();
     let lines: Vec<&str> = content.lines().collect();
-    let entries = walk_top_level(root, &lines, lang);
-
     format_entries(&entries, &lines, max_lines, lang)
 }
 
+/// Parse `content` and return its top-level outline entries (with one level
+/// of children). `None` when `lang` has no grammar or parsing fails.
+pub(crate) fn entries(content: &str, lang: Lang) -> Option<Vec<OutlineEntry>> {
+    let language = outline_language(lang)?;
+    let tree = crate::parser_pool::parse(content, &language)?;
+    let lines: Vec<&str> = content.lines().collect();
+    Some(walk_top_level(tree.root_node(), &lines, lang))
+}
+
 /// Get the tree-sitter Language for a given Lang variant.
 pub fn outline_language(lang: Lang) -> Option<tree_sitter::Language> {
     let lang = match lang {
@@ -74,17 +77,27 @@ fn node_to_entry(
     let start_line = node.start_position().row as u32 + 1;
     let end_line = node.end_position().row as u32 + 1;
 
+    // Python `@decorator` stacks wrap the definition they apply to
+    if kind_str == "decorated_definition" {
+        let definition = node.child_by_field_name("definition")?;
+        let mut entry = node_to_entry(definition, lines, lang, depth)?;
+        entry.start_line = start_line;
+        entry.decorators = extract_decorators(node, lines);
+        return Some(entry);
+    }
+
     let (kind, name, signature) = match kind_str {
         // Functions
         "function_declaration"
         | "function_definition"
         | "function_item"
+        | "function_signature_item"
         | "method_definition"
         | "method_declaration" => {
             let name = find_child_text(node, "name", lines)
                 .or_else(|| find_child_text(node, "identifier", lines))
                 .unwrap_or_else(|| "<anonymous>".into());
-            let sig = extract_signature(node, lines);
+            let sig = signature(node, lines);
             (OutlineKind::Function, name, Some(sig))
         }
 
@@ -116,10 +129,14 @@ fn node_to_entry(
             (OutlineKind::Enum, name, None)
         }
 
-        // Impl blocks (Rust)
+        // Impl blocks (Rust): `impl Type` or `impl Trait for Type`
         "impl_item" => {
-            let name = find_child_text(node, "type", lines).unwrap_or_else(|| "<impl>".into());
-            (OutlineKind::Module, format!("impl {name}"), None)
+            let ty = find_child_text(node, "type", lines).unwrap_or_else(|| "<impl>".into());
+            let name = match find_child_text(node, "trait", lines) {
+                Some(tr) => format!("impl {tr} for {ty}"),
+                None => format!("impl {ty}"),
+            };
+            (OutlineKind::Module, name, None)
         }
 
         // Objects (Scala companion objects, singletons)
@@ -165,19 +182,28 @@ fn node_to_entry(
         _ => return None,
     };
 
-    // Collect children for classes, impls, modules, traits/interfaces
+    // Collect children for classes, impls, modules, traits/interfaces.
+    // Rust inline modules don't count towards the depth limit, so the full
+    // `mod` hierarchy shows along with the members of impls inside it.
     let children = if matches!(
         kind,
         OutlineKind::Class | OutlineKind::Struct | OutlineKind::Module | OutlineKind::Interface
     ) && depth < 1
     {
-        collect_children(node, lines, lang, depth + 1)
+        let child_depth = if kind_str == "mod_item" {
+            depth
+        } else {
+            depth + 1
+        };
+        collect_children(node, lines, lang, child_depth)
     } else {
         Vec::new()
     };
 
     // Extract doc comment if present
     let doc = extract_doc(node, lines);
+    let cfg = extract_cfg(node, lines);
+    let decorators = extract_decorators(node, lines);
 
     Some(OutlineEntry {
         kind,
@@ -187,6 +213,8 @@ fn node_to_entry(
         signature,
         children,
         doc,
+        cfg,
+        decorators,
     })
 }
 
@@ -201,9 +229,12 @@ fn collect_children(
     let mut cursor = node.walk();
 
     // Look for a body node first
-    let body = node
-        .children(&mut cursor)
-        .find(|c| c.kind().contains("body") || c.kind().contains("block"));
+    let body = node.children(&mut cursor).find(|c| {
+        c.kind().contains("body")
+                || c.kind().contains("block")
+                // Rust impl/trait/mod bodies
+                || c.kind() == "declaration_list"
+    });
 
     let parent = body.unwrap_or(node);
     let mut cursor2 = parent.walk();
@@ -217,29 +248,49 @@ fn collect_children(
     children
 }
 
-/// Extract the first line as a function signature (name + params + return type).
-fn extract_signature(node: tree_sitter::Node, lines: &[&str]) -> String {
-    let start_row = node.start_position().row;
-    if start_row < lines.len() {
-        let line = lines[start_row].trim();
-        // Truncate at opening brace
-        if let Some(pos) = line.find('{') {
-            return line[..pos].trim().to_string();
-        }
-        if line.ends_with(':') {
-            // Python — truncate at trailing colon (for `def foo(x: int):` etc.)
-            if let Some(pos) = line.rfind(':') {
-                return line[..pos].trim().to_string();
-            }
-        }
-        // Full first line, truncated
-        if line.len() > 120 {
-            format!("{}...", crate::types::truncate_str(line, 117))
-        } else {
-            line.to_string()
-        }
+/// Longest signature shown before it is cut with `...`.
+const MAX_SIGNATURE_LEN: usize = 200;
+
+/// A definition's signature: everything before its body, with parameter
+/// lists that rustfmt/black wrapped over several lines joined back into
+/// one. Keeps `async`/`unsafe`/visibility qualifiers, generic parameters,
+/// return types and `where` clauses; drops annotation lines above it.
+pub(crate) fn signature(node: tree_sitter::Node, lines: &[&str]) -> String {
+    let mut cursor = node.walk();
+    let body = node.child_by_field_name("body").or_else(|| {
+        node.children(&mut cursor)
+            .find(|c| c.kind().contains("body") || c.kind().contains("block"))
+    });
+    let end = body.map_or_else(|| node.end_position(), |b| b.start_position());
+
+    // Java/C#/Kotlin annotations are part of the node; outlines show them
+    // separately
+    let mut start = node.start_position();
+    while start.row < end.row
+        && lines.get(start.row).is_some_and(|l| {
+            let l = l[start.column.min(l.len())..].trim_start();
+            l.starts_with('@') || l.starts_with('[') || l.starts_with("#[")
+        })
+    {
+        start = tree_sitter::Point {
+            row: start.row + 1,
+            column: 0,
+        };
+    }
+
+    let text = text_between(lines, start, end)
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(",)", ")");
+    let text = text
+        .trim_end_matches(|c: char| matches!(c, '{' | ':' | '=' | ';' | ',') || c.is_whitespace());
+    if text.len() > MAX_SIGNATURE_LEN {
+        format!(
+            "{}...",
+            crate::types::truncate_str(text, MAX_SIGNATURE_LEN - 3)
+        )
     } else {
-        String::new()
+        text.to_string()
     }
 }
 
@@ -297,9 +348,158 @@ fn first_identifier_text(node: tree_sitter::Node, lines: &[&str]) -> Option<Stri
     None
 }
 
+/// Preceding sibling nodes, skipping Rust attributes (`#[derive(..)]`,
+/// `#[cfg(..)]`) and TS member decorators that sit between an item and its
+/// doc comment.
+fn prev_non_attribute(node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    let mut prev = node.prev_sibling()?;
+    while matches!(prev.kind(), "attribute_item" | "decorator") {
+        prev = prev.prev_sibling()?;
+    }
+    Some(prev)
+}
+
+/// Rust attributes that don't change what an item does at runtime. `cfg` is
+/// shown separately.
+const QUIET_ATTRIBUTES: &[&str] = &[
+    "allow",
+    "warn",
+    "deny",
+    "forbid",
+    "expect",
+    "doc",
+    "derive",
+    "must_use",
+    "inline",
+    "cold",
+    "track_caller",
+    "cfg",
+    "cfg_attr",
+    "rustfmt::skip",
+];
+
+/// Java/Kotlin annotations that only inform the compiler.
+const QUIET_ANNOTATIONS: &[&str] = &["@Override", "@SuppressWarnings", "@Suppress"];
+
+/// Longest decorator text shown before it is cut with `...`.
+const MAX_DECORATOR_LEN: usize = 60;
+
+/// Decorators/attributes attached to `node`, in source order: preceding
+/// Rust attributes and TS member decorators, then the node's own decorator
+/// children (Python decorated definitions, TS classes, C# attribute lists,
+/// Java/Kotlin annotations inside `modifiers`).
+fn extract_decorators(node: tree_sitter::Node, lines: &[&str]) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut prev = node.prev_sibling();
+    while let Some(p) = prev.filter(|p| matches!(p.kind(), "attribute_item" | "decorator")) {
+        found.push(p);
+        prev = p.prev_sibling();
+    }
+    found.reverse();
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "decorator" | "attribute_list" => found.push(child),
+            "modifiers" => {
+                let mut inner = child.walk();
+                found.extend(
+                    child
+                        .children(&mut inner)
+                        .filter(|m| matches!(m.kind(), "annotation" | "marker_annotation")),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    found
+        .into_iter()
+        .map(|n| full_node_text(n, lines))
+        .filter(|text| is_significant_decorator(text))
+        .map(|text| {
+            if text.len() > MAX_DECORATOR_LEN {
+                format!(
+                    "{}...",
+                    crate::types::truncate_str(&text, MAX_DECORATOR_LEN - 3)
+                )
+            } else {
+                text
+            }
+        })
+        .collect()
+}
+
+fn is_significant_decorator(text: &str) -> bool {
+    if let Some(inner) = text.strip_prefix("#[") {
+        let name = inner.split(['(', '=', ']']).next().unwrap_or("").trim();
+        return !QUIET_ATTRIBUTES.contains(&name) && !name.starts_with("clippy::");
+    }
+    let name = text.split('(').next().unwrap_or(text).trim();
+    !QUIET_ANNOTATIONS.contains(&name)
+}
+
+/// Conditions of the `#[cfg(...)]` attributes directly above a Rust item,
+/// joined with `, ` when there are several.
+fn extract_cfg(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
+    let mut conditions = Vec::new();
+    let mut prev = node.prev_sibling();
+    while let Some(attr) = prev.filter(|p| p.kind() == "attribute_item") {
+        let text = full_node_text(attr, lines);
+        if let Some(cond) = cfg_condition(&text) {
+            conditions.push(cond.to_string());
+        }
+        prev = attr.prev_sibling();
+    }
+    if conditions.is_empty() {
+        return None;
+    }
+    conditions.reverse();
+    Some(conditions.join(", "))
+}
+
+/// `#[cfg(target_os = "linux")]` → `target_os = "linux"`.
+fn cfg_condition(attr: &str) -> Option<&str> {
+    let inner = attr.trim().strip_prefix("#[")?.strip_suffix(']')?.trim();
+    inner
+        .strip_prefix("cfg")?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Source text of `node` across all its lines, joined with single spaces.
+fn full_node_text(node: tree_sitter::Node, lines: &[&str]) -> String {
+    text_between(lines, node.start_position(), node.end_position())
+}
+
+/// Text from `start` up to (not including) `end`, each line trimmed and
+/// joined with single spaces.
+fn text_between(lines: &[&str], start: tree_sitter::Point, end: tree_sitter::Point) -> String {
+    let last = end.row.min(lines.len().saturating_sub(1));
+    let Some(rows) = lines.get(start.row..=last) else {
+        return String::new();
+    };
+    rows.iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let row = start.row + i;
+            let to = if row == end.row {
+                end.column.min(line.len())
+            } else {
+                line.len()
+            };
+            let from = if i == 0 { start.column.min(to) } else { 0 };
+            line[from..to].trim()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Extract a doc comment from the previous sibling.
 fn extract_doc(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
-    let prev = node.prev_sibling()?;
+    let prev = prev_non_attribute(node)?;
     let kind = prev.kind();
     if kind.contains("comment") || kind.contains("doc") {
         let text = node_text(prev, lines);
@@ -322,7 +522,7 @@ fn extract_doc(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
 /// Format outline entries into the spec'd output format.
 fn format_entries(
     entries: &[OutlineEntry],
-    _lines: &[&str],
+    lines: &[&str],
     max_lines: usize,
     lang: Lang,
 ) -> String {
@@ -348,14 +548,7 @@ fn format_entries(
             }
         }
 
-        out.push(format_entry(entry, 0, lang));
-
-        for child in &entry.children {
-            if out.len() >= max_lines {
-                break;
-            }
-            out.push(format_entry(child, 1, lang));
-        }
+        push_entry(&mut out, entry, 0, lines, max_lines, lang);
     }
 
     // Flush trailing imports
@@ -366,6 +559,25 @@ fn format_entries(
     out.join("\n")
 }
 
+/// Render `entry` and, indented beneath it, its children (nested Rust
+/// modules recurse further).
+fn push_entry(
+    out: &mut Vec<String>,
+    entry: &OutlineEntry,
+    indent: usize,
+    lines: &[&str],
+    max_lines: usize,
+    lang: Lang,
+) {
+    out.push(format_entry(entry, indent, lines, lang));
+    for child in &entry.children {
+        if out.len() >= max_lines {
+            break;
+        }
+        push_entry(out, child, indent + 1, lines, max_lines, lang);
+    }
+}
+
 /// Format a collapsed import summary grouped by source with counts.
 /// Spec format: `imports: react(4), express(2), @/lib(3)`
 fn format_imports(imports: &[&str], first_entry: Option<&OutlineEntry>) -> String {
@@ -460,16 +672,9 @@ pub(crate) fn extract_import_source(text: &str) -> String {
         .to_string()
 }
 
-/// Format a single outline entry with optional indentation.
-fn format_entry(entry: &OutlineEntry, indent: usize, lang: Lang) -> String {
-    let prefix = "  ".repeat(indent);
-    let range = if entry.start_line == entry.end_line {
-        format!("[{}]", entry.start_line)
-    } else {
-        format!("[{}-{}]", entry.start_line, entry.end_line)
-    };
-
-    let kind_label = match entry.kind {
+/// Label shown before an entry's name: `fn`, `struct`, `class`, ...
+pub(crate) fn kind_label(kind: OutlineKind, lang: Lang) -> &'static str {
+    match kind {
         OutlineKind::Function => {
             if lang == Lang::Scala {
                 "def"
@@ -510,6 +715,71 @@ fn format_entry(entry: &OutlineEntry, indent: usize, lang: Lang) -> String {
         OutlineKind::Import => "import",
         OutlineKind::TestSuite => "suite",
         OutlineKind::TestCase => "test",
+    }
+}
+
+/// A Rust `impl` block, named `impl Type` or `impl Trait for Type`.
+pub(crate) fn is_impl_block(entry: &OutlineEntry, lang: Lang) -> bool {
+    lang == Lang::Rust && entry.kind == OutlineKind::Module && entry.name.starts_with("impl ")
+}
+
+/// Declared visibility of an entry, read from its first line: `pub`,
+/// `pub(crate)`, `export`, `private`, ... Naming conventions stand in for
+/// Python (`_private`) and Go (`Exported`). `None` when the language has
+/// no notion of it or the item uses the default.
+pub(crate) fn visibility(entry: &OutlineEntry, lines: &[&str], lang: Lang) -> Option<String> {
+    let line = lines.get(entry.start_line as usize - 1)?.trim_start();
+    let first_word = line.split_whitespace().next().unwrap_or("");
+    match lang {
+        Lang::Rust => {
+            if line.starts_with("pub(") {
+                let close = line.find(')')?;
+                Some(line[..=close].to_string())
+            } else {
+                (first_word == "pub").then(|| "pub".to_string())
+            }
+        }
+        Lang::TypeScript | Lang::Tsx | Lang::JavaScript => {
+            matches!(first_word, "export" | "public" | "private" | "protected")
+                .then(|| first_word.to_string())
+        }
+        Lang::Java | Lang::Scala | Lang::Kotlin | Lang::CSharp | Lang::Swift => matches!(
+            first_word,
+            "public" | "private" | "protected" | "internal" | "fileprivate"
+        )
+        .then(|| first_word.to_string()),
+        Lang::Python => {
+            let name = entry.name.as_str();
+            if name.starts_with("__") && !name.ends_with("__") {
+                Some("private".to_string())
+            } else if name.starts_with('_') {
+                Some("internal".to_string())
+            } else {
+                None
+            }
+        }
+        Lang::Go => {
+            let exported = entry.name.chars().next()?.is_uppercase();
+            Some(if exported { "exported" } else { "unexported" }.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Format a single outline entry with optional indentation.
+fn format_entry(entry: &OutlineEntry, indent: usize, lines: &[&str], lang: Lang) -> String {
+    let prefix = "  ".repeat(indent);
+    let range = if entry.start_line == entry.end_line {
+        format!("[{}]", entry.start_line)
+    } else {
+        format!("[{}-{}]", entry.start_line, entry.end_line)
+    };
+
+    // Impl block names already read `impl Trait for Type`
+    let head = if is_impl_block(entry, lang) {
+        entry.name.clone()
+    } else {
+        format!("{} {}", kind_label(entry.kind, lang), entry.name)
     };
 
     let sig = match &entry.signature {
@@ -529,12 +799,29 @@ fn format_entry(entry: &OutlineEntry, indent: usize, lang: Lang) -> String {
         None => String::new(),
     };
 
-    format!("{prefix}{range:<12} {kind_label} {}{sig}{doc}", entry.name)
+    // Attributes, decorators and Rust visibility lead the entry as in source.
+    // Functions already carry the visibility in their signature line.
+    let mut attrs = String::new();
+    if let Some(cfg) = &entry.cfg {
+        let _ = write!(attrs, "#[cfg({cfg})] ");
+    }
+    for decorator in &entry.decorators {
+        attrs.push_str(decorator);
+        attrs.push(' ');
+    }
+    if lang == Lang::Rust && entry.signature.is_none() {
+        if let Some(vis) = visibility(entry, lines, lang) {
+            attrs.push_str(&vis);
+            attrs.push(' ');
+        }
+    }
+
+    format!("{prefix}{range:<12} {attrs}{head}{sig}{doc}")
 }
 
 /// Fallback when tree-sitter grammar isn't available.
 fn fallback_outline(content: &str, _max_lines: usize) -> String {
-    super::fallback::head_tail(content)
+    super::fallback::head_tail(content.as_bytes())
 }
 
 #[cfg(test)]
@@ -583,4 +870,156 @@ type UserId = String
         assert!(outline.contains("def connect"));
         assert!(outline.contains("def create"));
     }
+
+    #[test]
+    fn rust_modules_cfg_and_visibility() {
+        let rust_code = r"
+pub(crate) struct Handle;
+
+#[cfg(unix)]
+pub mod unix {
+    pub(super) mod fd {
+        pub fn dup() {}
+    }
+
+    impl super::Handle {
+        pub fn raw(&self) -> i32 { 0 }
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn works() {}
+}
+";
+
+        let outline = outline(rust_code, Lang::Rust, 1000);
+
+        assert!(outline.contains("pub(crate) struct Handle"), "{outline}");
+        assert!(outline.contains("#[cfg(unix)] pub mod unix"), "{outline}");
+        assert!(
+            outline.contains("  [6-8]        pub(super) mod fd"),
+            "{outline}"
+        );
+        assert!(outline.contains("    [7]          fn dup"), "{outline}");
+        assert!(outline.contains("    [11]         fn raw"), "{outline}");
+        assert!(
+            outline.contains("[15-17]      impl fmt::Debug for Handle"),
+            "{outline}"
+        );
+        assert!(outline.contains("  [16]         fn fmt"), "{outline}");
+        assert!(outline.contains("#[cfg(test)] mod tests"), "{outline}");
+        assert!(
+            outline.contains("  [22]         #[test] fn works"),
+            "{outline}"
+        );
+    }
+
+    #[test]
+    fn cfg_condition_text() {
+        assert_eq!(cfg_condition("#[cfg(unix)]"), Some("unix"));
+        assert_eq!(
+            cfg_condition(r#"#[cfg(all(target_os = "linux", feature = "io"))]"#),
+            Some(r#"all(target_os = "linux", feature = "io")"#)
+        );
+        assert_eq!(cfg_condition("#[cfg_attr(test, derive(Debug))]"), None);
+        assert_eq!(cfg_condition("#[derive(Debug)]"), None);
+    }
+
+    #[test]
+    fn decorators_and_attributes() {
+        let python_code = r#"
+@app.route("/users", methods=["GET"])
+def list_users():
+    pass
+
+class TestApi:
+    @pytest.fixture
+    def client(self):
+        pass
+"#;
+        let out = outline(python_code, Lang::Python, 1000);
+        assert!(
+            out.contains(r#"[2-4]        @app.route("/users", methods=["GET"]) fn list_users"#),
+            "{out}"
+        );
+        assert!(out.contains("@pytest.fixture fn client"), "{out}");
+
+        let rust_code = r#"
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config;
+
+#[tokio::main]
+#[allow(clippy::unwrap_used)]
+async fn main() {}
+"#;
+        let out = outline(rust_code, Lang::Rust, 1000);
+        assert!(
+            out.contains(r#"#[serde(rename_all = "camelCase")] pub struct Config"#),
+            "{out}"
+        );
+        assert!(out.contains("#[tokio::main] fn main"), "{out}");
+        assert!(!out.contains("derive"), "{out}");
+        assert!(!out.contains("allow"), "{out}");
+    }
+
+    #[test]
+    fn wrapped_signatures_are_joined() {
+        let rust_code = "\
+pub async unsafe fn spawn<T: Send>(
+    task: T,
+    name: &str,
+) -> Result<Handle, Error>
+where
+    T: 'static,
+{
+}
+
+trait Store {
+    fn get(&self, key: &str) -> Option<Vec<u8>>;
+}
+";
+        let found = entries(rust_code, Lang::Rust).unwrap();
+        assert_eq!(
+            found[0].signature.as_deref(),
+            Some("pub async unsafe fn spawn<T: Send>(task: T, name: &str) -> Result<Handle, Error> where T: 'static")
+        );
+        assert_eq!(
+            found[1].children[0].signature.as_deref(),
+            Some("fn get(&self, key: &str) -> Option<Vec<u8>>")
+        );
+
+        let python_code = "\
+async def fetch(
+    url: str,
+    timeout: float = 1.0,
+) -> bytes:
+    pass
+";
+        let found = entries(python_code, Lang::Python).unwrap();
+        assert_eq!(
+            found[0].signature.as_deref(),
+            Some("async def fetch(url: str, timeout: float = 1.0) -> bytes")
+        );
+
+        let java_code = "\
+class Api {
+    @GetMapping(\"/users\")
+    public <T> List<T> users(int page) throws IOException {
+        return null;
+    }
+}
+";
+        let found = entries(java_code, Lang::Java).unwrap();
+        assert_eq!(
+            found[0].children[0].signature.as_deref(),
+            Some("public <T> List<T> users(int page) throws IOException")
+        );
+    }
 }
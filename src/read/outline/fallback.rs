@@ -1,33 +1,93 @@
 use std::fmt::Write;
 
 /// Unknown file types: first 50 lines + last 10 lines.
-pub fn head_tail(content: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    let total = lines.len();
+pub fn head_tail(buf: &[u8]) -> String {
+    ends(buf, 50, 10)
+}
+
+/// Log files: first 10 lines + last 5 lines + total line count.
+pub fn log_view(buf: &[u8]) -> String {
+    ends(buf, 10, 5)
+}
 
-    if total <= 60 {
-        return content.to_string();
+/// First `head` and last `tail` lines of `buf`, with a count of what was
+/// skipped. Works on the raw bytes so only the kept lines are decoded —
+/// a large log never becomes a `String`.
+fn ends(buf: &[u8], head: usize, tail: usize) -> String {
+    // Line count as `str::lines` sees it: a final newline ends the last line
+    // rather than starting an empty one.
+    let body = buf.strip_suffix(b"\n").unwrap_or(buf);
+    let total = if buf.is_empty() {
+        0
+    } else {
+        memchr::memchr_iter(b'\n', body).count() + 1
+    };
+
+    if total <= head + tail {
+        return String::from_utf8_lossy(buf).into_owned();
     }
 
-    let omitted = total - 60;
-    let mut result = lines[..50].join("\n");
+    let head_end = memchr::memchr_iter(b'\n', body)
+        .nth(head - 1)
+        .unwrap_or(body.len());
+    let tail_start = memchr::memrchr_iter(b'\n', body)
+        .nth(tail - 1)
+        .map_or(0, |i| i + 1);
+
+    let omitted = total - head - tail;
+    let mut result = joined(&body[..head_end]);
     let _ = write!(result, "\n\n... {total} lines total, {omitted} omitted\n\n");
-    result.push_str(&lines[total - 10..].join("\n"));
+    result.push_str(&joined(&body[tail_start..]));
     result
 }
 
-/// Log files: first 10 lines + last 5 lines + total line count.
-pub fn log_view(content: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    let total = lines.len();
+/// Decode a run of lines, normalising `\r\n` endings to `\n`.
+fn joined(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if total <= 15 {
-        return content.to_string();
+    fn numbered(n: usize, ending: &str) -> String {
+        (1..=n)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join(ending)
+            + ending
     }
 
-    let mut result = lines[..10].join("\n");
-    let omitted = total - 15;
-    let _ = write!(result, "\n\n... {total} lines total, {omitted} omitted\n\n");
-    result.push_str(&lines[total - 5..].join("\n"));
-    result
+    #[test]
+    fn short_input_is_returned_whole() {
+        let src = numbered(15, "\n");
+        assert_eq!(log_view(src.as_bytes()), src);
+    }
+
+    #[test]
+    fn keeps_head_and_tail() {
+        for src in [
+            numbered(20, "\n"),
+            numbered(20, "\r\n"),
+            numbered(20, "\n").trim_end().to_string(),
+        ] {
+            let out = log_view(src.as_bytes());
+            let expected = format!(
+                "{}\n\n... 20 lines total, 5 omitted\n\n{}",
+                (1..=10)
+                    .map(|i| format!("line {i}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                (16..=20)
+                    .map(|i| format!("line {i}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            assert_eq!(out, expected);
+        }
+    }
 }
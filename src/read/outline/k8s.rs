@@ -0,0 +1,203 @@
+//! Kubernetes manifests, outlined by resource rather than by key: each `---`
+//! document with `apiVersion` and `kind` becomes one entry with its name,
+//! namespace, container images and ports.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use super::buildfile::BuildSymbol;
+use super::structured::{metadata_value, top_value, unquote, yaml_documents};
+
+/// One manifest document. Lines are 1-based.
+pub(crate) struct Resource<'a> {
+    pub kind: &'a str,
+    pub name: Option<&'a str>,
+    pub namespace: Option<&'a str>,
+    pub line: u32,
+    pub end_line: u32,
+    /// `image:` values with their lines, from every container list in the spec.
+    pub images: Vec<(u32, &'a str)>,
+    /// `containerPort:` values, plus `port:` for Services.
+    pub ports: Vec<(u32, &'a str)>,
+}
+
+/// Resources in a YAML file. Empty when no document looks like a manifest.
+pub(crate) fn resources(content: &str) -> Vec<Resource<'_>> {
+    let lines: Vec<&str> = content.lines().collect();
+    yaml_documents(&lines)
+        .into_iter()
+        .filter_map(|(start, end)| resource(&lines, start, end))
+        .collect()
+}
+
+fn resource<'a>(lines: &[&'a str], start: usize, end: usize) -> Option<Resource<'a>> {
+    let doc = &lines[start..end];
+    top_value(doc, "apiVersion")?;
+    let kind = top_value(doc, "kind")?;
+
+    // Range covers content only — not leading comments or trailing blank lines
+    let is_content = |l: &&str| {
+        let t = l.trim_start();
+        !t.is_empty() && !t.starts_with('#')
+    };
+    let first = start + doc.iter().position(is_content)?;
+    let last = start + doc.iter().rposition(is_content)?;
+
+    let mut images = Vec::new();
+    let mut ports = Vec::new();
+    for (i, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let t = line.trim_start();
+        let t = t.strip_prefix("- ").unwrap_or(t).trim_start();
+        let Some((key, value)) = t.split_once(':') else {
+            continue;
+        };
+        let value = unquote(value.split(" #").next().unwrap_or("").trim());
+        if value.is_empty() {
+            continue;
+        }
+        let n = (i + 1) as u32;
+        match key {
+            "image" => images.push((n, value)),
+            "containerPort" => ports.push((n, value)),
+            "port" if kind == "Service" => ports.push((n, value)),
+            _ => {}
+        }
+    }
+
+    Some(Resource {
+        kind,
+        name: metadata_value(doc, "name"),
+        namespace: metadata_value(doc, "namespace"),
+        line: (first + 1) as u32,
+        end_line: (last + 1) as u32,
+        images,
+        ports,
+    })
+}
+
+/// Resource outline, or `None` when the file isn't a manifest and the
+/// generic YAML outline should be used.
+pub fn outline(content: &str, max_lines: usize) -> Option<String> {
+    let resources = resources(content);
+    if resources.is_empty() {
+        return None;
+    }
+
+    let mut out = Vec::new();
+    for r in &resources {
+        let range = if r.line == r.end_line {
+            format!("[{}]", r.line)
+        } else {
+            format!("[{}-{}]", r.line, r.end_line)
+        };
+        let mut header = format!("{range} {}", r.kind);
+        if let Some(name) = r.name {
+            header.push(' ');
+            header.push_str(name);
+        }
+        if let Some(ns) = r.namespace {
+            let _ = write!(header, " (namespace: {ns})");
+        }
+        out.push(header);
+        for (line, image) in &r.images {
+            out.push(format!("  [{line}] image {image}"));
+        }
+        for (line, port) in &r.ports {
+            out.push(format!("  [{line}] port {port}"));
+        }
+    }
+    out.truncate(max_lines);
+    Some(out.join("\n"))
+}
+
+/// Named resources of a YAML manifest, for symbol search and indexing.
+/// `None` for other files, so callers fall back to their generic handling.
+pub(crate) fn symbols<'a>(path: &Path, content: &'a str) -> Option<Vec<BuildSymbol<'a>>> {
+    if !matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    ) {
+        return None;
+    }
+    let symbols: Vec<BuildSymbol> = resources(content)
+        .into_iter()
+        .filter_map(|r| {
+            Some(BuildSymbol {
+                name: r.name?,
+                kind: "resource",
+                line: r.line,
+                end_line: r.end_line,
+            })
+        })
+        .collect();
+    (!symbols.is_empty()).then_some(symbols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "\
+# web tier
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  namespace: prod
+  labels:
+    name: not-this
+spec:
+  template:
+    spec:
+      initContainers:
+        - name: migrate
+          image: \"registry/app:1.2\"
+      containers:
+        - name: app
+          image: registry/app:1.2
+          ports:
+            - containerPort: 8080
+          livenessProbe:
+            httpGet:
+              port: 8080
+
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+spec:
+  ports:
+    - port: 80
+      targetPort: 8080
+";
+
+    #[test]
+    fn outline_by_resource() {
+        let out = outline(MANIFEST, 100).unwrap();
+        assert_eq!(
+            out,
+            "[2-22] Deployment web (namespace: prod)\n\
+             \x20 [14] image registry/app:1.2\n\
+             \x20 [17] image registry/app:1.2\n\
+             \x20 [19] port 8080\n\
+             [25-32] Service web\n\
+             \x20 [31] port 80"
+        );
+    }
+
+    #[test]
+    fn plain_yaml_is_not_a_manifest() {
+        assert!(outline("name: x\nkind: y\n", 100).is_none());
+        assert!(symbols(Path::new("ci.yml"), "jobs:\n  test:\n").is_none());
+    }
+
+    #[test]
+    fn symbols_are_resource_names() {
+        let syms = symbols(Path::new("deploy/web.yaml"), MANIFEST).unwrap();
+        let found: Vec<(&str, u32, u32)> =
+            syms.iter().map(|s| (s.name, s.line, s.end_line)).collect();
+        assert_eq!(found, vec![("web", 2, 22), ("web", 25, 32)]);
+        assert!(symbols(Path::new("web.json"), MANIFEST).is_none());
+    }
+}
@@ -1,42 +1,73 @@
+pub mod buildfile;
+pub mod cargo;
 pub mod code;
 pub mod fallback;
+pub mod k8s;
 pub mod markdown;
+pub mod openapi;
+pub(crate) mod rules;
 pub mod structured;
 pub mod tabular;
 pub mod test_file;
+pub mod xml;
 
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
 use std::path::Path;
+use std::sync::OnceLock;
 
 use crate::types::FileType;
 
 const OUTLINE_CAP: usize = 100; // max outline lines for huge files
 
-/// Generate a smart view based on file type.
-pub fn generate(
-    path: &Path,
-    file_type: FileType,
-    content: &str,
-    buf: &[u8],
-    capped: bool,
-) -> String {
+/// Generate a smart view based on file type. Takes the raw (usually
+/// mmapped) bytes: markdown, log and plain-text views scan them directly, and
+/// the rest decode them without copying unless they hold invalid UTF-8.
+pub fn generate(path: &Path, file_type: FileType, buf: &[u8], capped: bool) -> String {
+    let buf = crate::format::strip_bom(buf);
     let max_lines = if capped { OUTLINE_CAP } else { usize::MAX };
+    let outline = builtin(path, file_type, buf, max_lines);
+    rules::merge(path, file_type, buf, outline, max_lines)
+}
+
+/// A hash of the `tilth.toml` grammars and outline rules this process
+/// outlines with, so outlines stored under other settings aren't reused.
+pub(crate) fn config_fingerprint() -> u64 {
+    static FINGERPRINT: OnceLock<u64> = OnceLock::new();
+    *FINGERPRINT.get_or_init(|| {
+        let mut hasher = DefaultHasher::new();
+        crate::grammars::fingerprint(&mut hasher);
+        rules::fingerprint(&mut hasher);
+        hasher.finish()
+    })
+}
+
+/// The outline tilth itself gives `buf`, before any `tilth.toml` rules.
+fn builtin(path: &Path, file_type: FileType, buf: &[u8], max_lines: usize) -> String {
+    let text = || String::from_utf8_lossy(buf);
 
     // Test files get special treatment regardless of language
     if is_test_file(path) {
         if let FileType::Code(lang) = file_type {
-            if let Some(outline) = test_file::outline(content, lang, max_lines) {
+            if let Some(outline) = test_file::outline(&text(), lang, max_lines) {
                 return outline;
             }
         }
     }
 
+    match crate::grammars::outline(path, &text(), max_lines) {
+        Some(Ok(outline)) => return outline,
+        Some(Err(e)) => return format!("> {e}\n\n{}", fallback::head_tail(buf)),
+        None => {}
+    }
+
     match file_type {
-        FileType::Code(lang) => code::outline(content, lang, max_lines),
+        FileType::Code(lang) => code::outline(&text(), lang, max_lines),
         FileType::Markdown => markdown::outline(buf, max_lines),
-        FileType::StructuredData => structured::outline(path, content, max_lines),
-        FileType::Tabular => tabular::outline(content, max_lines),
-        FileType::Log => fallback::log_view(content),
-        FileType::Other => fallback::head_tail(content),
+        FileType::StructuredData => structured::outline(path, &text(), max_lines),
+        FileType::Tabular => tabular::outline(&text(), max_lines),
+        FileType::Log => fallback::log_view(buf),
+        FileType::Other => fallback::head_tail(buf),
     }
 }
 
@@ -0,0 +1,378 @@
+//! `OpenAPI` / Swagger specs, outlined by operation and schema instead of by key.
+//!
+//! Works on YAML and pretty-printed JSON alike: both put one key per line with
+//! nesting shown by indentation, which is all the scanner needs. Minified JSON
+//! falls through to the generic outline.
+
+use std::path::Path;
+
+use super::buildfile::BuildSymbol;
+use super::structured::unquote;
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// A `key:` / `"key":` line. `line` is 0-based.
+struct Key<'a> {
+    line: usize,
+    indent: usize,
+    name: &'a str,
+    value: &'a str,
+}
+
+pub(crate) struct PathItem<'a> {
+    pub path: &'a str,
+    pub line: u32,
+    pub end_line: u32,
+    pub operations: Vec<Operation<'a>>,
+}
+
+pub(crate) struct Operation<'a> {
+    pub method: &'a str,
+    /// `operationId`.
+    pub id: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub line: u32,
+    pub end_line: u32,
+}
+
+pub(crate) struct Schema<'a> {
+    pub name: &'a str,
+    pub line: u32,
+    pub end_line: u32,
+}
+
+pub(crate) struct Spec<'a> {
+    /// `openapi 3.1.0` or `swagger 2.0`.
+    pub version: String,
+    pub title: Option<&'a str>,
+    pub paths: Vec<PathItem<'a>>,
+    pub schemas: Vec<Schema<'a>>,
+}
+
+fn scan<'a>(lines: &[&'a str]) -> Vec<Key<'a>> {
+    let mut out = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let (name, value) = if let Some(rest) = trimmed.strip_prefix('"') {
+            // JSON: `"name": value,`
+            let Some(close) = rest.find('"') else {
+                continue;
+            };
+            let Some(value) = rest[close + 1..].trim_start().strip_prefix(':') else {
+                continue;
+            };
+            (&rest[..close], value)
+        } else if trimmed.starts_with(['-', '#']) || trimmed.is_empty() {
+            continue;
+        } else if let Some(name) = trimmed.trim_end().strip_suffix(':') {
+            (unquote(name), "")
+        } else if let Some((name, value)) = trimmed.split_once(": ") {
+            (unquote(name), value)
+        } else {
+            continue;
+        };
+        let value = unquote(value.trim().trim_end_matches(',').trim_end());
+        out.push(Key {
+            line: i,
+            indent,
+            name,
+            value,
+        });
+    }
+    out
+}
+
+/// Indices of the direct children of `keys[parent]`.
+fn children(keys: &[Key], parent: usize) -> Vec<usize> {
+    let base = keys[parent].indent;
+    let Some(child_indent) = keys.get(parent + 1).map(|k| k.indent).filter(|&i| i > base) else {
+        return Vec::new();
+    };
+    (parent + 1..keys.len())
+        .take_while(|&j| keys[j].indent > base)
+        .filter(|&j| keys[j].indent == child_indent)
+        .collect()
+}
+
+fn child(keys: &[Key], parent: usize, name: &str) -> Option<usize> {
+    children(keys, parent)
+        .into_iter()
+        .find(|&j| keys[j].name == name)
+}
+
+/// 1-based `(start, end)` of the block opened by `keys[idx]`: up to the line
+/// before the next key at the same or shallower indent, minus trailing blank
+/// lines and the closing brackets of enclosing JSON objects.
+fn span(keys: &[Key], idx: usize, lines: &[&str]) -> (u32, u32) {
+    let start = keys[idx].line;
+    let indent = keys[idx].indent;
+    let mut end = keys[idx + 1..]
+        .iter()
+        .find(|k| k.indent <= indent)
+        .map_or(lines.len(), |k| k.line);
+    while end > start + 1 && {
+        let l = lines[end - 1];
+        l.trim().is_empty() || l.len() - l.trim_start().len() < indent
+    } {
+        end -= 1;
+    }
+    ((start + 1) as u32, end as u32)
+}
+
+/// Parse an `OpenAPI` 3 or Swagger 2 spec. `None` when the document isn't one.
+pub(crate) fn parse(content: &str) -> Option<Spec<'_>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let keys = scan(&lines);
+    let root_indent = keys.first()?.indent;
+    let root: Vec<usize> = (0..keys.len())
+        .filter(|&i| keys[i].indent == root_indent)
+        .collect();
+    let top = |name: &str| root.iter().copied().find(|&i| keys[i].name == name);
+
+    let marker = top("openapi").or_else(|| top("swagger"))?;
+    let version = format!("{} {}", keys[marker].name, keys[marker].value);
+    let title = top("info")
+        .and_then(|info| child(&keys, info, "title"))
+        .map(|t| keys[t].value)
+        .filter(|t| !t.is_empty());
+
+    let mut paths = Vec::new();
+    for path in top("paths").map_or_else(Vec::new, |p| children(&keys, p)) {
+        let mut operations = Vec::new();
+        for op in children(&keys, path) {
+            if !METHODS.contains(&keys[op].name) {
+                continue;
+            }
+            let field = |name: &str| {
+                child(&keys, op, name)
+                    .map(|j| keys[j].value)
+                    .filter(|v| !v.is_empty())
+            };
+            let (line, end_line) = span(&keys, op, &lines);
+            operations.push(Operation {
+                method: keys[op].name,
+                id: field("operationId"),
+                summary: field("summary"),
+                line,
+                end_line,
+            });
+        }
+        let (line, end_line) = span(&keys, path, &lines);
+        paths.push(PathItem {
+            path: keys[path].name,
+            line,
+            end_line,
+            operations,
+        });
+    }
+
+    // OpenAPI 3: components.schemas; Swagger 2: definitions
+    let schema_root = top("components")
+        .and_then(|c| child(&keys, c, "schemas"))
+        .or_else(|| top("definitions"));
+    let schemas = schema_root
+        .map(|root| {
+            children(&keys, root)
+                .into_iter()
+                .map(|s| {
+                    let (line, end_line) = span(&keys, s, &lines);
+                    Schema {
+                        name: keys[s].name,
+                        line,
+                        end_line,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(Spec {
+        version,
+        title,
+        paths,
+        schemas,
+    })
+}
+
+fn range(start: u32, end: u32) -> String {
+    if start == end {
+        format!("[{start}]")
+    } else {
+        format!("[{start}-{end}]")
+    }
+}
+
+/// Paths with their operations, then schemas. `None` when not a spec.
+pub fn outline(content: &str, max_lines: usize) -> Option<String> {
+    let spec = parse(content)?;
+    let mut out = Vec::new();
+
+    let mut header = spec.version;
+    if let Some(title) = spec.title {
+        header.push_str(" — ");
+        header.push_str(title);
+    }
+    out.push(header);
+
+    for item in &spec.paths {
+        out.push(format!("{} {}", range(item.line, item.end_line), item.path));
+        for op in &item.operations {
+            let mut entry = format!(
+                "  {} {}",
+                range(op.line, op.end_line),
+                op.method.to_ascii_uppercase()
+            );
+            if let Some(id) = op.id {
+                entry.push(' ');
+                entry.push_str(id);
+            }
+            if let Some(summary) = op.summary {
+                entry.push_str(" — ");
+                entry.push_str(summary);
+            }
+            out.push(entry);
+        }
+    }
+
+    for schema in &spec.schemas {
+        out.push(format!(
+            "{} schema {}",
+            range(schema.line, schema.end_line),
+            schema.name
+        ));
+    }
+
+    out.truncate(max_lines);
+    Some(out.join("\n"))
+}
+
+/// `operationId`s and schema names of a spec, for symbol search and indexing.
+pub(crate) fn symbols<'a>(path: &Path, content: &'a str) -> Option<Vec<BuildSymbol<'a>>> {
+    if !matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("json" | "yaml" | "yml")
+    ) {
+        return None;
+    }
+    let spec = parse(content)?;
+    let ops = spec
+        .paths
+        .iter()
+        .flat_map(|p| &p.operations)
+        .filter_map(|op| {
+            Some(BuildSymbol {
+                name: op.id?,
+                kind: "operation",
+                line: op.line,
+                end_line: op.end_line,
+            })
+        });
+    let schemas = spec.schemas.iter().map(|s| BuildSymbol {
+        name: s.name,
+        kind: "schema",
+        line: s.line,
+        end_line: s.end_line,
+    });
+    Some(ops.chain(schemas).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML_SPEC: &str = "\
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      summary: List all pets
+      parameters:
+        - name: limit
+          in: query
+    post:
+      operationId: createPet
+  '/pets/{id}':
+    get:
+      operationId: showPetById
+
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id:
+          type: integer
+    Error:
+      type: object
+";
+
+    #[test]
+    fn yaml_spec_outline() {
+        let out = outline(YAML_SPEC, 100).unwrap();
+        assert_eq!(
+            out,
+            "openapi 3.0.3 — Petstore\n\
+             [6-14] /pets\n\
+             \x20 [7-12] GET listPets — List all pets\n\
+             \x20 [13-14] POST createPet\n\
+             [15-17] /pets/{id}\n\
+             \x20 [16-17] GET showPetById\n\
+             [21-25] schema Pet\n\
+             [26-27] schema Error"
+        );
+    }
+
+    #[test]
+    fn json_swagger_outline() {
+        let json = r#"{
+  "swagger": "2.0",
+  "info": {
+    "title": "Store"
+  },
+  "paths": {
+    "/orders": {
+      "delete": {
+        "operationId": "deleteOrders"
+      }
+    }
+  },
+  "definitions": {
+    "Order": {
+      "type": "object"
+    }
+  }
+}"#;
+        let out = outline(json, 100).unwrap();
+        assert_eq!(
+            out,
+            "swagger 2.0 — Store\n\
+             [7-11] /orders\n\
+             \x20 [8-10] DELETE deleteOrders\n\
+             [14-16] schema Order"
+        );
+    }
+
+    #[test]
+    fn symbols_are_operations_and_schemas() {
+        let syms = symbols(Path::new("api/openapi.yaml"), YAML_SPEC).unwrap();
+        let names: Vec<(&str, u32)> = syms.iter().map(|s| (s.name, s.line)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("listPets", 7),
+                ("createPet", 13),
+                ("showPetById", 16),
+                ("Pet", 21),
+                ("Error", 26)
+            ]
+        );
+        assert!(symbols(Path::new("config.yaml"), "name: x\n").is_none());
+    }
+}
@@ -0,0 +1,274 @@
+//! Outline rules, per the `[[outline]]` entries of `tilth.toml`: each is a
+//! tree-sitter query over one language's files, and every match becomes an
+//! outline entry of the rule's `kind`, placed among the built-in entries in
+//! line order — `describe(...)` blocks, SQL handed to an ORM, route tables.
+//!
+//! `@entry` captures the span an entry covers and `@name` its label; with
+//! only one of them it is both, and with neither the first capture is.
+//! Captures named `_…` only feed predicates. Rules load with the first
+//! outline; one that doesn't compile says so atop its language's outlines.
+
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Language, Node, Query, QueryCursor};
+
+use super::buildfile;
+use super::code::outline_language;
+use crate::config::OutlineRule;
+use crate::types::FileType;
+
+struct Rule {
+    spec: OutlineRule,
+    /// Compiled for the language's grammar on first use.
+    query: OnceLock<Result<Query, String>>,
+}
+
+static RULES: OnceLock<Vec<Rule>> = OnceLock::new();
+
+/// An entry a rule's query matched.
+#[derive(Debug, PartialEq, Eq)]
+struct Found<'r> {
+    line: u32,
+    end_line: u32,
+    kind: &'r str,
+    label: String,
+}
+
+fn rules() -> &'static [Rule] {
+    RULES.get_or_init(|| {
+        crate::config::outline_rules()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|spec| Rule {
+                spec,
+                query: OnceLock::new(),
+            })
+            .collect()
+    })
+}
+
+/// Feeds the configured rules to `state`, for caches whose outlines depend
+/// on them.
+pub(crate) fn fingerprint(state: &mut impl Hasher) {
+    for rule in rules() {
+        rule.spec.hash(state);
+    }
+}
+
+/// `outline` with the entries of the rules for `path`'s language merged
+/// in; unchanged when there are none.
+pub(crate) fn merge(
+    path: &Path,
+    file_type: FileType,
+    buf: &[u8],
+    outline: String,
+    max_lines: usize,
+) -> String {
+    let rules = rules();
+    if rules.is_empty() {
+        return outline;
+    }
+    let Some((name, language)) = language(path, file_type) else {
+        return outline;
+    };
+    let rules: Vec<&Rule> = rules
+        .iter()
+        .filter(|r| r.spec.language.eq_ignore_ascii_case(&name))
+        .collect();
+    if rules.is_empty() {
+        return outline;
+    }
+    let content = String::from_utf8_lossy(buf);
+    let Some(tree) = crate::parser_pool::parse(&content, &language) else {
+        return outline;
+    };
+
+    let mut notes = Vec::new();
+    let mut found = Vec::new();
+    for rule in rules {
+        let query = rule
+            .query
+            .get_or_init(|| Query::new(&language, &rule.spec.query).map_err(|e| e.to_string()));
+        match query {
+            Ok(query) => found.extend(matches(query, tree.root_node(), &content, &rule.spec.kind)),
+            Err(e) => notes.push(format!(
+                "> outline rule {} for {} doesn't compile: {e}",
+                rule.spec.kind, rule.spec.language
+            )),
+        }
+    }
+    found.sort_by_key(|f| (f.line, std::cmp::Reverse(f.end_line)));
+    found.dedup();
+
+    let merged = interleave(&outline, &found, max_lines);
+    if notes.is_empty() {
+        merged
+    } else {
+        format!("{}\n\n{merged}", notes.join("\n"))
+    }
+}
+
+/// The name rules use for `path`'s language, and its grammar: a configured
+/// grammar's, else the built-in one's.
+fn language(path: &Path, file_type: FileType) -> Option<(String, Language)> {
+    if let Some((name, language)) = crate::grammars::language(path) {
+        return Some((name.to_string(), language.clone()));
+    }
+    let FileType::Code(lang) = file_type else {
+        return None;
+    };
+    Some((lang.to_string(), outline_language(lang)?))
+}
+
+fn matches<'r>(query: &Query, root: Node, content: &str, kind: &'r str) -> Vec<Found<'r>> {
+    let names = query.capture_names();
+    let mut cursor = QueryCursor::new();
+    let mut it = cursor.matches(query, root, content.as_bytes());
+    let mut found = Vec::new();
+    while let Some(m) = it.next() {
+        let capture = |wanted: &str| {
+            m.captures
+                .iter()
+                .find(|c| names[c.index as usize] == wanted)
+                .map(|c| c.node)
+        };
+        let first = m
+            .captures
+            .iter()
+            .find(|c| !names[c.index as usize].starts_with('_'))
+            .map(|c| c.node);
+        let Some(entry) = capture("entry").or(capture("name")).or(first) else {
+            continue;
+        };
+        let label = capture("name")
+            .or_else(|| capture("entry").is_none().then_some(first).flatten())
+            .and_then(|n| content.get(n.byte_range()))
+            .unwrap_or_else(|| {
+                content
+                    .get(entry.start_byte()..)
+                    .and_then(|rest| rest.lines().next())
+                    .unwrap_or_default()
+            });
+        found.push(Found {
+            line: entry.start_position().row as u32 + 1,
+            end_line: entry.end_position().row as u32 + 1,
+            kind,
+            label: label.split_whitespace().collect::<Vec<_>>().join(" "),
+        });
+    }
+    found
+}
+
+/// The `[start-end]` span an outline line opens, and its nesting depth.
+/// `None` for lines that continue the entry before them.
+fn span(line: &str) -> Option<(u32, u32, usize)> {
+    let trimmed = line.trim_start();
+    let depth = (line.len() - trimmed.len()) / 2;
+    let (range, _) = trimmed.strip_prefix('[')?.split_once(']')?;
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    Some((start.parse().ok()?, end.parse().ok()?, depth))
+}
+
+/// The built-in outline with `found` placed before the first entry that
+/// starts after them, nested beneath the deepest entry spanning them.
+fn interleave(outline: &str, found: &[Found], max_lines: usize) -> String {
+    let mut current = (0, 0, 0);
+    let lines: Vec<((u32, u32, usize), &str)> = outline
+        .lines()
+        .map(|line| {
+            if let Some(span) = span(line) {
+                current = span;
+            }
+            (current, line)
+        })
+        .collect();
+    let render = |f: &Found, before: &[((u32, u32, usize), &str)]| {
+        let depth = before
+            .iter()
+            .filter(|((start, end, _), _)| *start <= f.line && f.line <= *end)
+            .map(|((_, _, depth), _)| depth + 1)
+            .max()
+            .unwrap_or(0);
+        buildfile::entry(depth, f.line, f.end_line, f.kind, &f.label)
+    };
+
+    let mut out: Vec<String> = Vec::with_capacity(lines.len() + found.len());
+    let mut rest = found.iter().peekable();
+    for (i, ((start, _, _), line)) in lines.iter().enumerate() {
+        while let Some(f) = rest.next_if(|f| f.line < *start) {
+            out.push(render(f, &lines[..i]));
+        }
+        out.push((*line).to_string());
+    }
+    for f in rest {
+        out.push(render(f, &lines));
+    }
+    out.truncate(max_lines);
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "import { db } from './db';\n\nexport function load(id) {\n  return db.query(`SELECT * FROM users WHERE id = ?`, id);\n}\n\ndescribe(\"load\", () => {\n  it(\"finds a user\", () => {});\n});\n";
+
+    fn found(query: &str, kind: &'static str) -> Vec<Found<'static>> {
+        let language: Language = tree_sitter_javascript::LANGUAGE.into();
+        let tree = crate::parser_pool::parse(SOURCE, &language).unwrap();
+        let query = Query::new(&language, query).unwrap();
+        matches(&query, tree.root_node(), SOURCE, kind)
+    }
+
+    #[test]
+    fn captures_name_and_span_entries() {
+        let describe = found(
+            "(call_expression function: (identifier) @_f (#eq? @_f \"describe\") arguments: (arguments (string) @name)) @entry",
+            "suite",
+        );
+        assert_eq!(
+            describe,
+            vec![Found {
+                line: 7,
+                end_line: 9,
+                kind: "suite",
+                label: "\"load\"".into(),
+            }]
+        );
+        // Only an entry: labelled by its first line
+        let sql = found("(template_string) @entry", "sql");
+        assert_eq!(sql[0].line, 4);
+        assert_eq!(sql[0].label, "`SELECT * FROM users WHERE id = ?`, id);");
+    }
+
+    #[test]
+    fn entries_merge_in_line_order() {
+        let outline = "[1]          imports: ./db\n[3-5]        export fn load(id)\n[7-9]        call describe";
+        let found = vec![
+            Found {
+                line: 4,
+                end_line: 4,
+                kind: "sql",
+                label: "SELECT".into(),
+            },
+            Found {
+                line: 12,
+                end_line: 12,
+                kind: "route",
+                label: "/users".into(),
+            },
+        ];
+        assert_eq!(
+            interleave(outline, &found, 100),
+            "[1]          imports: ./db\n\
+             [3-5]        export fn load(id)\n  \
+             [4]          sql SELECT\n\
+             [7-9]        call describe\n\
+             [12]         route /users"
+        );
+        assert_eq!(interleave(outline, &found, 2).lines().count(), 2);
+    }
+}
@@ -1,20 +1,45 @@
 use std::path::Path;
 
-/// Depth-limited outline for JSON, YAML, TOML.
+use super::buildfile::BuildSymbol;
+
+/// Depth-limited outline for JSON, YAML, TOML, and the element tree of XML.
+/// `OpenAPI` specs and Kubernetes manifests are outlined by operation and
+/// resource instead.
 pub fn outline(path: &Path, content: &str, max_lines: usize) -> String {
     match path.extension().and_then(|e| e.to_str()) {
-        Some("json") => json_outline(content, max_lines),
-        Some("yaml" | "yml") => yaml_outline(content, max_lines),
+        Some("json") => super::openapi::outline(content, max_lines)
+            .unwrap_or_else(|| json_outline(content, max_lines)),
+        Some("yaml" | "yml") => super::openapi::outline(content, max_lines)
+            .or_else(|| super::k8s::outline(content, max_lines))
+            .unwrap_or_else(|| yaml_outline(content, max_lines)),
+        Some("toml") if path.file_name().is_some_and(|n| n == "Cargo.toml") => {
+            super::cargo::outline(path, content, max_lines)
+                .unwrap_or_else(|| toml_outline(content, max_lines))
+        }
         Some("toml") => toml_outline(content, max_lines),
+        Some("xml") => super::xml::outline(content, max_lines)
+            .unwrap_or_else(|| key_value_outline(content, max_lines)),
         _ => key_value_outline(content, max_lines),
     }
 }
 
+/// Named definitions in a structured file: `OpenAPI` operations and schemas,
+/// Kubernetes resources. `None` for plain data files.
+pub(crate) fn symbols<'a>(path: &Path, content: &'a str) -> Option<Vec<BuildSymbol<'a>>> {
+    super::openapi::symbols(path, content).or_else(|| super::k8s::symbols(path, content))
+}
+
 fn json_outline(content: &str, max_lines: usize) -> String {
     let value: serde_json::Value = match serde_json::from_str(content) {
         Ok(v) => v,
         Err(e) => return format!("[parse error: {e}]"),
     };
+    if let Some(table) = value
+        .as_array()
+        .and_then(|items| crate::read::records::table(items, None))
+    {
+        return table;
+    }
     let mut lines = Vec::new();
     walk_json(&value, "", 0, 2, max_lines, &mut lines);
     lines.join("\n")
@@ -121,11 +146,69 @@ fn truncate_json_value(v: &serde_json::Value, max: usize) -> String {
 }
 
 /// YAML outline via line scan — no parser needed.
-/// Detect keys by: optional whitespace, then a word, then `: ` or `:`+EOL.
-/// Indentation level = nesting depth (2-space standard).
+/// Multi-document files (`---` separated) get one header per document, naming
+/// its `kind`/`metadata.name` when present. Anchors are listed with their aliases.
 fn yaml_outline(content: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let docs = yaml_documents(&lines);
+
+    if docs.len() <= 1 {
+        let mut entries = Vec::new();
+        let (start, end) = docs.first().copied().unwrap_or((0, 0));
+        yaml_keys(&lines, start, end, 2, max_lines, &mut entries);
+        entries.extend(yaml_anchors(&lines, start, end));
+        return entries.join("\n");
+    }
+
     let mut entries = Vec::new();
-    for (i, line) in content.lines().enumerate() {
+    for (n, &(start, end)) in docs.iter().enumerate() {
+        if entries.len() >= max_lines {
+            break;
+        }
+        let mut header = format!("[{}-{}] --- doc {}", start + 1, end, n + 1);
+        if let Some(label) = yaml_doc_label(&lines[start..end]) {
+            header.push_str(": ");
+            header.push_str(&label);
+        }
+        entries.push(header);
+        yaml_keys(&lines, start, end, 1, max_lines, &mut entries);
+        entries.extend(yaml_anchors(&lines, start, end));
+    }
+    entries.truncate(max_lines);
+    entries.join("\n")
+}
+
+/// Line-index ranges `[start, end)` of each non-empty document, split on `---`.
+pub(super) fn yaml_documents(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut docs = Vec::new();
+    let mut start = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("---") || line.starts_with("...") {
+            docs.push((start, i));
+            start = i + 1;
+        }
+    }
+    docs.push((start, lines.len()));
+    docs.retain(|&(s, e)| {
+        lines[s..e].iter().any(|l| {
+            let t = l.trim_start();
+            !t.is_empty() && !t.starts_with('#')
+        })
+    });
+    docs
+}
+
+/// Detect keys by: optional whitespace, then a word, then `: ` or `:`+EOL.
+/// Indentation level = nesting depth (2-space standard).
+fn yaml_keys(
+    lines: &[&str],
+    start: usize,
+    end: usize,
+    max_depth: usize,
+    max_lines: usize,
+    entries: &mut Vec<String>,
+) {
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
         if entries.len() >= max_lines {
             break;
         }
@@ -143,7 +226,7 @@ fn yaml_outline(content: &str, max_lines: usize) -> String {
             }
             let indent = line.len() - trimmed.len();
             let depth = indent / 2;
-            if depth <= 2 {
+            if depth <= max_depth {
                 let prefix = "  ".repeat(depth);
                 let after_colon = trimmed[colon + 1..].trim();
                 if after_colon.is_empty() {
@@ -160,7 +243,99 @@ fn yaml_outline(content: &str, max_lines: usize) -> String {
             }
         }
     }
-    entries.join("\n")
+}
+
+/// `Kind/name` from a document's top-level `kind:` and `metadata.name`.
+fn yaml_doc_label(doc: &[&str]) -> Option<String> {
+    match (top_value(doc, "kind"), metadata_value(doc, "name")) {
+        (Some(k), Some(n)) => Some(format!("{k}/{n}")),
+        (Some(k), None) => Some(k.to_string()),
+        (None, Some(n)) => Some(n.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Inline value of a column-0 `key:` in a document, unquoted.
+pub(super) fn top_value<'a>(doc: &[&'a str], key: &str) -> Option<&'a str> {
+    doc.iter().find_map(|l| {
+        l.strip_prefix(key)?
+            .strip_prefix(':')
+            .map(|v| unquote(v.trim()))
+            .filter(|v| !v.is_empty())
+    })
+}
+
+/// Inline value of a direct child of the top-level `metadata:` block.
+pub(super) fn metadata_value<'a>(doc: &[&'a str], key: &str) -> Option<&'a str> {
+    let m = doc.iter().position(|l| l.trim_end() == "metadata:")?;
+    doc[m + 1..]
+        .iter()
+        .take_while(|l| l.is_empty() || l.starts_with([' ', '\t']))
+        .find_map(|l| {
+            let t = l.trim_start();
+            // Only direct children: `name` under `metadata.labels` is indented further
+            let child_indent = l.len() - t.len();
+            (child_indent <= 2)
+                .then(|| t.strip_prefix(key)?.strip_prefix(':'))
+                .flatten()
+        })
+        .map(|v| unquote(v.trim()))
+        .filter(|v| !v.is_empty())
+}
+
+pub(super) fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// `anchors: &defaults [3] ← *defaults [10, 15]` — one entry per anchor,
+/// linking its definition line to the lines that alias (or merge) it.
+fn yaml_anchors(lines: &[&str], start: usize, end: usize) -> Vec<String> {
+    let mut anchors: Vec<(&str, usize, Vec<usize>)> = Vec::new();
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        let code = line.split(" #").next().unwrap_or(line);
+        for (sigil, name) in yaml_refs(code) {
+            if sigil == '&' {
+                anchors.push((name, i + 1, Vec::new()));
+            } else if let Some(anchor) = anchors.iter_mut().rev().find(|a| a.0 == name) {
+                anchor.2.push(i + 1);
+            }
+        }
+    }
+
+    anchors
+        .into_iter()
+        .map(|(name, line, uses)| {
+            if uses.is_empty() {
+                format!("[{line}] anchor &{name} (unused)")
+            } else {
+                let uses: Vec<String> = uses.iter().map(ToString::to_string).collect();
+                format!("[{line}] anchor &{name} ← *{name} [{}]", uses.join(", "))
+            }
+        })
+        .collect()
+}
+
+/// `&anchor` and `*alias` tokens on a line. A sigil counts only at the start
+/// of a value (after whitespace, `[`, `{` or `,`), so `a && b` and `"*.rs"` don't.
+fn yaml_refs(line: &str) -> Vec<(char, &str)> {
+    let bytes = line.as_bytes();
+    let mut out = Vec::new();
+    for (i, &b) in bytes.iter().enumerate() {
+        if b != b'&' && b != b'*' {
+            continue;
+        }
+        if i > 0 && !matches!(bytes[i - 1], b' ' | b'\t' | b'[' | b'{' | b',') {
+            continue;
+        }
+        let rest = &line[i + 1..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if len > 0 {
+            out.push((char::from(b), &rest[..len]));
+        }
+    }
+    out
 }
 
 fn toml_outline(content: &str, max_lines: usize) -> String {
@@ -222,3 +397,66 @@ fn key_value_outline(content: &str, max_lines: usize) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_multi_document_headers() {
+        let yaml = "\
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  labels:
+    name: not-this
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web-svc
+";
+        let out = yaml_outline(yaml, 100);
+        let headers: Vec<&str> = out.lines().filter(|l| l.contains("--- doc")).collect();
+        assert_eq!(
+            headers,
+            vec![
+                "[1-6] --- doc 1: Deployment/web",
+                "[8-11] --- doc 2: Service/web-svc"
+            ]
+        );
+        // Depth is capped at one level per document
+        assert!(out.contains("labels:"), "{out}");
+        assert!(!out.contains("not-this"), "{out}");
+    }
+
+    #[test]
+    fn yaml_anchors_link_aliases() {
+        let yaml = "\
+defaults: &defaults
+  retries: 3
+prod:
+  <<: *defaults
+  host: prod
+stage:
+  <<: *defaults
+unused: &lonely 1
+cmd: make a && make b
+glob: \"*.rs\"
+";
+        let out = yaml_outline(yaml, 100);
+        assert!(
+            out.contains("[1] anchor &defaults ← *defaults [4, 7]"),
+            "{out}"
+        );
+        assert!(out.contains("[8] anchor &lonely (unused)"), "{out}");
+        assert_eq!(out.matches("anchor").count(), 2, "{out}");
+    }
+
+    #[test]
+    fn yaml_single_document_unchanged() {
+        let out = yaml_outline("---\nname: x\nspec:\n  a: 1\n", 100);
+        assert_eq!(out, "[2] name: x\n[3] spec:\n[4]   a: 1");
+    }
+}
@@ -0,0 +1,408 @@
+//! XML — Maven poms, Android manifests, build and config files — outlined as
+//! the element tree with line ranges and child counts. A `section` path like
+//! `project/dependencies` or `dependency[2]` reads one element's lines.
+
+use std::fmt::Write;
+
+/// Levels of the tree shown; children below are only counted.
+const MAX_DEPTH: usize = 3;
+/// Same-named siblings shown before the rest are counted.
+const MAX_REPEATS: usize = 3;
+/// Children of an element summed up on its own line, at most.
+const MAX_SUMMARY_CHILDREN: usize = 6;
+/// Characters of a one-line summary.
+const MAX_SUMMARY_CHARS: usize = 100;
+/// Characters of leaf text shown.
+const MAX_TEXT_CHARS: usize = 60;
+/// Attributes that name an element, shown beside it — first match wins.
+const LABELS: [&str; 3] = ["android:name", "name", "id"];
+
+struct Element<'a> {
+    name: &'a str,
+    /// The value of its first attribute from [`LABELS`].
+    label: Option<&'a str>,
+    /// Text of an element without children, when short and on one line.
+    text: Option<&'a str>,
+    line: u32,
+    end_line: u32,
+    children: Vec<Element<'a>>,
+}
+
+/// The element tree of `content`, or `None` if it isn't XML.
+pub fn outline(content: &str, max_lines: usize) -> Option<String> {
+    let root = parse(content)?;
+    let mut out = Vec::new();
+    render(&root, 0, &mut out);
+    out.truncate(max_lines);
+    Some(out.join("\n"))
+}
+
+/// The 1-based `(start, end)` lines of the element at `path`: names from the
+/// root down, separated by `/`, with `[n]` picking the nth of several
+/// same-named siblings. The root's own name may be left out. `None` when
+/// `content` isn't XML or `path` isn't shaped like an element path.
+pub(crate) fn section(content: &str, path: &str) -> Option<Result<(usize, usize), String>> {
+    let path = path.trim().trim_start_matches('/');
+    if !path.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        || !path
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_-.:/[]".contains(c))
+    {
+        return None;
+    }
+    let root = parse(content)?;
+    Some(find(&root, path))
+}
+
+fn find(root: &Element, path: &str) -> Result<(usize, usize), String> {
+    let mut steps = path.split('/').filter(|s| !s.is_empty()).peekable();
+    if steps
+        .peek()
+        .is_some_and(|s| same_name(root.name, s.split('[').next().unwrap_or(s)))
+    {
+        steps.next();
+    }
+    let mut el = root;
+    for step in steps {
+        let (name, index) = parse_step(step)?;
+        let found: Vec<&Element> = el
+            .children
+            .iter()
+            .filter(|c| same_name(c.name, name))
+            .collect();
+        el = match (index, found.len()) {
+            (_, 0) => {
+                let mut names: Vec<&str> = Vec::new();
+                for child in &el.children {
+                    if !names.contains(&child.name) {
+                        names.push(child.name);
+                    }
+                }
+                let has = if names.is_empty() {
+                    "no child elements".to_string()
+                } else {
+                    names.join(", ")
+                };
+                return Err(format!("no <{name}> in <{}> — it has: {has}", el.name));
+            }
+            (None, 1) => found[0],
+            (None, n) => {
+                return Err(format!(
+                    "{n} <{name}> elements in <{}> — pick one, e.g. \"{name}[2]\"",
+                    el.name
+                ))
+            }
+            (Some(i), n) => found
+                .get(i - 1)
+                .ok_or_else(|| format!("{step}: <{}> has {n} <{name}>", el.name))?,
+        };
+    }
+    Ok((el.line as usize, el.end_line as usize))
+}
+
+/// `"dependency[2]"` → `("dependency", Some(2))`.
+fn parse_step(step: &str) -> Result<(&str, Option<usize>), String> {
+    let Some((name, index)) = step.split_once('[') else {
+        return Ok((step, None));
+    };
+    index
+        .strip_suffix(']')
+        .and_then(|i| i.parse().ok())
+        .filter(|&i| i > 0)
+        .map(|i| (name, Some(i)))
+        .ok_or_else(|| format!("{step}: expected a 1-based index like {name}[2]"))
+}
+
+/// Whether element `name` is `step`, with or without its namespace prefix.
+fn same_name(name: &str, step: &str) -> bool {
+    name == step || (!step.contains(':') && name.rsplit(':').next() == Some(step))
+}
+
+fn parse(content: &str) -> Option<Element<'_>> {
+    if !content.trim_start().starts_with('<') {
+        return None;
+    }
+    let bytes = content.as_bytes();
+    let newlines: Vec<usize> = memchr::memchr_iter(b'\n', bytes).collect();
+    let line_at = |pos: usize| (newlines.partition_point(|&n| n < pos) + 1) as u32;
+
+    let mut stack: Vec<(Element, usize)> = Vec::new();
+    let mut root = None;
+    let mut i = 0;
+    while let Some(offset) = memchr::memchr(b'<', &bytes[i..]) {
+        let at = i + offset;
+        let rest = &content[at..];
+        // Comments, CDATA, declarations and processing instructions
+        let skipped = [
+            ("<!--", "-->"),
+            ("<![CDATA[", "]]>"),
+            ("<?", "?>"),
+            ("<!", ">"),
+        ]
+        .iter()
+        .find(|(open, _)| rest.starts_with(open));
+        if let Some((_, close)) = skipped {
+            i = rest
+                .find(close)
+                .map_or(bytes.len(), |e| at + e + close.len());
+            continue;
+        }
+        let Some(tag_end) = tag_len(rest).map(|len| at + len) else {
+            break;
+        };
+        let tag = &content[at + 1..tag_end - 1];
+        i = tag_end;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            let Some(depth) = stack.iter().rposition(|(e, _)| e.name == name) else {
+                continue;
+            };
+            // Elements left open inside this one end with it
+            while let Some((mut el, body)) = stack.pop() {
+                el.end_line = line_at(tag_end - 1);
+                if stack.len() == depth && el.children.is_empty() {
+                    el.text = leaf_text(&content[body..at]);
+                }
+                attach(&mut stack, &mut root, el);
+                if stack.len() == depth {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        if name_end == 0 {
+            continue;
+        }
+        let attributes = attributes(&tag[name_end..]);
+        let el = Element {
+            name: &tag[..name_end],
+            label: LABELS.iter().find_map(|label| {
+                attributes
+                    .iter()
+                    .find(|(key, _)| key == label)
+                    .map(|&(_, value)| value)
+            }),
+            text: None,
+            line: line_at(at),
+            end_line: line_at(tag_end - 1),
+            children: Vec::new(),
+        };
+        if self_closing {
+            attach(&mut stack, &mut root, el);
+        } else {
+            stack.push((el, tag_end));
+        }
+    }
+    // Unclosed elements run to the end of the file
+    while let Some((mut el, _)) = stack.pop() {
+        el.end_line = line_at(bytes.len());
+        attach(&mut stack, &mut root, el);
+    }
+    root
+}
+
+fn attach<'a>(stack: &mut [(Element<'a>, usize)], root: &mut Option<Element<'a>>, el: Element<'a>) {
+    match stack.last_mut() {
+        Some((parent, _)) => parent.children.push(el),
+        None => {
+            root.get_or_insert(el);
+        }
+    }
+}
+
+/// Bytes up to and including the `>` closing the tag that `rest` opens with,
+/// skipping any inside quoted attribute values.
+fn tag_len(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, b) in rest.bytes().enumerate().skip(1) {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `key="value"` pairs of a start tag, after its name.
+fn attributes(mut s: &str) -> Vec<(&str, &str)> {
+    let mut out = Vec::new();
+    while let Some(eq) = s.find('=') {
+        let key = s[..eq].trim();
+        let rest = s[eq + 1..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            break;
+        };
+        let Some(len) = rest[1..].find(quote) else {
+            break;
+        };
+        out.push((key, &rest[1..=len]));
+        s = &rest[len + 2..];
+    }
+    out
+}
+
+fn leaf_text(body: &str) -> Option<&str> {
+    let text = body.trim();
+    (!text.is_empty() && !text.contains(['\n', '<']) && text.chars().count() <= MAX_TEXT_CHARS)
+        .then_some(text)
+}
+
+fn render(el: &Element, depth: usize, out: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    let range = if el.line == el.end_line {
+        format!("[{}]", el.line)
+    } else {
+        format!("[{}-{}]", el.line, el.end_line)
+    };
+    let mut line = format!("{indent}{range} {}", el.name);
+    if let Some(label) = el.label {
+        let _ = write!(line, " {label}");
+    }
+    if let Some(text) = el.text {
+        let _ = write!(line, ": {text}");
+    }
+    if el.children.is_empty() {
+        out.push(line);
+        return;
+    }
+    if let Some(summary) = summary(&el.children) {
+        let _ = write!(line, ": {summary}");
+        out.push(line);
+        return;
+    }
+
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for child in &el.children {
+        match counts.iter_mut().find(|(name, _)| *name == child.name) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((child.name, 1)),
+        }
+    }
+    let expand = depth + 1 < MAX_DEPTH;
+    if !expand || counts.iter().any(|&(_, n)| n > 1) {
+        let counted: Vec<String> = counts
+            .iter()
+            .map(|(name, n)| format!("{n} {name}"))
+            .collect();
+        let _ = write!(line, " ({})", counted.join(", "));
+    }
+    out.push(line);
+    if !expand {
+        return;
+    }
+    let mut shown: Vec<(&str, usize)> = Vec::new();
+    for child in &el.children {
+        let n = if let Some((_, n)) = shown.iter_mut().find(|(name, _)| *name == child.name) {
+            *n += 1;
+            *n
+        } else {
+            shown.push((child.name, 1));
+            1
+        };
+        if n <= MAX_REPEATS {
+            render(child, depth + 1, out);
+        }
+    }
+    for (name, n) in counts {
+        if n > MAX_REPEATS {
+            out.push(format!("{indent}  ... {} more {name}", n - MAX_REPEATS));
+        }
+    }
+}
+
+/// An element whose children are all leaves, in one line:
+/// `groupId=org.slf4j, artifactId=slf4j-api, version=2.0.9`.
+fn summary(children: &[Element]) -> Option<String> {
+    if children.len() > MAX_SUMMARY_CHILDREN || children.iter().any(|c| !c.children.is_empty()) {
+        return None;
+    }
+    let mut summary = children
+        .iter()
+        .map(|c| match c.text.or(c.label) {
+            Some(value) => format!("{}={value}", c.name),
+            None => c.name.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if let Some((cut, _)) = summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        summary.truncate(cut);
+        summary.push('…');
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- build file -->
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <artifactId>demo</artifactId>
+  <dependencies>
+    <dependency>
+      <groupId>org.slf4j</groupId>
+      <artifactId>slf4j-api</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>junit</groupId>
+      <artifactId>junit</artifactId>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+  <build>
+    <plugins>
+      <plugin name="a"><configuration><skip>true</skip></configuration></plugin>
+    </plugins>
+  </build>
+</project>
+"#;
+
+    #[test]
+    fn outlines_the_element_tree() {
+        let shown = outline(POM, usize::MAX).unwrap();
+        let lines: Vec<&str> = shown.lines().collect();
+        assert_eq!(lines[0], "[3-22] project");
+        assert_eq!(lines[1], "  [4] modelVersion: 4.0.0");
+        assert_eq!(lines[3], "  [6-16] dependencies (2 dependency)");
+        assert_eq!(
+            lines[4],
+            "    [7-10] dependency: groupId=org.slf4j, artifactId=slf4j-api"
+        );
+        assert_eq!(lines[7], "    [18-20] plugins (1 plugin)");
+        assert!(outline("key = value", usize::MAX).is_none());
+    }
+
+    #[test]
+    fn sections_address_elements_by_path() {
+        assert_eq!(section(POM, "project/dependencies"), Some(Ok((6, 16))));
+        assert_eq!(
+            section(POM, "/dependencies/dependency[2]"),
+            Some(Ok((11, 15)))
+        );
+        assert_eq!(
+            section(POM, "build/plugins/plugin/configuration"),
+            Some(Ok((19, 19)))
+        );
+        let err = section(POM, "dependencies/dependency")
+            .unwrap()
+            .unwrap_err();
+        assert!(err.contains("dependency[2]"), "{err}");
+        let err = section(POM, "project/parent").unwrap().unwrap_err();
+        assert!(
+            err.contains("modelVersion, artifactId, dependencies, build"),
+            "{err}"
+        );
+        assert_eq!(section(POM, "45-89 x"), None);
+        assert_eq!(section(POM, "45"), None);
+        assert_eq!(section("# Title", "project"), None);
+    }
+}
@@ -0,0 +1,275 @@
+//! JSON arrays of like objects — fixtures, exports, API dumps — as a table:
+//! fields as columns, one row per object. The Keys view of such a file
+//! leads with its first rows; a read with `query` shows the rows matching
+//! it.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use super::{detect_file_type, suggest_similar};
+use crate::error::TilthError;
+use crate::format;
+use crate::tokens;
+use crate::types::ViewMode;
+
+/// Rows in the Keys view.
+const PREVIEW_ROWS: usize = 10;
+/// Rows a query shows at most.
+const MAX_MATCHES: usize = 50;
+/// Columns shown; rarer fields are only counted.
+const MAX_COLUMNS: usize = 8;
+/// Characters per cell.
+const CELL_CHARS: usize = 24;
+
+/// The rows of the JSON array of objects in `path` that match `query`:
+/// `field=value` compares one field, any other text is looked for in every
+/// field. Both ignore case.
+pub fn records(path: &Path, query: &str) -> Result<String, TilthError> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: suggest_similar(path),
+        },
+        _ => TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })?;
+    let value: Value = serde_json::from_str(&content).map_err(|e| TilthError::ParseError {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+    let table = value
+        .as_array()
+        .and_then(|items| table(items, Some(query)))
+        .ok_or_else(|| TilthError::InvalidQuery {
+            query: query.to_string(),
+            reason: format!(
+                "{} isn't a JSON array of like objects — query filters table rows",
+                path.display()
+            ),
+        })?;
+    let tokens = tokens::estimate(content.as_bytes(), detect_file_type(path));
+    let lines = content.lines().count() as u32;
+    let header = format::file_header(path, tokens, lines, ViewMode::Keys);
+    Ok(format!("{header}\n\n{table}"))
+}
+
+/// `items` as a table when they are all objects sharing most of their
+/// fields; `None` otherwise. Without a `query`, the first rows.
+pub(crate) fn table(items: &[Value], query: Option<&str>) -> Option<String> {
+    if items.len() < 2 {
+        return None;
+    }
+    let rows: Vec<&Map<String, Value>> =
+        items.iter().map(Value::as_object).collect::<Option<_>>()?;
+
+    // Fields by how many rows have them
+    let mut fields: Vec<(&str, usize)> = Vec::new();
+    for row in &rows {
+        for key in row.keys() {
+            match fields.iter_mut().find(|(name, _)| name == key) {
+                Some((_, count)) => *count += 1,
+                None => fields.push((key, 1)),
+            }
+        }
+    }
+    fields.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    let common: Vec<&str> = fields
+        .iter()
+        .filter(|&&(_, count)| count * 2 > rows.len())
+        .map(|&(name, _)| name)
+        .collect();
+    let in_common: usize = fields.iter().take(common.len()).map(|f| f.1).sum();
+    let all: usize = fields.iter().map(|f| f.1).sum();
+    if common.is_empty() || in_common * 4 < all * 3 {
+        return None;
+    }
+    let columns = &common[..common.len().min(MAX_COLUMNS)];
+
+    let filter = query.map(|q| Filter::parse(q, &fields));
+    let matching: Vec<(usize, &Map<String, Value>)> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| filter.as_ref().is_none_or(|f| f.matches(row)))
+        .map(|(i, row)| (i, *row))
+        .collect();
+    let limit = if query.is_some() {
+        MAX_MATCHES
+    } else {
+        PREVIEW_ROWS
+    };
+    let shown = &matching[..matching.len().min(limit)];
+
+    let mut out = format!("columns: {}", columns.join(", "));
+    if fields.len() > columns.len() {
+        let _ = write!(out, " (+{} more fields)", fields.len() - columns.len());
+    }
+    match query {
+        Some(q) => {
+            let _ = write!(
+                out,
+                "\nrows: {} of {} match \"{q}\"",
+                matching.len(),
+                rows.len()
+            );
+        }
+        None => {
+            let _ = write!(out, "\nrows: {}", rows.len());
+        }
+    }
+
+    let cells: Vec<Vec<String>> = shown
+        .iter()
+        .map(|(i, row)| {
+            std::iter::once(i.to_string())
+                .chain(
+                    columns
+                        .iter()
+                        .map(|c| row.get(*c).map_or_else(String::new, cell)),
+                )
+                .collect()
+        })
+        .collect();
+    let headers: Vec<&str> = std::iter::once("#")
+        .chain(columns.iter().copied())
+        .collect();
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(c, h)| {
+            cells
+                .iter()
+                .map(|row| row[c].chars().count())
+                .chain(std::iter::once(h.len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    if !cells.is_empty() {
+        out.push('\n');
+        let render = |values: &mut dyn Iterator<Item = &str>| {
+            let line = values
+                .zip(&widths)
+                .map(|(v, &w)| format!("{v:<w$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("\n{}", line.trim_end())
+        };
+        out.push_str(&render(&mut headers.iter().copied()));
+        for row in &cells {
+            out.push_str(&render(&mut row.iter().map(String::as_str)));
+        }
+    }
+    if matching.len() > shown.len() {
+        let _ = write!(out, "\n... {} more rows", matching.len() - shown.len());
+    }
+    if query.is_none() {
+        out.push_str(
+            "\n\n> Filter rows with query: \"field=value\", or text to find in any field.",
+        );
+    }
+    Some(out)
+}
+
+enum Filter {
+    Field(String, String),
+    Text(String),
+}
+
+impl Filter {
+    /// `field=value` when `field` is one of `fields`, else text.
+    fn parse(query: &str, fields: &[(&str, usize)]) -> Self {
+        if let Some((field, value)) = query.split_once('=') {
+            let field = field.trim();
+            if fields.iter().any(|(name, _)| *name == field) {
+                return Self::Field(field.to_string(), value.trim().to_lowercase());
+            }
+        }
+        Self::Text(query.to_lowercase())
+    }
+
+    fn matches(&self, row: &Map<String, Value>) -> bool {
+        match self {
+            Self::Field(field, value) => row
+                .get(field)
+                .is_some_and(|v| plain(v).to_lowercase() == *value),
+            Self::Text(text) => row
+                .values()
+                .any(|v| plain(v).to_lowercase().contains(text.as_str())),
+        }
+    }
+}
+
+/// A value as text: strings without their quotes, the rest as JSON.
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// A value cut to [`CELL_CHARS`] for the table, on one line.
+fn cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        other => plain(other),
+    };
+    let mut text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((cut, _)) = text.char_indices().nth(CELL_CHARS) {
+        text.truncate(cut);
+        text.push('…');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users() -> Vec<Value> {
+        (0..30)
+            .map(|i| {
+                serde_json::json!({
+                    "id": i,
+                    "name": format!("user{i}"),
+                    "role": if i % 10 == 0 { "admin" } else { "member" },
+                    "bio": null,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn like_objects_render_as_a_table() {
+        let shown = table(&users(), None).unwrap();
+        let lines: Vec<&str> = shown.lines().collect();
+        assert_eq!(lines[0], "columns: bio, id, name, role");
+        assert_eq!(lines[1], "rows: 30");
+        assert_eq!(lines[3], "#  bio  id  name   role");
+        assert_eq!(lines[4], "0       0   user0  admin");
+        assert!(shown.contains("... 20 more rows"), "{shown}");
+
+        let mixed = vec![serde_json::json!({"a": 1}), serde_json::json!({"b": 2})];
+        assert!(table(&mixed, None).is_none());
+        assert!(table(&[serde_json::json!({"a": 1}), serde_json::json!(2)], None).is_none());
+    }
+
+    #[test]
+    fn query_filters_rows() {
+        let shown = table(&users(), Some("role=ADMIN")).unwrap();
+        assert!(
+            shown.contains("rows: 3 of 30 match \"role=ADMIN\""),
+            "{shown}"
+        );
+        assert!(shown.contains("\n20  "), "{shown}");
+        assert!(!shown.contains("member"), "{shown}");
+
+        let shown = table(&users(), Some("user2")).unwrap();
+        // user2 and user20..user29
+        assert!(shown.contains("rows: 11 of 30"), "{shown}");
+    }
+}
@@ -0,0 +1,248 @@
+//! A self-contained snippet around one line: the enclosing function, the
+//! members of its own type that it reaches through `self`, and the type
+//! definitions it names from the same file — enough to read the function or
+//! build a minimal reproduction from it without the rest of the file.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::outline::code;
+use super::{detect_file_type, suggest_similar};
+use crate::error::TilthError;
+use crate::search::siblings;
+use crate::types::{FileType, Lang, OutlineEntry, OutlineKind};
+
+/// Type definitions pulled in, counting the ones those name in turn.
+const MAX_TYPES: usize = 10;
+
+/// The snippet for `line` (1-based) of `path`, as numbered lines with `...`
+/// between the pieces, headed by what was pulled in.
+pub fn snippet(path: &Path, line: u32) -> Result<String, TilthError> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: suggest_similar(path),
+        },
+        _ => TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })?;
+    let invalid = |reason: String| TilthError::InvalidQuery {
+        query: format!("{}:{line}", path.display()),
+        reason,
+    };
+    let FileType::Code(lang) = detect_file_type(path) else {
+        return Err(invalid("snippets need a code file".into()));
+    };
+    let entries = code::entries(&content, lang)
+        .ok_or_else(|| invalid(format!("no grammar for {lang} — read a line range instead")))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let target = siblings::find_enclosing_function(&entries, line)
+        .or_else(|| innermost(&entries, line))
+        .ok_or_else(|| invalid(format!("line {line} is not inside a definition")))?;
+    let target_range = (target.start_line, target.end_line);
+
+    let mut ranges: BTreeSet<(u32, u32)> = BTreeSet::new();
+    let mut pulled: Vec<String> = Vec::new();
+    ranges.insert(target_range);
+
+    // The type it is a member of: its header and closing line, and the
+    // members it uses
+    if let Some(parent) = siblings::find_parent_entry(&entries, target.start_line)
+        .filter(|p| is_type(p) || code::is_impl_block(p, lang))
+    {
+        enclose(parent, &lines, &mut ranges);
+        let refs = siblings::extract_sibling_references(
+            &content,
+            lang,
+            target_range,
+            siblings::owner_name(parent, lang),
+        );
+        for member in siblings::sibling_members(&entries, parent, lang) {
+            if member.start_line == target.start_line || !refs.contains(&member.name) {
+                continue;
+            }
+            ranges.insert((member.start_line, member.end_line));
+            pulled.push(label(member, lang));
+            if let Some(other) = siblings::find_parent_entry(&entries, member.start_line) {
+                enclose(other, &lines, &mut ranges);
+            }
+        }
+    }
+
+    // Types named anywhere in what is shown so far, then in those types
+    let mut types = Vec::new();
+    collect_types(&entries, &mut types);
+    types.retain(|t| !(t.start_line <= target.start_line && target.end_line <= t.end_line));
+    let mut seen: HashSet<u32> = HashSet::new();
+    let mut queue: Vec<(u32, u32)> = ranges.iter().copied().collect();
+    while let Some((start, end)) = queue.pop() {
+        let names = identifiers(&lines, start, end);
+        for ty in &types {
+            if seen.len() >= MAX_TYPES {
+                break;
+            }
+            if names.contains(ty.name.as_str()) && seen.insert(ty.start_line) {
+                ranges.insert((ty.start_line, ty.end_line));
+                pulled.push(label(ty, lang));
+                queue.push((ty.start_line, ty.end_line));
+            }
+        }
+    }
+
+    let mut out = format!(
+        "# {}:{}-{} {} — snippet",
+        path.display(),
+        target.start_line,
+        target.end_line,
+        label(target, lang)
+    );
+    if !pulled.is_empty() {
+        let _ = write!(out, "\n> with: {}", pulled.join(", "));
+    }
+    out.push('\n');
+    let mut last = 0;
+    for (start, end) in merge(&ranges) {
+        if last > 0 && start > last + 1 {
+            out.push_str("\n  ...");
+        }
+        for n in start..=end.min(lines.len() as u32) {
+            let _ = write!(out, "\n{n:>4} │ {}", lines[n as usize - 1]);
+        }
+        last = end;
+    }
+    Ok(out)
+}
+
+/// Innermost entry of any kind containing `line`.
+fn innermost(entries: &[OutlineEntry], line: u32) -> Option<&OutlineEntry> {
+    entries
+        .iter()
+        .filter(|e| e.kind != OutlineKind::Import && e.start_line <= line && line <= e.end_line)
+        .find_map(|e| innermost(&e.children, line).or(Some(e)))
+}
+
+fn is_type(entry: &OutlineEntry) -> bool {
+    matches!(
+        entry.kind,
+        OutlineKind::Class
+            | OutlineKind::Struct
+            | OutlineKind::Interface
+            | OutlineKind::TypeAlias
+            | OutlineKind::Enum
+    )
+}
+
+fn collect_types<'a>(entries: &'a [OutlineEntry], out: &mut Vec<&'a OutlineEntry>) {
+    for entry in entries {
+        if is_type(entry) {
+            out.push(entry);
+        }
+        collect_types(&entry.children, out);
+    }
+}
+
+/// A container's first line, and its last when that only closes it.
+fn enclose(entry: &OutlineEntry, lines: &[&str], ranges: &mut BTreeSet<(u32, u32)>) {
+    ranges.insert((entry.start_line, entry.start_line));
+    let last = lines
+        .get(entry.end_line as usize - 1)
+        .map_or("", |l| l.trim());
+    if entry.end_line > entry.start_line && matches!(last, "}" | "};" | "end") {
+        ranges.insert((entry.end_line, entry.end_line));
+    }
+}
+
+fn label(entry: &OutlineEntry, lang: Lang) -> String {
+    if code::is_impl_block(entry, lang) {
+        return entry.name.clone();
+    }
+    format!("{} {}", code::kind_label(entry.kind, lang), entry.name)
+}
+
+/// Identifiers on lines `start..=end`.
+fn identifiers<'a>(lines: &[&'a str], start: u32, end: u32) -> HashSet<&'a str> {
+    lines
+        .iter()
+        .take(end as usize)
+        .skip(start.saturating_sub(1) as usize)
+        .flat_map(|l| l.split(|c: char| !c.is_alphanumeric() && c != '_'))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Sorted ranges with overlapping and adjacent ones joined.
+fn merge(ranges: &BTreeSet<(u32, u32)>) -> Vec<(u32, u32)> {
+    let mut merged: Vec<(u32, u32)> = Vec::new();
+    for &(start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_with_its_type_members_and_named_types() {
+        let dir = std::env::temp_dir().join("tilth_test_snippet");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("config.rs");
+        fs::write(
+            &path,
+            "pub enum Mode {\n\
+             \x20   Fast,\n\
+             }\n\
+             \n\
+             pub struct Unused;\n\
+             \n\
+             pub struct Config {\n\
+             \x20   mode: Mode,\n\
+             }\n\
+             \n\
+             impl Config {\n\
+             \x20   fn other(&self) {}\n\
+             \n\
+             \x20   fn check(&self) -> bool {\n\
+             \x20       true\n\
+             \x20   }\n\
+             \n\
+             \x20   pub fn load(&self) -> Option<Limits> {\n\
+             \x20       self.check();\n\
+             \x20       None\n\
+             \x20   }\n\
+             }\n\
+             \n\
+             type Limits = Vec<u32>;\n",
+        )
+        .unwrap();
+
+        let out = snippet(&path, 19).unwrap();
+        assert!(out.contains(":18-21 fn load — snippet"), "{out}");
+        assert!(out.contains("  11 │ impl Config {"), "{out}");
+        assert!(
+            out.contains("  14 │     fn check(&self) -> bool {"),
+            "{out}"
+        );
+        assert!(out.contains("   7 │ pub struct Config {"), "{out}");
+        assert!(out.contains("   1 │ pub enum Mode {"), "{out}");
+        assert!(out.contains("  24 │ type Limits"), "{out}");
+        assert!(out.contains("  22 │ }"), "{out}");
+        assert!(!out.contains("Unused"), "{out}");
+        assert!(!out.contains("fn other"), "{out}");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn merges_adjacent_ranges() {
+        let ranges: BTreeSet<(u32, u32)> = [(1, 3), (4, 4), (6, 9), (7, 8)].into();
+        assert_eq!(merge(&ranges), vec![(1, 4), (6, 9)]);
+    }
+}
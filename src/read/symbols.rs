@@ -0,0 +1,192 @@
+//! Every definition in a file as JSON records — the data behind the outline,
+//! without its formatting, for callers that build their own views.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::outline::{buildfile, code, structured};
+use super::{detect_file_type, suggest_similar};
+use crate::error::TilthError;
+use crate::types::{FileType, Lang, OutlineEntry, OutlineKind};
+
+#[derive(Serialize)]
+struct FileSymbols<'a> {
+    path: String,
+    language: Option<String>,
+    symbols: Vec<Record<'a>>,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    name: &'a str,
+    kind: &'static str,
+    start_line: u32,
+    end_line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visibility: Option<String>,
+    /// Rust `#[cfg(...)]` condition gating the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cfg: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    decorators: Vec<&'a str>,
+    /// Name of the enclosing class/impl/module, for members.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<&'a str>,
+}
+
+/// All definitions in `path` as a JSON object:
+/// `{"path", "language", "symbols": [{name, kind, start_line, end_line, ...}]}`.
+/// Files without a grammar or symbol extractor give an empty `symbols` list.
+pub fn file_symbols(path: &Path) -> Result<String, TilthError> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TilthError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: suggest_similar(path),
+        },
+        _ => TilthError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })?;
+
+    let file_type = detect_file_type(path);
+    let lang = match file_type {
+        FileType::Code(lang) => Some(lang),
+        _ => None,
+    };
+
+    let entries = lang.and_then(|l| code::entries(&content, l));
+    let lines: Vec<&str> = content.lines().collect();
+    let symbols = if let (Some(lang), Some(entries)) = (lang, &entries) {
+        let mut out = Vec::new();
+        flatten(entries, None, &lines, lang, &mut out);
+        out
+    } else {
+        let scanned = crate::grammars::symbols(path, &content).or_else(|| match lang {
+            Some(l) => buildfile::symbols(l, &content),
+            None if file_type == FileType::StructuredData => structured::symbols(path, &content),
+            None => None,
+        });
+        scanned
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| Record {
+                name: s.name,
+                kind: s.kind,
+                start_line: s.line,
+                end_line: s.end_line,
+                signature: None,
+                visibility: None,
+                cfg: None,
+                decorators: Vec::new(),
+                parent: None,
+            })
+            .collect()
+    };
+
+    let out = FileSymbols {
+        path: path.display().to_string(),
+        language: lang.map(|l| l.to_string()),
+        symbols,
+    };
+    serde_json::to_string(&out).map_err(|e| TilthError::ParseError {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })
+}
+
+/// Depth-first, parents before their members. Imports are not definitions.
+fn flatten<'a>(
+    entries: &'a [OutlineEntry],
+    parent: Option<&'a str>,
+    lines: &[&str],
+    lang: Lang,
+    out: &mut Vec<Record<'a>>,
+) {
+    for entry in entries {
+        if entry.kind == OutlineKind::Import {
+            continue;
+        }
+        out.push(Record {
+            name: &entry.name,
+            kind: if code::is_impl_block(entry, lang) {
+                "impl"
+            } else {
+                code::kind_label(entry.kind, lang)
+            },
+            start_line: entry.start_line,
+            end_line: entry.end_line,
+            signature: entry.signature.as_deref(),
+            visibility: code::visibility(entry, lines, lang),
+            cfg: entry.cfg.as_deref(),
+            decorators: entry.decorators.iter().map(String::as_str).collect(),
+            parent,
+        });
+        flatten(&entry.children, Some(&entry.name), lines, lang, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(name, kind, visibility, parent)` per record.
+    fn summary(
+        src: &str,
+        lang: Lang,
+    ) -> Vec<(String, &'static str, Option<String>, Option<String>)> {
+        let entries = code::entries(src, lang).unwrap();
+        let lines: Vec<&str> = src.lines().collect();
+        let mut records = Vec::new();
+        flatten(&entries, None, &lines, lang, &mut records);
+        records
+            .into_iter()
+            .map(|r| {
+                (
+                    r.name.to_string(),
+                    r.kind,
+                    r.visibility,
+                    r.parent.map(str::to_string),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rust_visibility() {
+        let src = "use std::fmt;\n\
+                   pub fn open() {}\n\
+                   pub(crate) struct Handle {\n    fd: i32,\n}\n\
+                   fn helper() {}\n";
+        assert_eq!(
+            summary(src, Lang::Rust),
+            vec![
+                ("open".into(), "fn", Some("pub".into()), None),
+                ("Handle".into(), "struct", Some("pub(crate)".into()), None),
+                ("helper".into(), "fn", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn python_members_carry_parent() {
+        let src = "class Cache:\n    def get(self):\n        pass\n\n    def _evict(self):\n        pass\n";
+        assert_eq!(
+            summary(src, Lang::Python),
+            vec![
+                ("Cache".into(), "class", None, None),
+                ("get".into(), "fn", None, Some("Cache".into())),
+                (
+                    "_evict".into(),
+                    "fn",
+                    Some("internal".into()),
+                    Some("Cache".into())
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,416 @@
+//! The views [`read_file`](super::read_file) chooses between once a file is
+//! known to be text: a lockfile or generated-file summary, the whole
+//! content, a column outline of one enormous line, a bundle report, and
+//! the outline or keys view. Each is a [`Viewer`] registered for the file
+//! types it serves; they are tried in order until one answers.
+//!
+//! Embedders add viewers for formats tilth doesn't know with
+//! [`crate::api::register_viewer`] (by extension) and
+//! [`crate::api::register_type_viewer`] (by [`FileType`]). Theirs are tried
+//! first, newest first; those for an extension see binary files too, before
+//! tilth checks the content.
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::SystemTime;
+
+use crate::cache::OutlineCache;
+use crate::error::TilthError;
+use crate::format;
+use crate::tokens;
+use crate::types::{FileType, ViewMode};
+
+use super::{bundle, generated, lockfile, longline, outline, TOKEN_THRESHOLD};
+
+/// Files over this size get a capped outline.
+const FILE_SIZE_CAP: u64 = 500_000; // 500KB
+
+/// A way of showing a file.
+pub trait Viewer: Send + Sync {
+    /// The view of `file`, header included, or `None` to leave the file to
+    /// the next viewer.
+    fn view(&self, file: &FileView) -> Option<Result<String, TilthError>>;
+}
+
+/// A file being read, as a [`Viewer`] sees it. Only viewers registered for
+/// an extension are shown binary files.
+pub struct FileView<'a> {
+    pub(crate) path: &'a Path,
+    /// Content, byte-order mark removed.
+    pub(crate) buf: &'a [u8],
+    pub(crate) file_type: FileType,
+    pub(crate) byte_len: u64,
+    pub(crate) tokens: u64,
+    pub(crate) lines: u32,
+    pub(crate) mtime: SystemTime,
+    pub(crate) full: bool,
+    pub(crate) edit_mode: bool,
+    pub(crate) cache: &'a OutlineCache,
+}
+
+impl FileView<'_> {
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// The content, without a byte-order mark.
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        self.buf
+    }
+
+    /// The content as text, invalid UTF-8 replaced.
+    #[must_use]
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.buf)
+    }
+
+    /// The type tilth detected from the file's name.
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Whether the caller asked for the whole file.
+    #[must_use]
+    pub fn full(&self) -> bool {
+        self.full
+    }
+
+    /// Whether output should carry `line:hash` anchors.
+    #[must_use]
+    pub fn edit_mode(&self) -> bool {
+        self.edit_mode
+    }
+
+    /// Estimated tokens in the content.
+    #[must_use]
+    pub fn tokens(&self) -> u64 {
+        self.tokens
+    }
+
+    #[must_use]
+    pub fn lines(&self) -> u32 {
+        self.lines
+    }
+
+    /// The read header — `# path (N lines, ~T tokens) [mode]` — with `mode`
+    /// naming the view, e.g. `outline`.
+    #[must_use]
+    pub fn header(&self, mode: impl std::fmt::Display) -> String {
+        format::file_header(self.path, self.tokens, self.lines, mode)
+    }
+}
+
+/// Which files a registered viewer serves.
+enum Serves {
+    Types(fn(FileType) -> bool),
+    Type(FileType),
+    /// A file extension, without the dot, compared ignoring case.
+    Extension(String),
+}
+
+impl Serves {
+    fn covers(&self, path: &Path, file_type: FileType) -> bool {
+        match self {
+            Self::Types(test) => test(file_type),
+            Self::Type(served) => *served == file_type,
+            Self::Extension(ext) => path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case(ext)),
+        }
+    }
+}
+
+type Registry = Vec<(Serves, Arc<dyn Viewer>)>;
+
+/// Embedders' viewers, newest first.
+static REGISTERED: RwLock<Registry> = RwLock::new(Vec::new());
+
+/// Show files with extension `ext`, binary or not, through `viewer` before
+/// any built-in view.
+pub(crate) fn register(ext: &str, viewer: Arc<dyn Viewer>) {
+    let ext = ext.trim_start_matches('.').to_string();
+    add(Serves::Extension(ext), viewer);
+}
+
+/// Show text files of `file_type` through `viewer` before any built-in view.
+pub(crate) fn register_type(file_type: FileType, viewer: Arc<dyn Viewer>) {
+    add(Serves::Type(file_type), viewer);
+}
+
+fn add(serves: Serves, viewer: Arc<dyn Viewer>) {
+    REGISTERED
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(0, (serves, viewer));
+}
+
+/// The built-in viewers, in the order they are tried.
+fn builtin() -> &'static Registry {
+    static BUILTIN: std::sync::OnceLock<Registry> = std::sync::OnceLock::new();
+    BUILTIN.get_or_init(|| {
+        let any: fn(FileType) -> bool = |_| true;
+        vec![
+            (Serves::Types(any), Arc::new(Generated) as Arc<dyn Viewer>),
+            (Serves::Types(any), Arc::new(Whole)),
+            (
+                Serves::Types(|t| !matches!(t, FileType::Code(_))),
+                Arc::new(LongLine),
+            ),
+            (Serves::Types(any), Arc::new(Bundle)),
+            (
+                Serves::Types(|t| t == FileType::StructuredData),
+                Arc::new(Outline(ViewMode::Keys)),
+            ),
+            (Serves::Types(any), Arc::new(Outline(ViewMode::Outline))),
+        ]
+    })
+}
+
+/// The viewers registered for `file` by extension (`by_extension`) or by
+/// type, newest first.
+fn registered(file: &FileView, by_extension: bool) -> Vec<Arc<dyn Viewer>> {
+    REGISTERED
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .filter(|(serves, _)| matches!(serves, Serves::Extension(_)) == by_extension)
+        .filter(|(serves, _)| serves.covers(file.path, file.file_type))
+        .map(|(_, viewer)| Arc::clone(viewer))
+        .collect()
+}
+
+/// The first answer from the viewers registered for `file`'s extension —
+/// asked before the content is known to be text.
+pub(crate) fn view_by_extension(file: &FileView) -> Option<Result<String, TilthError>> {
+    registered(file, true)
+        .iter()
+        .find_map(|viewer| viewer.view(file))
+}
+
+/// The first answer from the viewers serving a text `file`: those
+/// registered for its type, then the built-in ones.
+pub(crate) fn view(file: &FileView) -> Result<String, TilthError> {
+    let registered = registered(file, false);
+    let builtin = builtin()
+        .iter()
+        .filter(|(serves, _)| serves.covers(file.path, file.file_type))
+        .map(|(_, viewer)| viewer);
+    for viewer in registered.iter().chain(builtin) {
+        if let Some(shown) = viewer.view(file) {
+            return shown;
+        }
+    }
+    // The outline view answers every file
+    unreachable!("no viewer for {}", file.path.display())
+}
+
+/// Lockfiles and generated code: a header, and for lockfiles a summary.
+struct Generated;
+
+impl Viewer for Generated {
+    fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+        let name = file.path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if !generated::is_generated_by_name(name) && !generated::is_generated_by_content(file.buf) {
+            return None;
+        }
+        let header = format::file_header(
+            file.path,
+            tokens::estimate_len(file.byte_len, FileType::Other),
+            file.lines,
+            ViewMode::Generated,
+        );
+        // Lockfiles are summed up rather than left as a bare header
+        Some(Ok(
+            match lockfile::parse(file.path, &String::from_utf8_lossy(file.buf)) {
+                Some(lock) => format!("{header}\n\n{}", lockfile::summary(&lock)),
+                None => header,
+            },
+        ))
+    }
+}
+
+/// The whole file, when asked for or small enough.
+struct Whole;
+
+impl Viewer for Whole {
+    fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+        if !file.full && file.tokens > TOKEN_THRESHOLD {
+            return None;
+        }
+        let header = file.header(ViewMode::Full);
+        let content = file.text();
+        // A workspace member's manifest, with what it inherits filled in
+        let is_manifest = file.path.file_name().is_some_and(|n| n == "Cargo.toml");
+        let manifest = (!file.full && is_manifest)
+            .then(|| outline::cargo::resolved(file.path, &content))
+            .flatten()
+            .map(|view| format!("\n\n── resolved with the workspace ──\n{view}"))
+            .unwrap_or_default();
+        if file.edit_mode {
+            let numbered = format::hashlines(&content, 1);
+            return Some(Ok(format!("{header}\n\n{numbered}{manifest}")));
+        }
+        let content = format::normalize_newlines(&content);
+        Some(Ok(format!("{header}\n\n{content}{manifest}")))
+    }
+}
+
+/// One enormous line of data or styles, outlined by column.
+struct LongLine;
+
+impl Viewer for LongLine {
+    fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+        if bundle::is_named_min(file.path) {
+            return None;
+        }
+        let columns = longline::outline(file.buf)?;
+        let header = file.header(ViewMode::Outline);
+        Some(Ok(format!("{header}\n\n{columns}")))
+    }
+}
+
+/// A minified bundle: its outline would be one giant line, so point at the
+/// sources instead.
+struct Bundle;
+
+impl Viewer for Bundle {
+    fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+        let report = bundle::describe(file.path, file.buf)?;
+        let header = file.header(ViewMode::Generated);
+        Some(Ok(format!("{header}\n\n{report}")))
+    }
+}
+
+/// The smart view by file type, cached by mtime; headed as `mode`.
+struct Outline(ViewMode);
+
+impl Viewer for Outline {
+    fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+        let capped = file.byte_len > FILE_SIZE_CAP;
+        // Outlines work from the mapped bytes — valid UTF-8 is never copied
+        // into a `String`, and a cache hit doesn't decode anything.
+        let shown = file.cache.get_or_compute(file.path, file.mtime, || {
+            outline::generate(file.path, file.file_type, file.buf, capped)
+        });
+        let header = file.header(self.0);
+        Some(Ok(format!("{header}\n\n{shown}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shout;
+
+    impl Viewer for Shout {
+        fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+            (!file.full()).then(|| {
+                Ok(format!(
+                    "{}\n\n{}",
+                    file.header("shout"),
+                    file.text().to_uppercase()
+                ))
+            })
+        }
+    }
+
+    fn cache() -> OutlineCache {
+        OutlineCache::new()
+    }
+
+    fn file<'a>(
+        name: &'a str,
+        content: &'a str,
+        full: bool,
+        cache: &'a OutlineCache,
+    ) -> FileView<'a> {
+        let file_type = crate::read::detect_file_type(Path::new(name));
+        FileView {
+            path: Path::new(name),
+            buf: content.as_bytes(),
+            file_type,
+            byte_len: content.len() as u64,
+            tokens: tokens::estimate(content.as_bytes(), file_type),
+            lines: content.lines().count() as u32,
+            mtime: SystemTime::UNIX_EPOCH,
+            full,
+            edit_mode: false,
+            cache,
+        }
+    }
+
+    /// What a text file `name` holding `content` reads as.
+    fn read(name: &str, content: &str, full: bool) -> String {
+        let cache = cache();
+        let file = file(name, content, full, &cache);
+        view_by_extension(&file)
+            .unwrap_or_else(|| view(&file))
+            .unwrap()
+    }
+
+    #[test]
+    fn registered_viewers_come_first_and_may_pass() {
+        register(".tilthtestfmt", Arc::new(Shout));
+        let cache = cache();
+        let shown = view_by_extension(&file("data.tilthtestfmt", "hello", false, &cache));
+        assert!(shown.unwrap().unwrap().ends_with("[shout]\n\nHELLO"));
+        // Declined: the built-in views take over
+        let declined = file("data.tilthtestfmt", "hello", true, &cache);
+        assert!(view_by_extension(&declined).is_none());
+        assert!(read("data.tilthtestfmt", "hello", true).ends_with("[full]\n\nhello"));
+        assert!(read("other.txt", "hello", false).ends_with("[full]\n\nhello"));
+    }
+
+    #[test]
+    fn type_viewers_serve_text_of_their_type() {
+        /// Answers for this test's files only: registration is process-wide.
+        struct Rows;
+
+        impl Viewer for Rows {
+            fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+                let name = file.path().file_name()?.to_str()?;
+                name.starts_with("tilthtest_")
+                    .then(|| Ok(format!("{:?} rows", file.file_type())))
+            }
+        }
+
+        register_type(FileType::Tabular, Arc::new(Rows));
+        assert_eq!(read("tilthtest_rows.tsv", "a\tb", false), "Tabular rows");
+        assert!(read("tilthtest_notes.txt", "a b", false).ends_with("[full]\n\na b"));
+    }
+
+    #[test]
+    fn extension_viewers_see_binary_files() {
+        struct Size;
+
+        impl Viewer for Size {
+            fn view(&self, file: &FileView) -> Option<Result<String, TilthError>> {
+                Some(Ok(format!("{} bytes", file.bytes().len())))
+            }
+        }
+
+        register("tilthtestbin", Arc::new(Size));
+        let dir = std::env::temp_dir().join("tilth_test_binary_viewer");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blob.tilthtestbin");
+        std::fs::write(&path, b"\0\x01\x02binary\0").unwrap();
+        let shown = crate::read::read_file(&path, None, false, &cache(), false).unwrap();
+        assert_eq!(shown, "10 bytes");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn builtin_views_by_file_type() {
+        let big = "fn f() {}\n".repeat(2_000);
+        assert!(read("big.rs", &big, false).contains("[outline]"));
+        let keys = "key: value\n".repeat(2_000);
+        assert!(read("big.yaml", &keys, false).contains("[keys]"));
+        assert!(read("package-lock.json", "{}", false).contains("[generated — skipped]"));
+    }
+}
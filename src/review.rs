@@ -0,0 +1,310 @@
+//! Everything needed to review the current branch in one response: the
+//! changed files and symbols against a base ref, the source of each changed
+//! definition, who calls the ones whose behavior changed, and which tests
+//! are likely affected.
+//!
+//! The branch is compared from its merge-base with the base, so commits on
+//! the base since the branch forked don't show up, and uncommitted work in
+//! the working tree does.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::changes::{self, git, FileChange};
+use crate::error::TilthError;
+use crate::index::bloom::BloomFilterCache;
+use crate::index::SymbolIndex;
+use crate::map::is_test_path;
+
+/// Changed definitions shown with their source.
+const MAX_EXPANDED: usize = 8;
+/// Source lines shown per changed definition.
+const MAX_EXPANDED_LINES: usize = 40;
+/// Modified or removed definitions whose callers are looked up.
+const MAX_CALLER_SYMBOLS: usize = 8;
+/// Call sites listed per definition.
+const MAX_CALLERS: usize = 5;
+/// File stems too common to tie a test file to a source file.
+const GENERIC_STEMS: &[&str] = &["mod", "lib", "main", "index", "init", "__init__", "utils"];
+
+/// The review bundle for the branch checked out under `scope` against
+/// `base` — by default `origin/HEAD`, else `main` or `master`.
+pub fn review(
+    scope: &Path,
+    base: Option<&str>,
+    bloom: &BloomFilterCache,
+    index: &SymbolIndex,
+) -> Result<String, TilthError> {
+    let base = match base {
+        Some(base) => base.to_string(),
+        None => default_base(scope)?,
+    };
+    let fork = git(scope, &["merge-base", &base, "HEAD"])?
+        .trim()
+        .to_string();
+    let branch = git(scope, &["rev-parse", "--abbrev-ref", "HEAD"])?
+        .trim()
+        .to_string();
+    let files = changes::collect(scope, &fork)?;
+
+    let short = fork.get(..7).unwrap_or(&fork);
+    let mut out = format!("# Review: {branch} vs {base} (merge-base {short})");
+    if files.is_empty() {
+        out.push_str(" — no changes");
+        return Ok(out);
+    }
+    let _ = write!(
+        out,
+        " — {}\n\n### Files ({})",
+        changes::totals(&files),
+        files.len()
+    );
+    changes::render(&files, &mut out);
+
+    expanded_symbols(&files, &mut out);
+    let callers = callers(&files, scope, bloom, index, &mut out);
+    affected_tests(&files, &callers, scope, &mut out);
+    Ok(out)
+}
+
+/// `origin/HEAD` if the remote has one, else whichever of `main` and
+/// `master` exists.
+fn default_base(scope: &Path) -> Result<String, TilthError> {
+    if let Ok(remote) = git(scope, &["rev-parse", "--abbrev-ref", "origin/HEAD"]) {
+        return Ok(remote.trim().to_string());
+    }
+    ["main", "master"]
+        .into_iter()
+        .find(|b| git(scope, &["rev-parse", "--verify", "--quiet", b]).is_ok())
+        .map(str::to_string)
+        .ok_or_else(|| TilthError::InvalidQuery {
+            query: scope.display().to_string(),
+            reason: "no origin/HEAD, main or master to compare with — pass `base`".into(),
+        })
+}
+
+/// The current source of each added or modified definition.
+fn expanded_symbols(files: &[FileChange], out: &mut String) {
+    let changed: Vec<(&FileChange, &changes::SymbolChange)> = files
+        .iter()
+        .flat_map(|f| f.symbols.iter().map(move |s| (f, s)))
+        .filter(|(_, s)| s.mark != '-' && !s.name.is_empty())
+        .collect();
+    if changed.is_empty() {
+        return;
+    }
+    let _ = write!(out, "\n\n### Changed symbols ({})", changed.len());
+    for (file, symbol) in changed.iter().take(MAX_EXPANDED) {
+        let (start, end) = symbol.lines;
+        let state = if symbol.mark == '+' {
+            "added"
+        } else {
+            "modified"
+        };
+        let _ = write!(
+            out,
+            "\n\n## {}:{start}-{end} {} [{state}]",
+            file.path, symbol.label
+        );
+        let body = file
+            .content
+            .lines()
+            .enumerate()
+            .skip(start.saturating_sub(1) as usize)
+            .take((end + 1).saturating_sub(start) as usize);
+        for (i, line) in body.clone().take(MAX_EXPANDED_LINES) {
+            let _ = write!(out, "\n{:>4} │ {line}", i + 1);
+        }
+        let hidden = body.count().saturating_sub(MAX_EXPANDED_LINES);
+        if hidden > 0 {
+            let _ = write!(out, "\n  ... {hidden} more lines");
+        }
+    }
+    if changed.len() > MAX_EXPANDED {
+        let _ = write!(
+            out,
+            "\n\n... {} more changed symbols listed under Files",
+            changed.len() - MAX_EXPANDED
+        );
+    }
+}
+
+/// A call site of a changed definition: (caller path, line, calling
+/// function, changed name).
+type Caller = (String, u32, String, String);
+
+/// Call sites of modified and removed definitions, outside the definition
+/// itself.
+fn callers(
+    files: &[FileChange],
+    scope: &Path,
+    bloom: &BloomFilterCache,
+    index: &SymbolIndex,
+    out: &mut String,
+) -> Vec<Caller> {
+    let targets: Vec<(&FileChange, &changes::SymbolChange)> = files
+        .iter()
+        .flat_map(|f| f.symbols.iter().map(move |s| (f, s)))
+        .filter(|(_, s)| s.mark != '+' && !s.name.is_empty())
+        .take(MAX_CALLER_SYMBOLS)
+        .collect();
+
+    let visible = crate::redact::visible();
+    let mut all = Vec::new();
+    let mut section = String::new();
+    for (file, symbol) in targets {
+        let Ok(found) = crate::search::callers::find_callers(&symbol.name, scope, bloom, index)
+        else {
+            continue;
+        };
+        let mut sites: Vec<Caller> = found
+            .into_iter()
+            .filter(|c| visible(&c.path))
+            .map(|c| {
+                (
+                    crate::pathnorm::relative(&c.path, scope),
+                    c.line,
+                    c.calling_function,
+                    c.call_text,
+                )
+            })
+            .filter(|(path, line, _, _)| {
+                !(path == &file.path && (symbol.lines.0..=symbol.lines.1).contains(line))
+            })
+            .collect();
+        if sites.is_empty() {
+            continue;
+        }
+        sites.sort();
+        let gone = if symbol.mark == '-' { ", removed" } else { "" };
+        let _ = write!(
+            section,
+            "\n\n## {} — {} call sites{gone}",
+            symbol.label,
+            sites.len()
+        );
+        for (path, line, caller, text) in sites.iter().take(MAX_CALLERS) {
+            let _ = write!(section, "\n  {path}:{line} in {caller} — {}", text.trim());
+        }
+        if sites.len() > MAX_CALLERS {
+            let _ = write!(section, "\n  ... {} more", sites.len() - MAX_CALLERS);
+        }
+        all.extend(
+            sites
+                .into_iter()
+                .map(|(path, line, caller, _)| (path, line, caller, symbol.name.clone())),
+        );
+    }
+    if !section.is_empty() {
+        let _ = write!(out, "\n\n### Callers ({}){section}", all.len());
+    }
+    all
+}
+
+/// Tests that exercise the change: changed test files, Rust files with
+/// in-file tests whose code changed, tests calling a changed definition, and
+/// test files named after a changed source file.
+fn affected_tests(files: &[FileChange], callers: &[Caller], scope: &Path, out: &mut String) {
+    let mut tests: BTreeMap<String, String> = BTreeMap::new();
+    for file in files {
+        if is_test_path(Path::new(&file.path)) {
+            tests.insert(file.path.clone(), "changed".into());
+        } else if !file.symbols.is_empty() && file.content.contains("#[cfg(test)]") {
+            tests.insert(file.path.clone(), "in-file tests".into());
+        }
+    }
+    for (path, _, caller, name) in callers {
+        if is_test_path(Path::new(path)) || caller.starts_with("test") {
+            tests
+                .entry(path.clone())
+                .or_insert_with(|| format!("calls {name}"));
+        }
+    }
+
+    let stems: Vec<(&str, &str)> = files
+        .iter()
+        .filter(|f| !is_test_path(Path::new(&f.path)))
+        .filter_map(|f| {
+            let stem = Path::new(&f.path).file_stem()?.to_str()?;
+            (stem.len() >= 3 && !GENERIC_STEMS.contains(&stem)).then_some((stem, f.path.as_str()))
+        })
+        .collect();
+    if !stems.is_empty() {
+        let test_files = crate::analyze::collect_files(scope, |p| {
+            is_test_path(p.strip_prefix(scope).unwrap_or(p))
+        });
+        for path in test_files {
+            let rel = crate::pathnorm::relative(&path, scope);
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if let Some((_, source)) = stems.iter().find(|(stem, _)| name.contains(stem)) {
+                tests
+                    .entry(rel)
+                    .or_insert_with(|| format!("named after {source}"));
+            }
+        }
+    }
+
+    if tests.is_empty() {
+        return;
+    }
+    let _ = write!(out, "\n\n### Affected tests ({})\n", tests.len());
+    for (path, why) in &tests {
+        let _ = write!(out, "\n  {path} — {why}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn bundle_covers_symbols_callers_and_tests() {
+        let dir = std::env::temp_dir().join("tilth_test_review");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "-q", "-b", "main"]);
+        run(&["config", "user.email", "t@example.com"]);
+        run(&["config", "user.name", "t"]);
+        fs::write(dir.join("parser.rs"), "fn parse() -> u32 {\n    1\n}\n").unwrap();
+        fs::write(
+            dir.join("tests/parser_test.rs"),
+            "fn test_parse() {\n    parse();\n}\n",
+        )
+        .unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "init"]);
+        run(&["checkout", "-q", "-b", "feature"]);
+        fs::write(dir.join("parser.rs"), "fn parse() -> u32 {\n    2\n}\n").unwrap();
+
+        let out = review(&dir, None, &BloomFilterCache::new(), &SymbolIndex::new()).unwrap();
+        assert!(out.starts_with("# Review: feature vs main"), "{out}");
+        assert!(
+            out.contains("## parser.rs [modified] +1 -1\n  ~ fn parse  L1"),
+            "{out}"
+        );
+        assert!(
+            out.contains("## parser.rs:1-3 fn parse [modified]\n   1 │ fn parse() -> u32 {"),
+            "{out}"
+        );
+        assert!(
+            out.contains("tests/parser_test.rs:2 in test_parse — parse();"),
+            "{out}"
+        );
+        assert!(out.contains("tests/parser_test.rs — calls parse"), "{out}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,127 @@
+//! One file, several paths — hard links, and packages symlinked into place
+//! (pnpm workspaces). Each physical file's matches are kept under a single
+//! path; the others are reported as aliases of it instead of as more hits.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::types::Match;
+
+/// Drop matches in files already matched under another path, keeping the
+/// path that sorts first. Returns each kept path with the other paths its
+/// file was found under, sorted.
+pub(crate) fn dedupe(matches: &mut Vec<Match>) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    let mut paths: Vec<&Path> = matches.iter().map(|m| m.path.as_path()).collect();
+    paths.sort();
+    paths.dedup();
+    if paths.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut by_file: HashMap<FileId, Vec<PathBuf>> = HashMap::new();
+    for path in paths {
+        if let Some(id) = file_id(path) {
+            by_file.entry(id).or_default().push(path.to_path_buf());
+        }
+    }
+    let mut aliases: Vec<(PathBuf, Vec<PathBuf>)> = by_file
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(|mut paths| {
+            let kept = paths.remove(0);
+            (kept, paths)
+        })
+        .collect();
+    if aliases.is_empty() {
+        return aliases;
+    }
+    aliases.sort();
+
+    let dropped: HashSet<PathBuf> = aliases
+        .iter()
+        .flat_map(|(_, others)| others.iter().cloned())
+        .collect();
+    matches.retain(|m| !dropped.contains(&m.path));
+    aliases
+}
+
+/// Device and inode — shared by hard links, and by a symlink and its target
+/// since the metadata follows the link.
+#[cfg(unix)]
+type FileId = (u64, u64);
+
+#[cfg(unix)]
+fn file_id(path: &Path) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+/// Without inodes, the canonical path catches symlinks (not hard links).
+#[cfg(not(unix))]
+type FileId = PathBuf;
+
+#[cfg(not(unix))]
+fn file_id(path: &Path) -> Option<FileId> {
+    path.canonicalize().ok()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::SystemTime;
+
+    fn hit(path: &Path, line: u32) -> Match {
+        Match {
+            path: path.to_path_buf(),
+            line,
+            column: 0,
+            text: String::new(),
+            is_definition: false,
+            exact: true,
+            file_lines: 10,
+            mtime: SystemTime::UNIX_EPOCH,
+            def_range: None,
+            def_name: None,
+            def_weight: 0,
+            impl_target: None,
+            trait_method: None,
+        }
+    }
+
+    #[test]
+    fn links_collapse_to_one_path() {
+        let dir = std::env::temp_dir().join("tilth_test_aliases");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("a.ts");
+        let hard = dir.join("b.ts");
+        let soft = dir.join("c.ts");
+        let other = dir.join("d.ts");
+        fs::write(&original, "x").unwrap();
+        fs::write(&other, "x").unwrap();
+        fs::hard_link(&original, &hard).unwrap();
+        std::os::unix::fs::symlink(&original, &soft).unwrap();
+
+        let mut matches = vec![
+            hit(&soft, 1),
+            hit(&original, 1),
+            hit(&other, 1),
+            hit(&hard, 1),
+            hit(&original, 5),
+        ];
+        let aliases = dedupe(&mut matches);
+        assert_eq!(aliases, vec![(original.clone(), vec![hard, soft])]);
+        let kept: Vec<(&Path, u32)> = matches.iter().map(|m| (m.path.as_path(), m.line)).collect();
+        assert_eq!(
+            kept,
+            vec![
+                (original.as_path(), 1),
+                (other.as_path(), 1),
+                (original.as_path(), 5)
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use streaming_iterator::StreamingIterator;
 
-use crate::cache::OutlineCache;
+use crate::cache::{FileContents, OutlineCache};
 use crate::read::outline::code::outline_language;
 use crate::types::{Lang, OutlineEntry};
 
@@ -94,12 +94,7 @@ pub fn extract_callee_names(
         return Vec::new();
     };
 
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-
-    let Some(tree) = parser.parse(content, None) else {
+    let Some(tree) = crate::parser_pool::parse(content, &ts_lang) else {
         return Vec::new();
     };
 
@@ -143,12 +138,7 @@ pub fn get_outline_entries(content: &str, lang: Lang) -> Vec<OutlineEntry> {
         return Vec::new();
     };
 
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-
-    let Some(tree) = parser.parse(content, None) else {
+    let Some(tree) = crate::parser_pool::parse(content, &ts_lang) else {
         return Vec::new();
     };
 
@@ -206,6 +196,7 @@ pub fn resolve_callees(
     source_content: &str,
     _cache: &OutlineCache,
     bloom: &crate::index::bloom::BloomFilterCache,
+    files: &FileContents,
 ) -> Vec<ResolvedCallee> {
     if callee_names.is_empty() {
         return Vec::new();
@@ -238,7 +229,7 @@ pub fn resolve_callees(
         }
 
         // Read file content once for both bloom check and parsing
-        let Ok(import_content) = std::fs::read_to_string(&import_path) else {
+        let Some(import_content) = files.get(&import_path) else {
             continue;
         };
 
@@ -276,7 +267,7 @@ pub fn resolve_callees(
 
     // 3. For Go: scan same-directory files (same package, no explicit imports)
     if lang == Lang::Go {
-        resolve_same_package(&mut remaining, &mut resolved, source_path);
+        resolve_same_package(&mut remaining, &mut resolved, source_path, files);
     }
 
     resolved
@@ -291,6 +282,7 @@ fn resolve_same_package(
     remaining: &mut std::collections::HashSet<&str>,
     resolved: &mut Vec<ResolvedCallee>,
     source_path: &Path,
+    files: &FileContents,
 ) {
     const MAX_FILES: usize = 20;
     const MAX_FILE_SIZE: u64 = 100_000; // 100KB
@@ -326,7 +318,7 @@ fn resolve_same_package(
             break;
         }
 
-        let Ok(content) = std::fs::read_to_string(&go_path) else {
+        let Some(content) = files.get(&go_path) else {
             continue;
         };
 
@@ -349,11 +341,19 @@ pub fn resolve_callees_transitive(
     source_content: &str,
     cache: &OutlineCache,
     bloom: &crate::index::bloom::BloomFilterCache,
+    files: &FileContents,
     depth_limit: u32,
     budget: usize,
 ) -> Vec<ResolvedCalleeNode> {
     // 1st hop: resolve direct callees (existing logic)
-    let first_hop = resolve_callees(initial_names, source_path, source_content, cache, bloom);
+    let first_hop = resolve_callees(
+        initial_names,
+        source_path,
+        source_content,
+        cache,
+        bloom,
+        files,
+    );
 
     if depth_limit < 2 || first_hop.is_empty() {
         return first_hop
@@ -378,7 +378,14 @@ pub fn resolve_callees_transitive(
 
     for parent in first_hop {
         let children = if budget_remaining > 0 {
-            resolve_second_hop(&parent, cache, bloom, &mut visited, &mut budget_remaining)
+            resolve_second_hop(
+                &parent,
+                cache,
+                bloom,
+                files,
+                &mut visited,
+                &mut budget_remaining,
+            )
         } else {
             Vec::new()
         };
@@ -396,6 +403,7 @@ fn resolve_second_hop(
     parent: &ResolvedCallee,
     cache: &OutlineCache,
     bloom: &crate::index::bloom::BloomFilterCache,
+    files: &FileContents,
     visited: &mut HashSet<(PathBuf, u32)>,
     budget: &mut usize,
 ) -> Vec<ResolvedCallee> {
@@ -404,7 +412,7 @@ fn resolve_second_hop(
         return Vec::new();
     };
 
-    let Ok(content) = std::fs::read_to_string(&parent.file) else {
+    let Some(content) = files.get(&parent.file) else {
         return Vec::new();
     };
 
@@ -415,7 +423,7 @@ fn resolve_second_hop(
         return Vec::new();
     }
 
-    let mut resolved = resolve_callees(&nested_names, &parent.file, &content, cache, bloom);
+    let mut resolved = resolve_callees(&nested_names, &parent.file, &content, cache, bloom, files);
 
     // Filter: skip self-recursive calls and already-visited callees
     resolved.retain(|c| {
@@ -4,21 +4,19 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 
+use rayon::prelude::*;
 use streaming_iterator::StreamingIterator;
 
 use super::treesitter::{extract_definition_name, DEFINITION_KINDS};
 
 use crate::cache::OutlineCache;
 use crate::error::TilthError;
+use crate::index::SymbolIndex;
 use crate::read::detect_file_type;
 use crate::read::outline::code::outline_language;
 use crate::session::Session;
 use crate::types::FileType;
 
-const MAX_MATCHES: usize = 10;
-/// Stop walking once we have this many raw matches. Generous headroom for dedup + ranking.
-const EARLY_QUIT_THRESHOLD: usize = 30;
-
 /// A single caller match — a call site of a target symbol.
 #[derive(Debug)]
 pub struct CallerMatch {
@@ -33,14 +31,29 @@ pub struct CallerMatch {
 }
 
 /// Find all call sites of a target symbol across the codebase using tree-sitter.
+///
+/// When `index` records usages, only the files it lists for `target` are
+/// parsed; otherwise every file under `scope` is walked.
 pub fn find_callers(
     target: &str,
     scope: &Path,
     bloom: &crate::index::bloom::BloomFilterCache,
+    index: &SymbolIndex,
 ) -> Result<Vec<CallerMatch>, TilthError> {
+    if let Some(usages) = index.lookup_usages(target, scope) {
+        let mut paths: Vec<PathBuf> = usages.into_iter().map(|loc| loc.path).collect();
+        paths.sort();
+        paths.dedup();
+        return Ok(paths
+            .par_iter()
+            .flat_map_iter(|path| callers_in_file(path, target, bloom))
+            .collect());
+    }
+
     let matches: Mutex<Vec<CallerMatch>> = Mutex::new(Vec::new());
     let found_count = AtomicUsize::new(0);
-    let needle = target.as_bytes();
+    // Generous headroom for dedup + ranking
+    let quit_at = super::limits::current().early_quit();
 
     let walker = super::walker(scope);
 
@@ -50,7 +63,7 @@ pub fn find_callers(
 
         Box::new(move |entry| {
             // Early termination: enough callers found
-            if found_count.load(Ordering::Relaxed) >= EARLY_QUIT_THRESHOLD {
+            if found_count.load(Ordering::Relaxed) >= quit_at {
                 return ignore::WalkState::Quit;
             }
 
@@ -62,44 +75,7 @@ pub fn find_callers(
                 return ignore::WalkState::Continue;
             }
 
-            let path = entry.path();
-
-            // Skip oversized files
-            if let Ok(meta) = std::fs::metadata(path) {
-                if meta.len() > 500_000 {
-                    return ignore::WalkState::Continue;
-                }
-            }
-
-            // Single read: read file once, use buffer for both check and parse
-            let Ok(content) = fs::read_to_string(path) else {
-                return ignore::WalkState::Continue;
-            };
-
-            // Bloom pre-filter: skip if target is definitely not in file
-            let mtime = std::fs::metadata(path)
-                .and_then(|m| m.modified())
-                .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-            if !bloom.contains(path, mtime, &content, target) {
-                return ignore::WalkState::Continue;
-            }
-
-            // Fast byte check via memchr::memmem (SIMD) — skip files without the symbol
-            if memchr::memmem::find(content.as_bytes(), needle).is_none() {
-                return ignore::WalkState::Continue;
-            }
-
-            // Only process files with tree-sitter grammars
-            let file_type = detect_file_type(path);
-            let FileType::Code(lang) = file_type else {
-                return ignore::WalkState::Continue;
-            };
-
-            let Some(ts_lang) = outline_language(lang) else {
-                return ignore::WalkState::Continue;
-            };
-
-            let file_callers = find_callers_treesitter(path, target, &ts_lang, &content, lang);
+            let file_callers = callers_in_file(entry.path(), target, bloom);
 
             if !file_callers.is_empty() {
                 found_count.fetch_add(file_callers.len(), Ordering::Relaxed);
@@ -118,6 +94,50 @@ pub fn find_callers(
         .unwrap_or_else(std::sync::PoisonError::into_inner))
 }
 
+/// Call sites of `target` in one file. Cheap checks first — size, bloom
+/// filter, a byte search — so most files are rejected without parsing.
+fn callers_in_file(
+    path: &Path,
+    target: &str,
+    bloom: &crate::index::bloom::BloomFilterCache,
+) -> Vec<CallerMatch> {
+    // Skip oversized files
+    if let Ok(meta) = std::fs::metadata(path) {
+        if meta.len() > 500_000 {
+            return Vec::new();
+        }
+    }
+
+    // Single read: read file once, use buffer for both check and parse
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    // Bloom pre-filter: skip if target is definitely not in file
+    let mtime = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+    if !bloom.contains(path, mtime, &content, target) {
+        return Vec::new();
+    }
+
+    // Fast byte check via memchr::memmem (SIMD) — skip files without the symbol
+    if memchr::memmem::find(content.as_bytes(), target.as_bytes()).is_none() {
+        return Vec::new();
+    }
+
+    // Only process files with tree-sitter grammars
+    let FileType::Code(lang) = detect_file_type(path) else {
+        return Vec::new();
+    };
+
+    let Some(ts_lang) = outline_language(lang) else {
+        return Vec::new();
+    };
+
+    find_callers_treesitter(path, target, &ts_lang, &content, lang)
+}
+
 /// Tree-sitter call site detection.
 fn find_callers_treesitter(
     path: &Path,
@@ -140,12 +160,7 @@ fn find_callers_treesitter(
         return Vec::new();
     };
 
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(ts_lang).is_err() {
-        return Vec::new();
-    }
-
-    let Some(tree) = parser.parse(content, None) else {
+    let Some(tree) = crate::parser_pool::parse(content, ts_lang) else {
         return Vec::new();
     };
 
@@ -239,13 +254,17 @@ fn find_enclosing_function(
 pub fn search_callers_expanded(
     target: &str,
     scope: &Path,
-    _cache: &OutlineCache,
-    _session: &Session,
+    cache: &OutlineCache,
+    session: &Session,
     bloom: &crate::index::bloom::BloomFilterCache,
+    index: &SymbolIndex,
     expand: usize,
     context: Option<&Path>,
 ) -> Result<String, TilthError> {
-    let callers = find_callers(target, scope, bloom)?;
+    if index.records_usages() && !index.is_built(scope) {
+        crate::index::warm(scope, index, bloom, cache);
+    }
+    let callers = find_callers(target, scope, bloom, index)?;
 
     if callers.is_empty() {
         return Ok(format!(
@@ -260,7 +279,9 @@ pub fn search_callers_expanded(
     rank_callers(&mut sorted_callers, scope, context);
 
     let total = sorted_callers.len();
-    sorted_callers.truncate(MAX_MATCHES);
+    // Call sites are usages
+    let limits = super::limits::current();
+    sorted_callers.truncate(limits.total.min(limits.usages));
 
     // Format the output
     let mut output = format!(
@@ -276,11 +297,7 @@ pub fn search_callers_expanded(
         let _ = write!(
             output,
             "\n## {}:{} [caller: {}]\n",
-            caller
-                .path
-                .strip_prefix(scope)
-                .unwrap_or(&caller.path)
-                .display(),
+            crate::pathnorm::relative(&caller.path, scope),
             caller.line,
             caller.calling_function
         );
@@ -290,39 +307,152 @@ pub fn search_callers_expanded(
 
         // Expand if requested and we have the range
         if i < expand {
-            if let Some((start, end)) = caller.caller_range {
+            if let Some(range) = caller.caller_range {
                 // Use cached content — no re-read needed
-                let lines: Vec<&str> = caller.content.lines().collect();
-                let start_idx = (start as usize).saturating_sub(1);
-                let end_idx = (end as usize).min(lines.len());
-
                 output.push('\n');
-                output.push_str("```\n");
-
-                for (idx, line) in lines[start_idx..end_idx].iter().enumerate() {
-                    let line_num = start_idx + idx + 1;
-                    let prefix = if line_num == caller.line as usize {
-                        "► "
-                    } else {
-                        "  "
-                    };
-                    let _ = writeln!(output, "{prefix}{line_num:4} │ {line}");
-                }
-
-                output.push_str("```\n");
+                push_body(
+                    &mut output,
+                    &caller.content,
+                    range,
+                    &[caller.line],
+                    session.edit_mode(),
+                );
             }
         }
     }
 
     // Show token estimate
-    let token_est = crate::types::estimate_tokens(output.len() as u64);
+    let token_est = crate::tokens::estimate(output.as_bytes(), crate::types::FileType::Other);
     let _ = writeln!(output, "\n[~{token_est} tokens]");
 
     Ok(output)
 }
 
+/// Call sites of `target` grouped by file, and within a file by the
+/// function making them, for `tilth_callers`. The first `expand` calling
+/// functions, in rank order, show their source.
+pub fn callers_by_file(
+    target: &str,
+    scope: &Path,
+    cache: &OutlineCache,
+    session: &Session,
+    bloom: &crate::index::bloom::BloomFilterCache,
+    index: &SymbolIndex,
+    expand: usize,
+    context: Option<&Path>,
+) -> Result<String, TilthError> {
+    if index.records_usages() && !index.is_built(scope) {
+        crate::index::warm(scope, index, bloom, cache);
+    }
+    let mut callers = find_callers(target, scope, bloom, index)?;
+    rank_callers(&mut callers, scope, context);
+    let total = callers.len();
+    let limits = super::limits::current();
+    callers.truncate(limits.total.min(limits.usages));
+
+    let mut output = grouped(target, scope, &callers, total, expand, session.edit_mode());
+    let token_est = crate::tokens::estimate(output.as_bytes(), crate::types::FileType::Other);
+    let _ = writeln!(output, "\n[~{token_est} tokens]");
+    Ok(output)
+}
+
+/// Ranked `callers` as file sections, each listing its calling functions
+/// with their call sites.
+fn grouped(
+    target: &str,
+    scope: &Path,
+    callers: &[CallerMatch],
+    total: usize,
+    expand: usize,
+    edit_mode: bool,
+) -> String {
+    // Files in rank order, each with its callers in line order
+    let mut files: Vec<(&Path, Vec<&CallerMatch>)> = Vec::new();
+    for caller in callers {
+        match files.iter_mut().find(|(path, _)| *path == caller.path) {
+            Some((_, sites)) => sites.push(caller),
+            None => files.push((&caller.path, vec![caller])),
+        }
+    }
+    let mut output = format!(
+        "# Callers of \"{target}\" in {} — {total} call site{} in {} file{}",
+        scope.display(),
+        if total == 1 { "" } else { "s" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+    );
+    if total > callers.len() {
+        let _ = write!(output, " (first {} shown)", callers.len());
+    }
+    output.push('\n');
+    if files.is_empty() {
+        output.push_str("\nNo call sites found.\n");
+    }
+
+    let mut expanded = 0;
+    for (path, mut sites) in files {
+        sites.sort_by_key(|c| (c.caller_range.map_or(0, |r| r.0), c.line));
+        let _ = write!(
+            output,
+            "\n## {} — {} call site{}\n",
+            crate::pathnorm::relative(path, scope),
+            sites.len(),
+            if sites.len() == 1 { "" } else { "s" }
+        );
+        for group in sites.chunk_by(|a, b| {
+            a.calling_function == b.calling_function && a.caller_range == b.caller_range
+        }) {
+            let first = group[0];
+            let range = first
+                .caller_range
+                .map_or_else(String::new, |(start, end)| format!("[{start}-{end}]"));
+            let _ = writeln!(output, "{range:<12} {}", first.calling_function);
+            for site in group {
+                let _ = writeln!(output, "  {:>4} → {}", site.line, site.call_text);
+            }
+            if let Some(range) = first.caller_range.filter(|_| expanded < expand) {
+                expanded += 1;
+                let marked: Vec<u32> = group.iter().map(|c| c.line).collect();
+                push_body(&mut output, &first.content, range, &marked, edit_mode);
+            }
+        }
+    }
+    output
+}
+
+/// Lines `start..=end` of `content` in a code block, the `marked` ones
+/// flagged with `►`; hashlined in edit mode.
+fn push_body(
+    output: &mut String,
+    content: &str,
+    (start, end): (u32, u32),
+    marked: &[u32],
+    edit_mode: bool,
+) {
+    let lines: Vec<&str> = content.lines().collect();
+    let start_idx = (start as usize).saturating_sub(1);
+    let end_idx = (end as usize).min(lines.len());
+
+    output.push_str("```\n");
+    for (idx, line) in lines[start_idx.min(end_idx)..end_idx].iter().enumerate() {
+        let line_num = start_idx + idx + 1;
+        let prefix = if marked.contains(&(line_num as u32)) {
+            "► "
+        } else {
+            "  "
+        };
+        if edit_mode {
+            let hash = crate::format::line_hash(line.as_bytes());
+            let _ = writeln!(output, "{prefix}{line_num}:{hash:03x}|{line}");
+        } else {
+            let _ = writeln!(output, "{prefix}{line_num:4} │ {line}");
+        }
+    }
+    output.push_str("```\n");
+}
+
 /// Simple ranking: context file first, then by path length (proximity heuristic).
-fn rank_callers(callers: &mut [CallerMatch], scope: &Path, context: Option<&Path>) {
+pub(crate) fn rank_callers(callers: &mut [CallerMatch], scope: &Path, context: Option<&Path>) {
     callers.sort_by(|a, b| {
         // Context file wins
         if let Some(ctx) = context {
@@ -344,3 +474,67 @@ fn rank_callers(callers: &mut [CallerMatch], scope: &Path, context: Option<&Path
             .then_with(|| a.line.cmp(&b.line))
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "fn main() {\n    let a = parse(x);\n    parse(y);\n}\n\nfn reload() {\n    parse(z);\n}\n";
+
+    fn site(path: &str, line: u32, function: &str, range: (u32, u32)) -> CallerMatch {
+        CallerMatch {
+            path: PathBuf::from(path),
+            line,
+            calling_function: function.to_string(),
+            call_text: SOURCE
+                .lines()
+                .nth(line as usize - 1)
+                .unwrap()
+                .trim()
+                .to_string(),
+            caller_range: Some(range),
+            content: SOURCE.to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_sites_by_file_and_function() {
+        let callers = vec![
+            site("/repo/src/cli.rs", 7, "reload", (6, 8)),
+            site("/repo/src/cli.rs", 3, "main", (1, 4)),
+            site("/repo/src/cli.rs", 2, "main", (1, 4)),
+            site("/repo/lib.rs", 2, "main", (1, 4)),
+        ];
+        let shown = grouped("parse", Path::new("/repo"), &callers, 4, 0, false);
+        assert_eq!(
+            shown,
+            "# Callers of \"parse\" in /repo — 4 call sites in 2 files\n\
+             \n## src/cli.rs — 3 call sites\n\
+             [1-4]        main\n\
+             \x20    2 → let a = parse(x);\n\
+             \x20    3 → parse(y);\n\
+             [6-8]        reload\n\
+             \x20    7 → parse(z);\n\
+             \n## lib.rs — 1 call site\n\
+             [1-4]        main\n\
+             \x20    2 → let a = parse(x);\n"
+        );
+    }
+
+    #[test]
+    fn expands_the_first_callers_with_every_site_marked() {
+        let callers = vec![
+            site("/repo/a.rs", 2, "main", (1, 4)),
+            site("/repo/a.rs", 3, "main", (1, 4)),
+            site("/repo/a.rs", 7, "reload", (6, 8)),
+        ];
+        let shown = grouped("parse", Path::new("/repo"), &callers, 3, 1, false);
+        assert!(shown.contains("►    2 │     let a = parse(x);"), "{shown}");
+        assert!(shown.contains("►    3 │     parse(y);"), "{shown}");
+        assert!(!shown.contains("   6 │ fn reload"), "{shown}");
+        assert!(
+            grouped("parse", Path::new("/repo"), &[], 0, 1, false).contains("No call sites found.")
+        );
+    }
+}
@@ -1,19 +1,62 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Mutex;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use super::file_metadata;
 
 use crate::error::TilthError;
-use crate::search::rank;
+use crate::index::bloom::{self, Region, Syntax};
+use crate::search::{excerpt, limits, rank};
 use crate::types::{Match, SearchResult};
-use grep_regex::RegexMatcher;
-use grep_searcher::sinks::UTF8;
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::sinks::Lossy;
 use grep_searcher::Searcher;
 
-const MAX_MATCHES: usize = 10;
-const EARLY_QUIT_THRESHOLD: usize = MAX_MATCHES * 3;
 const MAX_SEARCH_FILE_SIZE: u64 = 500_000;
+/// Caps on what a regex query compiles to — its program, its lazy DFA and
+/// its group nesting — so a pathological pattern fails before any file is
+/// read.
+const REGEX_SIZE_LIMIT: usize = 4 << 20;
+const REGEX_DFA_SIZE_LIMIT: usize = 8 << 20;
+const REGEX_NEST_LIMIT: u32 = 50;
+/// Longest a regex may spend on one file. A pattern slower than this stalls
+/// the whole walk, so the search stops and asks for a simpler one.
+const FILE_TIME_LIMIT: Duration = Duration::from_secs(2);
+
+/// Where a hit must sit to be kept: the `in_comments` option and
+/// `kind: "strings"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Within {
+    #[default]
+    Anywhere,
+    Code,
+    CommentsOrStrings,
+    Strings,
+}
+
+impl Within {
+    /// From an `in_comments` value: `include`, `exclude` or `only`.
+    #[must_use]
+    pub fn from_in_comments(s: &str) -> Option<Self> {
+        match s {
+            "include" => Some(Self::Anywhere),
+            "exclude" => Some(Self::Code),
+            "only" => Some(Self::CommentsOrStrings),
+            _ => None,
+        }
+    }
+
+    fn admits(self, region: Region) -> bool {
+        match self {
+            Self::Anywhere => true,
+            Self::Code => region == Region::Code,
+            Self::CommentsOrStrings => region != Region::Code,
+            Self::Strings => region == Region::String,
+        }
+    }
+}
 
 /// Content search using ripgrep crates. Literal by default, regex if `is_regex`.
 pub fn search(
@@ -21,21 +64,25 @@ pub fn search(
     scope: &Path,
     is_regex: bool,
     context: Option<&Path>,
+    within: Within,
 ) -> Result<SearchResult, TilthError> {
     let matcher = if is_regex {
-        RegexMatcher::new(pattern)
+        compile(pattern)?
     } else {
-        RegexMatcher::new(&regex_syntax::escape(pattern))
-    }
-    .map_err(|e| TilthError::InvalidQuery {
-        query: pattern.to_string(),
-        reason: e.to_string(),
-    })?;
+        RegexMatcher::new(&regex_syntax::escape(pattern)).map_err(|e| TilthError::InvalidQuery {
+            query: pattern.to_string(),
+            reason: e.to_string(),
+        })?
+    };
+    // A regex too slow for one file, and how long it took there
+    let too_slow: Mutex<Option<(PathBuf, Duration)>> = Mutex::new(None);
 
     let matches: Mutex<Vec<Match>> = Mutex::new(Vec::new());
     // Relaxed is correct: walker.run() joins all threads before we read the final value.
     // Early-quit checks are approximate by design — one extra iteration is harmless.
     let total_found = AtomicUsize::new(0);
+    let limits = limits::current();
+    let quit_at = limits.early_quit();
 
     let walker = super::walker(scope);
 
@@ -43,9 +90,10 @@ pub fn search(
         let matcher = &matcher;
         let matches = &matches;
         let total_found = &total_found;
+        let too_slow = &too_slow;
 
         Box::new(move |entry| {
-            if total_found.load(Ordering::Relaxed) >= EARLY_QUIT_THRESHOLD {
+            if total_found.load(Ordering::Relaxed) >= quit_at {
                 return ignore::WalkState::Quit;
             }
 
@@ -70,16 +118,27 @@ pub fn search(
 
             let mut file_matches = Vec::new();
             let mut searcher = Searcher::new();
+            let started = Instant::now();
 
             let _ = searcher.search_path(
                 matcher,
                 path,
-                UTF8(|line_num, line| {
+                Lossy(|line_num, line| {
+                    // Invalid UTF-8 arrives replaced, so a mixed file is
+                    // searched to its end; hits in its binary parts are dropped
+                    let at = matcher
+                        .find(line.as_bytes())
+                        .ok()
+                        .flatten()
+                        .map_or(0, |m| m.start());
+                    let Some(text) = excerpt::render(line.trim_end(), at) else {
+                        return Ok(true);
+                    };
                     file_matches.push(Match {
                         path: path.to_path_buf(),
                         line: line_num as u32,
-                        column: 0,
-                        text: line.trim_end().to_string(),
+                        column: at as u32,
+                        text,
                         is_definition: false,
                         exact: false,
                         file_lines,
@@ -88,10 +147,21 @@ pub fn search(
                         def_name: None,
                         def_weight: 0,
                         impl_target: None,
+                        trait_method: None,
                     });
                     Ok(true)
                 }),
             );
+            let took = started.elapsed();
+            if is_regex && took > FILE_TIME_LIMIT {
+                *too_slow.lock().unwrap_or_else(PoisonError::into_inner) =
+                    Some((path.to_path_buf(), took));
+                return ignore::WalkState::Quit;
+            }
+
+            if within != Within::Anywhere && !file_matches.is_empty() {
+                filter_regions(path, matcher, &mut file_matches, within);
+            }
 
             if !file_matches.is_empty() {
                 total_found.fetch_add(file_matches.len(), Ordering::Relaxed);
@@ -101,7 +171,7 @@ pub fn search(
                 all.extend(file_matches);
             }
 
-            if total_found.load(Ordering::Relaxed) >= EARLY_QUIT_THRESHOLD {
+            if total_found.load(Ordering::Relaxed) >= quit_at {
                 ignore::WalkState::Quit
             } else {
                 ignore::WalkState::Continue
@@ -109,13 +179,32 @@ pub fn search(
         })
     });
 
-    let total = total_found.load(Ordering::Relaxed);
+    if let Some((path, took)) = too_slow
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+    {
+        return Err(TilthError::InvalidQuery {
+            query: pattern.to_string(),
+            reason: format!(
+                "regex took {}ms on {} alone, so the search was stopped. \
+                 Simplify it: start it with a literal word, replace `.*` with a bounded \
+                 class like `[^\"]*`, and use smaller repetition counts",
+                took.as_millis(),
+                path.display()
+            ),
+        });
+    }
+
     let mut all_matches = matches
         .into_inner()
         .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let found = all_matches.len();
+    let aliases = super::aliases::dedupe(&mut all_matches);
+    let total = total_found.load(Ordering::Relaxed) - (found - all_matches.len());
 
+    let match_paths = all_matches.iter().map(|m| m.path.clone()).collect();
     rank::sort(&mut all_matches, pattern, scope, context);
-    all_matches.truncate(MAX_MATCHES);
+    limits.apply(&mut all_matches);
 
     Ok(SearchResult {
         query: pattern.to_string(),
@@ -124,5 +213,97 @@ pub fn search(
         total_found: total,
         definitions: 0,
         usages: total,
+        match_paths,
+        unparsed: Vec::new(),
+        aliases,
     })
 }
+
+/// A regex query within the size and nesting caps. Going over one is
+/// reported with how to shrink the pattern.
+fn compile(pattern: &str) -> Result<RegexMatcher, TilthError> {
+    RegexMatcherBuilder::new()
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .nest_limit(REGEX_NEST_LIMIT)
+        .build(pattern)
+        .map_err(|e| {
+            let error = e.to_string();
+            let reason = if error.contains("size limit") {
+                format!(
+                    "{error} — the regex is too complex. Use smaller repetition counts \
+                     (`\\w{{1,20}}`, not `\\w{{1,1000}}`), fewer alternatives, or search \
+                     for a literal part of it with kind: \"content\""
+                )
+            } else if error.contains("nested parentheses") {
+                format!("{error} — flatten the nested groups")
+            } else {
+                error
+            };
+            TilthError::InvalidQuery {
+                query: pattern.to_string(),
+                reason,
+            }
+        })
+}
+
+/// Drop the hits outside `within`. A line is kept if any occurrence on it
+/// is; files without comment syntax are all code.
+fn filter_regions(path: &Path, matcher: &RegexMatcher, hits: &mut Vec<Match>, within: Within) {
+    let Some(syntax) = Syntax::for_path(path) else {
+        if !within.admits(Region::Code) {
+            hits.clear();
+        }
+        return;
+    };
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let bytes = content.as_bytes();
+
+    // Byte offset of every occurrence, paired with the hit it belongs to
+    let mut offsets = Vec::new();
+    let mut owners = Vec::new();
+    let (mut line, mut line_start) = (1, 0);
+    for (i, hit) in hits.iter().enumerate() {
+        while line < hit.line {
+            let Some(nl) = memchr::memchr(b'\n', &bytes[line_start..]) else {
+                break;
+            };
+            line_start += nl + 1;
+            line += 1;
+        }
+        let _ = matcher.find_iter(hit.text.as_bytes(), |m| {
+            offsets.push(line_start + m.start());
+            owners.push(i);
+            true
+        });
+    }
+
+    let mut keep = vec![false; hits.len()];
+    for (&i, region) in owners
+        .iter()
+        .zip(bloom::regions(&content, syntax, &offsets))
+    {
+        keep[i] |= within.admits(region);
+    }
+    let mut keep = keep.into_iter();
+    hits.retain(|_| keep.next().unwrap_or(false));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_regexes_fail_with_advice() {
+        let reason = |pattern: &str| match compile(pattern) {
+            Err(TilthError::InvalidQuery { reason, .. }) => reason,
+            other => panic!("expected InvalidQuery, got {other:?}"),
+        };
+        assert!(reason(r"\w{1000}{1000}").contains("too complex"));
+        let nested = format!("{}a{}", "(".repeat(60), ")".repeat(60));
+        assert!(reason(&nested).contains("flatten"));
+        assert!(compile(r"fn \w+\(.*\) -> Result<").is_ok());
+    }
+}
@@ -0,0 +1,325 @@
+//! Search by what a symbol does rather than what it is called: "function
+//! that collapses blank lines" finds `shape` from its doc comment.
+//!
+//! Each definition in scope becomes a short passage — kind, name split into
+//! words, signature and the doc comment above it — and passages and query
+//! are compared as sentence embeddings. The model runs locally through
+//! `fastembed` and is only compiled in with the `embeddings` feature; it is
+//! downloaded on first use. Without the feature, [`search`] says how to get it.
+//!
+//! Passages are embedded once per file and kept until the file's mtime
+//! changes, so only the first query over a tree pays for all of it.
+
+// Without the model, only the tests reach the passage builders
+#![cfg_attr(not(feature = "embeddings"), allow(dead_code))]
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::TilthError;
+use crate::read::outline::code::{entries, kind_label};
+use crate::types::{FileType, OutlineEntry, OutlineKind};
+
+/// Largest file whose definitions are embedded. Matches symbol search.
+const MAX_FILE_SIZE: u64 = 500_000;
+
+/// A definition as the model sees it.
+#[derive(Debug, Clone)]
+struct Passage {
+    path: PathBuf,
+    line: u32,
+    kind: &'static str,
+    name: String,
+    signature: String,
+    doc: String,
+}
+
+impl Passage {
+    /// The text embedded: `fn shape (shape) — The finished response, with
+    /// blank lines dropped when compact.`
+    fn text(&self) -> String {
+        let mut text = format!("{} {}", self.kind, words(&self.name));
+        if !self.signature.is_empty() {
+            let _ = write!(text, " ({})", self.signature);
+        }
+        if !self.doc.is_empty() {
+            let _ = write!(text, " — {}", self.doc);
+        }
+        text
+    }
+}
+
+/// Definitions in `scope` ranked by how well their name and doc comment
+/// match `query`, best first.
+pub fn search(query: &str, scope: &Path, limit: usize) -> Result<String, TilthError> {
+    #[cfg(feature = "embeddings")]
+    {
+        let ranked = model::rank(query, scope, limit)?;
+        Ok(format_ranked(query, scope, &ranked))
+    }
+    #[cfg(not(feature = "embeddings"))]
+    {
+        let _ = (scope, limit);
+        Err(TilthError::InvalidQuery {
+            query: query.to_string(),
+            reason: "kind: \"describe\" needs a build with the `embeddings` feature \
+                     (cargo install tilth --features embeddings)"
+                .into(),
+        })
+    }
+}
+
+fn format_ranked(query: &str, scope: &Path, ranked: &[(f32, Passage)]) -> String {
+    let mut out = format!(
+        "# Describe: \"{query}\" in {} — {} candidates",
+        scope.display(),
+        ranked.len()
+    );
+    for (score, p) in ranked {
+        let _ = write!(
+            out,
+            "\n\n## {}:{} {} {} (similarity {score:.2})",
+            super::rel(&p.path, scope),
+            p.line,
+            p.kind,
+            p.name
+        );
+        if !p.signature.is_empty() {
+            let _ = write!(out, "\n{}", p.signature);
+        }
+        if !p.doc.is_empty() {
+            let _ = write!(out, "\n> {}", p.doc);
+        }
+    }
+    if ranked.is_empty() {
+        out.push_str("\n\nNo definitions found in scope.");
+    }
+    out
+}
+
+/// The definitions in one file, with the doc comment above each.
+fn passages(path: &Path) -> Vec<Passage> {
+    let FileType::Code(lang) = crate::read::detect_file_type(path) else {
+        return Vec::new();
+    };
+    if !std::fs::metadata(path).is_ok_and(|m| m.len() <= MAX_FILE_SIZE) {
+        return Vec::new();
+    }
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let Some(top) = entries(&content, lang) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut out = Vec::new();
+    let mut push = |entry: &OutlineEntry| {
+        if matches!(entry.kind, OutlineKind::Import | OutlineKind::Export) {
+            return;
+        }
+        let doc = doc_above(&lines, entry.start_line)
+            .or_else(|| entry.doc.clone())
+            .unwrap_or_default();
+        out.push(Passage {
+            path: path.to_path_buf(),
+            line: entry.start_line,
+            kind: kind_label(entry.kind, lang),
+            name: entry.name.clone(),
+            signature: entry.signature.clone().unwrap_or_default(),
+            doc,
+        });
+    };
+    for entry in &top {
+        push(entry);
+        for child in &entry.children {
+            push(child);
+        }
+    }
+    out
+}
+
+/// The comment block ending just above 1-based `line`, markers stripped and
+/// lines joined. Attribute and decorator lines in between are skipped.
+fn doc_above(lines: &[&str], line: u32) -> Option<String> {
+    let mut block = Vec::new();
+    for l in lines[..(line as usize).saturating_sub(1).min(lines.len())]
+        .iter()
+        .rev()
+    {
+        let t = l.trim();
+        if t.starts_with("#[") || t.starts_with('@') {
+            continue;
+        }
+        let text = if let Some(rest) = ["///", "//!", "//", "/**", "/*", "*/", "*", "#"]
+            .iter()
+            .find_map(|m| t.strip_prefix(m))
+        {
+            rest.trim_end_matches("*/").trim()
+        } else {
+            break;
+        };
+        block.push(text);
+    }
+    block.reverse();
+    let doc = block
+        .into_iter()
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// `collapse_blank_lines` and `collapseBlankLines` → `collapse blank lines`.
+fn words(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            if !out.ends_with(' ') && !out.is_empty() {
+                out.push(' ');
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            out.push(' ');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        out.extend(c.to_lowercase());
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(feature = "embeddings")]
+mod model {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, OnceLock, PoisonError};
+    use std::time::SystemTime;
+
+    use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+
+    use super::Passage;
+    use crate::error::TilthError;
+
+    /// Passages of one file and their vectors, as of `mtime`.
+    struct FileEntry {
+        mtime: SystemTime,
+        passages: Vec<(Passage, Vec<f32>)>,
+    }
+
+    static MODEL: OnceLock<Result<TextEmbedding, String>> = OnceLock::new();
+    static FILES: OnceLock<Mutex<HashMap<PathBuf, FileEntry>>> = OnceLock::new();
+
+    /// Where the model is downloaded: `TILTH_MODEL_DIR`, else
+    /// `~/.cache/tilth/models`.
+    fn cache_dir() -> PathBuf {
+        if let Some(dir) = std::env::var_os("TILTH_MODEL_DIR") {
+            return PathBuf::from(dir);
+        }
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map_or_else(std::env::temp_dir, PathBuf::from);
+        home.join(".cache").join("tilth").join("models")
+    }
+
+    fn model() -> Result<&'static TextEmbedding, TilthError> {
+        let model = MODEL.get_or_init(|| {
+            // Progress bars would land on stdout, which is the MCP transport
+            let options = InitOptions::new(EmbeddingModel::AllMiniLML6V2)
+                .with_cache_dir(cache_dir())
+                .with_show_download_progress(false);
+            TextEmbedding::try_new(options).map_err(|e| e.to_string())
+        });
+        model.as_ref().map_err(|e| TilthError::IoError {
+            path: cache_dir(),
+            source: std::io::Error::other(format!("loading embedding model: {e}")),
+        })
+    }
+
+    fn embed(model: &TextEmbedding, texts: Vec<String>) -> Result<Vec<Vec<f32>>, TilthError> {
+        model.embed(texts, None).map_err(|e| TilthError::IoError {
+            path: cache_dir(),
+            source: std::io::Error::other(format!("embedding: {e}")),
+        })
+    }
+
+    /// The `limit` passages in `scope` closest to `query`.
+    pub(super) fn rank(
+        query: &str,
+        scope: &Path,
+        limit: usize,
+    ) -> Result<Vec<(f32, Passage)>, TilthError> {
+        let model = model()?;
+        let files = crate::analyze::collect_files(scope, |p| {
+            matches!(
+                crate::read::detect_file_type(p),
+                crate::types::FileType::Code(_)
+            )
+        });
+
+        let mut cache = FILES
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        for path in &files {
+            let (_, mtime) = crate::search::file_metadata(path);
+            if cache.get(path).is_some_and(|f| f.mtime == mtime) {
+                continue;
+            }
+            let passages = super::passages(path);
+            let vectors = embed(model, passages.iter().map(Passage::text).collect())?;
+            let passages = passages.into_iter().zip(vectors).collect();
+            cache.insert(path.clone(), FileEntry { mtime, passages });
+        }
+
+        let Some(query_vec) = embed(model, vec![query.to_string()])?.pop() else {
+            return Ok(Vec::new());
+        };
+        let mut ranked: Vec<(f32, Passage)> = files
+            .iter()
+            .filter_map(|path| cache.get(path))
+            .flat_map(|f| &f.passages)
+            .map(|(p, v)| (cosine(&query_vec, v), p.clone()))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranked.truncate(limit);
+        Ok(ranked)
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let denom = norm(a) * norm(b);
+        if denom <= f32::EPSILON {
+            0.0
+        } else {
+            dot / denom
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passage_reads_as_a_sentence() {
+        let source = "/// The finished response,\n/// with blank lines dropped.\n#[must_use]\nfn collapse_blank_lines() {}";
+        let lines: Vec<&str> = source.lines().collect();
+        let passage = Passage {
+            path: PathBuf::from("a.rs"),
+            line: 4,
+            kind: "fn",
+            name: "collapseBlank_lines".into(),
+            signature: String::new(),
+            doc: doc_above(&lines, 4).unwrap(),
+        };
+        assert_eq!(
+            passage.text(),
+            "fn collapse blank lines — The finished response, with blank lines dropped."
+        );
+        assert_eq!(doc_above(&lines, 1), None);
+    }
+}
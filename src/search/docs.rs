@@ -0,0 +1,420 @@
+//! Search over documentation only: markdown sections and doc comments, for
+//! questions like "where is the retry policy documented".
+//!
+//! The query is taken as a bag of words rather than a literal string.
+//! Markdown is cut into sections at each heading, and code contributes its
+//! doc comment blocks (`///`, `//!`, `/** */`, Python docstrings), each
+//! headed by the item it documents. A block ranks by how many query words it
+//! has, then by words in its heading, then by how close below the heading
+//! the rest appear.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::TilthError;
+use crate::types::FileType;
+
+/// Largest file searched. Matches content search.
+const MAX_FILE_SIZE: u64 = 500_000;
+/// Matching lines quoted under each section.
+const QUOTED_LINES: usize = 4;
+/// Body lines below a heading that still count as close to it.
+const PROXIMITY_LINES: usize = 20;
+
+/// Words that say what is being asked rather than what about.
+const STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "for",
+    "are",
+    "was",
+    "how",
+    "what",
+    "where",
+    "which",
+    "who",
+    "why",
+    "when",
+    "does",
+    "did",
+    "can",
+    "this",
+    "that",
+    "with",
+    "from",
+    "into",
+    "about",
+    "there",
+    "here",
+    "any",
+    "all",
+    "doc",
+    "docs",
+    "documented",
+    "documentation",
+    "described",
+    "explained",
+    "mentioned",
+    "defined",
+    "written",
+    "find",
+    "show",
+    "explain",
+];
+
+/// A markdown section or a doc comment block.
+#[derive(Debug)]
+struct Block {
+    path: PathBuf,
+    /// 1-based line of the heading, or of the first doc line.
+    line: u32,
+    /// Heading trail (`Configuration › Retry policy`), or the documented item.
+    heading: String,
+    /// Non-blank lines with their 1-based numbers.
+    body: Vec<(u32, String)>,
+    markdown: bool,
+}
+
+/// Documentation in `scope` ranked against the words of `query`.
+pub fn search(query: &str, scope: &Path, limit: usize) -> Result<String, TilthError> {
+    let terms = terms(query);
+    if terms.is_empty() {
+        return Err(TilthError::InvalidQuery {
+            query: query.to_string(),
+            reason: "no words to look for — name the topic, e.g. \"retry policy\"".into(),
+        });
+    }
+
+    let files = crate::analyze::collect_files(scope, |p| {
+        matches!(
+            crate::read::detect_file_type(p),
+            FileType::Markdown | FileType::Code(_)
+        )
+    });
+    let mut ranked: Vec<(u32, Block)> = Vec::new();
+    for path in files {
+        if crate::deadline::reached() {
+            break;
+        }
+        if !std::fs::metadata(&path).is_ok_and(|m| m.len() <= MAX_FILE_SIZE) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let lower = content.to_lowercase();
+        if !terms.iter().any(|t| lower.contains(t.as_str())) {
+            continue;
+        }
+        let blocks = if crate::read::detect_file_type(&path) == FileType::Markdown {
+            markdown_sections(&path, &content)
+        } else {
+            doc_blocks(&path, &content)
+        };
+        ranked.extend(
+            blocks
+                .into_iter()
+                .filter_map(|b| score(&b, &terms).map(|s| (s, b))),
+        );
+    }
+    ranked.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| a.1.path.cmp(&b.1.path))
+            .then_with(|| a.1.line.cmp(&b.1.line))
+    });
+    let total = ranked.len();
+    ranked.truncate(limit);
+
+    let mut out = format!(
+        "# Docs: \"{query}\" in {} — {total} sections",
+        scope.display()
+    );
+    if total > ranked.len() {
+        let _ = write!(out, " (top {} shown)", ranked.len());
+    }
+    for (_, block) in &ranked {
+        let tag = if block.markdown { "doc" } else { "doc comment" };
+        let _ = write!(
+            out,
+            "\n\n## {}:{} [{tag}] {}",
+            super::rel(&block.path, scope),
+            block.line,
+            block.heading
+        );
+        for (n, line) in quoted(block, &terms) {
+            let _ = write!(out, "\n{n:>4} │ {line}");
+        }
+    }
+    if total == 0 {
+        let _ = write!(
+            out,
+            "\n\nNo markdown or doc comments mention {}. Try kind: \"content\" for comments and code.",
+            terms.join(", ")
+        );
+    }
+    Ok(out)
+}
+
+/// Lowercased query words, stopwords dropped, reduced to a stem so `retries`
+/// finds `retry` and `retrying`.
+fn terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(str::to_lowercase)
+        .filter(|w| w.len() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .map(|w| stem(&w).to_string())
+        .collect();
+    terms.dedup();
+    terms
+}
+
+fn stem(word: &str) -> &str {
+    for suffix in ["ies", "ing", "ed", "es", "s"] {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() >= 4 {
+                return base;
+            }
+        }
+    }
+    word
+}
+
+/// `None` when the block has under half the terms; otherwise higher is
+/// better: terms covered, then terms in the heading, then how soon after
+/// the heading the others first appear.
+fn score(block: &Block, terms: &[String]) -> Option<u32> {
+    let heading = block.heading.to_lowercase();
+    let mut covered = 0u32;
+    let mut in_heading = 0u32;
+    let mut proximity = 0u32;
+    for term in terms {
+        let first = block
+            .body
+            .iter()
+            .position(|(_, l)| l.to_lowercase().contains(term.as_str()));
+        if heading.contains(term.as_str()) {
+            covered += 1;
+            in_heading += 1;
+        } else if let Some(i) = first {
+            covered += 1;
+            proximity += PROXIMITY_LINES.saturating_sub(i) as u32;
+        }
+    }
+    let needed = terms.len().div_ceil(2) as u32;
+    if covered < needed.max(1) {
+        return None;
+    }
+    Some(covered * 1000 + in_heading * 100 + proximity)
+}
+
+/// Body lines that mention a term, or the opening lines if none do.
+fn quoted<'a>(block: &'a Block, terms: &[String]) -> Vec<(u32, &'a str)> {
+    let hits: Vec<(u32, &str)> = block
+        .body
+        .iter()
+        .filter(|(_, l)| {
+            let l = l.to_lowercase();
+            terms.iter().any(|t| l.contains(t.as_str()))
+        })
+        .map(|(n, l)| (*n, l.as_str()))
+        .take(QUOTED_LINES)
+        .collect();
+    if !hits.is_empty() {
+        return hits;
+    }
+    block
+        .body
+        .iter()
+        .take(2)
+        .map(|(n, l)| (*n, l.as_str()))
+        .collect()
+}
+
+/// One block per heading, titled with the headings above it. Text before
+/// the first heading is titled with the file name. Fenced code stays in.
+fn markdown_sections(path: &Path, content: &str) -> Vec<Block> {
+    let file_name = path
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    let mut trail: Vec<(usize, String)> = Vec::new();
+    let mut blocks = vec![Block {
+        path: path.to_path_buf(),
+        line: 1,
+        heading: file_name,
+        body: Vec::new(),
+        markdown: true,
+    }];
+    let mut in_fence = false;
+    for (i, line) in content.lines().enumerate() {
+        let n = i as u32 + 1;
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        let level = line.bytes().take_while(|&b| b == b'#').count();
+        if !in_fence && (1..=6).contains(&level) && line[level..].starts_with(' ') {
+            trail.retain(|(l, _)| *l < level);
+            trail.push((level, line[level..].trim().to_string()));
+            let heading = trail
+                .iter()
+                .map(|(_, t)| t.as_str())
+                .collect::<Vec<_>>()
+                .join(" › ");
+            blocks.push(Block {
+                path: path.to_path_buf(),
+                line: n,
+                heading,
+                body: Vec::new(),
+                markdown: true,
+            });
+        } else if !line.trim().is_empty() {
+            if let Some(block) = blocks.last_mut() {
+                block.body.push((n, line.trim().to_string()));
+            }
+        }
+    }
+    if blocks[0].body.is_empty() {
+        blocks.remove(0);
+    }
+    blocks
+}
+
+/// Doc comment blocks, each titled with the line it documents: the item
+/// below a `///` or `/** */` block, the `def`/`class` above a docstring, and
+/// `module` for `//!`.
+fn doc_blocks(path: &Path, content: &str) -> Vec<Block> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let t = lines[i].trim();
+        let start = i;
+        let (body, heading) = if t.starts_with("///") || t.starts_with("//!") {
+            let marker = &t[..3];
+            let mut body = Vec::new();
+            while i < lines.len() && lines[i].trim().starts_with(marker) {
+                body.push((i, lines[i].trim()[3..].trim()));
+                i += 1;
+            }
+            let heading = if marker == "//!" {
+                "module".to_string()
+            } else {
+                item_below(&lines, i)
+            };
+            (body, heading)
+        } else if t.starts_with("/**") {
+            let mut body = Vec::new();
+            loop {
+                let text = lines[i]
+                    .trim()
+                    .trim_start_matches("/**")
+                    .trim_end_matches("*/")
+                    .trim_start_matches('*')
+                    .trim();
+                body.push((i, text));
+                i += 1;
+                if lines[i - 1].contains("*/") || i == lines.len() {
+                    break;
+                }
+            }
+            (body, item_below(&lines, i))
+        } else if (t.starts_with("\"\"\"") || t.starts_with("r\"\"\""))
+            && start > 0
+            && lines[start - 1].trim_end().ends_with(':')
+        {
+            let mut body = Vec::new();
+            loop {
+                let text = lines[i].trim();
+                let text = if i == start {
+                    text.strip_prefix('r').unwrap_or(text)
+                } else {
+                    text
+                };
+                let text = text.trim_matches('"').trim();
+                body.push((i, text));
+                i += 1;
+                // The opening quotes are on the first line
+                let quotes = if i - 1 == start { 2 } else { 1 };
+                let closed = lines[i - 1].matches("\"\"\"").count() >= quotes;
+                if closed || i == lines.len() {
+                    break;
+                }
+            }
+            (
+                body,
+                lines[start - 1].trim().trim_end_matches(':').to_string(),
+            )
+        } else {
+            i += 1;
+            continue;
+        };
+        let body: Vec<(u32, String)> = body
+            .into_iter()
+            .filter(|(_, l)| !l.is_empty())
+            .map(|(n, l)| (n as u32 + 1, l.to_string()))
+            .collect();
+        if !body.is_empty() {
+            blocks.push(Block {
+                path: path.to_path_buf(),
+                line: start as u32 + 1,
+                heading,
+                body,
+                markdown: false,
+            });
+        }
+    }
+    blocks
+}
+
+/// The first line at or after `from` that is not an attribute, decorator
+/// or blank, trimmed.
+fn item_below(lines: &[&str], from: usize) -> String {
+    lines[from.min(lines.len())..]
+        .iter()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with("#[") && !l.starts_with('@'))
+        .map_or_else(String::new, |l| {
+            crate::types::truncate_str(l.trim_end_matches('{').trim_end(), 80).to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heading_match_outranks_passing_mention() {
+        let md = "# Client\n\nUses a pool.\n\n## Retry policy\n\nFailed requests are retried three times.\n\n## Logging\n\nA retry is logged at warn.\n";
+        let blocks = markdown_sections(Path::new("README.md"), md);
+        let terms = terms("where is the retry policy documented?");
+        assert_eq!(terms, vec!["retry", "policy"]);
+
+        let mut ranked: Vec<(u32, &Block)> = blocks
+            .iter()
+            .filter_map(|b| score(b, &terms).map(|s| (s, b)))
+            .collect();
+        ranked.sort_by_key(|(s, _)| std::cmp::Reverse(*s));
+        let headings: Vec<&str> = ranked.iter().map(|(_, b)| b.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Client › Retry policy", "Client › Logging"]);
+        assert_eq!(ranked[0].1.line, 5);
+    }
+
+    #[test]
+    fn doc_comments_are_titled_by_their_item() {
+        let src = "//! Networking.\n\n/// Backoff between retries,\n/// doubling each time.\n#[must_use]\npub fn backoff(n: u32) -> u64 {\n    // not a doc\n}\n\ndef fetch():\n    \"\"\"Fetch with retries.\"\"\"\n";
+        let blocks = doc_blocks(Path::new("a.rs"), src);
+        let summary: Vec<(u32, &str, usize)> = blocks
+            .iter()
+            .map(|b| (b.line, b.heading.as_str(), b.body.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "module", 1),
+                (3, "pub fn backoff(n: u32) -> u64", 2),
+                (11, "def fetch()", 1)
+            ]
+        );
+        assert_eq!(blocks[2].body[0].1, "Fetch with retries.");
+    }
+}
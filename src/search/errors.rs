@@ -0,0 +1,287 @@
+//! Where a runtime error message was written: `kind: "error"` takes the
+//! message as it was printed and finds the format strings that could have
+//! produced it.
+//!
+//! The parts of a message that vary from run to run — numbers, paths, URLs,
+//! quoted values — are dropped, leaving its fixed words. Every string
+//! literal in code gets the same treatment, with its placeholders (`{}`,
+//! `%s`, `${x}`) dropped too, and ranks by how many of the message's words
+//! it has in order. A message wrapped in context (`loading config: open
+//! failed: …`) still finds each part's literal.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::TilthError;
+use crate::index::bloom::{string_literals, Syntax};
+use crate::types::FileType;
+
+const MAX_FILE_SIZE: u64 = 500_000;
+/// Lowest similarity, in percent, a literal needs to be listed.
+const MIN_SIMILARITY: u32 = 30;
+/// Words on a line that mark it as where an error is raised, not just
+/// where the text appears.
+const RAISE_MARKERS: &[&str] = &[
+    "raise ",
+    "throw ",
+    "panic!",
+    "Err(",
+    "bail!",
+    "anyhow!",
+    "Error(",
+    "Errorf(",
+    "errors.New(",
+    "error!",
+    "Exception(",
+    "reject(",
+];
+
+struct Candidate {
+    path: PathBuf,
+    line: u32,
+    literal: String,
+    similarity: u32,
+    raises: bool,
+    text: String,
+}
+
+/// Format strings in `scope` ranked by how closely they match the error
+/// `message`, best `limit` first.
+pub fn search(message: &str, scope: &Path, limit: usize) -> Result<String, TilthError> {
+    let wanted = message_words(message);
+    if wanted.is_empty() {
+        return Err(TilthError::InvalidQuery {
+            query: message.to_string(),
+            reason: "no fixed words left once numbers, paths and quoted values are dropped — paste the whole message".into(),
+        });
+    }
+    // Files worth scanning mention one of the message's longest words
+    let mut probes: Vec<&str> = wanted.iter().map(String::as_str).collect();
+    probes.sort_by_key(|w| std::cmp::Reverse(w.len()));
+    probes.truncate(3);
+
+    let files = crate::analyze::collect_files(scope, |p| {
+        matches!(crate::read::detect_file_type(p), FileType::Code(_))
+    });
+    let mut found: Vec<Candidate> = Vec::new();
+    for path in files {
+        if crate::deadline::reached() {
+            break;
+        }
+        if !std::fs::metadata(&path).is_ok_and(|m| m.len() <= MAX_FILE_SIZE) {
+            continue;
+        }
+        let Some(syntax) = Syntax::for_path(&path) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let lower = content.to_lowercase();
+        if !probes.iter().any(|w| lower.contains(w)) {
+            continue;
+        }
+        let lines: Vec<&str> = content.lines().collect();
+        let (mut line, mut counted) = (1u32, 0);
+        for (at, literal) in string_literals(&content, syntax) {
+            line += content[counted..at].matches('\n').count() as u32;
+            counted = at;
+            let words = literal_words(literal);
+            let Some(similarity) = similarity(&wanted, &words) else {
+                continue;
+            };
+            let text = lines.get(line as usize - 1).map_or("", |l| l.trim());
+            found.push(Candidate {
+                path: path.clone(),
+                line,
+                literal: literal.to_string(),
+                similarity,
+                raises: RAISE_MARKERS.iter().any(|m| text.contains(m)),
+                text: super::excerpt::fit(text).into_owned(),
+            });
+        }
+    }
+    found.sort_by(|a, b| {
+        b.similarity
+            .cmp(&a.similarity)
+            .then(b.raises.cmp(&a.raises))
+            .then_with(|| a.path.cmp(&b.path))
+            .then(a.line.cmp(&b.line))
+    });
+    let total = found.len();
+    found.truncate(limit);
+
+    let mut out = format!(
+        "# Error: \"{}\" in {} — {total} candidates",
+        super::excerpt::fit(message.trim()),
+        scope.display()
+    );
+    if total > found.len() {
+        let _ = write!(out, " (top {} shown)", found.len());
+    }
+    let _ = write!(out, "\n[fixed words: {}]", wanted.join(" "));
+    for c in &found {
+        let tag = if c.raises { "raise site" } else { "literal" };
+        let _ = write!(
+            out,
+            "\n\n## {}:{} [{tag}] {}% \"{}\"\n{:>4} │ {}",
+            super::rel(&c.path, scope),
+            c.line,
+            c.similarity,
+            super::excerpt::fit(&c.literal),
+            c.line,
+            c.text
+        );
+    }
+    if total == 0 {
+        out.push_str("\n\nNo string literal in scope matches. The message may come from a dependency, or be built from pieces — try kind: \"content\" with its most distinctive words.");
+    }
+    Ok(out)
+}
+
+/// The fixed words of an error message, lowercased, in order: quoted
+/// values, paths, URLs and anything with a digit dropped.
+pub(super) fn message_words(message: &str) -> Vec<String> {
+    let mut kept = String::with_capacity(message.len());
+    let chars: Vec<char> = message.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let opens = matches!(c, '"' | '\'' | '`') && (i == 0 || !chars[i - 1].is_alphanumeric());
+        if opens {
+            // A quote that closes before a non-word character ends a value;
+            // `can't` and `don't` don't open one
+            let close = (i + 1..chars.len())
+                .find(|&j| chars[j] == c && chars.get(j + 1).is_none_or(|n| !n.is_alphanumeric()));
+            if let Some(j) = close {
+                kept.push(' ');
+                i = j + 1;
+                continue;
+            }
+        }
+        kept.push(c);
+        i += 1;
+    }
+    kept.split_whitespace()
+        .filter(|token| !token.contains('/') && !token.contains('\\'))
+        .flat_map(words)
+        .collect()
+}
+
+/// The fixed words of a string literal: escapes and format placeholders
+/// dropped.
+pub(super) fn literal_words(literal: &str) -> Vec<String> {
+    let mut kept = String::with_capacity(literal.len());
+    let mut chars = literal.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+                kept.push(' ');
+            }
+            // `{}`, `{name}`, `{0:>4}`, and `${expr}`
+            '{' | '$' if c == '{' || chars.peek() == Some(&'{') => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+                kept.push(' ');
+            }
+            // `%s`, `%d`, `%-5.2f`, `%v`
+            '%' if chars.peek().is_some_and(|n| !n.is_whitespace()) => {
+                while let Some(&n) = chars.peek() {
+                    chars.next();
+                    if n.is_ascii_alphabetic() || n == '%' {
+                        break;
+                    }
+                }
+                kept.push(' ');
+            }
+            _ => kept.push(c),
+        }
+    }
+    words(&kept)
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty() && !w.chars().any(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// How much of the message `literal` accounts for, in percent: the
+/// message's words it has in order, against the larger of how many words
+/// it has and half the message — so a literal for one part of a wrapped
+/// message still ranks, but a stray common word doesn't. `None` below
+/// [`MIN_SIMILARITY`], or with under two words in common when the message
+/// has two or more — one shared word says little about a longer message.
+pub(super) fn similarity(message: &[String], literal: &[String]) -> Option<u32> {
+    if literal.is_empty() {
+        return None;
+    }
+    let common = lcs(message, literal);
+    if common < 2.min(message.len()) {
+        return None;
+    }
+    let against = literal.len().max(message.len().div_ceil(2));
+    let percent = (common * 100 / against) as u32;
+    (percent >= MIN_SIMILARITY).then_some(percent)
+}
+
+/// Length of the longest common subsequence of `a` and `b`.
+fn lcs(a: &[String], b: &[String]) -> usize {
+    let mut row = vec![0usize; b.len() + 1];
+    for x in a {
+        let mut diagonal = 0;
+        for (j, y) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if x == y {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_parts_are_dropped() {
+        assert_eq!(
+            message_words(
+                "failed to open \"/etc/app.toml\": can't read 42 bytes at /var/x (os error 13)"
+            ),
+            vec!["failed", "to", "open", "can", "t", "read", "bytes", "at", "os", "error"]
+        );
+        assert_eq!(
+            literal_words("failed to open {path:?}: can't read {} bytes at %s\\n"),
+            vec!["failed", "to", "open", "can", "t", "read", "bytes", "at"]
+        );
+        assert_eq!(
+            literal_words("user ${id} not found (%d%%)"),
+            vec!["user", "not", "found"]
+        );
+    }
+
+    #[test]
+    fn ranks_the_format_string_first() {
+        let message = message_words("loading config: user 'bob' not found in group 7");
+        let score = |literal: &str| similarity(&message, &literal_words(literal));
+        let exact = score("user {} not found in group {}").unwrap();
+        let context = score("loading config: {}").unwrap();
+        assert!(exact > context, "{exact} vs {context}");
+        assert_eq!(score("not"), None);
+        assert_eq!(score("group settings"), None);
+
+        let short = message_words("mcp error");
+        assert_eq!(similarity(&short, &literal_words("error")), None);
+        assert_eq!(similarity(&short, &literal_words("mcp error")), Some(100));
+    }
+}
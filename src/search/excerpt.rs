@@ -0,0 +1,95 @@
+//! Matched lines fit for output. A hit on a minified bundle's one long line,
+//! or in a file with some binary data in it, would otherwise print kilobytes
+//! or garbage: a line is cut to a window around the match, only the text
+//! around a hit in a mixed file is shown, and a hit in binary data is
+//! dropped.
+
+use std::borrow::Cow;
+
+/// Characters of a line shown with a match.
+const MAX_LINE_CHARS: usize = 200;
+
+/// `line` as a match shows it, with the hit at byte `at`: the run of text
+/// around the hit when the line also holds binary data, and a long line cut
+/// to a window around the hit, with `…` where anything was left out. `None`
+/// when the hit itself is on binary data.
+pub(crate) fn render(line: &str, at: usize) -> Option<String> {
+    let mut at = at.min(line.len());
+    while !line.is_char_boundary(at) {
+        at -= 1;
+    }
+    let start = line[..at]
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| !is_text(c))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let end = line[at..]
+        .find(|c: char| !is_text(c))
+        .map_or(line.len(), |i| at + i);
+    if start >= end {
+        return None;
+    }
+    let (window, cut_start, cut_end) = window(&line[start..end], at - start);
+    let mut out = String::with_capacity(window.len() + 8);
+    if cut_start || start > 0 {
+        out.push('…');
+    }
+    out.push_str(window);
+    if cut_end || end < line.len() {
+        out.push('…');
+    }
+    Some(out)
+}
+
+/// `line` cut to [`MAX_LINE_CHARS`] from its start, for a match whose hit
+/// position isn't known.
+pub(crate) fn fit(line: &str) -> Cow<'_, str> {
+    if line.len() <= MAX_LINE_CHARS || line.chars().count() <= MAX_LINE_CHARS {
+        return Cow::Borrowed(line);
+    }
+    let (window, _, _) = window(line, 0);
+    Cow::Owned(format!("{window}…"))
+}
+
+fn is_text(c: char) -> bool {
+    c == '\t' || !(c.is_control() || c == char::REPLACEMENT_CHARACTER)
+}
+
+/// Up to [`MAX_LINE_CHARS`] of `line` around byte `at`, about a third
+/// before it, and whether the start and end were cut.
+fn window(line: &str, at: usize) -> (&str, bool, bool) {
+    let chars = line.chars().count();
+    if chars <= MAX_LINE_CHARS {
+        return (line, false, false);
+    }
+    let at_char = line.char_indices().take_while(|&(i, _)| i < at).count();
+    let first = at_char
+        .saturating_sub(MAX_LINE_CHARS / 3)
+        .min(chars - MAX_LINE_CHARS);
+    let byte = |n: usize| line.char_indices().nth(n).map_or(line.len(), |(i, _)| i);
+    let (start, end) = (byte(first), byte(first + MAX_LINE_CHARS));
+    (&line[start..end], first > 0, end < line.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_lines_are_cut_around_the_hit() {
+        let line = format!("{}needle{}", "a".repeat(500), "b".repeat(500));
+        let shown = render(&line, 500).unwrap();
+        assert!(shown.starts_with('…') && shown.ends_with('…'), "{shown}");
+        assert!(shown.contains("needle"));
+        assert_eq!(shown.chars().count(), MAX_LINE_CHARS + 2);
+        assert_eq!(render("short needle", 6).unwrap(), "short needle");
+        assert_eq!(fit(&line).chars().count(), MAX_LINE_CHARS + 1);
+    }
+
+    #[test]
+    fn text_runs_of_mixed_lines() {
+        let line = "\0\u{1}\u{2}version=1.2\tstable\0\u{3}\u{fffd}";
+        assert_eq!(render(line, 3).unwrap(), "…version=1.2\tstable…");
+        assert!(render(line, 1).is_none());
+    }
+}
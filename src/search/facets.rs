@@ -1,10 +1,14 @@
 use std::path::{Path, PathBuf};
 
-use crate::types::Match;
+use crate::types::{Match, TraitMethod};
 
 /// Faceted search results grouped by match type and location.
 pub struct FacetedResult {
     pub definitions: Vec<Match>,
+    /// Rust trait methods' default bodies, written in the trait itself.
+    pub trait_defaults: Vec<Match>,
+    /// The same methods as written in each `impl Trait for Type`.
+    pub overrides: Vec<Match>,
     pub implementations: Vec<Match>,
     pub tests: Vec<Match>,
     pub usages_local: Vec<Match>,
@@ -23,13 +27,19 @@ pub fn facet_matches(matches: Vec<Match>, _scope: &Path) -> FacetedResult {
         .map(std::path::Path::to_path_buf);
 
     let mut definitions = Vec::new();
+    let mut trait_defaults = Vec::new();
+    let mut overrides = Vec::new();
     let mut implementations = Vec::new();
     let mut tests = Vec::new();
     let mut usages_local = Vec::new();
     let mut usages_cross = Vec::new();
 
     for m in matches {
-        if m.is_definition && m.impl_target.is_some() {
+        if matches!(m.trait_method, Some(TraitMethod::Default { .. })) {
+            trait_defaults.push(m);
+        } else if matches!(m.trait_method, Some(TraitMethod::Override { .. })) {
+            overrides.push(m);
+        } else if m.is_definition && m.impl_target.is_some() {
             implementations.push(m);
         } else if m.is_definition {
             definitions.push(m);
@@ -44,6 +54,8 @@ pub fn facet_matches(matches: Vec<Match>, _scope: &Path) -> FacetedResult {
 
     FacetedResult {
         definitions,
+        trait_defaults,
+        overrides,
         implementations,
         tests,
         usages_local,
@@ -54,7 +66,7 @@ pub fn facet_matches(matches: Vec<Match>, _scope: &Path) -> FacetedResult {
 /// Check if a match is in a test file or contains test markers.
 fn is_test_match(m: &Match) -> bool {
     // Path-based detection
-    let path_str = m.path.to_string_lossy();
+    let path_str = crate::pathnorm::display(&m.path);
     if path_str.contains("_test.")
         || path_str.contains("/test/")
         || path_str.contains("/tests/")
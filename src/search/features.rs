@@ -0,0 +1,316 @@
+//! Which crates turn a Cargo feature on: `kind: "feature"` takes a feature
+//! name — `derive`, or `serde/derive` for one dependency's — and finds every
+//! `Cargo.toml` in scope that enables it, through a dependency's `features`
+//! (workspace inheritance resolved), `[workspace.dependencies]`, or an entry
+//! of its own `[features]`.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+
+use crate::error::TilthError;
+use crate::read::outline::cargo::{self, KeyLines};
+
+/// One place a manifest enables the feature.
+struct Site {
+    path: PathBuf,
+    line: Option<u32>,
+    /// `defines`, `dependency`, `workspace dependency`, `feature` or
+    /// `default feature`.
+    kind: &'static str,
+    /// The crate whose manifest it is.
+    package: String,
+    what: String,
+}
+
+/// The query split into its crate, if named, and feature.
+struct Wanted<'a> {
+    krate: Option<&'a str>,
+    feature: &'a str,
+}
+
+impl Wanted<'_> {
+    /// Whether a `[features]` item — `serde/derive`, `serde?/derive`, or a
+    /// feature of `package` itself — is the wanted one.
+    fn is_item(&self, item: &str, package: &str) -> bool {
+        match item.split_once('/') {
+            Some((krate, feature)) => {
+                feature == self.feature
+                    && self.krate.is_none_or(|k| k == krate.trim_end_matches('?'))
+            }
+            None => item == self.feature && self.krate.is_none_or(|k| k == package),
+        }
+    }
+}
+
+pub fn search(query: &str, scope: &Path, limit: usize) -> Result<String, TilthError> {
+    let query = query.trim().trim_matches(['"', '\'']);
+    let wanted = match query.split_once('/') {
+        Some((krate, feature)) => Wanted {
+            krate: Some(krate.trim_end_matches('?')),
+            feature,
+        },
+        None => Wanted {
+            krate: None,
+            feature: query,
+        },
+    };
+    if wanted.feature.is_empty() || wanted.krate.is_some_and(str::is_empty) {
+        return Err(TilthError::InvalidQuery {
+            query: query.to_string(),
+            reason: "expected a feature name, e.g. \"derive\" or \"serde/derive\"".into(),
+        });
+    }
+
+    let manifests =
+        crate::analyze::collect_files(scope, |p| p.file_name().is_some_and(|n| n == "Cargo.toml"));
+    let mut found: Vec<Site> = Vec::new();
+    for path in &manifests {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let Ok(manifest) = content.parse::<Value>() else {
+            continue;
+        };
+        let workspace = cargo::workspace_of(path);
+        found.extend(sites(
+            path,
+            &manifest,
+            &content,
+            workspace.as_ref(),
+            &wanted,
+        ));
+    }
+    let total = found.len();
+    let crates = {
+        let mut paths: Vec<&Path> = found.iter().map(|s| s.path.as_path()).collect();
+        paths.dedup();
+        paths.len()
+    };
+    found.truncate(limit);
+
+    let mut out = format!(
+        "# Feature: \"{query}\" in {} — {total} sites in {crates} manifests",
+        scope.display()
+    );
+    if total > found.len() {
+        let _ = write!(out, " (first {} shown)", found.len());
+    }
+    for site in &found {
+        let _ = write!(out, "\n\n## {}", super::rel(&site.path, scope));
+        if let Some(line) = site.line {
+            let _ = write!(out, ":{line}");
+        }
+        let _ = write!(out, " [{}] {}: {}", site.kind, site.package, site.what);
+        let text = site.line.and_then(|n| {
+            std::fs::read_to_string(&site.path)
+                .ok()?
+                .lines()
+                .nth(n as usize - 1)
+                .map(|l| super::excerpt::fit(l.trim_end()).into_owned())
+        });
+        if let (Some(line), Some(text)) = (site.line, text) {
+            let _ = write!(out, "\n{line:>4} │ {text}");
+        }
+    }
+    if total == 0 {
+        let _ = write!(
+            out,
+            "\n\nNo Cargo.toml in scope ({} found) enables it.",
+            manifests.len()
+        );
+    }
+    Ok(out)
+}
+
+/// Where `manifest` defines or enables the wanted feature, in line order.
+fn sites(
+    path: &Path,
+    manifest: &Value,
+    content: &str,
+    workspace: Option<&Value>,
+    wanted: &Wanted,
+) -> Vec<Site> {
+    let lines = KeyLines::scan(content);
+    let package = manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(Value::as_str)
+        .map_or_else(
+            || {
+                path.parent()
+                    .and_then(Path::file_name)
+                    .map_or_else(String::new, |n| n.to_string_lossy().into_owned())
+            },
+            str::to_string,
+        );
+    let site = |line, kind, what: String| Site {
+        path: path.to_path_buf(),
+        line,
+        kind,
+        package: package.clone(),
+        what,
+    };
+    let mut found = Vec::new();
+
+    let features = manifest.get("features").and_then(Value::as_table);
+    if wanted.krate.is_none_or(|k| k == package) {
+        if let Some(enables) = features.and_then(|f| f.get(wanted.feature)) {
+            let items: Vec<&str> = enables
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            found.push(site(
+                lines.get("features", wanted.feature),
+                "defines",
+                format!("{} = [{}]", wanted.feature, items.join(", ")),
+            ));
+        }
+    }
+
+    for dep in cargo::dependencies(manifest, workspace) {
+        let krate = dep.package.as_deref().unwrap_or(&dep.name);
+        if !dep.features.iter().any(|f| f == wanted.feature)
+            || wanted.krate.is_some_and(|k| k != krate && k != dep.name)
+        {
+            continue;
+        }
+        let mut what = format!("{} +{}", dep.name, wanted.feature);
+        if dep.optional {
+            what.push_str(" (optional)");
+        }
+        if dep.inherited {
+            what.push_str(" (workspace)");
+        }
+        if dep.table != "dependencies" {
+            let _ = write!(what, " in {}", dep.table);
+        }
+        found.push(site(lines.get(&dep.table, &dep.name), "dependency", what));
+    }
+
+    if let Some(shared) = manifest
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(Value::as_table)
+    {
+        for (name, spec) in shared {
+            let krate = spec.get("package").and_then(Value::as_str).unwrap_or(name);
+            let enables = spec
+                .get("features")
+                .and_then(Value::as_array)
+                .is_some_and(|f| f.iter().any(|f| f.as_str() == Some(wanted.feature)));
+            if enables && wanted.krate.is_none_or(|k| k == krate || k == name) {
+                found.push(site(
+                    lines.get("workspace.dependencies", name),
+                    "workspace dependency",
+                    format!("{name} +{} for members that inherit it", wanted.feature),
+                ));
+            }
+        }
+    }
+
+    for (name, enables) in features.into_iter().flatten() {
+        for item in enables
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if wanted.is_item(item, &package) {
+                let kind = if name == "default" {
+                    "default feature"
+                } else {
+                    "feature"
+                };
+                found.push(site(
+                    lines.get("features", name),
+                    kind,
+                    format!("{name} → {item}"),
+                ));
+            }
+        }
+    }
+
+    found.sort_by_key(|s| s.line);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"[package]
+name = "app"
+
+[features]
+default = ["json"]
+json = ["dep:serde_json", "serde/derive"]
+derive = []
+
+[dependencies]
+serde = { workspace = true }
+serde_json = { version = "1", optional = true, features = ["derive"] }
+"#;
+
+    fn found(query: &str) -> Vec<(Option<u32>, &'static str, String)> {
+        let (krate, feature) = match query.split_once('/') {
+            Some((k, f)) => (Some(k), f),
+            None => (None, query),
+        };
+        let workspace: Value =
+            "[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n"
+                .parse()
+                .unwrap();
+        let manifest: Value = MANIFEST.parse().unwrap();
+        sites(
+            Path::new("app/Cargo.toml"),
+            &manifest,
+            MANIFEST,
+            Some(&workspace),
+            &Wanted { krate, feature },
+        )
+        .into_iter()
+        .map(|s| (s.line, s.kind, s.what))
+        .collect()
+    }
+
+    #[test]
+    fn finds_dependency_and_feature_sites() {
+        assert_eq!(
+            found("serde/derive"),
+            vec![
+                (Some(6), "feature", "json → serde/derive".to_string()),
+                (
+                    Some(10),
+                    "dependency",
+                    "serde +derive (workspace)".to_string()
+                ),
+            ]
+        );
+        let all = found("derive");
+        let kinds: Vec<&str> = all.iter().map(|s| s.1).collect();
+        assert_eq!(
+            kinds,
+            vec!["feature", "defines", "dependency", "dependency"]
+        );
+    }
+
+    #[test]
+    fn local_features_enable_each_other() {
+        assert_eq!(
+            found("json"),
+            vec![
+                (Some(5), "default feature", "default → json".to_string()),
+                (
+                    Some(6),
+                    "defines",
+                    "json = [dep:serde_json, serde/derive]".to_string()
+                ),
+            ]
+        );
+        assert!(found("other/json").is_empty());
+    }
+}
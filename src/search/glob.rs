@@ -4,7 +4,8 @@ use std::path::{Path, PathBuf};
 use globset::Glob;
 
 use crate::error::TilthError;
-use crate::types::estimate_tokens;
+use crate::read::detect_file_type;
+use crate::tokens;
 
 const MAX_FILES: usize = 20;
 
@@ -110,6 +111,6 @@ pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, TilthError> {
 /// Quick preview: token estimate, or "test file", or "module" based on exports.
 fn file_preview(path: &Path) -> Option<String> {
     let meta = std::fs::metadata(path).ok()?;
-    let tokens = estimate_tokens(meta.len());
+    let tokens = tokens::estimate_len(meta.len(), detect_file_type(path));
     Some(format!("~{tokens} tokens"))
 }
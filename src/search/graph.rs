@@ -0,0 +1,543 @@
+//! Call graphs for `kind: "graph"`: from root symbols, what they call and
+//! what calls them, followed hop by hop to a depth — past the two levels of
+//! the callee footer on an expanded definition. Callees are resolved the way
+//! that footer resolves them (own file, then imports); callers are found the
+//! way `kind: "callers"` finds them, by name.
+//!
+//! A function is one node however many paths lead to it, so the graph can be
+//! drawn as an indented tree, Graphviz DOT or JSON.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::callees::{extract_callee_names, resolve_callees};
+use super::callers::find_callers;
+use super::symbol;
+use crate::cache::{FileContents, OutlineCache};
+use crate::error::TilthError;
+use crate::index::bloom::BloomFilterCache;
+use crate::index::SymbolIndex;
+use crate::types::FileType;
+
+/// Functions a graph holds at most; the walk stops adding past it.
+const MAX_NODES: usize = 200;
+
+/// Which way edges are followed from the roots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// What the roots call (fan-out).
+    Callees,
+    /// What calls the roots (fan-in).
+    Callers,
+    Both,
+}
+
+impl Direction {
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "callees" => Some(Self::Callees),
+            "callers" => Some(Self::Callers),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    fn callees(self) -> bool {
+        self != Self::Callers
+    }
+
+    fn callers(self) -> bool {
+        self != Self::Callees
+    }
+}
+
+/// A function in a call graph.
+#[derive(Debug)]
+pub struct GraphNode {
+    pub name: String,
+    /// Defining file; `None` for a root with no definition in scope.
+    pub path: Option<PathBuf>,
+    /// Line range of the definition; `None` for a file's top-level code.
+    pub range: Option<(u32, u32)>,
+}
+
+/// A directed call graph: an edge runs from caller to callee.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    pub nodes: Vec<GraphNode>,
+    /// `(caller, callee)` indices into `nodes`.
+    pub edges: Vec<(usize, usize)>,
+    pub roots: Vec<usize>,
+    /// Whether the walk stopped at the node cap.
+    pub truncated: bool,
+    /// The depth the walk went to, for the header.
+    depth: u32,
+    ids: HashMap<(String, Option<PathBuf>, u32), usize>,
+    edge_set: HashSet<(usize, usize)>,
+}
+
+impl CallGraph {
+    /// The node for `name` at `path`/`range`, added if new — `None` once
+    /// the graph is full. The flag says whether it was added.
+    fn node(
+        &mut self,
+        name: &str,
+        path: Option<&Path>,
+        range: Option<(u32, u32)>,
+    ) -> Option<(usize, bool)> {
+        let key = (
+            name.to_string(),
+            path.map(Path::to_path_buf),
+            range.map_or(0, |r| r.0),
+        );
+        if let Some(&id) = self.ids.get(&key) {
+            return Some((id, false));
+        }
+        if self.nodes.len() >= MAX_NODES {
+            self.truncated = true;
+            return None;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(GraphNode {
+            name: name.to_string(),
+            path: path.map(Path::to_path_buf),
+            range,
+        });
+        self.ids.insert(key, id);
+        Some((id, true))
+    }
+
+    fn edge(&mut self, from: usize, to: usize) {
+        if self.edge_set.insert((from, to)) {
+            self.edges.push((from, to));
+        }
+    }
+
+    /// What `id` calls, or what calls it, by file and line.
+    fn next(&self, id: usize, callees: bool) -> Vec<usize> {
+        let mut next: Vec<usize> = self
+            .edges
+            .iter()
+            .filter_map(|&(from, to)| {
+                if callees {
+                    (from == id).then_some(to)
+                } else {
+                    (to == id).then_some(from)
+                }
+            })
+            .collect();
+        next.sort_by_key(|&n| (&self.nodes[n].path, self.nodes[n].range));
+        next
+    }
+
+    /// `name  path:start-end`, the path relative to `scope`.
+    fn label(&self, id: usize, scope: &Path) -> String {
+        let node = &self.nodes[id];
+        let place = match (&node.path, node.range) {
+            (Some(path), Some((start, end))) => {
+                format!("{}:{start}-{end}", crate::pathnorm::relative(path, scope))
+            }
+            (Some(path), None) => crate::pathnorm::relative(path, scope),
+            (None, _) => "(no definition in scope)".to_string(),
+        };
+        format!("{}  {place}", node.name)
+    }
+
+    /// Each root with what it calls (`→`) and what calls it (`←`), indented
+    /// by hop. A function already shown in a section ends `(above)` there.
+    #[must_use]
+    pub fn tree(&self, scope: &Path) -> String {
+        // A root defined in several places is named once
+        let mut names = HashSet::new();
+        let mut out = format!(
+            "# Call graph of {} in {} — {} function{}, {} call{}, depth {}",
+            self.roots
+                .iter()
+                .map(|&r| &self.nodes[r].name)
+                .filter(|name| names.insert(*name))
+                .map(|name| format!("\"{name}\""))
+                .collect::<Vec<_>>()
+                .join(", "),
+            scope.display(),
+            self.nodes.len(),
+            if self.nodes.len() == 1 { "" } else { "s" },
+            self.edges.len(),
+            if self.edges.len() == 1 { "" } else { "s" },
+            self.depth,
+        );
+        if self.truncated {
+            let _ = write!(out, " (stopped at {MAX_NODES} functions)");
+        }
+        out.push('\n');
+        for &root in &self.roots {
+            let _ = write!(out, "\n## {}\n", self.label(root, scope));
+            for (callees, heading) in [(true, "calls"), (false, "called by")] {
+                if self.next(root, callees).is_empty() {
+                    continue;
+                }
+                let _ = writeln!(out, "{heading}:");
+                let mut seen = HashSet::from([root]);
+                self.branch(&mut out, root, callees, 1, &mut seen, scope);
+            }
+        }
+        out
+    }
+
+    fn branch(
+        &self,
+        out: &mut String,
+        id: usize,
+        callees: bool,
+        hop: u32,
+        seen: &mut HashSet<usize>,
+        scope: &Path,
+    ) {
+        let arrow = if callees { "→" } else { "←" };
+        for next in self.next(id, callees) {
+            let indent = "  ".repeat(hop as usize);
+            let label = self.label(next, scope);
+            if !seen.insert(next) {
+                let _ = writeln!(out, "{indent}{arrow} {label} (above)");
+                continue;
+            }
+            let _ = writeln!(out, "{indent}{arrow} {label}");
+            if hop < self.depth {
+                self.branch(out, next, callees, hop + 1, seen, scope);
+            }
+        }
+    }
+
+    /// A Graphviz `digraph`, roots boxed.
+    #[must_use]
+    pub fn dot(&self, scope: &Path) -> String {
+        let mut out = String::from("digraph calls {\n  rankdir=LR;\n");
+        for id in 0..self.nodes.len() {
+            let label = self.label(id, scope).replacen("  ", "\\n", 1);
+            let shape = if self.roots.contains(&id) {
+                ", shape=box"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "  n{id} [label=\"{}\"{shape}];",
+                label.replace('"', "\\\"")
+            );
+        }
+        for (from, to) in &self.edges {
+            let _ = writeln!(out, "  n{from} -> n{to};");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// `{"roots", "nodes", "edges", "truncated"}`, nodes numbered by `id`.
+    #[must_use]
+    pub fn json(&self, scope: &Path) -> String {
+        let nodes: Vec<serde_json::Value> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| {
+                serde_json::json!({
+                    "id": id,
+                    "name": node.name,
+                    "path": node.path.as_deref().map(|p| crate::pathnorm::relative(p, scope)),
+                    "start_line": node.range.map(|r| r.0),
+                    "end_line": node.range.map(|r| r.1),
+                })
+            })
+            .collect();
+        let edges: Vec<serde_json::Value> = self
+            .edges
+            .iter()
+            .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+            .collect();
+        serde_json::json!({
+            "roots": self.roots,
+            "nodes": nodes,
+            "edges": edges,
+            "truncated": self.truncated,
+        })
+        .to_string()
+    }
+}
+
+/// The call graph around `roots` under `scope`, `depth` hops each way
+/// `direction` allows. A root with several definitions gets a node for
+/// each; one with none is still a node, for its callers.
+pub fn build_call_graph(
+    scope: &Path,
+    roots: &[&str],
+    direction: Direction,
+    depth: u32,
+    cache: &OutlineCache,
+    bloom: &BloomFilterCache,
+    index: &SymbolIndex,
+) -> Result<CallGraph, TilthError> {
+    if direction.callers() && index.records_usages() && !index.is_built(scope) {
+        crate::index::warm(scope, index, bloom, cache);
+    }
+    let files = FileContents::default();
+    let mut graph = CallGraph {
+        depth,
+        ..CallGraph::default()
+    };
+
+    for &root in roots {
+        let result = symbol::search(root, scope, None)?;
+        let mut found = false;
+        for m in &result.matches {
+            let Some(range) = m
+                .def_range
+                .filter(|_| m.is_definition && m.impl_target.is_none())
+            else {
+                continue;
+            };
+            let name = m.def_name.as_deref().unwrap_or(root);
+            if let Some((id, _)) = graph.node(name, Some(&m.path), Some(range)) {
+                graph.roots.push(id);
+                found = true;
+            }
+        }
+        if !found {
+            if let Some((id, _)) = graph.node(root, None, None) {
+                graph.roots.push(id);
+            }
+        }
+    }
+    graph.roots.dedup();
+
+    if direction.callees() {
+        let mut frontier = graph.roots.clone();
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in frontier {
+                for (name, path, range) in callees_of(&graph.nodes[id], cache, bloom, &files) {
+                    let Some((callee, added)) = graph.node(&name, Some(&path), Some(range)) else {
+                        break;
+                    };
+                    graph.edge(id, callee);
+                    if added {
+                        next.push(callee);
+                    }
+                }
+            }
+            frontier = next;
+        }
+    }
+
+    if direction.callers() {
+        let mut frontier = graph.roots.clone();
+        // Callers are found by name, so functions sharing one share them
+        let mut walked: HashMap<String, Vec<usize>> = HashMap::new();
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in frontier {
+                let name = graph.nodes[id].name.clone();
+                // `<top-level>` and `<anonymous>` have no name to call them by
+                if name.starts_with('<') {
+                    continue;
+                }
+                if let Some(callers) = walked.get(&name) {
+                    for &from in callers {
+                        graph.edge(from, id);
+                    }
+                    continue;
+                }
+                let mut callers = Vec::new();
+                for caller in find_callers(&name, scope, bloom, index)? {
+                    let Some((from, added)) = graph.node(
+                        &caller.calling_function,
+                        Some(&caller.path),
+                        caller.caller_range,
+                    ) else {
+                        break;
+                    };
+                    graph.edge(from, id);
+                    callers.push(from);
+                    if added {
+                        next.push(from);
+                    }
+                }
+                walked.insert(name, callers);
+            }
+            frontier = next;
+        }
+    }
+
+    Ok(graph)
+}
+
+/// The functions `node`'s body calls that resolve to a definition, as
+/// `(name, path, range)`.
+fn callees_of(
+    node: &GraphNode,
+    cache: &OutlineCache,
+    bloom: &BloomFilterCache,
+    files: &FileContents,
+) -> Vec<(String, PathBuf, (u32, u32))> {
+    let (Some(path), Some(range)) = (&node.path, node.range) else {
+        return Vec::new();
+    };
+    let FileType::Code(lang) = crate::read::detect_file_type(path) else {
+        return Vec::new();
+    };
+    let Some(content) = files.get(path) else {
+        return Vec::new();
+    };
+    let names = extract_callee_names(&content, lang, Some(range));
+    let mut resolved: Vec<_> = resolve_callees(&names, path, &content, cache, bloom, files)
+        .into_iter()
+        .map(|c| (c.name, c.file, (c.start_line, c.end_line)))
+        .collect();
+    resolved.sort();
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> CallGraph {
+        let mut graph = CallGraph {
+            depth: 2,
+            ..CallGraph::default()
+        };
+        let file = Path::new("/repo/src/lib.rs");
+        let (load, _) = graph.node("load", Some(file), Some((5, 7))).unwrap();
+        let (parse, _) = graph.node("parse", Some(file), Some((9, 11))).unwrap();
+        let (main, _) = graph
+            .node("main", Some(Path::new("/repo/src/main.rs")), Some((1, 4)))
+            .unwrap();
+        graph.roots.push(load);
+        graph.edge(load, parse);
+        graph.edge(parse, load);
+        graph.edge(main, load);
+        graph.edge(main, load);
+        graph
+    }
+
+    #[test]
+    fn renders_tree_dot_and_json() {
+        let graph = sample();
+        let scope = Path::new("/repo");
+        assert_eq!(
+            graph.tree(scope),
+            "# Call graph of \"load\" in /repo — 3 functions, 3 calls, depth 2\n\
+             \n## load  src/lib.rs:5-7\n\
+             calls:\n\
+             \x20 → parse  src/lib.rs:9-11\n\
+             \x20   → load  src/lib.rs:5-7 (above)\n\
+             called by:\n\
+             \x20 ← parse  src/lib.rs:9-11\n\
+             \x20   ← load  src/lib.rs:5-7 (above)\n\
+             \x20 ← main  src/main.rs:1-4\n"
+        );
+        let dot = graph.dot(scope);
+        assert!(
+            dot.contains("n0 [label=\"load\\nsrc/lib.rs:5-7\", shape=box];"),
+            "{dot}"
+        );
+        assert!(dot.contains("n2 -> n0;"), "{dot}");
+        let json: serde_json::Value = serde_json::from_str(&graph.json(scope)).unwrap();
+        assert_eq!(json["nodes"][2]["name"], "main");
+        assert_eq!(json["edges"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn walks_callees_and_callers_transitively() {
+        let dir = std::env::temp_dir().join(format!("tilth_graph_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("lib.rs"),
+            "fn main() {\n    run();\n}\n\nfn run() {\n    load();\n}\n\nfn load() -> u32 {\n    parse()\n}\n\nfn parse() -> u32 {\n    digit()\n}\n\nfn digit() -> u32 {\n    1\n}\n",
+        )
+        .unwrap();
+
+        let names = |graph: &CallGraph| -> Vec<(String, String)> {
+            let mut edges: Vec<_> = graph
+                .edges
+                .iter()
+                .map(|&(a, b)| (graph.nodes[a].name.clone(), graph.nodes[b].name.clone()))
+                .collect();
+            edges.sort();
+            edges
+        };
+        let (cache, bloom, index) = (
+            OutlineCache::new(),
+            BloomFilterCache::new(),
+            SymbolIndex::new(),
+        );
+        let graph =
+            build_call_graph(&dir, &["load"], Direction::Both, 2, &cache, &bloom, &index).unwrap();
+        let edge = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(
+            names(&graph),
+            vec![
+                edge("load", "parse"),
+                edge("main", "run"),
+                edge("parse", "digit"),
+                edge("run", "load"),
+            ]
+        );
+        let graph = build_call_graph(
+            &dir,
+            &["load"],
+            Direction::Callees,
+            1,
+            &cache,
+            &bloom,
+            &index,
+        )
+        .unwrap();
+        assert_eq!(names(&graph), vec![edge("load", "parse")]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn same_named_roots_share_their_callers() {
+        let dir = std::env::temp_dir().join(format!("tilth_graph_twin_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.rs"), "fn reached() -> bool {\n    true\n}\n").unwrap();
+        std::fs::write(dir.join("b.rs"), "fn reached() -> bool {\n    false\n}\n").unwrap();
+        std::fs::write(dir.join("main.rs"), "fn main() {\n    reached();\n}\n").unwrap();
+
+        let (cache, bloom, index) = (
+            OutlineCache::new(),
+            BloomFilterCache::new(),
+            SymbolIndex::new(),
+        );
+        let graph = build_call_graph(
+            &dir,
+            &["reached"],
+            Direction::Callers,
+            1,
+            &cache,
+            &bloom,
+            &index,
+        )
+        .unwrap();
+        assert_eq!(graph.roots.len(), 2);
+        for &root in &graph.roots {
+            let callers: Vec<&str> = graph
+                .next(root, false)
+                .into_iter()
+                .map(|id| graph.nodes[id].name.as_str())
+                .collect();
+            assert_eq!(callers, vec!["main"]);
+        }
+        assert!(
+            graph
+                .tree(&dir)
+                .starts_with("# Call graph of \"reached\" in "),
+            "{}",
+            graph.tree(&dir)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,244 @@
+//! Follow-up queries for searches that found too much, or only usages.
+//! Each hint is a parameter the caller can paste into the next call instead
+//! of a generic "narrow with scope".
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::index::SymbolIndex;
+use crate::types::SearchResult;
+
+/// Footer for a result cut down to its top matches: how many were dropped,
+/// then the retries that would shrink the list.
+pub(crate) fn more_matches(result: &SearchResult) -> String {
+    let omitted = result.total_found.saturating_sub(result.matches.len());
+    if omitted == 0 {
+        return String::new();
+    }
+    let shown_defs = result.matches.iter().filter(|m| m.is_definition).count();
+    let omitted_defs = result.definitions.saturating_sub(shown_defs);
+    let mut out = if result.definitions > 0 {
+        format!(
+            "\n\n... and {omitted} more matches ({omitted_defs} definitions, {} usages).",
+            omitted.saturating_sub(omitted_defs)
+        )
+    } else {
+        format!("\n\n... and {omitted} more matches.")
+    };
+
+    let total = result.match_paths.len();
+    let mut hinted = false;
+    match covering_scopes(&result.match_paths, &result.scope).as_slice() {
+        [] => {}
+        [(dir, n)] => {
+            let _ = write!(
+                out,
+                "\nRetry with scope=\"{}\" — {n} of {total} matches.",
+                display_scope(&result.scope.join(dir))
+            );
+            hinted = true;
+        }
+        [(a, na), (b, nb), ..] => {
+            let _ = write!(
+                out,
+                "\nRetry with scope=\"{}\" ({na} of {total} matches) or scope=\"{}\" ({nb}).",
+                display_scope(&result.scope.join(a)),
+                display_scope(&result.scope.join(b))
+            );
+            hinted = true;
+        }
+    }
+    if omitted_defs > 0 && shown_defs >= super::limits::current().definitions {
+        let _ = write!(
+            out,
+            "\nRetry with max_definitions={} — every definition.",
+            result.definitions
+        );
+        hinted = true;
+    }
+    if result.definitions > 0 && result.usages > result.definitions * 4 {
+        out.push_str("\nRetry with kind=\"callers\" — call sites only.");
+        hinted = true;
+    }
+    if !hinted {
+        out.push_str(" Narrow with scope.");
+    }
+    out
+}
+
+/// For a symbol search that found usages but no definition in scope: defined
+/// names close to the query, since the name may be misspelled or re-exported
+/// under another one.
+pub(crate) fn no_definition(result: &SearchResult, index: &SymbolIndex) -> String {
+    if result.definitions > 0 || result.usages == 0 {
+        return String::new();
+    }
+    if !index.is_built(&result.scope) {
+        index.build(&result.scope);
+    }
+    let names: Vec<String> = index
+        .suggest(&result.query, &result.scope, 3)
+        .into_iter()
+        .filter(|n| *n != result.query)
+        .map(|n| format!("`{n}`"))
+        .collect();
+    if names.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n\nNo definition in scope. Similar symbols: {}.",
+        names.join(", ")
+    )
+}
+
+/// Footnote for a result that leans on files tree-sitter couldn't parse —
+/// their definitions came from a keyword match, so may be missing or wrong.
+pub(crate) fn unparsed(result: &SearchResult) -> String {
+    let n = result.unparsed.len();
+    if n == 0 {
+        return String::new();
+    }
+    let shown: Vec<String> = result
+        .unparsed
+        .iter()
+        .take(3)
+        .map(|p| display_scope(p))
+        .collect();
+    let more = if n > 3 { ", ..." } else { "" };
+    let files = if n == 1 { "file" } else { "files" };
+    format!(
+        "\n\n{n} {files} in scope could not be parsed; definitions there are matched by keyword: {}{more}",
+        shown.join(", ")
+    )
+}
+
+/// Footnote naming the other paths of files matched under several — the
+/// matches above are listed once, under the first.
+pub(crate) fn aliases(result: &SearchResult) -> String {
+    let mut out = String::new();
+    for (kept, others) in &result.aliases {
+        let others: Vec<String> = others.iter().map(|p| display_scope(p)).collect();
+        let _ = write!(
+            out,
+            "\n\nSame file as {}, listed once: {}",
+            display_scope(kept),
+            others.join(", ")
+        );
+    }
+    out
+}
+
+/// Up to two directories under `scope`, as deep as possible, that together
+/// hold at least 80% of the matches — one when a single directory does.
+/// Relative to `scope`, busiest first. Empty when no split narrows anything.
+fn covering_scopes(paths: &[PathBuf], scope: &Path) -> Vec<(PathBuf, usize)> {
+    let total = paths.len();
+    let mut counts: BTreeMap<&Path, usize> = BTreeMap::new();
+    for path in paths {
+        let Ok(rel) = path.strip_prefix(scope) else {
+            continue;
+        };
+        for dir in rel.ancestors().skip(1) {
+            if dir.as_os_str().is_empty() {
+                break;
+            }
+            *counts.entry(dir).or_default() += 1;
+        }
+    }
+    let covers = |n: usize| n * 5 >= total * 4;
+    let depth = |dir: &Path| dir.components().count();
+
+    if let Some((dir, n)) = counts
+        .iter()
+        .filter(|&(_, &n)| covers(n) && n < total)
+        .max_by_key(|(dir, _)| depth(dir))
+    {
+        return vec![(dir.to_path_buf(), *n)];
+    }
+
+    let dirs: Vec<(&Path, usize)> = counts.into_iter().collect();
+    let mut best: Option<((usize, usize), usize, usize)> = None;
+    for (i, &(a, na)) in dirs.iter().enumerate() {
+        for (j, &(b, nb)) in dirs.iter().enumerate().skip(i + 1) {
+            if a.starts_with(b) || b.starts_with(a) || !covers(na + nb) {
+                continue;
+            }
+            let key = (depth(a) + depth(b), na + nb);
+            if best.is_none_or(|(k, ..)| key > k) {
+                best = Some((key, i, j));
+            }
+        }
+    }
+    let Some((_, i, j)) = best else {
+        return Vec::new();
+    };
+    let mut pair = vec![
+        (dirs[i].0.to_path_buf(), dirs[i].1),
+        (dirs[j].0.to_path_buf(), dirs[j].1),
+    ];
+    pair.sort_by_key(|p| std::cmp::Reverse(p.1));
+    pair
+}
+
+/// Scopes are canonicalized before searching; hand them back relative to the
+/// working directory so they can be passed straight into the next call.
+fn display_scope(dir: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .map(|cwd| crate::pathnorm::canonical(&cwd))
+        .and_then(|cwd| dir.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .unwrap_or_else(|| dir.to_path_buf())
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(list: &[&str]) -> Vec<PathBuf> {
+        list.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn one_scope_covers_most_matches() {
+        let scope = Path::new("/repo");
+        let found = paths(&[
+            "/repo/src/server/http.rs",
+            "/repo/src/server/routes/api.rs",
+            "/repo/src/server/routes/admin.rs",
+            "/repo/src/client.rs",
+            "/repo/tests/server.rs",
+        ]);
+        assert_eq!(
+            covering_scopes(&found, scope),
+            vec![(PathBuf::from("src"), 4)]
+        );
+        // Everything under one directory with no split: scoping changes nothing
+        assert!(covering_scopes(&found[1..3], scope).is_empty());
+    }
+
+    #[test]
+    fn two_scopes_when_one_is_not_enough() {
+        let scope = Path::new("/repo");
+        let found = paths(&[
+            "/repo/src/server/a.rs",
+            "/repo/src/server/b.rs",
+            "/repo/src/server/c.rs",
+            "/repo/lib/net/d.rs",
+            "/repo/lib/net/e.rs",
+            "/repo/docs/f.md",
+        ]);
+        assert_eq!(
+            covering_scopes(&found, scope),
+            vec![
+                (PathBuf::from("src/server"), 3),
+                (PathBuf::from("lib/net"), 2)
+            ]
+        );
+        // Spread evenly: no pair reaches 80%
+        assert!(covering_scopes(&found[2..], scope).is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+//! How many matches a search shows, from the `max_definitions`,
+//! `max_usages` and `max_total` arguments. Searches rank everything they
+//! found, then [`Limits::apply`] keeps the best of each kind within its cap.
+//! Content and regex matches count as usages.
+//!
+//! Like the deadline, the limits are process-wide for the call in progress.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::types::Match;
+
+/// Matches shown when the caller doesn't say.
+pub(crate) const DEFAULT_TOTAL: usize = 10;
+
+static DEFINITIONS: AtomicUsize = AtomicUsize::new(usize::MAX);
+static USAGES: AtomicUsize = AtomicUsize::new(usize::MAX);
+static TOTAL: AtomicUsize = AtomicUsize::new(DEFAULT_TOTAL);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Limits {
+    pub definitions: usize,
+    pub usages: usize,
+    pub total: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            definitions: usize::MAX,
+            usages: usize::MAX,
+            total: DEFAULT_TOTAL,
+        }
+    }
+}
+
+impl Limits {
+    /// Cut ranked `matches` to the best definitions and usages within their
+    /// caps, then to the best `total` of those, keeping the ranked order.
+    pub(crate) fn apply(self, matches: &mut Vec<Match>) {
+        let (mut definitions, mut usages) = (0, 0);
+        matches.retain(|m| {
+            let count = if m.is_definition {
+                &mut definitions
+            } else {
+                &mut usages
+            };
+            let cap = if m.is_definition {
+                self.definitions
+            } else {
+                self.usages
+            };
+            *count += 1;
+            *count <= cap
+        });
+        matches.truncate(self.total);
+    }
+
+    /// Raw matches a walk gathers before it stops early — enough to rank
+    /// from, at three per shown match.
+    pub(crate) fn early_quit(self) -> usize {
+        self.total.saturating_mul(3)
+    }
+}
+
+/// The limits of the call in progress.
+pub(crate) fn current() -> Limits {
+    Limits {
+        definitions: DEFINITIONS.load(Ordering::Relaxed),
+        usages: USAGES.load(Ordering::Relaxed),
+        total: TOTAL.load(Ordering::Relaxed),
+    }
+}
+
+/// Sets the limits for one call; back to the defaults on drop.
+pub(crate) struct Scoped(());
+
+impl Scoped {
+    pub(crate) fn set(limits: Limits) -> Self {
+        store(limits);
+        Self(())
+    }
+}
+
+impl Drop for Scoped {
+    fn drop(&mut self) {
+        store(Limits::default());
+    }
+}
+
+fn store(limits: Limits) {
+    DEFINITIONS.store(limits.definitions, Ordering::Relaxed);
+    USAGES.store(limits.usages, Ordering::Relaxed);
+    TOTAL.store(limits.total, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn hit(line: u32, is_definition: bool) -> Match {
+        Match {
+            path: PathBuf::from("a.rs"),
+            line,
+            column: 0,
+            text: String::new(),
+            is_definition,
+            exact: true,
+            file_lines: 10,
+            mtime: SystemTime::UNIX_EPOCH,
+            def_range: None,
+            def_name: None,
+            def_weight: 0,
+            impl_target: None,
+            trait_method: None,
+        }
+    }
+
+    #[test]
+    fn caps_each_kind_then_the_total() {
+        let ranked = || {
+            vec![
+                hit(1, true),
+                hit(2, true),
+                hit(3, true),
+                hit(4, false),
+                hit(5, false),
+                hit(6, false),
+            ]
+        };
+        let lines = |limits: Limits| {
+            let mut matches = ranked();
+            limits.apply(&mut matches);
+            matches.iter().map(|m| m.line).collect::<Vec<_>>()
+        };
+        let limits = Limits {
+            definitions: 1,
+            ..Limits::default()
+        };
+        assert_eq!(lines(limits), vec![1, 4, 5, 6]);
+        let limits = Limits {
+            usages: 0,
+            total: 2,
+            ..Limits::default()
+        };
+        assert_eq!(lines(limits), vec![1, 2]);
+    }
+}
@@ -0,0 +1,418 @@
+//! Where a production log line was written: `kind: "log"` takes a
+//! structured line — JSON or `key=value` — and finds the logging calls that
+//! emit its fields.
+//!
+//! The line's own keys (minus the ones every logger adds: time, level,
+//! caller, …) are looked for in the arguments of each logging call in
+//! scope, whether they appear as a string literal (`zap.String("user_id",
+//! …)`), a keyword (`user_id=…`, `user_id = %id`) or template text
+//! (`"user_id={}"`). The message, when the line has one, is matched against
+//! the call's string literals the way `kind: "error"` matches messages.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use super::errors::{literal_words, message_words, similarity};
+use crate::error::TilthError;
+use crate::index::bloom::{regions, string_literals, Region, Syntax};
+use crate::types::FileType;
+
+const MAX_FILE_SIZE: u64 = 500_000;
+/// Lines a logging call may span.
+const MAX_CALL_LINES: usize = 10;
+/// Fields loggers add on their own, not written at the call site.
+const STANDARD_KEYS: &[&str] = &[
+    "ts",
+    "time",
+    "timestamp",
+    "@timestamp",
+    "level",
+    "lvl",
+    "severity",
+    "logger",
+    "caller",
+    "source",
+    "pid",
+    "hostname",
+    "host",
+    "thread",
+    "v",
+    "stacktrace",
+];
+/// Keys holding the message itself.
+const MESSAGE_KEYS: &[&str] = &["msg", "message", "event", "@message"];
+/// Text that starts a logging call.
+const LOG_CALLS: &[&str] = &[
+    "log.",
+    "logger.",
+    "logging.",
+    "log::",
+    "slog.",
+    "zap.",
+    "zerolog.",
+    "logrus.",
+    "console.",
+    "tracing::",
+    "info!(",
+    "warn!(",
+    "error!(",
+    "debug!(",
+    "trace!(",
+    "event!(",
+    ".Info(",
+    ".Warn(",
+    ".Error(",
+    ".Debug(",
+    ".info(",
+    ".warn(",
+    ".warning(",
+    ".error(",
+    ".debug(",
+    ".exception(",
+];
+
+/// What a log line says: its message, if it has one, and the fields the
+/// call site wrote.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Entry {
+    message: Option<String>,
+    keys: Vec<String>,
+}
+
+struct Site {
+    path: PathBuf,
+    line: u32,
+    lines: Vec<String>,
+    matched: Vec<String>,
+    similarity: u32,
+}
+
+/// Logging calls in `scope` ranked by how many of the fields of log `line`
+/// they write, then by how closely their message matches, best `limit`
+/// first.
+pub fn search(line: &str, scope: &Path, limit: usize) -> Result<String, TilthError> {
+    let entry = parse(line);
+    let wanted = entry
+        .message
+        .as_deref()
+        .map(message_words)
+        .unwrap_or_default();
+    if entry.keys.is_empty() && wanted.is_empty() {
+        return Err(TilthError::InvalidQuery {
+            query: line.to_string(),
+            reason: "no fields or message found — pass one JSON or key=value log line".into(),
+        });
+    }
+
+    let files = crate::analyze::collect_files(scope, |p| {
+        matches!(crate::read::detect_file_type(p), FileType::Code(_))
+    });
+    let mut found: Vec<Site> = Vec::new();
+    for path in files {
+        if crate::deadline::reached() {
+            break;
+        }
+        if !std::fs::metadata(&path).is_ok_and(|m| m.len() <= MAX_FILE_SIZE) {
+            continue;
+        }
+        let Some(syntax) = Syntax::for_path(&path) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let relevant = entry.keys.iter().any(|k| content.contains(k.as_str()))
+            || wanted
+                .iter()
+                .any(|w| w.len() > 3 && content.contains(w.as_str()));
+        if !relevant {
+            continue;
+        }
+        for (start, call) in calls(&content, syntax) {
+            let matched: Vec<String> = entry
+                .keys
+                .iter()
+                .filter(|k| has_word(&call, k))
+                .cloned()
+                .collect();
+            let similarity = if wanted.is_empty() {
+                0
+            } else {
+                string_literals(&call, syntax)
+                    .iter()
+                    .filter_map(|(_, literal)| similarity(&wanted, &literal_words(literal)))
+                    .max()
+                    .unwrap_or(0)
+            };
+            // Most of the line's fields, or its message with some of them
+            let fields = matched.len() * 2 >= entry.keys.len().max(1);
+            let message = similarity > 0 && (entry.keys.is_empty() || !matched.is_empty());
+            if !((fields && !matched.is_empty()) || message) {
+                continue;
+            }
+            found.push(Site {
+                path: path.clone(),
+                line: start,
+                lines: call
+                    .lines()
+                    .map(|l| super::excerpt::fit(l).into_owned())
+                    .collect(),
+                matched,
+                similarity,
+            });
+        }
+    }
+    found.sort_by(|a, b| {
+        b.matched
+            .len()
+            .cmp(&a.matched.len())
+            .then(b.similarity.cmp(&a.similarity))
+            .then_with(|| a.path.cmp(&b.path))
+            .then(a.line.cmp(&b.line))
+    });
+    let total = found.len();
+    found.truncate(limit);
+
+    let mut out = format!("# Log: {} in {}", describe(&entry), scope.display());
+    let _ = write!(out, " — {total} call sites");
+    if total > found.len() {
+        let _ = write!(out, " (top {} shown)", found.len());
+    }
+    for site in &found {
+        let _ = write!(
+            out,
+            "\n\n## {}:{} [log call] keys {}/{}",
+            super::rel(&site.path, scope),
+            site.line,
+            site.matched.len(),
+            entry.keys.len()
+        );
+        if site.similarity > 0 {
+            let _ = write!(out, " · msg {}%", site.similarity);
+        }
+        let missing: Vec<&str> = entry
+            .keys
+            .iter()
+            .filter(|k| !site.matched.contains(k))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() && !site.matched.is_empty() {
+            let _ = write!(out, "\n  missing: {}", missing.join(", "));
+        }
+        for (i, text) in site.lines.iter().enumerate() {
+            let _ = write!(out, "\n{:>4} │ {text}", site.line as usize + i);
+        }
+    }
+    if total == 0 {
+        out.push_str("\n\nNo logging call in scope writes these fields. The line may come from middleware or a dependency — try kind: \"strings\" with one of the keys.");
+    }
+    Ok(out)
+}
+
+/// `3 keys (user_id, order_id, status), msg "order shipped"`.
+fn describe(entry: &Entry) -> String {
+    let mut out = format!("{} keys", entry.keys.len());
+    if !entry.keys.is_empty() {
+        let _ = write!(out, " ({})", entry.keys.join(", "));
+    }
+    if let Some(message) = &entry.message {
+        let _ = write!(out, ", msg \"{}\"", super::excerpt::fit(message));
+    }
+    out
+}
+
+/// The message and call-site keys of a JSON or `key=value` log line, which
+/// may follow a prefix such as a timestamp. Words outside `key=value` pairs
+/// count as the message when no key holds one.
+fn parse(line: &str) -> Entry {
+    let line = line.trim();
+    let mut entry = Entry::default();
+    let mut loose = Vec::new();
+    if let Some(json) = line
+        .find('{')
+        .and_then(|at| serde_json::from_str::<serde_json::Value>(&line[at..]).ok())
+    {
+        if let Some(fields) = json.as_object() {
+            collect_json(fields, &mut entry);
+        }
+    } else {
+        for token in logfmt(line) {
+            match token {
+                (Some(key), value) => add_field(&mut entry, key, &value),
+                (None, word) => loose.push(word),
+            }
+        }
+    }
+    if entry.message.is_none() && !loose.is_empty() {
+        entry.message = Some(loose.join(" "));
+    }
+    entry
+}
+
+fn collect_json(fields: &serde_json::Map<String, serde_json::Value>, entry: &mut Entry) {
+    for (key, value) in fields {
+        match value {
+            // Nested objects are usually a group of the call's own fields
+            serde_json::Value::Object(inner) if !STANDARD_KEYS.contains(&key.as_str()) => {
+                collect_json(inner, entry);
+            }
+            serde_json::Value::String(s) => add_field(entry, key, s),
+            _ => add_field(entry, key, ""),
+        }
+    }
+}
+
+fn add_field(entry: &mut Entry, key: &str, value: &str) {
+    if MESSAGE_KEYS.contains(&key) {
+        if entry.message.is_none() && !value.is_empty() {
+            entry.message = Some(value.to_string());
+        }
+    } else if !STANDARD_KEYS.contains(&key) && !entry.keys.iter().any(|k| k == key) {
+        entry.keys.push(key.to_string());
+    }
+}
+
+/// `key=value` pairs of a logfmt line, values unquoted, and the words
+/// between them with no key.
+fn logfmt(line: &str) -> Vec<(Option<&str>, String)> {
+    let mut out = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let key_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '@')))
+            .unwrap_or(rest.len());
+        if key_len > 0 && rest[key_len..].starts_with('=') {
+            let key = &rest[..key_len];
+            let after = &rest[key_len + 1..];
+            let (value, next) = if let Some(quoted) = after.strip_prefix('"') {
+                let mut end = quoted.len();
+                let mut escaped = false;
+                for (i, c) in quoted.char_indices() {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => {
+                            end = i;
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                let next = quoted.get(end + 1..).unwrap_or("");
+                (quoted[..end].replace("\\\"", "\""), next)
+            } else {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (after[..end].to_string(), &after[end..])
+            };
+            out.push((Some(key), value));
+            rest = next.trim_start();
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            out.push((None, rest[..end].to_string()));
+            rest = rest[end..].trim_start();
+        }
+    }
+    out
+}
+
+/// Each logging call in `content`: its 1-based first line and its text,
+/// through the line its parentheses close on. A marker in a string or
+/// comment doesn't start one.
+fn calls(content: &str, syntax: Syntax) -> Vec<(u32, String)> {
+    let mut markers: Vec<usize> = LOG_CALLS
+        .iter()
+        .flat_map(|c| content.match_indices(c).map(|(at, _)| at))
+        .collect();
+    markers.sort_unstable();
+    markers.dedup();
+    let in_code: Vec<usize> = regions(content, syntax, &markers)
+        .into_iter()
+        .zip(markers)
+        .filter_map(|(region, at)| (region == Region::Code).then_some(at))
+        .collect();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let start = lines[i].as_ptr() as usize - content.as_ptr() as usize;
+        let first = in_code.partition_point(|&at| at < start);
+        let Some(at) = in_code
+            .get(first)
+            .filter(|&&at| at < start + lines[i].len())
+            .map(|at| at - start)
+        else {
+            i += 1;
+            continue;
+        };
+        let mut depth = 0i32;
+        let mut end = i;
+        for (j, line) in lines.iter().enumerate().skip(i).take(MAX_CALL_LINES) {
+            let from = if j == i { at } else { 0 };
+            depth += line[from..].matches('(').count() as i32;
+            depth -= line[from..].matches(')').count() as i32;
+            end = j;
+            if depth <= 0 {
+                break;
+            }
+        }
+        out.push((i as u32 + 1, lines[i..=end].join("\n")));
+        i = end + 1;
+    }
+    out
+}
+
+/// Whether `text` has `word` with no identifier character either side.
+fn has_word(text: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(at, _)| {
+        !text[..at].chars().next_back().is_some_and(is_ident)
+            && !text[at + word.len()..].chars().next().is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_and_logfmt_lines() {
+        let json = r#"2024-05-01T10:00:00Z {"level":"info","ts":1714557600,"msg":"order shipped","order_id":"A17","user":{"user_id":42},"latency_ms":12}"#;
+        assert_eq!(
+            parse(json),
+            Entry {
+                message: Some("order shipped".into()),
+                keys: vec!["latency_ms".into(), "order_id".into(), "user_id".into()],
+            }
+        );
+        let logfmt = r#"time=2024-05-01T10:00:00Z level=warn msg="retry \"upstream\" failed" attempt=3 host=web-1 upstream=billing"#;
+        assert_eq!(
+            parse(logfmt),
+            Entry {
+                message: Some("retry \"upstream\" failed".into()),
+                keys: vec!["attempt".into(), "upstream".into()],
+            }
+        );
+        assert_eq!(
+            parse("WARN cache miss key=user:42").message.as_deref(),
+            Some("WARN cache miss")
+        );
+    }
+
+    #[test]
+    fn finds_multi_line_logging_calls() {
+        let src = "fn ship() {\n    info!(\n        order_id = %id,\n        \"order shipped\"\n    );\n    let x = 1;\n    logger.warn(\"late\", extra={\"order_id\": id})\n}\n";
+        let found = calls(src, Syntax::Rust);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, 2);
+        assert!(found[0].1.ends_with(");"));
+        assert!(has_word(&found[0].1, "order_id"));
+        assert!(!has_word("border_id = 1", "order_id"));
+        assert_eq!(found[1].0, 7);
+
+        // Markers quoted or commented out aren't calls
+        let src = "const CALLS: &[&str] = &[\"log.\", \"info!(\"];\n// logger.warn(\"x\")\nfn f() { log::info!(\"ok\"); }\n";
+        let found = calls(src, Syntax::Rust);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 3);
+    }
+}
@@ -1,9 +1,21 @@
+mod aliases;
 pub mod callees;
 pub mod callers;
 pub mod content;
+pub mod describe;
+pub mod docs;
+pub mod errors;
+mod excerpt;
 pub mod facets;
+pub mod features;
 pub mod glob;
+pub mod graph;
+mod hints;
+pub mod limits;
+pub mod logs;
+pub mod qualified;
 pub mod rank;
+pub mod scopes;
 pub mod siblings;
 pub mod strip;
 pub mod symbol;
@@ -12,25 +24,28 @@ pub mod truncate;
 
 use std::collections::HashSet;
 use std::fmt::Write;
-use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::SystemTime;
 
 use ignore::WalkBuilder;
 
-use crate::cache::OutlineCache;
+use crate::cache::{FileContents, OutlineCache};
+use crate::deadline::Deadline;
 use crate::error::TilthError;
 use crate::format;
 use crate::read;
 use crate::session::Session;
-use crate::types::{estimate_tokens, FileType, Match, SearchResult};
+use crate::tokens;
+use crate::types::{FileType, Lang, Match, OutlineEntry, SearchResult, TraitMethod};
+use crate::verbosity::Verbosity;
+
+pub use content::Within;
 
 /// Path relative to scope for cleaner output. Falls back to full path.
 fn rel(path: &Path, scope: &Path) -> String {
-    path.strip_prefix(scope)
-        .unwrap_or(path)
-        .display()
-        .to_string()
+    crate::pathnorm::relative(path, scope)
 }
 
 // Directories that are always skipped — build artifacts, dependencies, VCS internals.
@@ -68,10 +83,24 @@ pub(crate) const SKIP_DIRS: &[&str] = &[
     "target",
     ".bloop",
     ".metals",
+    ".tilth",
 ];
 
+/// Whether a directory named `name` is one of [`SKIP_DIRS`] — ignoring case
+/// on Windows, whose file system does.
+pub(crate) fn is_skipped_dir(name: &str) -> bool {
+    if cfg!(windows) {
+        SKIP_DIRS.iter().any(|dir| dir.eq_ignore_ascii_case(name))
+    } else {
+        SKIP_DIRS.contains(&name)
+    }
+}
+
 const EXPAND_FULL_FILE_THRESHOLD: u64 = 800;
 
+/// Matches shown per facet once the session is over its token budget.
+const TERSE_FACET_CAP: usize = 5;
+
 /// Walk up from `path` to find the nearest package manifest (Cargo.toml,
 /// package.json, go.mod, etc.). Returns the directory containing it.
 pub(crate) fn package_root(path: &Path) -> Option<&Path> {
@@ -96,9 +125,19 @@ pub(crate) fn package_root(path: &Path) -> Option<&Path> {
     }
 }
 
+/// Files the search walker has offered to callers since startup.
+static WALKED_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Files visited by [`walker`] since startup, for `tilth_session` `action: "perf"`.
+pub(crate) fn walked_files() -> usize {
+    WALKED_FILES.load(Ordering::Relaxed)
+}
+
 /// Build a parallel directory walker that searches ALL files except known junk directories.
 /// Does NOT respect .gitignore — ensures gitignored but locally-relevant files are found.
 pub(crate) fn walker(scope: &Path) -> ignore::WalkParallel {
+    let visible = crate::redact::visible();
+    let deadline = crate::deadline::Deadline::current();
     WalkBuilder::new(scope)
         .hidden(false)
         .git_ignore(false)
@@ -106,11 +145,17 @@ pub(crate) fn walker(scope: &Path) -> ignore::WalkParallel {
         .git_exclude(false)
         .ignore(false)
         .parents(false)
-        .filter_entry(|entry| {
+        .filter_entry(move |entry| {
+            // Past the call's deadline: skip everything so the walk winds down
+            if deadline.as_ref().is_some_and(Deadline::reached) || !visible(entry.path()) {
+                return false;
+            }
             if entry.file_type().is_some_and(|ft| ft.is_dir()) {
                 if let Some(name) = entry.file_name().to_str() {
-                    return !SKIP_DIRS.contains(&name);
+                    return !is_skipped_dir(name);
                 }
+            } else {
+                WALKED_FILES.fetch_add(1, Ordering::Relaxed);
             }
             true
         })
@@ -118,7 +163,7 @@ pub(crate) fn walker(scope: &Path) -> ignore::WalkParallel {
 }
 
 /// Parse `/pattern/` regex syntax. Returns (pattern, `is_regex`).
-fn parse_pattern(query: &str) -> (&str, bool) {
+pub(crate) fn parse_pattern(query: &str) -> (&str, bool) {
     if query.starts_with('/') && query.ends_with('/') && query.len() > 2 {
         (&query[1..query.len() - 1], true)
     } else {
@@ -146,7 +191,15 @@ pub fn search_symbol(
 ) -> Result<String, TilthError> {
     let result = symbol::search(query, scope, None)?;
     let bloom = crate::index::bloom::BloomFilterCache::new();
-    format_search_result(&result, cache, None, &bloom, 0)
+    let mut out = format_search_result(&result, cache, None, &bloom, 0)?;
+    if result.total_found == 0 || result.definitions == 0 {
+        let index = crate::index::SymbolIndex::new();
+        if result.total_found == 0 {
+            out.push_str(&did_you_mean(query, scope, &index));
+        }
+        out.push_str(&hints::no_definition(&result, &index));
+    }
+    Ok(out)
 }
 
 pub fn search_symbol_expanded(
@@ -159,12 +212,84 @@ pub fn search_symbol_expanded(
     expand: usize,
     context: Option<&Path>,
 ) -> Result<String, TilthError> {
-    // Index is available but not yet used for search fast-path.
-    // Build will be triggered when the lookup path is wired in.
-    let _ = index;
+    let qualified = qualified::try_search(query, scope, context)?;
+    let narrowed = qualified.is_some();
+    let result = if let Some(result) = qualified {
+        result
+    } else {
+        if let Some(name) = crate::classify::identifier_in(query) {
+            return search_punctuated(
+                query, name, scope, cache, session, index, bloom, expand, context,
+            );
+        }
+        symbol::search(query, scope, context)?
+    };
+    let mut out = format_search_result(&result, cache, Some(session), bloom, expand)?;
+    if narrowed {
+        out = label_header(&out, &format!("[qualified, from \"{query}\"]"));
+    }
+    if result.total_found == 0 {
+        out.push_str(&did_you_mean(query, scope, index));
+    }
+    out.push_str(&hints::no_definition(&result, index));
+    Ok(out)
+}
 
-    let result = symbol::search(query, scope, context)?;
-    format_search_result(&result, cache, Some(session), bloom, expand)
+/// Symbol search for a query that isn't a bare identifier — `Vec<u8>`,
+/// `operator==`, `#[derive(Serialize)]`. The query is matched as exact text,
+/// then `name`, the identifier inside it, as a symbol; each section's header
+/// is labelled, and a line appears only once, definitions taking precedence.
+fn search_punctuated(
+    query: &str,
+    name: &str,
+    scope: &Path,
+    cache: &OutlineCache,
+    session: &Session,
+    index: &crate::index::SymbolIndex,
+    bloom: &crate::index::bloom::BloomFilterCache,
+    expand: usize,
+    context: Option<&Path>,
+) -> Result<String, TilthError> {
+    let mut exact = content::search(query, scope, false, context, Within::Anywhere)?;
+    let mut symbol = symbol::search(name, scope, context)?;
+
+    let at = |matches: &[Match], m: &Match, defs_only: bool| {
+        matches
+            .iter()
+            .any(|o| (o.is_definition || !defs_only) && o.path == m.path && o.line == m.line)
+    };
+    let before = exact.matches.len();
+    exact.matches.retain(|m| !at(&symbol.matches, m, true));
+    exact.total_found -= before - exact.matches.len();
+    let before = symbol.matches.len();
+    symbol
+        .matches
+        .retain(|m| m.is_definition || !at(&exact.matches, m, false));
+    let dropped = before - symbol.matches.len();
+    symbol.total_found -= dropped;
+    symbol.usages = symbol.usages.saturating_sub(dropped);
+
+    let exact_out = format_search_result(&exact, cache, Some(session), bloom, expand)?;
+    let symbol_expand = expand.saturating_sub(exact.matches.len());
+    let mut symbol_out = format_search_result(&symbol, cache, Some(session), bloom, symbol_expand)?;
+    if symbol.total_found == 0 && exact.total_found == 0 {
+        symbol_out.push_str(&did_you_mean(name, scope, index));
+    }
+    symbol_out.push_str(&hints::no_definition(&symbol, index));
+
+    Ok(format!(
+        "{}\n\n---\n{}",
+        label_header(&exact_out, "[exact text]"),
+        label_header(&symbol_out, &format!("[symbol, from \"{query}\"]"))
+    ))
+}
+
+/// Append `label` to the `# Search:` line that starts `out`.
+fn label_header(out: &str, label: &str) -> String {
+    match out.split_once('\n') {
+        Some((head, rest)) => format!("{head} {label}\n{rest}"),
+        None => format!("{out} {label}"),
+    }
 }
 
 pub fn search_multi_symbol_expanded(
@@ -177,8 +302,6 @@ pub fn search_multi_symbol_expanded(
     expand: usize,
     context: Option<&Path>,
 ) -> Result<String, TilthError> {
-    let _ = index; // Available but not yet used for search fast-path
-
     // Shared expand budget: at least 1 slot per query, or explicit expand if higher.
     // expand=0 means no expansion at all.
     let mut expand_remaining = if expand == 0 {
@@ -187,10 +310,16 @@ pub fn search_multi_symbol_expanded(
         expand.max(queries.len())
     };
     let mut expanded_files = HashSet::new();
+    let files = FileContents::default();
     let mut sections = Vec::with_capacity(queries.len());
 
     for query in queries {
-        let result = symbol::search(query, scope, context)?;
+        let qualified = qualified::try_search(query, scope, context)?;
+        let narrowed = qualified.is_some();
+        let result = match qualified {
+            Some(result) => result,
+            None => symbol::search(query, scope, context)?,
+        };
         let mut out = format::search_header(
             &result.query,
             &result.scope,
@@ -198,36 +327,93 @@ pub fn search_multi_symbol_expanded(
             result.definitions,
             result.usages,
         );
+        if narrowed {
+            let _ = write!(out, " [qualified, from \"{query}\"]");
+        }
         format_matches(
             &result.matches,
             &result.scope,
             cache,
             Some(session),
             bloom,
+            &files,
             &mut expand_remaining,
             &mut expanded_files,
             &mut out,
         );
-        if result.total_found > result.matches.len() {
-            let omitted = result.total_found - result.matches.len();
-            let _ = write!(
-                out,
-                "\n\n... and {omitted} more matches. Narrow with scope."
-            );
+        out.push_str(&hints::more_matches(&result));
+        out.push_str(&hints::unparsed(&result));
+        out.push_str(&hints::aliases(&result));
+        if result.total_found == 0 {
+            out.push_str(&did_you_mean(query, scope, index));
         }
+        out.push_str(&hints::no_definition(&result, index));
         sections.push(out);
     }
 
     Ok(sections.join("\n\n---\n"))
 }
 
+/// `Did you mean` hint for a symbol query with no matches, from names in the
+/// symbol index. Builds the index for `scope` on first use. Empty when nothing is close.
+fn did_you_mean(query: &str, scope: &Path, index: &crate::index::SymbolIndex) -> String {
+    if !index.is_built(scope) {
+        index.build(scope);
+    }
+    let names = index.suggest(query, scope, 3);
+    if names.is_empty() {
+        return String::new();
+    }
+    let names: Vec<String> = names.iter().map(|n| format!("`{n}`")).collect();
+    format!("\n\nDid you mean {}?", names.join(", "))
+}
+
+/// Symbol names in `scope` matching `pattern` as a prefix or substring, one
+/// line each with kind and first definition site. Builds the index on first use.
+pub fn complete_symbols(
+    pattern: &str,
+    scope: &Path,
+    index: &crate::index::SymbolIndex,
+    limit: usize,
+) -> String {
+    if !index.is_built(scope) {
+        index.build(scope);
+    }
+    let completions = index.complete(pattern, scope, limit);
+
+    let mut out = format!(
+        "# Complete: \"{pattern}\" in {} — {} symbols",
+        scope.display(),
+        completions.len()
+    );
+    let width = completions
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, defs) in &completions {
+        let first = &defs[0];
+        let _ = write!(
+            out,
+            "\n{name:<width$}  {:<6} {}:{}",
+            first.kind,
+            rel(&first.path, scope),
+            first.line
+        );
+        if defs.len() > 1 {
+            let _ = write!(out, "  (+{} more)", defs.len() - 1);
+        }
+    }
+    out
+}
+
 pub fn search_content(
     query: &str,
     scope: &Path,
     cache: &OutlineCache,
 ) -> Result<String, TilthError> {
     let (pattern, is_regex) = parse_pattern(query);
-    let result = content::search(pattern, scope, is_regex, None)?;
+    let result = content::search(pattern, scope, is_regex, None, Within::Anywhere)?;
     let bloom = crate::index::bloom::BloomFilterCache::new();
     format_search_result(&result, cache, None, &bloom, 0)
 }
@@ -237,13 +423,14 @@ pub fn search_content_expanded(
     scope: &Path,
     cache: &OutlineCache,
     session: &Session,
+    bloom: &crate::index::bloom::BloomFilterCache,
     expand: usize,
     context: Option<&Path>,
+    within: Within,
 ) -> Result<String, TilthError> {
     let (pattern, is_regex) = parse_pattern(query);
-    let result = content::search(pattern, scope, is_regex, context)?;
-    let bloom = crate::index::bloom::BloomFilterCache::new();
-    format_search_result(&result, cache, Some(session), &bloom, expand)
+    let result = content::search(pattern, scope, is_regex, context, within)?;
+    format_search_result(&result, cache, Some(session), bloom, expand)
 }
 
 /// Raw symbol search — returns structured result for programmatic inspection.
@@ -254,7 +441,7 @@ pub fn search_symbol_raw(query: &str, scope: &Path) -> Result<SearchResult, Tilt
 /// Raw content search — returns structured result for programmatic inspection.
 pub fn search_content_raw(query: &str, scope: &Path) -> Result<SearchResult, TilthError> {
     let (pattern, is_regex) = parse_pattern(query);
-    content::search(pattern, scope, is_regex, None)
+    content::search(pattern, scope, is_regex, None, Within::Anywhere)
 }
 
 /// Format a symbol search result (public for Fallthrough path in lib.rs).
@@ -292,6 +479,7 @@ fn format_matches(
     cache: &OutlineCache,
     session: Option<&Session>,
     bloom: &crate::index::bloom::BloomFilterCache,
+    files: &FileContents,
     expand_remaining: &mut usize,
     expanded_files: &mut HashSet<PathBuf>,
     out: &mut String,
@@ -301,14 +489,18 @@ fn format_matches(
     let multi_file = matches
         .first()
         .is_some_and(|first| matches.iter().any(|m| m.path != first.path));
+    let verbosity = crate::verbosity::current();
 
     for m in matches {
-        let kind = if m.impl_target.is_some() {
-            "impl"
-        } else if m.is_definition {
-            "definition"
-        } else {
-            "usage"
+        let kind = match &m.trait_method {
+            Some(TraitMethod::Default { trait_name }) => format!("default in trait {trait_name}"),
+            Some(TraitMethod::Override {
+                trait_name,
+                impl_type,
+            }) => format!("override in impl {trait_name} for {impl_type}"),
+            None if m.impl_target.is_some() => "impl".to_string(),
+            None if m.is_definition => "definition".to_string(),
+            None => "usage".to_string(),
         };
 
         // Show line range for definitions with def_range, otherwise just the line
@@ -321,17 +513,53 @@ fn format_matches(
                     start,
                     end
                 );
+                // What a symbol-addressed tilth_edit checks before replacing it
+                if session.is_some_and(Session::edit_mode) {
+                    if let Some(hash) = body_hash(files, &m.path, start, end) {
+                        let _ = write!(out, " body:{hash:03x}");
+                    }
+                }
             } else {
                 let _ = write!(out, "\n\n## {}:{} [{kind}]", rel(&m.path, scope), m.line);
             }
         } else {
             let _ = write!(out, "\n\n## {}:{} [{kind}]", rel(&m.path, scope), m.line);
+            // Where the usage lives, so the list reads without expansion
+            if let Some(within) = code_outline(&m.path, cache, files)
+                .filter(|_| verbosity.context())
+                .and_then(|outline| enclosing_entry(&outline, m.line))
+            {
+                let _ = write!(out, " in {within}");
+            }
+        }
+
+        out.push_str(&crate::owners::annotation(&m.path));
+        if crate::sourcemap::enabled() {
+            if let Some(content) = files.get(&m.path) {
+                out.push_str(&crate::sourcemap::annotation(
+                    &m.path, &content, m.line, m.column, scope,
+                ));
+            }
+        }
+        if files
+            .get(&m.path)
+            .is_some_and(|c| crate::read::bundle::is_minified(c.as_bytes()))
+        {
+            out.push_str(" [minified]");
+        }
+        if let Some(session) = session {
+            let _ = write!(
+                out,
+                " [{}]",
+                session.breadcrumb(&m.path, m.line, m.def_range)
+            );
+            session.disclose(&m.path, "match", Some(m.line.to_string()));
         }
 
         // Skip outline for small files — the expanded code speaks for itself
-        if m.file_lines < 50 {
+        if !verbosity.context() || (m.file_lines < 50 && verbosity != Verbosity::Rich) {
             let _ = write!(out, "\n→ [{}]   {}", m.line, m.text);
-        } else if let Some(context) = outline_context_for_match(&m.path, m.line, cache) {
+        } else if let Some(context) = outline_context_for_match(&m.path, m.line, cache, files) {
             out.push_str(&context);
         } else {
             let _ = write!(out, "\n→ [{}]   {}", m.line, m.text);
@@ -360,7 +588,13 @@ fn format_matches(
                 // Single-file within one query: expand sequentially (no per-file dedup).
                 let skip = multi_file && expanded_files.contains(&m.path);
                 if !skip {
-                    if let Some((code, content)) = expand_match(m, scope) {
+                    let anchors = session.is_some_and(Session::edit_mode);
+                    if let Some((code, content, (start, end))) =
+                        expand_match(m, scope, files, anchors)
+                    {
+                        if let Some(s) = session {
+                            s.disclose(&m.path, "expansion", Some(format!("{start}-{end}")));
+                        }
                         // Record expansion for future dedup
                         if m.is_definition && m.def_range.is_some() {
                             if let Some(s) = session {
@@ -400,110 +634,55 @@ fn format_matches(
                         out.push('\n');
                         out.push_str(&stripped_code);
 
-                        if m.is_definition && m.def_range.is_some() {
-                            // Definition expansion: transitive callee resolution footer
-                            if let crate::types::FileType::Code(lang) = file_type {
-                                let callee_names =
-                                    callees::extract_callee_names(&content, lang, m.def_range);
-                                if !callee_names.is_empty() {
-                                    let mut nodes = callees::resolve_callees_transitive(
-                                        &callee_names,
+                        if !verbosity.footers() {
+                            // Compact: the code alone
+                        } else if m.is_definition && m.def_range.is_some() {
+                            // Definition expansion: callee and sibling footers
+                            if let (FileType::Code(lang), Some(def_range)) =
+                                (file_type, m.def_range)
+                            {
+                                let own_name = m.def_name.as_deref();
+                                out.push_str(&calls_footer(
+                                    &m.path, &content, lang, def_range, own_name, scope, cache,
+                                    bloom, files,
+                                ));
+                                let entries = callees::get_outline_entries(&content, lang);
+                                out.push_str(&siblings_footer(
+                                    &m.path, &content, lang, &entries, def_range, m.line, own_name,
+                                    scope,
+                                ));
+                            }
+                        } else {
+                            // Usage expansion: the same footers for the enclosing
+                            // function, then related file hints
+                            if let FileType::Code(lang) = file_type {
+                                let entries = callees::get_outline_entries(&content, lang);
+                                if let Some(f) = siblings::find_enclosing_function(&entries, m.line)
+                                {
+                                    let range = (f.start_line, f.end_line);
+                                    out.push_str(&calls_footer(
                                         &m.path,
                                         &content,
+                                        lang,
+                                        range,
+                                        Some(&f.name),
+                                        scope,
                                         cache,
                                         bloom,
-                                        2,  // depth_limit
-                                        15, // budget for 2nd-hop callees
-                                    );
-
-                                    // Filter out self-recursive calls (current function name)
-                                    if let Some(ref name) = m.def_name {
-                                        nodes.retain(|n| n.callee.name != *name);
-                                    }
-
-                                    // Cap 1st-hop at 8, prioritize cross-file over same-file
-                                    if nodes.len() > 8 {
-                                        nodes.sort_by_key(|n| i32::from(n.callee.file == m.path));
-                                        nodes.truncate(8);
-                                    }
-
-                                    if !nodes.is_empty() {
-                                        out.push_str("\n\n\u{2500}\u{2500} calls \u{2500}\u{2500}");
-                                        for n in &nodes {
-                                            let c = &n.callee;
-                                            let _ = write!(
-                                                out,
-                                                "\n  {}  {}:{}-{}",
-                                                c.name,
-                                                rel(&c.file, scope),
-                                                c.start_line,
-                                                c.end_line
-                                            );
-                                            if let Some(ref sig) = c.signature {
-                                                let _ = write!(out, "  {sig}");
-                                            }
-                                            for child in &n.children {
-                                                let _ = write!(
-                                                    out,
-                                                    "\n    \u{2192} {}  {}:{}-{}",
-                                                    child.name,
-                                                    rel(&child.file, scope),
-                                                    child.start_line,
-                                                    child.end_line
-                                                );
-                                                if let Some(ref sig) = child.signature {
-                                                    let _ = write!(out, "  {sig}");
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-
-                                // Sibling surfacing: show referenced fields/methods
-                                // from the same struct/class/impl
-                                if let Some(def_range) = m.def_range {
-                                    let entries = callees::get_outline_entries(&content, lang);
-                                    if let Some(parent) =
-                                        siblings::find_parent_entry(&entries, m.line)
-                                    {
-                                        let refs = siblings::extract_sibling_references(
-                                            &content, lang, def_range,
-                                        );
-                                        if !refs.is_empty() {
-                                            // Filter out the current method itself
-                                            let filtered: Vec<String> =
-                                                if let Some(ref name) = m.def_name {
-                                                    refs.into_iter().filter(|r| r != name).collect()
-                                                } else {
-                                                    refs
-                                                };
-
-                                            let resolved = siblings::resolve_siblings(
-                                                &filtered,
-                                                &parent.children,
-                                            );
-                                            if !resolved.is_empty() {
-                                                out.push_str(
-                                                    "\n\n\u{2500}\u{2500} siblings \u{2500}\u{2500}",
-                                                );
-                                                for s in &resolved {
-                                                    let _ = write!(
-                                                        out,
-                                                        "\n  {}  {}:{}-{}  {}",
-                                                        s.name,
-                                                        rel(&m.path, scope),
-                                                        s.start_line,
-                                                        s.end_line,
-                                                        s.signature,
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
+                                        files,
+                                    ));
+                                    out.push_str(&siblings_footer(
+                                        &m.path,
+                                        &content,
+                                        lang,
+                                        &entries,
+                                        range,
+                                        f.start_line,
+                                        Some(&f.name),
+                                        scope,
+                                    ));
                                 }
                             }
-                        } else {
-                            // Usage expansion: related file hints
                             let related = crate::read::imports::resolve_related_files_with_content(
                                 &m.path, &content,
                             );
@@ -528,6 +707,133 @@ fn format_matches(
     }
 }
 
+/// `── calls ──` footer for the code in `range`: resolved callees, each with
+/// its own callees beneath. `own_name` is left out so recursion isn't listed.
+fn calls_footer(
+    path: &Path,
+    content: &str,
+    lang: Lang,
+    range: (u32, u32),
+    own_name: Option<&str>,
+    scope: &Path,
+    cache: &OutlineCache,
+    bloom: &crate::index::bloom::BloomFilterCache,
+    files: &FileContents,
+) -> String {
+    let callee_names = callees::extract_callee_names(content, lang, Some(range));
+    if callee_names.is_empty() {
+        return String::new();
+    }
+    let verbosity = crate::verbosity::current();
+    let mut nodes = callees::resolve_callees_transitive(
+        &callee_names,
+        path,
+        content,
+        cache,
+        bloom,
+        files,
+        2,                        // depth_limit
+        verbosity.footer_cap(15), // budget for 2nd-hop callees
+    );
+
+    // Filter out self-recursive calls (current function name)
+    if let Some(name) = own_name {
+        nodes.retain(|n| n.callee.name != name);
+    }
+
+    // Cap 1st-hop at 8, prioritize cross-file over same-file
+    let cap = verbosity.footer_cap(8);
+    if nodes.len() > cap {
+        nodes.sort_by_key(|n| i32::from(n.callee.file == path));
+        nodes.truncate(cap);
+    }
+
+    let mut out = String::new();
+    if nodes.is_empty() {
+        return out;
+    }
+    out.push_str("\n\n\u{2500}\u{2500} calls \u{2500}\u{2500}");
+    for n in &nodes {
+        let c = &n.callee;
+        let _ = write!(
+            out,
+            "\n  {}  {}:{}-{}",
+            c.name,
+            rel(&c.file, scope),
+            c.start_line,
+            c.end_line
+        );
+        if let Some(ref sig) = c.signature {
+            let _ = write!(out, "  {sig}");
+        }
+        for child in &n.children {
+            let _ = write!(
+                out,
+                "\n    \u{2192} {}  {}:{}-{}",
+                child.name,
+                rel(&child.file, scope),
+                child.start_line,
+                child.end_line
+            );
+            if let Some(ref sig) = child.signature {
+                let _ = write!(out, "  {sig}");
+            }
+        }
+    }
+    out
+}
+
+/// `── siblings ──` footer: fields and methods of the enclosing
+/// struct/class/impl referenced by the member at `member_line`, whose body
+/// spans `range`.
+fn siblings_footer(
+    path: &Path,
+    content: &str,
+    lang: Lang,
+    entries: &[OutlineEntry],
+    range: (u32, u32),
+    member_line: u32,
+    own_name: Option<&str>,
+    scope: &Path,
+) -> String {
+    let Some(parent) = siblings::find_parent_entry(entries, member_line) else {
+        return String::new();
+    };
+    let refs: Vec<String> = siblings::extract_sibling_references(
+        content,
+        lang,
+        range,
+        siblings::owner_name(parent, lang),
+    )
+    .into_iter()
+    // Filter out the current method itself
+    .filter(|r| Some(r.as_str()) != own_name)
+    .collect();
+    if refs.is_empty() {
+        return String::new();
+    }
+
+    let members = siblings::sibling_members(entries, parent, lang);
+    let resolved = siblings::resolve_siblings(&refs, &members);
+    let mut out = String::new();
+    if resolved.is_empty() {
+        return out;
+    }
+    out.push_str("\n\n\u{2500}\u{2500} siblings \u{2500}\u{2500}");
+    for s in &resolved {
+        let _ = write!(
+            out,
+            "\n  {}  {}:{}-{}  {}",
+            s.name,
+            rel(path, scope),
+            s.start_line,
+            s.end_line,
+            s.signature,
+        );
+    }
+    out
+}
+
 /// Format a symbol/content search result.
 /// When an outline cache is available, wraps each match in the file's outline context.
 /// When `expand > 0`, the top N matches inline actual code (def body or ±10 lines).
@@ -547,94 +853,50 @@ fn format_search_result(
         result.usages,
     );
     let mut out = header;
-    let mut expand_remaining = expand;
+    // Over the session's token budget: headers and signature lines only
+    let terse = session.is_some_and(Session::over_budget);
+    let mut expand_remaining = if terse { 0 } else { expand };
     let mut expanded_files = HashSet::new();
+    let files = FileContents::default();
 
     // Apply faceting when there are many matches (>5)
     if result.matches.len() > 5 {
         let faceted = facets::facet_matches(result.matches.clone(), &result.scope);
 
         // Format each non-empty facet with section headers
-        if !faceted.definitions.is_empty() {
-            let _ = write!(out, "\n\n### Definitions ({})", faceted.definitions.len());
-            format_matches(
-                &faceted.definitions,
-                &result.scope,
-                cache,
-                session,
-                bloom,
-                &mut expand_remaining,
-                &mut expanded_files,
-                &mut out,
-            );
-        }
-
-        if !faceted.implementations.is_empty() {
-            let _ = write!(
-                out,
-                "\n\n### Implementations ({})",
-                faceted.implementations.len()
-            );
-            format_matches(
-                &faceted.implementations,
-                &result.scope,
-                cache,
-                session,
-                bloom,
-                &mut expand_remaining,
-                &mut expanded_files,
-                &mut out,
-            );
-        }
-
-        if !faceted.tests.is_empty() {
-            let _ = write!(out, "\n\n### Tests ({})", faceted.tests.len());
-            format_matches(
-                &faceted.tests,
-                &result.scope,
-                cache,
-                session,
-                bloom,
-                &mut expand_remaining,
-                &mut expanded_files,
-                &mut out,
-            );
-        }
-
-        if !faceted.usages_local.is_empty() {
-            let _ = write!(
-                out,
-                "\n\n### Usages — same package ({})",
-                faceted.usages_local.len()
-            );
-            format_matches(
-                &faceted.usages_local,
-                &result.scope,
-                cache,
-                session,
-                bloom,
-                &mut expand_remaining,
-                &mut expanded_files,
-                &mut out,
-            );
-        }
-
-        if !faceted.usages_cross.is_empty() {
-            let _ = write!(
-                out,
-                "\n\n### Usages — other ({})",
-                faceted.usages_cross.len()
-            );
+        let sections = [
+            ("Definitions", &faceted.definitions),
+            ("Trait defaults", &faceted.trait_defaults),
+            ("Overrides", &faceted.overrides),
+            ("Implementations", &faceted.implementations),
+            ("Tests", &faceted.tests),
+            ("Usages — same package", &faceted.usages_local),
+            ("Usages — other", &faceted.usages_cross),
+        ];
+        for (title, matches) in sections {
+            if matches.is_empty() {
+                continue;
+            }
+            let _ = write!(out, "\n\n### {title} ({})", matches.len());
+            let shown = if terse {
+                &matches[..matches.len().min(TERSE_FACET_CAP)]
+            } else {
+                matches.as_slice()
+            };
             format_matches(
-                &faceted.usages_cross,
+                shown,
                 &result.scope,
                 cache,
                 session,
                 bloom,
+                &files,
                 &mut expand_remaining,
                 &mut expanded_files,
                 &mut out,
             );
+            if shown.len() < matches.len() {
+                let _ = write!(out, "\n\n... {} more", matches.len() - shown.len());
+            }
         }
     } else {
         // Linear display for ≤5 matches
@@ -644,34 +906,51 @@ fn format_search_result(
             cache,
             session,
             bloom,
+            &files,
             &mut expand_remaining,
             &mut expanded_files,
             &mut out,
         );
     }
 
-    if result.total_found > result.matches.len() {
-        let omitted = result.total_found - result.matches.len();
-        let _ = write!(
-            out,
-            "\n\n... and {omitted} more matches. Narrow with scope."
-        );
-    }
+    out.push_str(&hints::more_matches(result));
+    out.push_str(&hints::unparsed(result));
+    out.push_str(&hints::aliases(result));
     Ok(out)
 }
 
 /// Inline the actual code for a match. Returns `(formatted_block, raw_content)`.
-/// The raw content is returned so the caller can reuse it (e.g. for related-file hints)
-/// without a redundant file read.
+/// The raw content comes from the call's `FileContents`, so callee and sibling
+/// resolution and related-file hints reuse it without another read.
 ///
 /// For definitions: use tree-sitter node range (`def_range`).
 /// For usages: ±10 lines around the match.
-fn expand_match(m: &Match, scope: &Path) -> Option<(String, String)> {
-    let content = fs::read_to_string(&m.path).ok()?;
+/// With `anchors`, lines are hashlined as in edit-mode reads.
+/// [`format::body_hash`] of lines `start..=end` of a file.
+fn body_hash(files: &FileContents, path: &Path, start: u32, end: u32) -> Option<u16> {
+    let content = files.get(path)?;
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let lines: Vec<&str> = content
+        .lines()
+        .skip((start as usize).checked_sub(1)?)
+        .take((end.checked_sub(start)? + 1) as usize)
+        .collect();
+    Some(format::body_hash(&lines))
+}
+
+fn expand_match(
+    m: &Match,
+    scope: &Path,
+    files: &FileContents,
+    anchors: bool,
+) -> Option<(String, Arc<str>, (u32, u32))> {
+    let content = files.get(&m.path)?;
     let lines: Vec<&str> = content.lines().collect();
     let total = lines.len() as u32;
 
-    let (mut start, end) = if estimate_tokens(content.len() as u64) < EXPAND_FULL_FILE_THRESHOLD {
+    let (mut start, end) = if tokens::estimate(content.as_bytes(), read::detect_file_type(&m.path))
+        < EXPAND_FULL_FILE_THRESHOLD
+    {
         (1, total)
     } else {
         let (s, e) = m
@@ -724,12 +1003,21 @@ fn expand_match(m: &Match, scope: &Path) -> Option<(String, String)> {
                 continue;
             }
 
-            let _ = write!(out, "\n{i:>4} │ {line}");
+            if anchors {
+                let hash = format::line_hash(line.as_bytes());
+                let _ = write!(out, "\n{i}:{hash:03x}|{line}");
+            } else if i == m.line {
+                let shown = excerpt::render(line, m.column as usize)
+                    .unwrap_or_else(|| excerpt::fit(line).into_owned());
+                let _ = write!(out, "\n{i:>4} │ {shown}");
+            } else {
+                let _ = write!(out, "\n{i:>4} │ {}", excerpt::fit(line));
+            }
             prev_blank = is_blank;
         }
     }
     out.push_str("\n```");
-    Some((out, content))
+    Some((out, content, (start, end)))
 }
 
 /// Filter formatted code lines using a set of line numbers to skip.
@@ -747,10 +1035,11 @@ fn filter_code_lines(code: &str, skip_lines: &HashSet<u32>) -> String {
             continue;
         }
 
-        // Extract line number from formatted line: "  42 │ content"
+        // Extract line number from formatted line: "  42 │ content", or
+        // "42:a3f|content" when hashlined
         let line_num = segment
-            .find('│')
-            .and_then(|pos| segment[..pos].trim().parse::<u32>().ok());
+            .split_once(['│', ':'])
+            .and_then(|(num, _)| num.trim().parse::<u32>().ok());
 
         if let Some(num) = line_num {
             if skip_lines.contains(&num) {
@@ -783,27 +1072,9 @@ fn outline_context_for_match(
     path: &std::path::Path,
     match_line: u32,
     cache: &OutlineCache,
+    files: &FileContents,
 ) -> Option<String> {
-    let file_type = read::detect_file_type(path);
-    if !matches!(file_type, FileType::Code(_)) {
-        return None;
-    }
-
-    // Get or compute the file's outline
-    let meta = std::fs::metadata(path).ok()?;
-    let mtime = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-    let byte_len = meta.len();
-
-    // Only compute outline context for reasonably sized files
-    if byte_len > 500_000 {
-        return None;
-    }
-
-    let outline_str = cache.get_or_compute(path, mtime, || {
-        let content = std::fs::read_to_string(path).unwrap_or_default();
-        let buf = content.as_bytes();
-        read::outline::generate(path, file_type, &content, buf, false)
-    });
+    let outline_str = code_outline(path, cache, files)?;
 
     // Parse the outline to find entries near the match line
     let outline_lines: Vec<&str> = outline_str.lines().collect();
@@ -831,6 +1102,57 @@ fn outline_context_for_match(
     Some(context)
 }
 
+/// The file's outline, from the cache or computed and cached. `None` for
+/// non-code files and files too large to outline cheaply.
+fn code_outline(path: &Path, cache: &OutlineCache, files: &FileContents) -> Option<Arc<str>> {
+    let file_type = read::detect_file_type(path);
+    if !matches!(file_type, FileType::Code(_)) {
+        return None;
+    }
+
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+
+    // Only compute outline context for reasonably sized files
+    if meta.len() > 500_000 {
+        return None;
+    }
+
+    Some(cache.get_or_compute(path, mtime, || {
+        let content = files.get(path);
+        let buf = content.as_deref().unwrap_or_default().as_bytes();
+        read::outline::generate(path, file_type, buf, false)
+    }))
+}
+
+/// Innermost multi-line outline entry around `line`, as `fn handle (120-180)`.
+/// Later outline lines are nested in or follow earlier ones, so the last
+/// range containing `line` is the innermost.
+fn enclosing_entry(outline: &str, line: u32) -> Option<String> {
+    let (entry, (start, end)) = outline
+        .lines()
+        .rev()
+        .filter_map(|l| Some((l.trim(), extract_line_range(l)?)))
+        .find(|&(_, (s, e))| s < e && s <= line && line <= e)?;
+    let head = entry[entry.find(']')? + 1..]
+        .split("  // ")
+        .next()
+        .unwrap_or("")
+        .trim();
+    // Impl headers are the whole name; otherwise drop leading attributes,
+    // decorators and visibility to keep `kind name`
+    let head = if let Some(i) = head.find("impl ") {
+        head[i..].to_string()
+    } else {
+        let words: Vec<&str> = head.split_whitespace().collect();
+        words[words.len().saturating_sub(2)..].join(" ")
+    };
+    if head.is_empty() {
+        return None;
+    }
+    Some(format!("{head} ({start}-{end})"))
+}
+
 /// Extract (`start_line`, `end_line`) from an outline entry like "[20-115]" or "[16]".
 fn extract_line_range(line: &str) -> Option<(u32, u32)> {
     let trimmed = line.trim();
@@ -886,3 +1208,46 @@ fn format_glob_result(result: &glob::GlobResult, scope: &Path) -> Result<String,
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_enclosing_entry() {
+        let outline = "[1-4]        imports: std::fmt, std::io\n\
+                       [6-40]       impl Display for Server\n\
+                       \x20 [8-20]     fn fmt  // Render the address\n\
+                       [42-90]      #[cfg(test)] mod tests\n\
+                       \x20 [44]       const PORT\n\
+                       \x20 [50-70]    fn binds";
+        assert_eq!(
+            enclosing_entry(outline, 10).as_deref(),
+            Some("fn fmt (8-20)")
+        );
+        assert_eq!(
+            enclosing_entry(outline, 30).as_deref(),
+            Some("impl Display for Server (6-40)")
+        );
+        assert_eq!(
+            enclosing_entry(outline, 44).as_deref(),
+            Some("mod tests (42-90)")
+        );
+        assert_eq!(
+            enclosing_entry(outline, 55).as_deref(),
+            Some("fn binds (50-70)")
+        );
+        assert_eq!(enclosing_entry(outline, 41), None);
+    }
+
+    #[test]
+    fn filter_hashlined_code() {
+        let code = "\n```a.rs:1-6\n1:0aa|fn a() {\n2:0bb|    x();\n3:0cc|    y();\n\
+                    4:0dd|    z();\n5:0ee|    w();\n6:0ff|}\n```";
+        let skip: HashSet<u32> = (2..=5).collect();
+        assert_eq!(
+            filter_code_lines(code, &skip),
+            "\n```a.rs:1-6\n1:0aa|fn a() {\n       ... (4 lines omitted)\n6:0ff|}\n```"
+        );
+    }
+}
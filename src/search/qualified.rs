@@ -0,0 +1,183 @@
+//! Qualified-name queries — `search::rank::sort`, `com.foo.Bar#method`,
+//! `AuthService.validate`. The last segment is searched as a symbol; the
+//! segments before it must each name a directory or file on the match's path,
+//! or a type, module or impl the match sits in.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{callees, siblings, symbol};
+use crate::error::TilthError;
+use crate::read::detect_file_type;
+use crate::types::{FileType, Match, OutlineEntry, SearchResult};
+
+/// Leading segments that say where a path resolves from, not what it names.
+const ANCHORS: &[&str] = &["crate", "self", "super", "this"];
+
+/// A query split at its separators: `::`, `.`, `#` and `\`.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Qualified<'a> {
+    pub qualifiers: Vec<&'a str>,
+    pub name: &'a str,
+}
+
+/// Split a qualified name. None unless there are at least two segments and
+/// each one is a plain identifier.
+pub(crate) fn split(query: &str) -> Option<Qualified<'_>> {
+    let segments: Vec<&str> = query
+        .split([':', '.', '#', '\\'])
+        .filter(|s| !s.is_empty())
+        .collect();
+    if segments.len() < 2 || !segments.iter().all(|s| is_plain_identifier(s)) {
+        return None;
+    }
+    let (name, qualifiers) = segments.split_last()?;
+    Some(Qualified {
+        qualifiers: qualifiers
+            .iter()
+            .copied()
+            .filter(|q| !ANCHORS.contains(q))
+            .collect(),
+        name,
+    })
+}
+
+/// [`search`] for a query that splits as a qualified name, when that finds
+/// anything. None means the query should be searched as written.
+pub(crate) fn try_search(
+    query: &str,
+    scope: &Path,
+    context: Option<&Path>,
+) -> Result<Option<SearchResult>, TilthError> {
+    let Some(q) = split(query) else {
+        return Ok(None);
+    };
+    let result = search(&q, scope, context)?;
+    Ok((result.total_found > 0).then_some(result))
+}
+
+/// Symbol search for `q.name`, keeping the matches every qualifier is found
+/// around, and usages written out qualified (`Bar.method(…)`) wherever they
+/// are. Outlines are only parsed for files whose path doesn't already account
+/// for all the qualifiers.
+pub(crate) fn search(
+    q: &Qualified,
+    scope: &Path,
+    context: Option<&Path>,
+) -> Result<SearchResult, TilthError> {
+    let written: Vec<String> = q.qualifiers.last().map_or_else(Vec::new, |last| {
+        ["::", ".", "#", "\\"]
+            .iter()
+            .map(|sep| format!("{last}{sep}{}", q.name))
+            .collect()
+    });
+    let mut outlines: HashMap<PathBuf, Vec<OutlineEntry>> = HashMap::new();
+    symbol::search_in(q.name, scope, context, |m| {
+        if written.iter().any(|w| m.text.contains(w.as_str())) {
+            return true;
+        }
+        let missing: Vec<&str> = q
+            .qualifiers
+            .iter()
+            .copied()
+            .filter(|qual| !on_path(&m.path, qual))
+            .collect();
+        if missing.is_empty() {
+            return true;
+        }
+        let containers = enclosing_names(m, &mut outlines);
+        missing
+            .iter()
+            .all(|qual| containers.iter().any(|c| same_name(c, qual)))
+    })
+}
+
+/// `qualifier` names a directory on `path`, or the file itself — its stem or
+/// any dotted part of it (`auth` for `auth.service.ts`).
+fn on_path(path: &Path, qualifier: &str) -> bool {
+    let dirs = path
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .filter_map(|c| c.as_os_str().to_str());
+    let stem = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .into_iter()
+        .flat_map(|n| n.split('.'));
+    dirs.chain(stem).any(|part| same_name(part, qualifier))
+}
+
+/// Names of the definitions containing `m` — classes, modules, and the types
+/// of Rust impl blocks — outermost first.
+fn enclosing_names(m: &Match, outlines: &mut HashMap<PathBuf, Vec<OutlineEntry>>) -> Vec<String> {
+    let FileType::Code(lang) = detect_file_type(&m.path) else {
+        return Vec::new();
+    };
+    let entries = outlines.entry(m.path.clone()).or_insert_with(|| {
+        std::fs::read_to_string(&m.path)
+            .map(|content| callees::get_outline_entries(&content, lang))
+            .unwrap_or_default()
+    });
+
+    let mut names = Vec::new();
+    let mut level: &[OutlineEntry] = entries;
+    while let Some(entry) = level
+        .iter()
+        .find(|e| e.start_line <= m.line && m.line <= e.end_line && e.start_line != m.line)
+    {
+        names.push(siblings::owner_name(entry, lang).to_string());
+        level = &entry.children;
+    }
+    names
+}
+
+/// Names compared the way they drift between a qualifier and the code:
+/// case-insensitive, ignoring `_` and `-` (`AuthService` ~ `auth_service`).
+fn same_name(a: &str, b: &str) -> bool {
+    let fold = |s: &str| {
+        s.chars()
+            .filter(|c| !matches!(c, '_' | '-'))
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    fold(a) == fold(b)
+}
+
+fn is_plain_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_separators() {
+        let q = split("search::rank::sort").unwrap();
+        assert_eq!(q.qualifiers, ["search", "rank"]);
+        assert_eq!(q.name, "sort");
+        let q = split("com.foo.Bar#method").unwrap();
+        assert_eq!(q.qualifiers, ["com", "foo", "Bar"]);
+        assert_eq!(q.name, "method");
+        let q = split("crate::index::SymbolIndex").unwrap();
+        assert_eq!(q.qualifiers, ["index"]);
+        assert_eq!(split("App\\Http\\Kernel").unwrap().name, "Kernel");
+        assert_eq!(split("sort"), None);
+        assert_eq!(split("Vec<u8>::new"), None);
+    }
+
+    #[test]
+    fn qualifiers_on_path() {
+        let path = Path::new("/repo/src/search/rank.rs");
+        assert!(on_path(path, "search"));
+        assert!(on_path(path, "rank"));
+        assert!(!on_path(path, "symbol"));
+        assert!(on_path(Path::new("/app/auth_service.ts"), "AuthService"));
+        assert!(on_path(Path::new("/app/auth.service.ts"), "auth"));
+    }
+}
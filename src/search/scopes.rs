@@ -0,0 +1,178 @@
+//! One search across several directories — an app and the shared library
+//! it uses — with the ranked matches of each merged into one result.
+//!
+//! Every scope is searched on its own and keeps its own ranking. The shown
+//! matches are then dealt out in proportion to the scopes' weights: a scope
+//! of weight 2 gets two slots for each one a scope of weight 1 gets, and
+//! slots a scope can't fill go to the others.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::{did_you_mean, format_search_result, hints, label_header, limits, rel};
+use crate::cache::OutlineCache;
+use crate::error::TilthError;
+use crate::index::bloom::BloomFilterCache;
+use crate::index::SymbolIndex;
+use crate::session::Session;
+use crate::types::SearchResult;
+
+/// A directory to search and its share of the shown matches.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub path: PathBuf,
+    pub weight: f64,
+}
+
+/// Run `search` in each of `scopes` and format the merged result, its
+/// header labelled with each scope's shown and found counts.
+pub fn search_expanded(
+    query: &str,
+    scopes: &[Scope],
+    search: impl Fn(&Path) -> Result<SearchResult, TilthError>,
+    cache: &OutlineCache,
+    session: &Session,
+    index: &SymbolIndex,
+    bloom: &BloomFilterCache,
+    expand: usize,
+) -> Result<String, TilthError> {
+    let results = scopes
+        .iter()
+        .map(|s| search(&s.path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let weights: Vec<f64> = scopes.iter().map(|s| s.weight).collect();
+    let (merged, shown) = merge(query, results, &weights);
+
+    let out = format_search_result(&merged, cache, Some(session), bloom, expand)?;
+    let counts: Vec<String> = scopes
+        .iter()
+        .zip(&shown)
+        .map(|(scope, (shown, found))| {
+            let name = rel(&scope.path, &merged.scope);
+            let name = if name.is_empty() { "." } else { &name };
+            format!("{name} ({shown} of {found})")
+        })
+        .collect();
+    let label = format!("[scopes: {}]", counts.join(", "));
+    let mut out = label_header(&out, &label);
+    if merged.total_found == 0 {
+        for scope in scopes {
+            out.push_str(&did_you_mean(query, &scope.path, index));
+        }
+    }
+    out.push_str(&hints::no_definition(&merged, index));
+    Ok(out)
+}
+
+/// One result from per-scope `results`, ranked best first within each:
+/// matches dealt out by weight up to the call's total limit, a line found from two
+/// overlapping scopes shown once, and counts summed. Also returns each
+/// scope's shown and found counts.
+fn merge(
+    query: &str,
+    results: Vec<SearchResult>,
+    weights: &[f64],
+) -> (SearchResult, Vec<(usize, usize)>) {
+    let root = common_ancestor(results.iter().map(|r| r.scope.as_path()));
+    let lists: Vec<usize> = results.iter().map(|r| r.matches.len()).collect();
+    let order = deal(&lists, weights, limits::current().total);
+
+    let mut merged = SearchResult {
+        query: query.to_string(),
+        scope: root,
+        matches: Vec::new(),
+        total_found: 0,
+        definitions: 0,
+        usages: 0,
+        match_paths: Vec::new(),
+        unparsed: Vec::new(),
+        aliases: Vec::new(),
+    };
+    let mut shown: Vec<(usize, usize)> = results.iter().map(|r| (0, r.total_found)).collect();
+    let mut seen = HashSet::new();
+    let mut taken = vec![0; results.len()];
+    for scope in order {
+        let m = &results[scope].matches[taken[scope]];
+        taken[scope] += 1;
+        if seen.insert((m.path.clone(), m.line)) {
+            merged.matches.push(m.clone());
+            shown[scope].0 += 1;
+        }
+    }
+    for result in results {
+        merged.total_found += result.total_found;
+        merged.definitions += result.definitions;
+        merged.usages += result.usages;
+        merged.match_paths.extend(result.match_paths);
+        merged.unparsed.extend(result.unparsed);
+        merged.aliases.extend(result.aliases);
+    }
+    merged.unparsed.sort();
+    merged.unparsed.dedup();
+    (merged, shown)
+}
+
+/// The order to draw from lists of `lens` matches so that, up to `cap`,
+/// each list's share follows its weight: the next match always comes from
+/// the list furthest below its share, ties to the earlier list. Lists that
+/// run out drop out, leaving their slots to the rest.
+fn deal(lens: &[usize], weights: &[f64], cap: usize) -> Vec<usize> {
+    let mut taken = vec![0usize; lens.len()];
+    let mut order = Vec::new();
+    while order.len() < cap {
+        let next = (0..lens.len())
+            .filter(|&i| taken[i] < lens[i])
+            .min_by(|&a, &b| {
+                #[allow(clippy::cast_precision_loss)] // at most `cap` matches
+                let share = |i: usize| (taken[i] + 1) as f64 / weights[i];
+                share(a).total_cmp(&share(b)).then(a.cmp(&b))
+            });
+        let Some(i) = next else { break };
+        taken[i] += 1;
+        order.push(i);
+    }
+    order
+}
+
+/// The deepest directory containing every one of `paths`.
+fn common_ancestor<'a>(mut paths: impl Iterator<Item = &'a Path>) -> PathBuf {
+    let Some(first) = paths.next() else {
+        return PathBuf::from(".");
+    };
+    let mut root = first.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&root) {
+            if !root.pop() {
+                break;
+            }
+        }
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deals_matches_by_weight_and_passes_on_unused_slots() {
+        // Equal weights alternate
+        assert_eq!(deal(&[5, 5], &[1.0, 1.0], 4), vec![0, 1, 0, 1]);
+        // Weight 2 gets two of every three
+        let order = deal(&[10, 10], &[2.0, 1.0], 9);
+        assert_eq!(order.iter().filter(|&&i| i == 0).count(), 6);
+        // A short list's leftover slots go to the other
+        assert_eq!(deal(&[1, 9], &[1.0, 1.0], 5), vec![0, 1, 1, 1, 1]);
+        assert_eq!(deal(&[1, 2], &[1.0, 1.0], 10).len(), 3);
+    }
+
+    #[test]
+    fn common_ancestor_of_scopes() {
+        let paths = [Path::new("/repo/app/src"), Path::new("/repo/lib/proto")];
+        assert_eq!(common_ancestor(paths.into_iter()), Path::new("/repo"));
+        assert_eq!(
+            common_ancestor([Path::new("/repo/app")].into_iter()),
+            Path::new("/repo/app")
+        );
+    }
+}
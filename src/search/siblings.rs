@@ -1,6 +1,6 @@
 use streaming_iterator::StreamingIterator;
 
-use crate::read::outline::code::outline_language;
+use crate::read::outline::code::{is_impl_block, outline_language};
 use crate::types::{Lang, OutlineEntry, OutlineKind};
 
 /// A sibling field or method resolved from the same parent struct/class/impl.
@@ -17,22 +17,29 @@ pub struct ResolvedSibling {
 const MAX_SIBLINGS: usize = 6;
 
 /// Tree-sitter query for self/this field and method references by language.
-/// Each pattern captures `@ref` on the accessed member name.
+/// Each pattern captures `@ref` on the accessed member name. Patterns that
+/// also capture `@obj` only count when the object is a receiver of the
+/// enclosing type — see [`is_receiver`] — which is how static members
+/// (`Self::new`, `Config.DEFAULT`, `cls.registry`) are picked up.
 fn sibling_query_str(lang: Lang) -> Option<&'static str> {
     match lang {
         Lang::Rust => Some(concat!(
             "(field_expression value: (self) field: (field_identifier) @ref)\n",
             "(call_expression function: (field_expression value: (self) field: (field_identifier) @ref))\n",
+            "(scoped_identifier path: (identifier) @obj name: (identifier) @ref)\n",
         )),
         Lang::Python => Some(
             "(attribute object: (identifier) @obj attribute: (identifier) @ref)\n",
         ),
-        Lang::TypeScript | Lang::JavaScript | Lang::Tsx => Some(
+        Lang::TypeScript | Lang::JavaScript | Lang::Tsx => Some(concat!(
             "(member_expression object: (this) property: (property_identifier) @ref)\n",
-        ),
+            "(member_expression object: (identifier) @obj property: (property_identifier) @ref)\n",
+        )),
         Lang::Java => Some(concat!(
             "(field_access object: (this) field: (identifier) @ref)\n",
             "(method_invocation object: (this) name: (identifier) @ref)\n",
+            "(field_access object: (identifier) @obj field: (identifier) @ref)\n",
+            "(method_invocation object: (identifier) @obj name: (identifier) @ref)\n",
         )),
         Lang::Scala => Some(concat!(
             "(field_expression (identifier) @obj (identifier) @ref)\n",
@@ -45,12 +52,32 @@ fn sibling_query_str(lang: Lang) -> Option<&'static str> {
     }
 }
 
-/// Extract self/this member references from within a definition's line range.
+/// Whether `obj` in `obj.member` / `obj::member` refers to the type named
+/// `owner` or its instance: the type itself for static members, plus the
+/// receiver names the grammar has no dedicated node for.
+fn is_receiver(obj: &str, lang: Lang, owner: &str) -> bool {
+    obj == owner
+        || match lang {
+            Lang::Rust => obj == "Self",
+            Lang::Python => obj == "self" || obj == "cls",
+            Lang::Scala => obj == "this",
+            _ => false,
+        }
+}
+
+/// Extract self/this and static member references from within a definition's
+/// line range.
 ///
 /// Parses the file with tree-sitter and runs per-language queries to find
-/// field accesses and method calls on `self`/`this`. Returns deduplicated,
-/// sorted member names.
-pub fn extract_sibling_references(content: &str, lang: Lang, def_range: (u32, u32)) -> Vec<String> {
+/// field accesses and method calls on `self`/`this`, and on `owner` — the
+/// enclosing type's name — for static members and constructors. Returns
+/// deduplicated, sorted member names.
+pub fn extract_sibling_references(
+    content: &str,
+    lang: Lang,
+    def_range: (u32, u32),
+    owner: &str,
+) -> Vec<String> {
     let Some(ts_lang) = outline_language(lang) else {
         return Vec::new();
     };
@@ -67,8 +94,8 @@ pub fn extract_sibling_references(content: &str, lang: Lang, def_range: (u32, u3
         return Vec::new();
     };
 
-    // For Python, we also need @obj to filter `self.x` vs `other.x`.
-    // For Scala, we also need @obj to filter `this.x` vs `other.x`.
+    // Patterns capturing @obj need the object checked: `self.x` vs `other.x`,
+    // `Config::load` vs `String::new`.
     let obj_idx = query.capture_index_for_name("obj");
     // For Go, we need @recv to filter receiver-only accesses.
     let recv_idx = query.capture_index_for_name("recv");
@@ -78,12 +105,7 @@ pub fn extract_sibling_references(content: &str, lang: Lang, def_range: (u32, u3
         None
     };
 
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-
-    let Some(tree) = parser.parse(content, None) else {
+    let Some(tree) = crate::parser_pool::parse(content, &ts_lang) else {
         return Vec::new();
     };
 
@@ -95,29 +117,14 @@ pub fn extract_sibling_references(content: &str, lang: Lang, def_range: (u32, u3
     let mut names: Vec<String> = Vec::new();
 
     while let Some(m) = matches.next() {
-        // For Python: verify @obj == "self"
-        if lang == Lang::Python {
-            if let Some(oi) = obj_idx {
-                let obj_ok = m
-                    .captures
-                    .iter()
-                    .any(|c| c.index == oi && c.node.utf8_text(bytes).is_ok_and(|t| t == "self"));
-                if !obj_ok {
-                    continue;
-                }
-            }
-        }
-
-        // For Scala: verify @obj == "this"
-        if lang == Lang::Scala {
-            if let Some(oi) = obj_idx {
-                let obj_ok = m
-                    .captures
-                    .iter()
-                    .any(|c| c.index == oi && c.node.utf8_text(bytes).is_ok_and(|t| t == "this"));
-                if !obj_ok {
-                    continue;
-                }
+        if let Some(oi) = obj_idx {
+            let obj = m.captures.iter().find(|c| c.index == oi);
+            if obj.is_some_and(|c| {
+                !c.node
+                    .utf8_text(bytes)
+                    .is_ok_and(|t| is_receiver(t, lang, owner))
+            }) {
+                continue;
             }
         }
 
@@ -168,9 +175,7 @@ fn extract_go_receiver_name(content: &str, ts_lang: &tree_sitter::Language) -> O
     let query = tree_sitter::Query::new(ts_lang, query_str).ok()?;
     let recv_idx = query.capture_index_for_name("recv")?;
 
-    let mut parser = tree_sitter::Parser::new();
-    parser.set_language(ts_lang).ok()?;
-    let tree = parser.parse(content, None)?;
+    let tree = crate::parser_pool::parse(content, ts_lang)?;
 
     let bytes = content.as_bytes();
     let mut cursor = tree_sitter::QueryCursor::new();
@@ -189,10 +194,11 @@ fn extract_go_receiver_name(content: &str, ts_lang: &tree_sitter::Language) -> O
 
 /// Match extracted sibling names against a parent entry's children.
 ///
-/// Returns up to `MAX_SIBLINGS` resolved siblings, preferring methods over fields.
+/// Returns up to `MAX_SIBLINGS` resolved siblings (more at rich verbosity),
+/// preferring methods over fields.
 pub fn resolve_siblings(
     sibling_names: &[String],
-    parent_children: &[OutlineEntry],
+    parent_children: &[&OutlineEntry],
 ) -> Vec<ResolvedSibling> {
     let mut resolved: Vec<ResolvedSibling> = Vec::new();
 
@@ -222,27 +228,174 @@ pub fn resolve_siblings(
         b_is_fn.cmp(&a_is_fn).then_with(|| a.name.cmp(&b.name))
     });
 
-    resolved.truncate(MAX_SIBLINGS);
+    resolved.truncate(crate::verbosity::current().footer_cap(MAX_SIBLINGS));
     resolved
 }
 
+/// Name the members of `parent` are reached through statically: the class
+/// name, or for a Rust impl block the implementing type.
+pub fn owner_name(parent: &OutlineEntry, lang: Lang) -> &str {
+    impl_self_type(parent, lang).unwrap_or(&parent.name)
+}
+
 /// Find the parent entry (struct/class/impl) whose children contain a member
-/// at the given line number.
+/// at the given line number. Searches inside nested Rust modules.
 pub fn find_parent_entry(entries: &[OutlineEntry], method_line: u32) -> Option<&OutlineEntry> {
     for entry in entries {
-        for child in &entry.children {
-            if child.start_line == method_line {
-                return Some(entry);
-            }
+        if entry.children.iter().any(|c| c.start_line == method_line) {
+            return Some(entry);
+        }
+        if let Some(found) = find_parent_entry(&entry.children, method_line) {
+            return Some(found);
         }
     }
     None
 }
 
+/// Innermost function or method whose line range contains `line`.
+pub fn find_enclosing_function(entries: &[OutlineEntry], line: u32) -> Option<&OutlineEntry> {
+    entries
+        .iter()
+        .filter(|e| e.start_line <= line && line <= e.end_line)
+        .find_map(|e| {
+            find_enclosing_function(&e.children, line).or_else(|| {
+                matches!(e.kind, OutlineKind::Function | OutlineKind::Method).then_some(e)
+            })
+        })
+}
+
+/// Members a method in `parent` can reach through `self`. For a Rust impl
+/// block that is every method of the type across all of its impl blocks in
+/// the file (inherent and trait impls alike); otherwise `parent`'s children.
+pub fn sibling_members<'a>(
+    entries: &'a [OutlineEntry],
+    parent: &'a OutlineEntry,
+    lang: Lang,
+) -> Vec<&'a OutlineEntry> {
+    let Some(ty) = impl_self_type(parent, lang) else {
+        return parent.children.iter().collect();
+    };
+    let mut members = Vec::new();
+    collect_impl_members(entries, ty, lang, &mut members);
+    members
+}
+
+fn collect_impl_members<'a>(
+    entries: &'a [OutlineEntry],
+    ty: &str,
+    lang: Lang,
+    out: &mut Vec<&'a OutlineEntry>,
+) {
+    for entry in entries {
+        if impl_self_type(entry, lang) == Some(ty) {
+            out.extend(&entry.children);
+        } else if entry.kind == OutlineKind::Module {
+            collect_impl_members(&entry.children, ty, lang, out);
+        }
+    }
+}
+
+/// `impl Trait for Foo<T>` → `Foo`.
+fn impl_self_type(entry: &OutlineEntry, lang: Lang) -> Option<&str> {
+    if !is_impl_block(entry, lang) {
+        return None;
+    }
+    let ty = entry.name.rsplit(" for ").next()?;
+    let ty = ty.trim_start_matches("impl ");
+    let ty = ty.split('<').next()?.trim();
+    Some(ty.rsplit("::").next().unwrap_or(ty))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn entry(
+        kind: OutlineKind,
+        name: &str,
+        line: u32,
+        children: Vec<OutlineEntry>,
+    ) -> OutlineEntry {
+        OutlineEntry {
+            kind,
+            name: name.to_string(),
+            start_line: line,
+            end_line: line,
+            signature: None,
+            children,
+            doc: None,
+            cfg: None,
+            decorators: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rust_siblings_span_impl_blocks() {
+        let f = |name, line| entry(OutlineKind::Function, name, line, Vec::new());
+        let entries = vec![
+            entry(
+                OutlineKind::Module,
+                "impl Parser<'_>",
+                1,
+                vec![f("parse", 2)],
+            ),
+            entry(
+                OutlineKind::Module,
+                "impl Iterator for Parser<'_>",
+                10,
+                vec![f("next", 11)],
+            ),
+            entry(OutlineKind::Module, "impl Lexer", 20, vec![f("peek", 21)]),
+            entry(
+                OutlineKind::Module,
+                "io",
+                30,
+                vec![entry(
+                    OutlineKind::Module,
+                    "impl Read for crate::Parser<'_>",
+                    31,
+                    vec![f("read", 32)],
+                )],
+            ),
+        ];
+
+        let parent = find_parent_entry(&entries, 11).unwrap();
+        assert_eq!(parent.name, "impl Iterator for Parser<'_>");
+        let names: Vec<&str> = sibling_members(&entries, parent, Lang::Rust)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["parse", "next", "read"]);
+
+        let nested = find_parent_entry(&entries, 32).unwrap();
+        assert_eq!(nested.name, "impl Read for crate::Parser<'_>");
+    }
+
+    #[test]
+    fn enclosing_function_is_innermost() {
+        let span = |kind, name, start, end, children| OutlineEntry {
+            end_line: end,
+            ..entry(kind, name, start, children)
+        };
+        let entries = vec![
+            span(
+                OutlineKind::Module,
+                "impl Server",
+                1,
+                20,
+                vec![
+                    span(OutlineKind::Function, "start", 2, 5, Vec::new()),
+                    span(OutlineKind::Function, "handle", 6, 10, Vec::new()),
+                ],
+            ),
+            span(OutlineKind::Function, "main", 21, 30, Vec::new()),
+        ];
+        let name = |line| find_enclosing_function(&entries, line).map(|e| e.name.as_str());
+        assert_eq!(name(7), Some("handle"));
+        assert_eq!(name(25), Some("main"));
+        assert_eq!(name(15), None);
+    }
+
     #[test]
     fn scala_sibling_extraction() {
         let scala_code = r#"
@@ -261,10 +414,29 @@ class Example {
 "#;
 
         // Extract siblings from the process() method (lines ~5-9)
-        let siblings = extract_sibling_references(scala_code, Lang::Scala, (5, 9));
+        let siblings = extract_sibling_references(scala_code, Lang::Scala, (5, 9), "Example");
 
         // Should capture: field, helper (both explicit this. and implicit)
         assert!(siblings.contains(&"field".to_string()));
         assert!(siblings.contains(&"helper".to_string()));
     }
+
+    #[test]
+    fn rust_static_siblings() {
+        let code = r"
+impl Config {
+    pub fn load() -> Self {
+        let raw = Self::read_file();
+        let name = String::new();
+        Config::parse(&raw, name)
+    }
+
+    fn read_file() -> String {
+        String::new()
+    }
+}
+";
+        let siblings = extract_sibling_references(code, Lang::Rust, (3, 7), "Config");
+        assert_eq!(siblings, vec!["parse", "read_file"]);
+    }
 }
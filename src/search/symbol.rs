@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 use std::time::SystemTime;
@@ -7,23 +7,29 @@ use std::time::SystemTime;
 use super::file_metadata;
 use super::treesitter::{
     definition_weight, extract_definition_name, extract_impl_trait, extract_impl_type,
-    extract_implemented_interfaces, DEFINITION_KINDS,
+    extract_implemented_interfaces, trait_method, DEFINITION_KINDS,
 };
 
+use crate::deadline::{within, Deadline};
 use crate::error::TilthError;
 use crate::read::detect_file_type;
-use crate::read::outline::code::outline_language;
-use crate::search::rank;
+use crate::read::outline::code::{self, outline_language};
+use crate::read::outline::{buildfile, structured};
+use crate::search::{excerpt, limits, rank};
 use crate::types::{FileType, Match, SearchResult};
 use grep_regex::RegexMatcher;
-use grep_searcher::sinks::UTF8;
+use grep_searcher::sinks::{Lossy, UTF8};
 use grep_searcher::Searcher;
 
-const MAX_MATCHES: usize = 10;
-/// Stop walking once we have this many raw definition matches.
+/// Stop walking once we have this many raw definition matches, or more
+/// when the call shows more.
 const EARLY_QUIT_THRESHOLD_DEFINITIONS: usize = 50;
-/// Stop walking once we have this many raw usage matches.
-const EARLY_QUIT_THRESHOLD_USAGES: usize = MAX_MATCHES * 3;
+
+/// Raw definition and usage matches a walk gathers before it stops early.
+fn early_quit() -> (usize, usize) {
+    let usages = limits::current().early_quit();
+    (EARLY_QUIT_THRESHOLD_DEFINITIONS.max(usages), usages)
+}
 
 /// Symbol search: find definitions via tree-sitter, usages via ripgrep, concurrently.
 /// Merge results, deduplicate, definitions first.
@@ -31,6 +37,17 @@ pub fn search(
     query: &str,
     scope: &Path,
     context: Option<&Path>,
+) -> Result<SearchResult, TilthError> {
+    search_in(query, scope, context, |_| true)
+}
+
+/// [`search`], keeping only the matches `keep` accepts. The filter runs
+/// before ranking and truncation, so counts and the top matches reflect it.
+pub(crate) fn search_in(
+    query: &str,
+    scope: &Path,
+    context: Option<&Path>,
+    keep: impl FnMut(&Match) -> bool,
 ) -> Result<SearchResult, TilthError> {
     // Compile regex once, share across both arms
     let word_pattern = format!(r"\b{}\b", regex_syntax::escape(query));
@@ -39,14 +56,149 @@ pub fn search(
         reason: e.to_string(),
     })?;
 
+    // Either arm may run on a rayon thread; both walk under this call's deadline
+    let deadline = Deadline::current();
     let (defs, usages) = rayon::join(
-        || find_definitions(query, scope),
-        || find_usages(query, &matcher, scope),
+        || within(deadline.as_ref(), || find_definitions(query, scope)),
+        || within(deadline.as_ref(), || find_usages(query, &matcher, scope)),
     );
 
-    let defs = defs?;
-    let usages = usages?;
+    let (defs, unparsed) = defs?;
+    Ok(assemble(
+        query, scope, context, defs, unparsed, usages?, keep,
+    ))
+}
+
+/// [`search`] for each of `queries` in one walk of `scope`: every file is
+/// read once and parsed once, however many of the queries it mentions.
+/// Results come back in query order, unformatted.
+pub fn search_many(queries: &[&str], scope: &Path) -> Result<Vec<SearchResult>, TilthError> {
+    let matchers = queries
+        .iter()
+        .map(|query| {
+            RegexMatcher::new(&format!(r"\b{}\b", regex_syntax::escape(query))).map_err(|e| {
+                TilthError::InvalidQuery {
+                    query: (*query).to_string(),
+                    reason: e.to_string(),
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let found: Vec<Mutex<Found>> = queries.iter().map(|_| Mutex::default()).collect();
+    // Per query, for the same early quit as separate searches
+    let def_counts: Vec<AtomicUsize> = queries.iter().map(|_| AtomicUsize::new(0)).collect();
+    let usage_counts: Vec<AtomicUsize> = queries.iter().map(|_| AtomicUsize::new(0)).collect();
+    let (def_quit, usage_quit) = early_quit();
+    let wants_defs = |i: usize| def_counts[i].load(Ordering::Relaxed) < def_quit;
+    let wants_usages = |i: usize| usage_counts[i].load(Ordering::Relaxed) < usage_quit;
+
+    super::walker(scope).run(|| {
+        let (found, def_counts, usage_counts) = (&found, &def_counts, &usage_counts);
+        let (matchers, wants_defs, wants_usages) = (&matchers, &wants_defs, &wants_usages);
+        Box::new(move |entry| {
+            let pending: Vec<usize> = (0..queries.len())
+                .filter(|&i| wants_defs(i) || wants_usages(i))
+                .collect();
+            if pending.is_empty() {
+                return ignore::WalkState::Quit;
+            }
+            let Ok(entry) = entry else {
+                return ignore::WalkState::Continue;
+            };
+            if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                return ignore::WalkState::Continue;
+            }
+            let path = entry.path();
+            if std::fs::metadata(path).is_ok_and(|meta| meta.len() > 500_000) {
+                return ignore::WalkState::Continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else {
+                return ignore::WalkState::Continue;
+            };
+            let mentioned: Vec<usize> = pending
+                .into_iter()
+                .filter(|&i| {
+                    memchr::memmem::find(content.as_bytes(), queries[i].as_bytes()).is_some()
+                })
+                .collect();
+            if mentioned.is_empty() {
+                return ignore::WalkState::Continue;
+            }
+
+            let (file_lines, mtime) = file_metadata(path);
+            let parsed = Parsed::new(path, &content);
+            for i in mentioned {
+                let query = queries[i];
+                let (defs, clean) = if wants_defs(i) {
+                    parsed.definitions(path, query, &content, file_lines, mtime)
+                } else {
+                    (Vec::new(), true)
+                };
+                let mut usages = Vec::new();
+                if wants_usages(i) {
+                    let _ = Searcher::new().search_slice(
+                        &matchers[i],
+                        content.as_bytes(),
+                        UTF8(|line_num, line| {
+                            usages.push(usage(path, line_num, line, query, file_lines, mtime));
+                            Ok(true)
+                        }),
+                    );
+                }
+                def_counts[i].fetch_add(defs.len(), Ordering::Relaxed);
+                usage_counts[i].fetch_add(usages.len(), Ordering::Relaxed);
+                let mut found = found[i]
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                found.defs.extend(defs);
+                found.usages.extend(usages);
+                if !clean {
+                    found.unparsed.push(path.to_path_buf());
+                }
+            }
+            ignore::WalkState::Continue
+        })
+    });
+
+    Ok(queries
+        .iter()
+        .zip(found)
+        .map(|(query, found)| {
+            let found = found
+                .into_inner()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            assemble(
+                query,
+                scope,
+                None,
+                found.defs,
+                found.unparsed,
+                found.usages,
+                |_| true,
+            )
+        })
+        .collect())
+}
+
+/// One query's raw matches in [`search_many`].
+#[derive(Default)]
+struct Found {
+    defs: Vec<Match>,
+    unparsed: Vec<PathBuf>,
+    usages: Vec<Match>,
+}
 
+/// Definitions and usages merged into a result: usages on a definition's
+/// line dropped, `keep` applied, then ranked and cut to the top matches.
+fn assemble(
+    query: &str,
+    scope: &Path,
+    context: Option<&Path>,
+    defs: Vec<Match>,
+    mut unparsed: Vec<PathBuf>,
+    usages: Vec<Match>,
+    mut keep: impl FnMut(&Match) -> bool,
+) -> SearchResult {
     // Deduplicate: remove usage matches that overlap with definition matches.
     // Linear scan — max ~30 defs from EARLY_QUIT_THRESHOLD, no allocation needed.
     let mut merged: Vec<Match> = defs;
@@ -61,46 +213,59 @@ pub fn search(
         }
     }
 
+    merged.retain(|m| keep(m));
+    let aliases = super::aliases::dedupe(&mut merged);
+    let def_count = merged.iter().filter(|m| m.is_definition).count();
     let total = merged.len();
     let usage_count = total - def_count;
 
+    let match_paths = merged.iter().map(|m| m.path.clone()).collect();
     rank::sort(&mut merged, query, scope, context);
-    merged.truncate(MAX_MATCHES);
+    limits::current().apply(&mut merged);
+    unparsed.sort();
 
-    Ok(SearchResult {
+    SearchResult {
         query: query.to_string(),
         scope: scope.to_path_buf(),
         matches: merged,
         total_found: total,
         definitions: def_count,
         usages: usage_count,
-    })
+        match_paths,
+        unparsed,
+        aliases,
+    }
 }
 
 /// Find definitions using tree-sitter structural detection.
 /// For each file containing the query string, parse with tree-sitter and walk
 /// definition nodes to see if any declare the queried symbol.
-/// Falls back to keyword heuristic for files without grammars.
+/// Falls back to keyword heuristic for files without grammars, and alongside
+/// tree-sitter for files it can't parse cleanly — those are also returned,
+/// so the result can say its definitions there are approximate.
 ///
 /// Single-read design: reads each file once, checks for symbol via
 /// `memchr::memmem` (SIMD), then reuses the buffer for tree-sitter parsing.
 /// Early termination: quits the parallel walker once enough defs are found.
-fn find_definitions(query: &str, scope: &Path) -> Result<Vec<Match>, TilthError> {
+fn find_definitions(query: &str, scope: &Path) -> Result<(Vec<Match>, Vec<PathBuf>), TilthError> {
     let matches: Mutex<Vec<Match>> = Mutex::new(Vec::new());
+    let unparsed: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
     // Relaxed is correct: walker.run() joins all threads before we read the final value.
     // Early-quit checks are approximate by design — one extra iteration is harmless.
     let found_count = AtomicUsize::new(0);
+    let (quit_at, _) = early_quit();
     let needle = query.as_bytes();
 
     let walker = super::walker(scope);
 
     walker.run(|| {
         let matches = &matches;
+        let unparsed = &unparsed;
         let found_count = &found_count;
 
         Box::new(move |entry| {
             // Early termination: enough definitions found
-            if found_count.load(Ordering::Relaxed) >= EARLY_QUIT_THRESHOLD_DEFINITIONS {
+            if found_count.load(Ordering::Relaxed) >= quit_at {
                 return ignore::WalkState::Quit;
             }
 
@@ -134,24 +299,13 @@ fn find_definitions(query: &str, scope: &Path) -> Result<Vec<Match>, TilthError>
             // Get file metadata once per file
             let (file_lines, mtime) = file_metadata(path);
 
-            // Try tree-sitter structural detection
-            let file_type = detect_file_type(path);
-            let lang = match file_type {
-                FileType::Code(l) => Some(l),
-                _ => None,
-            };
-
-            let ts_language = lang.and_then(outline_language);
-
-            let mut file_defs = if let Some(ref ts_lang) = ts_language {
-                find_defs_treesitter(path, query, ts_lang, &content, file_lines, mtime)
-            } else {
-                Vec::new()
-            };
-
-            // Fallback: keyword heuristic for files without grammars
-            if file_defs.is_empty() && ts_language.is_none() {
-                file_defs = find_defs_heuristic_buf(path, query, &content, file_lines, mtime);
+            let (file_defs, clean) =
+                Parsed::new(path, &content).definitions(path, query, &content, file_lines, mtime);
+            if !clean {
+                unparsed
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .push(path.to_path_buf());
             }
 
             if !file_defs.is_empty() {
@@ -166,37 +320,133 @@ fn find_definitions(query: &str, scope: &Path) -> Result<Vec<Match>, TilthError>
         })
     });
 
-    Ok(matches
-        .into_inner()
-        .unwrap_or_else(std::sync::PoisonError::into_inner))
+    Ok((
+        matches
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+        unparsed
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    ))
+}
+
+/// A file read and parsed once, for every query run over it.
+struct Parsed<'a> {
+    grammar: bool,
+    tree: Option<tree_sitter::Tree>,
+    /// Symbols of build files, specs and manifests: stages, targets,
+    /// operations and resources.
+    scanned: Option<Vec<buildfile::BuildSymbol<'a>>>,
 }
 
-/// Tree-sitter structural definition detection.
+impl<'a> Parsed<'a> {
+    fn new(path: &Path, content: &'a str) -> Self {
+        let lang = match detect_file_type(path) {
+            FileType::Code(l) => Some(l),
+            _ => None,
+        };
+        let ts_language = lang.and_then(outline_language);
+        let tree = ts_language
+            .as_ref()
+            .and_then(|l| crate::parser_pool::parse(content, l));
+        let scanned = crate::grammars::symbols(path, content).or_else(|| match lang {
+            Some(l) => buildfile::symbols(l, content),
+            None => structured::symbols(path, content),
+        });
+        Self {
+            grammar: ts_language.is_some(),
+            tree,
+            scanned,
+        }
+    }
+
+    /// Definitions of `query` in the file, and whether tree-sitter parsed it
+    /// cleanly — always so for files without a grammar.
+    fn definitions(
+        &self,
+        path: &Path,
+        query: &str,
+        content: &str,
+        file_lines: u32,
+        mtime: SystemTime,
+    ) -> (Vec<Match>, bool) {
+        let (mut defs, clean) = if self.grammar {
+            find_defs_treesitter(path, query, self.tree.as_ref(), content, file_lines, mtime)
+        } else {
+            (Vec::new(), true)
+        };
+        if let Some(symbols) = &self.scanned {
+            defs = find_defs_buildfile(path, query, symbols, content, file_lines, mtime);
+        } else if defs.is_empty() && !self.grammar {
+            // Fallback: keyword heuristic for files without grammars
+            defs = find_defs_heuristic_buf(path, query, content, file_lines, mtime);
+        }
+        (defs, clean)
+    }
+}
+
+/// Tree-sitter structural definition detection, and whether the file parsed
+/// without errors. When it didn't, definitions the tree lost are recovered
+/// by keyword heuristic, line by line.
 /// Accepts pre-read content — no redundant file read.
 fn find_defs_treesitter(
     path: &Path,
     query: &str,
-    ts_lang: &tree_sitter::Language,
+    tree: Option<&tree_sitter::Tree>,
     content: &str,
     file_lines: u32,
     mtime: SystemTime,
-) -> Vec<Match> {
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(ts_lang).is_err() {
-        return Vec::new();
+) -> (Vec<Match>, bool) {
+    let mut defs = Vec::new();
+    if let Some(tree) = tree {
+        let lines: Vec<&str> = content.lines().collect();
+        walk_for_definitions(
+            tree.root_node(),
+            query,
+            path,
+            &lines,
+            file_lines,
+            mtime,
+            &mut defs,
+            0,
+        );
     }
 
-    let Some(tree) = parser.parse(content, None) else {
+    let parsed = tree.is_some_and(|t| !t.root_node().has_error());
+    if !parsed {
+        let recovered: Vec<Match> =
+            find_defs_heuristic_buf(path, query, content, file_lines, mtime)
+                .into_iter()
+                .filter(|h| {
+                    heuristic_definition_name(&h.text) == Some(query)
+                        && !defs.iter().any(|d| d.line == h.line)
+                })
+                .collect();
+        defs.extend(recovered);
+    }
+    (defs, parsed)
+}
+
+/// Line ranges of `name`'s definitions in one file's content, as tree-sitter
+/// finds them now. Empty for files without a grammar.
+pub(crate) fn definition_ranges(path: &Path, name: &str, content: &str) -> Vec<(u32, u32)> {
+    let FileType::Code(lang) = detect_file_type(path) else {
         return Vec::new();
     };
-
-    let lines: Vec<&str> = content.lines().collect();
-    let root = tree.root_node();
-    let mut defs = Vec::new();
-
-    walk_for_definitions(root, query, path, &lines, file_lines, mtime, &mut defs, 0);
-
-    defs
+    let Some(ts_lang) = outline_language(lang) else {
+        return Vec::new();
+    };
+    let file_lines = content.lines().count() as u32;
+    let tree = crate::parser_pool::parse(content, &ts_lang);
+    let (defs, _) = find_defs_treesitter(
+        path,
+        name,
+        tree.as_ref(),
+        content,
+        file_lines,
+        SystemTime::UNIX_EPOCH,
+    );
+    defs.into_iter().filter_map(|d| d.def_range).collect()
 }
 
 /// Recursively walk AST nodes looking for definitions of the queried symbol.
@@ -225,11 +475,23 @@ fn walk_for_definitions(
                     .get(node.start_position().row)
                     .unwrap_or(&"")
                     .trim_end();
+                // Functions show their whole signature, even when the
+                // parameters wrap or a decorator sits on the first line
+                let sig_node = if kind == "decorated_definition" {
+                    node.child_by_field_name("definition").unwrap_or(node)
+                } else {
+                    node
+                };
+                let text = if sig_node.child_by_field_name("parameters").is_some() {
+                    code::signature(sig_node, lines)
+                } else {
+                    line_text.to_string()
+                };
                 defs.push(Match {
                     path: path.to_path_buf(),
                     line: line_num,
                     column: node.start_position().column as u32,
-                    text: line_text.to_string(),
+                    text,
                     is_definition: true,
                     exact: true,
                     file_lines,
@@ -241,6 +503,7 @@ fn walk_for_definitions(
                     def_name: Some(query.to_string()),
                     def_weight: definition_weight(node.kind()),
                     impl_target: None,
+                    trait_method: trait_method(node, lines),
                 });
             }
         }
@@ -273,6 +536,7 @@ fn walk_for_definitions(
                         def_name: Some(format!("impl {query} for {impl_type}")),
                         def_weight: 80,
                         impl_target: Some(query.to_string()),
+                        trait_method: None,
                     });
                 }
             }
@@ -302,6 +566,7 @@ fn walk_for_definitions(
                     def_name: Some(format!("{class_name} implements {query}")),
                     def_weight: 80,
                     impl_target: Some(query.to_string()),
+                    trait_method: None,
                 });
             }
         }
@@ -323,6 +588,41 @@ fn walk_for_definitions(
     }
 }
 
+/// Definitions from a build file or manifest, matched by exact name.
+fn find_defs_buildfile(
+    path: &Path,
+    query: &str,
+    symbols: &[buildfile::BuildSymbol],
+    content: &str,
+    file_lines: u32,
+    mtime: SystemTime,
+) -> Vec<Match> {
+    symbols
+        .iter()
+        .filter(|sym| sym.name == query)
+        .map(|sym| Match {
+            path: path.to_path_buf(),
+            line: sym.line,
+            column: 0,
+            text: content
+                .lines()
+                .nth(sym.line as usize - 1)
+                .unwrap_or("")
+                .trim_end()
+                .to_string(),
+            is_definition: true,
+            exact: true,
+            file_lines,
+            mtime,
+            def_range: Some((sym.line, sym.end_line)),
+            def_name: Some(query.to_string()),
+            def_weight: 100,
+            impl_target: None,
+            trait_method: None,
+        })
+        .collect()
+}
+
 /// Keyword heuristic fallback for files without tree-sitter grammars.
 /// Operates on pre-read buffer — no redundant file read.
 fn find_defs_heuristic_buf(
@@ -349,6 +649,7 @@ fn find_defs_heuristic_buf(
                 def_name: Some(query.to_string()),
                 def_weight: 60,
                 impl_target: None,
+                trait_method: None,
             });
         }
     }
@@ -367,6 +668,7 @@ fn find_usages(
     let matches: Mutex<Vec<Match>> = Mutex::new(Vec::new());
     // Relaxed: same reasoning as find_definitions — approximate early-quit, joined before read
     let found_count = AtomicUsize::new(0);
+    let (_, quit_at) = early_quit();
 
     let walker = super::walker(scope);
 
@@ -376,7 +678,7 @@ fn find_usages(
 
         Box::new(move |entry| {
             // Early termination: enough usages found
-            if found_count.load(Ordering::Relaxed) >= EARLY_QUIT_THRESHOLD_USAGES {
+            if found_count.load(Ordering::Relaxed) >= quit_at {
                 return ignore::WalkState::Quit;
             }
 
@@ -405,21 +707,8 @@ fn find_usages(
             let _ = searcher.search_path(
                 matcher,
                 path,
-                UTF8(|line_num, line| {
-                    file_matches.push(Match {
-                        path: path.to_path_buf(),
-                        line: line_num as u32,
-                        column: 0,
-                        text: line.trim_end().to_string(),
-                        is_definition: false,
-                        exact: line.contains(query),
-                        file_lines,
-                        mtime,
-                        def_range: None,
-                        def_name: None,
-                        def_weight: 0,
-                        impl_target: None,
-                    });
+                Lossy(|line_num, line| {
+                    file_matches.push(usage(path, line_num, line, query, file_lines, mtime));
                     Ok(true)
                 }),
             );
@@ -441,7 +730,77 @@ fn find_usages(
         .unwrap_or_else(std::sync::PoisonError::into_inner))
 }
 
-/// Keyword heuristic fallback — only used when tree-sitter grammar unavailable.
+/// A usage match on line `line_num` of `path`.
+fn usage(
+    path: &Path,
+    line_num: u64,
+    line: &str,
+    query: &str,
+    file_lines: u32,
+    mtime: SystemTime,
+) -> Match {
+    let at = line.find(query);
+    let line = line.trim_end();
+    Match {
+        path: path.to_path_buf(),
+        line: line_num as u32,
+        column: at.unwrap_or(0) as u32,
+        text: at
+            .and_then(|at| excerpt::render(line, at))
+            .unwrap_or_else(|| excerpt::fit(line).into_owned()),
+        is_definition: false,
+        exact: at.is_some(),
+        file_lines,
+        mtime,
+        def_range: None,
+        def_name: None,
+        def_weight: 0,
+        impl_target: None,
+        trait_method: None,
+    }
+}
+
+/// Words that introduce a definition in the lines [`is_definition_line`]
+/// accepts, skipped to reach the defined name.
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "async",
+    "class",
+    "const",
+    "crate",
+    "def",
+    "default",
+    "enum",
+    "export",
+    "fn",
+    "func",
+    "function",
+    "impl",
+    "interface",
+    "let",
+    "pub",
+    "struct",
+    "trait",
+    "type",
+    "var",
+];
+
+/// Name a keyword-led definition line declares — `hello` for
+/// `pub async fn hello(`, `Name` for Go's `func (r *Recv) Name(`.
+pub(crate) fn heuristic_definition_name(line: &str) -> Option<&str> {
+    if !is_definition_line(line) {
+        return None;
+    }
+    let mut rest = line.trim();
+    if let Some(after) = rest.strip_prefix("func (") {
+        rest = after.split_once(')').map_or(after, |(_, tail)| tail);
+    }
+    rest.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|w| !w.is_empty())
+        .find(|w| !DEFINITION_KEYWORDS.contains(w))
+}
+
+/// Keyword heuristic fallback — used when tree-sitter grammar unavailable,
+/// or the file doesn't parse.
 fn is_definition_line(line: &str) -> bool {
     let trimmed = line.trim();
     trimmed.starts_with("fn ")
@@ -481,6 +840,7 @@ fn is_definition_line(line: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::TraitMethod;
     use std::time::SystemTime;
 
     #[test]
@@ -502,37 +862,162 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
 "#;
         let ts_lang =
             crate::read::outline::code::outline_language(crate::types::Lang::Rust).unwrap();
+        let tree = crate::parser_pool::parse(code, &ts_lang);
 
-        let defs = find_defs_treesitter(
+        let (defs, parsed) = find_defs_treesitter(
             std::path::Path::new("test.rs"),
             "hello",
-            &ts_lang,
+            tree.as_ref(),
             code,
             15,
             SystemTime::now(),
         );
+        assert!(parsed);
         assert!(!defs.is_empty(), "should find 'hello' definition");
         assert!(defs[0].is_definition);
         assert!(defs[0].def_range.is_some());
 
-        let defs = find_defs_treesitter(
+        let (defs, _) = find_defs_treesitter(
             std::path::Path::new("test.rs"),
             "Foo",
-            &ts_lang,
+            tree.as_ref(),
             code,
             15,
             SystemTime::now(),
         );
         assert!(!defs.is_empty(), "should find 'Foo' definition");
 
-        let defs = find_defs_treesitter(
+        let (defs, _) = find_defs_treesitter(
             std::path::Path::new("test.rs"),
             "dispatch_tool",
-            &ts_lang,
+            tree.as_ref(),
             code,
             15,
             SystemTime::now(),
         );
         assert!(!defs.is_empty(), "should find 'dispatch_tool' definition");
     }
+
+    #[test]
+    fn rust_trait_default_and_overrides() {
+        let code = r"trait Render {
+    fn render(&self) -> String {
+        String::new()
+    }
+}
+
+impl Render for Page {
+    fn render(&self) -> String {
+        self.body.clone()
+    }
+}
+
+impl Page {
+    fn render(&self) {}
+}
+";
+        let ts_lang =
+            crate::read::outline::code::outline_language(crate::types::Lang::Rust).unwrap();
+        let tree = crate::parser_pool::parse(code, &ts_lang);
+        let (defs, _) = find_defs_treesitter(
+            std::path::Path::new("test.rs"),
+            "render",
+            tree.as_ref(),
+            code,
+            15,
+            SystemTime::now(),
+        );
+        let roles: Vec<_> = defs.iter().map(|d| d.trait_method.clone()).collect();
+        assert_eq!(
+            roles,
+            vec![
+                Some(TraitMethod::Default {
+                    trait_name: "Render".to_string()
+                }),
+                Some(TraitMethod::Override {
+                    trait_name: "Render".to_string(),
+                    impl_type: "Page".to_string()
+                }),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn unparseable_file_falls_back_to_keywords() {
+        let code = "pub fn hello() -> u32 {\n    1\n}\n\nfn broken( {\n";
+        let ts_lang =
+            crate::read::outline::code::outline_language(crate::types::Lang::Rust).unwrap();
+        let tree = crate::parser_pool::parse(code, &ts_lang);
+        let find = |query| {
+            find_defs_treesitter(
+                std::path::Path::new("test.rs"),
+                query,
+                tree.as_ref(),
+                code,
+                5,
+                SystemTime::now(),
+            )
+        };
+
+        let (defs, parsed) = find("hello");
+        assert!(!parsed);
+        assert_eq!(defs.len(), 1, "tree and heuristic agree on one definition");
+        let (defs, _) = find("broken");
+        assert_eq!(defs.iter().map(|d| d.line).collect::<Vec<_>>(), vec![5]);
+    }
+
+    #[test]
+    fn heuristic_names() {
+        assert_eq!(
+            heuristic_definition_name("pub(crate) fn run() {"),
+            Some("run")
+        );
+        assert_eq!(
+            heuristic_definition_name("export default function App() {"),
+            Some("App")
+        );
+        assert_eq!(
+            heuristic_definition_name("func (s *Server) Serve(l net.Listener) {"),
+            Some("Serve")
+        );
+        assert_eq!(
+            heuristic_definition_name("    def method(self):"),
+            Some("method")
+        );
+        assert_eq!(heuristic_definition_name("return hello()"), None);
+    }
+
+    #[test]
+    fn search_many_matches_separate_searches() {
+        let dir = std::env::temp_dir().join("tilth_test_search_many");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("lib.rs"),
+            "pub fn parse() -> Token {\n    lex()\n}\n\nfn lex() -> Token {\n    Token\n}\n\npub struct Token;\n",
+        )
+        .unwrap();
+        fs::write(dir.join("main.rs"), "fn main() {\n    parse();\n}\n").unwrap();
+
+        let queries = ["parse", "Token", "missing"];
+        let many = search_many(&queries, &dir).unwrap();
+        assert_eq!(many.len(), 3);
+        for (query, result) in queries.iter().zip(&many) {
+            let single = search(query, &dir, None).unwrap();
+            assert_eq!(result.query, *query);
+            assert_eq!(result.definitions, single.definitions, "{query}");
+            assert_eq!(result.usages, single.usages, "{query}");
+            let lines = |r: &SearchResult| {
+                let mut v: Vec<(PathBuf, u32)> =
+                    r.matches.iter().map(|m| (m.path.clone(), m.line)).collect();
+                v.sort();
+                v
+            };
+            assert_eq!(lines(result), lines(&single), "{query}");
+        }
+        assert_eq!(many[0].definitions, 1);
+        assert_eq!(many[2].total_found, 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
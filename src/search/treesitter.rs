@@ -1,5 +1,7 @@
 //! Shared tree-sitter utilities used by symbol search and caller search.
 
+use crate::types::TraitMethod;
+
 /// Definition node kinds across tree-sitter grammars.
 pub(crate) const DEFINITION_KINDS: &[&str] = &[
     // Functions
@@ -26,6 +28,7 @@ pub(crate) const DEFINITION_KINDS: &[&str] = &[
     "static_item",
     // Rust-specific
     "trait_item",
+    "function_signature_item",
     "impl_item",
     "mod_item",
     // Python
@@ -101,6 +104,27 @@ pub(crate) fn extract_impl_type(node: tree_sitter::Node, lines: &[&str]) -> Opti
     Some(node_text_simple(type_node, lines))
 }
 
+/// Classify a Rust `fn` by the block it sits in: a default body inside
+/// `trait Name { .. }`, or an override inside `impl Trait for Type { .. }`.
+/// None for free functions, inherent methods and bodiless signatures.
+pub(crate) fn trait_method(node: tree_sitter::Node, lines: &[&str]) -> Option<TraitMethod> {
+    if node.kind() != "function_item" {
+        return None;
+    }
+    let block = node.parent().filter(|p| p.kind() == "declaration_list")?;
+    let owner = block.parent()?;
+    match owner.kind() {
+        "trait_item" => Some(TraitMethod::Default {
+            trait_name: node_text_simple(owner.child_by_field_name("name")?, lines),
+        }),
+        "impl_item" => Some(TraitMethod::Override {
+            trait_name: extract_impl_trait(owner, lines)?,
+            impl_type: extract_impl_type(owner, lines)?,
+        }),
+        _ => None,
+    }
+}
+
 /// Extract implemented interface names from TS/Java class declaration.
 /// Walks `implements_clause` (TS) and `super_interfaces` (Java) children.
 pub(crate) fn extract_implemented_interfaces(
@@ -143,7 +167,7 @@ pub(crate) fn definition_weight(kind: &str) -> u16 {
         | "type_item"
         | "type_declaration"
         | "decorated_definition" => 100,
-        "impl_item" => 90,
+        "impl_item" | "function_signature_item" => 90,
         "const_item" | "static_item" => 80,
         "mod_item" => 70,
         "lexical_declaration" | "variable_declaration" => 40,
@@ -1,8 +1,17 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
-use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::format::token_count;
+use crate::tokens;
+use crate::types::FileType;
 
 /// Tracks MCP activity across calls.
 /// Stored alongside `OutlineCache` in server state.
@@ -13,6 +22,54 @@ pub struct Session {
     symbols: Mutex<HashMap<String, usize>>, // query → search count
     dir_hits: Mutex<HashMap<String, usize>>, // dir → count
     expanded: Mutex<HashSet<String>>,       // "path:line" → expanded status
+    turns: AtomicUsize,
+    sent: Mutex<HashMap<String, (u64, usize)>>, // read key → (content checksum, turn)
+    calls: Mutex<HashMap<String, Vec<Duration>>>, // tool → latency of each call
+    edit_mode: bool,                            // expanded search code carries hashline anchors
+    tokens_sent: AtomicU64,                     // estimated tokens across all responses
+    token_budget: Option<u64>,                  // past this, tools go terse
+    crumbs: Mutex<HashMap<usize, Vec<Crumb>>>,  // turn → matches shown, in order
+    disclosed: Mutex<Vec<Disclosure>>,          // audit trail; survives reset
+    audit_log: Option<Mutex<File>>,             // disclosures appended as JSON lines
+}
+
+/// One entry of the audit trail: code from `path` that a response showed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Disclosure {
+    pub turn: usize,
+    /// Milliseconds since the Unix epoch.
+    pub time: u64,
+    /// `read`, `outline`, `section`, `symbols`, `snippet`, `records`,
+    /// `match`, `expansion`, `diff`, `edit`, or `scope` — a tool that may
+    /// show code from anywhere under the path.
+    pub kind: &'static str,
+    pub path: String,
+    /// The lines shown, `45-89` or `42`, the heading of a section read, or
+    /// the query of a records read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<String>,
+}
+
+/// A search match as a later call can name it, by the breadcrumb shown on
+/// its header: `S12.3` is the third match shown on turn 12.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crumb {
+    pub path: PathBuf,
+    pub line: u32,
+    /// The definition's line range, for definition matches.
+    pub range: Option<(u32, u32)>,
+}
+
+/// Process-wide cache, index and walker counters shown by [`Session::perf`].
+/// Each pair is `(hits, lookups)`.
+pub struct PerfCounters {
+    pub outline_cache: (usize, usize),
+    pub bloom_cache: (usize, usize),
+    pub index_lookups: (usize, usize),
+    pub index_builds: usize,
+    /// Indexed files tree-sitter couldn't parse cleanly.
+    pub index_unparsed: usize,
+    pub walked_files: usize,
 }
 
 impl Session {
@@ -24,9 +81,116 @@ impl Session {
             symbols: Mutex::new(HashMap::new()),
             dir_hits: Mutex::new(HashMap::new()),
             expanded: Mutex::new(HashSet::new()),
+            turns: AtomicUsize::new(0),
+            sent: Mutex::new(HashMap::new()),
+            calls: Mutex::new(HashMap::new()),
+            edit_mode: false,
+            tokens_sent: AtomicU64::new(0),
+            token_budget: None,
+            crumbs: Mutex::new(HashMap::new()),
+            disclosed: Mutex::new(Vec::new()),
+            audit_log: None,
+        }
+    }
+
+    /// A session for an edit-mode server: expanded search results render
+    /// with `line:hash` anchors, ready for `tilth_edit`.
+    pub fn with_edit_mode(edit_mode: bool) -> Self {
+        Session {
+            edit_mode,
+            ..Session::new()
+        }
+    }
+
+    /// Cap the tokens this session sends before tools switch to terse
+    /// output — for hosts whose context window can't take many full responses.
+    #[must_use]
+    pub fn with_token_budget(self, token_budget: Option<u64>) -> Self {
+        Session {
+            token_budget,
+            ..self
         }
     }
 
+    /// Also append every disclosure to `log` as a JSON line, as it happens.
+    #[must_use]
+    pub fn with_audit_log(self, log: Option<File>) -> Self {
+        Session {
+            audit_log: log.map(Mutex::new),
+            ..self
+        }
+    }
+
+    pub fn edit_mode(&self) -> bool {
+        self.edit_mode
+    }
+
+    /// Count a response against the session's token budget.
+    pub fn record_output(&self, output: &str) {
+        let tokens = tokens::estimate(output.as_bytes(), FileType::Other);
+        self.tokens_sent.fetch_add(tokens, Ordering::Relaxed);
+    }
+
+    /// Whether responses so far have used up the token budget. Search then
+    /// skips expansion and caps each facet.
+    pub fn over_budget(&self) -> bool {
+        self.token_budget
+            .is_some_and(|budget| self.tokens_sent.load(Ordering::Relaxed) >= budget)
+    }
+
+    /// Tokens sent, and of what budget: `~12.3k tokens of 50000`.
+    pub fn token_usage(&self) -> String {
+        let sent = token_count(self.tokens_sent.load(Ordering::Relaxed));
+        match self.token_budget {
+            Some(budget) => format!("~{sent} of {budget}"),
+            None => format!("~{sent}"),
+        }
+    }
+
+    /// Advance the tool-call counter. Returns the new (1-based) turn number.
+    pub fn next_turn(&self) -> usize {
+        self.turns.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Remember that `content` was sent for `key` (path + section) on the current turn.
+    /// Returns the turn it was first sent on if identical content was already sent.
+    pub fn record_sent(&self, key: &str, content: &str) -> Option<usize> {
+        let checksum = checksum(content);
+        let turn = self.turns.load(Ordering::Relaxed);
+
+        let mut sent = self
+            .sent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match sent.get(key) {
+            Some(&(prev, first)) if prev == checksum => Some(first),
+            _ => {
+                sent.insert(key.to_string(), (checksum, turn));
+                None
+            }
+        }
+    }
+
+    /// The turn identical `content` was first sent for `key` on, without
+    /// recording this send.
+    pub fn was_sent(&self, key: &str, content: &str) -> Option<usize> {
+        let checksum = checksum(content);
+        self.sent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(key)
+            .and_then(|&(prev, first)| (prev == checksum).then_some(first))
+    }
+
+    pub fn record_call(&self, tool: &str, elapsed: Duration) {
+        self.calls
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(tool.to_string())
+            .or_default()
+            .push(elapsed);
+    }
+
     pub fn record_read(&self, path: &Path) {
         self.reads.fetch_add(1, Ordering::Relaxed);
         self.record_dir(path);
@@ -62,7 +226,15 @@ impl Session {
         let searches = self.searches.load(Ordering::Relaxed);
         let maps = self.maps.load(Ordering::Relaxed);
 
-        let mut out = format!("Files read: {reads} | Searches: {searches} | Maps: {maps}");
+        let mut out = format!(
+            "Files read: {reads} | Searches: {searches} | Maps: {maps} | Sent: {}",
+            self.token_usage()
+        );
+        if self.over_budget() {
+            out.push_str(
+                "\nWARNING: token budget used up — search expansion is off, facets and responses are capped until reset.",
+            );
+        }
 
         // Top symbols
         let syms = self
@@ -99,10 +271,76 @@ impl Session {
         out
     }
 
+    /// Per-tool call counts and p50/p95 latency for this session, followed by
+    /// cache hit rates and walker totals since the server started.
+    pub fn perf(&self, counters: &PerfCounters) -> String {
+        let calls = self
+            .calls
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let total: usize = calls.values().map(Vec::len).sum();
+        let mut out = format!("# Session perf — {total} tool calls");
+
+        if !calls.is_empty() {
+            let mut tools: Vec<(&String, Vec<Duration>)> = calls
+                .iter()
+                .map(|(tool, times)| {
+                    let mut sorted = times.clone();
+                    sorted.sort_unstable();
+                    (tool, sorted)
+                })
+                .collect();
+            tools.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+            let _ = write!(
+                out,
+                "\n\n{:<16}{:>6}{:>11}{:>11}",
+                "tool", "calls", "p50", "p95"
+            );
+            for (tool, sorted) in &tools {
+                let _ = write!(
+                    out,
+                    "\n{tool:<16}{:>6}{:>11}{:>11}",
+                    sorted.len(),
+                    ms(percentile(sorted, 50)),
+                    ms(percentile(sorted, 95))
+                );
+            }
+        }
+
+        out.push_str("\n\nSince server start:");
+        let _ = write!(
+            out,
+            "\n  outline cache  {}",
+            hit_rate(counters.outline_cache)
+        );
+        let _ = write!(out, "\n  bloom filters  {}", hit_rate(counters.bloom_cache));
+        let _ = write!(
+            out,
+            "\n  symbol index   {}, {} builds",
+            hit_rate(counters.index_lookups),
+            counters.index_builds
+        );
+        if counters.index_unparsed > 0 {
+            let _ = write!(
+                out,
+                ", {} files unparsed (keyword fallback)",
+                counters.index_unparsed
+            );
+        }
+        let _ = write!(
+            out,
+            "\n  walker         {} files visited",
+            counters.walked_files
+        );
+        out
+    }
+
     pub fn reset(&self) {
         self.reads.store(0, Ordering::Relaxed);
         self.searches.store(0, Ordering::Relaxed);
         self.maps.store(0, Ordering::Relaxed);
+        self.turns.store(0, Ordering::Relaxed);
+        self.tokens_sent.store(0, Ordering::Relaxed);
         self.symbols
             .lock()
             .unwrap_or_else(std::sync::PoisonError::into_inner)
@@ -115,6 +353,85 @@ impl Session {
             .lock()
             .unwrap_or_else(std::sync::PoisonError::into_inner)
             .clear();
+        self.sent
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+        self.calls
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+        self.crumbs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Record that this turn's response showed `lines` of `path`.
+    pub fn disclose(&self, path: &Path, kind: &'static str, lines: Option<String>) {
+        let entry = Disclosure {
+            turn: self.turns.load(Ordering::Relaxed),
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            kind,
+            path: crate::pathnorm::display(&crate::pathnorm::canonical(path)),
+            lines,
+        };
+        if let Some(log) = &self.audit_log {
+            let mut log = log
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if let Ok(line) = serde_json::to_string(&entry) {
+                let _ = writeln!(log, "{line}");
+            }
+        }
+        self.disclosed
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(entry);
+    }
+
+    /// Every disclosure this session, oldest first, as a JSON array.
+    pub fn audit(&self) -> String {
+        let disclosed = self
+            .disclosed
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        serde_json::to_string_pretty(&*disclosed).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// The breadcrumb for a match shown on this turn, `S12.3`. A match shown
+    /// twice in one response keeps its first id.
+    pub fn breadcrumb(&self, path: &Path, line: u32, range: Option<(u32, u32)>) -> String {
+        let turn = self.turns.load(Ordering::Relaxed);
+        let mut crumbs = self
+            .crumbs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let trail = crumbs.entry(turn).or_default();
+        let n = if let Some(i) = trail.iter().position(|c| c.path == path && c.line == line) {
+            i + 1
+        } else {
+            trail.push(Crumb {
+                path: path.to_path_buf(),
+                line,
+                range,
+            });
+            trail.len()
+        };
+        format!("S{turn}.{n}")
+    }
+
+    /// The match an earlier breadcrumb `id` names, if this session showed it.
+    pub fn crumb(&self, id: &str) -> Option<Crumb> {
+        let (turn, n) = parse_breadcrumb(id)?;
+        self.crumbs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(&turn)?
+            .get(n.checked_sub(1)?)
+            .cloned()
     }
 
     pub fn is_expanded(&self, path: &Path, line: u32) -> bool {
@@ -139,3 +456,167 @@ impl Default for Session {
         Self::new()
     }
 }
+
+/// The turn and match number of a breadcrumb, `S12.3` → `(12, 3)`.
+pub fn parse_breadcrumb(id: &str) -> Option<(usize, usize)> {
+    let (turn, n) = id.strip_prefix(['S', 's'])?.split_once('.')?;
+    Some((turn.parse().ok()?, n.parse().ok()?))
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    let rank = (sorted.len() * p).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+fn ms(d: Duration) -> String {
+    format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+}
+
+fn hit_rate((hits, lookups): (usize, usize)) -> String {
+    if lookups == 0 {
+        return "no lookups".to_string();
+    }
+    format!("{hits}/{lookups} hits ({}%)", hits * 100 / lookups)
+}
+
+fn checksum(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_content_reports_first_turn() {
+        let session = Session::new();
+        session.next_turn();
+        assert_eq!(session.record_sent("a.rs#", "fn a() {}"), None);
+        session.next_turn();
+        session.next_turn();
+        assert_eq!(session.record_sent("a.rs#", "fn a() {}"), Some(1));
+    }
+
+    #[test]
+    fn changed_content_or_reset_forgets() {
+        let session = Session::new();
+        session.next_turn();
+        session.record_sent("a.rs#", "fn a() {}");
+        session.next_turn();
+        assert_eq!(session.was_sent("a.rs#", "fn b() {}"), None);
+        assert_eq!(session.record_sent("a.rs#", "fn b() {}"), None);
+        assert_eq!(session.record_sent("a.rs#", "fn b() {}"), Some(2));
+        assert_eq!(session.was_sent("a.rs#", "fn b() {}"), Some(2));
+
+        session.reset();
+        assert_eq!(session.record_sent("a.rs#", "fn b() {}"), None);
+    }
+
+    #[test]
+    fn audit_trail_survives_reset() {
+        let session = Session::new();
+        session.next_turn();
+        session.disclose(Path::new("/repo/src/a.rs"), "section", Some("10-20".into()));
+        session.next_turn();
+        session.disclose(Path::new("/repo/src/b.rs"), "outline", None);
+        session.reset();
+
+        let audit: Vec<serde_json::Value> = serde_json::from_str(&session.audit()).unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0]["turn"], 1);
+        assert_eq!(audit[0]["kind"], "section");
+        assert_eq!(audit[0]["path"], "/repo/src/a.rs");
+        assert_eq!(audit[0]["lines"], "10-20");
+        assert!(audit[1].get("lines").is_none());
+    }
+
+    #[test]
+    fn breadcrumbs_name_matches_by_turn() {
+        let session = Session::new();
+        session.next_turn();
+        session.next_turn();
+        let a = Path::new("/repo/src/a.rs");
+        assert_eq!(session.breadcrumb(a, 10, Some((10, 20))), "S2.1");
+        assert_eq!(session.breadcrumb(a, 42, None), "S2.2");
+        assert_eq!(session.breadcrumb(a, 10, Some((10, 20))), "S2.1");
+        session.next_turn();
+        assert_eq!(session.breadcrumb(a, 42, None), "S3.1");
+
+        let crumb = session.crumb("S2.1").unwrap();
+        assert_eq!((crumb.line, crumb.range), (10, Some((10, 20))));
+        assert_eq!(session.crumb("s2.2").unwrap().line, 42);
+        assert!(session.crumb("S2.3").is_none());
+        assert!(session.crumb("S2.0").is_none());
+        assert_eq!(parse_breadcrumb("src/a.rs"), None);
+
+        session.reset();
+        assert!(session.crumb("S2.1").is_none());
+    }
+
+    #[test]
+    fn token_budget_turns_terse_until_reset() {
+        let session = Session::new().with_token_budget(Some(100));
+        session.record_output(&"word ".repeat(60));
+        assert!(!session.over_budget());
+        assert!(session.summary().contains("Sent: ~75 tokens of 100"));
+
+        session.record_output(&"word ".repeat(20));
+        assert!(session.over_budget());
+        assert!(session
+            .summary()
+            .contains("\nWARNING: token budget used up"));
+
+        session.reset();
+        assert!(!session.over_budget());
+        assert!(!Session::new().over_budget());
+    }
+
+    #[test]
+    fn perf_reports_latency_percentiles() {
+        let session = Session::new();
+        for ms in 1..=20 {
+            session.record_call("tilth_search", Duration::from_millis(ms));
+        }
+        session.record_call("tilth_read", Duration::from_micros(500));
+        let out = session.perf(&PerfCounters {
+            outline_cache: (3, 4),
+            bloom_cache: (0, 0),
+            index_lookups: (5, 10),
+            index_builds: 1,
+            index_unparsed: 2,
+            walked_files: 1200,
+        });
+        assert!(out.starts_with("# Session perf — 21 tool calls"), "{out}");
+        assert!(
+            out.contains("\ntilth_search        20     10.0ms     19.0ms"),
+            "{out}"
+        );
+        assert!(
+            out.contains("\ntilth_read           1      0.5ms      0.5ms"),
+            "{out}"
+        );
+        assert!(out.contains("outline cache  3/4 hits (75%)"), "{out}");
+        assert!(out.contains("bloom filters  no lookups"), "{out}");
+        assert!(
+            out.contains(
+                "symbol index   5/10 hits (50%), 1 builds, 2 files unparsed (keyword fallback)"
+            ),
+            "{out}"
+        );
+
+        session.reset();
+        assert!(session
+            .perf(&PerfCounters {
+                outline_cache: (0, 0),
+                bloom_cache: (0, 0),
+                index_lookups: (0, 0),
+                index_builds: 0,
+                index_unparsed: 0,
+                walked_files: 0,
+            })
+            .starts_with("# Session perf — 0 tool calls"));
+    }
+}
@@ -0,0 +1,249 @@
+//! Where a match in built output came from, for the `sourcemaps` option: a
+//! match in `dist/app.js` with a source map beside it also names the line
+//! of the original source it was built from, so a stack trace that points
+//! into the bundle leads back to code that can be read and edited.
+//!
+//! Maps are found as reads find them — the `sourceMappingURL` comment, else
+//! an adjacent `.map` — and decoded once per map until it changes.
+//!
+//! Like owners, the option is process-wide for the call in progress.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::SystemTime;
+
+use crate::read::bundle::{self, MapRef};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns source map translation on for one call; off again on drop.
+pub struct Translate(());
+
+impl Translate {
+    #[must_use]
+    pub fn set(enabled: bool) -> Self {
+        ENABLED.store(enabled, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Drop for Translate {
+    fn drop(&mut self) {
+        ENABLED.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Whether the call in progress asked for source locations.
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// ` [source: src/app.ts:42]` for the match at 1-based `line` and byte
+/// `column` of `path`, whose text is `content` — the original location per
+/// the file's source map, relative to `scope` when the source is in the
+/// tree and as the map names it otherwise. Empty when translation is off
+/// or the file has no map covering that spot.
+pub(crate) fn annotation(
+    path: &Path,
+    content: &str,
+    line: u32,
+    column: u32,
+    scope: &Path,
+) -> String {
+    if !enabled() {
+        return String::new();
+    }
+    let Some(MapRef::File(map_path)) = bundle::map_reference(path, content.as_bytes()) else {
+        return String::new();
+    };
+    let Some(map) = load(&map_path) else {
+        return String::new();
+    };
+    let Some((source, original)) = map.lookup(line, column) else {
+        return String::new();
+    };
+    let shown = match &source.path {
+        Some(path) => crate::pathnorm::relative(path, &crate::pathnorm::canonical(scope)),
+        None => source.name.clone(),
+    };
+    format!(" [source: {shown}:{original}]")
+}
+
+/// A source a map lists: its name as written there, and the file in this
+/// tree when it resolves to one.
+#[derive(Debug)]
+struct Source {
+    name: String,
+    path: Option<PathBuf>,
+}
+
+/// One mapped span start: from `column` of a generated line, the code came
+/// from `line` (0-based) of `sources[source]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    column: u32,
+    source: u32,
+    line: u32,
+}
+
+#[derive(Debug)]
+struct SourceMap {
+    sources: Vec<Source>,
+    /// Segments of each generated line, by column.
+    lines: Vec<Vec<Segment>>,
+}
+
+impl SourceMap {
+    fn parse(json: &str, dir: &Path) -> Option<Self> {
+        let json: serde_json::Value = serde_json::from_str(json).ok()?;
+        let root = json
+            .get("sourceRoot")
+            .and_then(|r| r.as_str())
+            .unwrap_or("");
+        let sources = json
+            .get("sources")?
+            .as_array()?
+            .iter()
+            .map(|s| {
+                let name = s.as_str().unwrap_or("").to_string();
+                let path = bundle::resolve_source(dir, root, &name);
+                Source { name, path }
+            })
+            .collect();
+        let lines = decode(json.get("mappings")?.as_str()?)?;
+        Some(Self { sources, lines })
+    }
+
+    /// The source and 1-based line the code at 1-based `line`, byte
+    /// `column` was built from: the last segment starting at or before the
+    /// column, or the line's first when the column is before them all.
+    fn lookup(&self, line: u32, column: u32) -> Option<(&Source, u32)> {
+        let segments = self.lines.get(line.checked_sub(1)? as usize)?;
+        let at = segments.partition_point(|s| s.column <= column);
+        let segment = segments.get(at.saturating_sub(1))?;
+        Some((self.sources.get(segment.source as usize)?, segment.line + 1))
+    }
+}
+
+/// The segments of a `mappings` string, per generated line. Segments with
+/// no source are dropped. `None` when the string is malformed.
+fn decode(mappings: &str) -> Option<Vec<Vec<Segment>>> {
+    // Every field but the generated column carries over from line to line
+    let (mut source, mut line) = (0i64, 0i64);
+    let mut lines = Vec::new();
+    for text in mappings.split(';') {
+        let mut column = 0i64;
+        let mut segments = Vec::new();
+        for segment in text.split(',').filter(|s| !s.is_empty()) {
+            let mut bytes = segment.bytes();
+            column += vlq(&mut bytes)?;
+            let Some(delta) = vlq(&mut bytes) else {
+                continue;
+            };
+            source += delta;
+            line += vlq(&mut bytes)?;
+            segments.push(Segment {
+                column: u32::try_from(column).ok()?,
+                source: u32::try_from(source).ok()?,
+                line: u32::try_from(line).ok()?,
+            });
+        }
+        segments.sort_by_key(|s| s.column);
+        lines.push(segments);
+    }
+    Some(lines)
+}
+
+/// The next base64 VLQ value in `bytes`; `None` at the end or on a bad digit.
+fn vlq(bytes: &mut impl Iterator<Item = u8>) -> Option<i64> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    loop {
+        let digit = match bytes.next()? {
+            b @ b'A'..=b'Z' => b - b'A',
+            b @ b'a'..=b'z' => b - b'a' + 26,
+            b @ b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        if shift > 60 {
+            return None;
+        }
+        value |= i64::from(digit & 0b1_1111) << shift;
+        shift += 5;
+        if digit & 0b10_0000 == 0 {
+            break;
+        }
+    }
+    let magnitude = value >> 1;
+    Some(if value & 1 == 1 {
+        -magnitude
+    } else {
+        magnitude
+    })
+}
+
+/// Decoded maps, re-read when they change.
+type MapCache = HashMap<PathBuf, (SystemTime, Option<Arc<SourceMap>>)>;
+
+fn load(map: &Path) -> Option<Arc<SourceMap>> {
+    static CACHE: OnceLock<Mutex<MapCache>> = OnceLock::new();
+    let mtime = std::fs::metadata(map).and_then(|m| m.modified()).ok()?;
+    let mut cache = CACHE
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((stamp, parsed)) = cache.get(map) {
+        if *stamp == mtime {
+            return parsed.clone();
+        }
+    }
+    let dir = map.parent().unwrap_or(Path::new("."));
+    let parsed = std::fs::read_to_string(map)
+        .ok()
+        .and_then(|json| SourceMap::parse(&json, dir))
+        .map(Arc::new);
+    cache.insert(map.to_path_buf(), (mtime, parsed.clone()));
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_vlq_segments_across_lines() {
+        let mut digits = "AAgBD".bytes();
+        assert_eq!(vlq(&mut digits), Some(0));
+        assert_eq!(vlq(&mut digits), Some(0));
+        assert_eq!(vlq(&mut digits), Some(16));
+        assert_eq!(vlq(&mut digits), Some(-1));
+        // Line 1: col 0 → src 0 line 0; col 10 → src 1 line 4. Line 2:
+        // col 2 → src 1 line 5, the source and line carried over.
+        let lines = decode("AAAA,UCIA;EACA").unwrap();
+        let seg = |column, source, line| Segment {
+            column,
+            source,
+            line,
+        };
+        assert_eq!(lines[0], vec![seg(0, 0, 0), seg(10, 1, 4)]);
+        assert_eq!(lines[1], vec![seg(2, 1, 5)]);
+    }
+
+    #[test]
+    fn looks_up_the_segment_covering_a_column() {
+        let map = SourceMap::parse(
+            r#"{"version":3,"sources":["a.ts","b.ts"],"mappings":"AAAA,UCIA;EACA"}"#,
+            Path::new("/nonexistent"),
+        )
+        .unwrap();
+        let at = |line, column| map.lookup(line, column).map(|(s, l)| (s.name.as_str(), l));
+        assert_eq!(at(1, 5), Some(("a.ts", 1)));
+        assert_eq!(at(1, 40), Some(("b.ts", 5)));
+        assert_eq!(at(2, 0), Some(("b.ts", 6)));
+        assert_eq!(at(3, 0), None);
+    }
+}
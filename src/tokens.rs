@@ -0,0 +1,125 @@
+//! Token estimates that account for what the bytes are.
+//!
+//! A flat bytes/4 ([`crate::types::estimate_tokens`]) is close for prose and
+//! ordinary source, but minified bundles pack a token into fewer bytes and
+//! CJK text spends three bytes on a character that is a token on its own —
+//! both come out far under, and a file that should have been outlined gets
+//! read in full. [`estimate`] looks at the content: whitespace density picks
+//! the bytes-per-token ratio for ASCII, and each character outside the
+//! two-byte UTF-8 range counts as a token. [`estimate_len`] is for when only
+//! a size is at hand, and calibrates by file type alone.
+//!
+//! Built with the `tokenizer` feature, [`estimate`] counts real `cl100k_base`
+//! tokens instead.
+
+use crate::types::FileType;
+
+/// Bytes per token, ×10 to keep the arithmetic integral, for ASCII text of
+/// each kind of file.
+fn bytes_per_token_x10(file_type: FileType) -> u64 {
+    match file_type {
+        // Braces, quotes and short keys split into many small tokens
+        FileType::StructuredData | FileType::Tabular => 33,
+        // Timestamps and ids
+        FileType::Log => 35,
+        FileType::Code(_) | FileType::Markdown | FileType::Other => 40,
+    }
+}
+
+/// ASCII that is under this share of whitespace (in percent) reads as
+/// minified: long identifiers are rare, punctuation is everywhere.
+const DENSE_WHITESPACE_PCT: u64 = 8;
+/// Bytes per token ×10 for minified text.
+const DENSE_BYTES_PER_TOKEN_X10: u64 = 25;
+
+/// Estimated tokens in `content`, a file of `file_type`.
+#[must_use]
+pub(crate) fn estimate(content: &[u8], file_type: FileType) -> u64 {
+    #[cfg(feature = "tokenizer")]
+    if let Ok(text) = std::str::from_utf8(content) {
+        return tokenizer::count(text);
+    }
+
+    let (mut narrow, mut wide, mut space) = (0u64, 0u64, 0u64);
+    for &b in content {
+        match b {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                space += 1;
+                narrow += 1;
+            }
+            // ASCII, and the lead byte of a two-byte character (accented
+            // Latin, Cyrillic, Greek) — about as dense as a letter
+            0x00..=0x7F | 0xC0..=0xDF => narrow += 1,
+            // Lead byte of a three- or four-byte character: CJK, emoji
+            0xE0..=0xFF => wide += 1,
+            // Continuation bytes
+            _ => {}
+        }
+    }
+    let per_token = if narrow >= 256 && space * 100 < narrow * DENSE_WHITESPACE_PCT {
+        DENSE_BYTES_PER_TOKEN_X10
+    } else {
+        bytes_per_token_x10(file_type)
+    };
+    (narrow * 10).div_ceil(per_token) + wide
+}
+
+/// Estimated tokens in a `file_type` file of `byte_len` bytes, unread.
+#[must_use]
+pub(crate) fn estimate_len(byte_len: u64, file_type: FileType) -> u64 {
+    (byte_len * 10).div_ceil(bytes_per_token_x10(file_type))
+}
+
+/// About how many leading bytes of `text` fit in `tokens` — the inverse of
+/// [`estimate`] at `text`'s own density. Not on a char boundary.
+#[must_use]
+pub(crate) fn bytes_for(tokens: u64, text: &str) -> usize {
+    let total = estimate(text.as_bytes(), FileType::Other).max(1);
+    (tokens.saturating_mul(text.len() as u64) / total) as usize
+}
+
+#[cfg(feature = "tokenizer")]
+mod tokenizer {
+    /// Exact `cl100k_base` count.
+    pub(super) fn count(text: &str) -> u64 {
+        let bpe = tiktoken_rs::cl100k_base_singleton();
+        let bpe = bpe.lock();
+        bpe.encode_ordinary(text).len() as u64
+    }
+}
+
+// Exact figures assume the estimate, not the tokenizer
+#[cfg(all(test, not(feature = "tokenizer")))]
+mod tests {
+    use super::*;
+    use crate::types::Lang;
+
+    #[test]
+    fn ordinary_source_stays_near_bytes_over_four() {
+        let source =
+            "fn main() {\n    let total = items.iter().map(|i| i.len()).sum();\n}\n".repeat(20);
+        let tokens = estimate(source.as_bytes(), FileType::Code(Lang::Rust));
+        assert_eq!(tokens, (source.len() as u64).div_ceil(4));
+    }
+
+    #[test]
+    fn minified_and_cjk_count_higher() {
+        let minified = "function(a,b){return a.x+b.y*(c||d)};".repeat(40);
+        let flat = (minified.len() as u64).div_ceil(4);
+        let tokens = estimate(minified.as_bytes(), FileType::Code(Lang::JavaScript));
+        assert!(tokens > flat * 3 / 2, "{tokens} vs {flat}");
+
+        let cjk = "这是一个测试文件。".repeat(50);
+        let flat = (cjk.len() as u64).div_ceil(4);
+        let tokens = estimate(cjk.as_bytes(), FileType::Markdown);
+        assert_eq!(tokens, cjk.chars().count() as u64);
+        assert!(tokens > flat, "{tokens} vs {flat}");
+    }
+
+    #[test]
+    fn bytes_for_inverts_estimate() {
+        let text = "word ".repeat(100);
+        assert_eq!(bytes_for(50, &text), 200);
+        assert_eq!(estimate_len(400, FileType::StructuredData), 122);
+    }
+}
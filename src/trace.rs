@@ -0,0 +1,511 @@
+//! Stack traces pasted from a failure, resolved to the code they point at.
+//! Each frame becomes its file in scope and a hashlined snippet around the
+//! line, so a trace goes straight to reading and editing the right lines.
+//!
+//! Frames are recognized line by line, whatever the mix of formats:
+//! Python's `File "x.py", line 42, in f`, Java's `at pkg.Cls.m(Cls.java:42)`,
+//! JS `at f (x.js:42:7)` and `f@x.js:42:7`, Go's function line followed by
+//! `/path/x.go:42 +0x1d`, and Rust panics and backtraces. A trace from
+//! another machine is matched to the tree by the longest path suffix.
+
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::TilthError;
+
+/// Frames shown with a snippet; later ones are listed as locations only.
+const MAX_SNIPPETS: usize = 20;
+
+/// Paths in a frame that belong to a runtime or dependency, not the tree.
+const OUTSIDE: &[&str] = &[
+    "site-packages/",
+    "dist-packages/",
+    "node_modules/",
+    "/rustc/",
+    ".cargo/registry/",
+    "/go/src/runtime/",
+    "/usr/lib/",
+    "/usr/local/lib/",
+];
+
+/// One frame of a trace: where it points and, when the format says, the
+/// function it was in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Frame {
+    file: String,
+    line: u32,
+    function: Option<String>,
+    lang: &'static str,
+}
+
+/// Resolve the frames of `trace` against `scope`, with `context` lines of
+/// snippet either side of each.
+pub fn trace(trace: &str, scope: &Path, context: u32) -> Result<String, TilthError> {
+    let frames = parse(trace);
+    if frames.is_empty() {
+        return Err(TilthError::InvalidQuery {
+            query: trace.lines().next().unwrap_or("").to_string(),
+            reason: "no stack frames found — expected Rust, Python, JS, Java or Go trace lines"
+                .to_string(),
+        });
+    }
+
+    let mut tree = None;
+    let resolved: Vec<Option<PathBuf>> = frames
+        .iter()
+        .map(|f| resolve(&f.file, scope, &mut tree))
+        .collect();
+    let mut langs: Vec<&str> = Vec::new();
+    for frame in &frames {
+        if !langs.contains(&frame.lang) {
+            langs.push(frame.lang);
+        }
+    }
+    let in_scope = resolved.iter().filter(|r| r.is_some()).count();
+    let mut out = format!(
+        "# Trace: {} frames ({}) — {in_scope} in {}",
+        frames.len(),
+        langs.join(", "),
+        scope.display()
+    );
+
+    let root = crate::pathnorm::canonical(scope);
+    let mut snippets = 0;
+    let mut i = 0;
+    while i < frames.len() {
+        let frame = &frames[i];
+        // Recursion repeats a frame; show it once with the count
+        let repeats = frames[i..].iter().take_while(|f| *f == frame).count();
+        let function = frame
+            .function
+            .as_deref()
+            .map(|f| format!(" in {f}"))
+            .unwrap_or_default();
+        let repeated = if repeats > 1 {
+            format!(" (×{repeats})")
+        } else {
+            String::new()
+        };
+        match &resolved[i] {
+            Some(path) if snippets < MAX_SNIPPETS => {
+                snippets += 1;
+                let _ = write!(
+                    out,
+                    "\n\n## {}. {}:{}{function}{repeated}\n",
+                    i + 1,
+                    crate::pathnorm::relative(path, &root),
+                    frame.line
+                );
+                out.push_str(&snippet(path, frame.line, context));
+            }
+            Some(path) => {
+                let _ = write!(
+                    out,
+                    "\n\n## {}. {}:{}{function}{repeated}",
+                    i + 1,
+                    crate::pathnorm::relative(path, &root),
+                    frame.line
+                );
+            }
+            None => {
+                let _ = write!(
+                    out,
+                    "\n\n- {}. {}:{}{function}{repeated} [outside scope]",
+                    i + 1,
+                    frame.file,
+                    frame.line
+                );
+            }
+        }
+        i += repeats;
+    }
+    if in_scope == 0 {
+        out.push_str("\n\n> No frame resolved to a file in scope — widen `scope` to the project the trace came from.");
+    }
+    Ok(out)
+}
+
+/// Lines `line - context ..= line + context` of `path`, hashlined.
+fn snippet(path: &Path, line: u32, context: u32) -> String {
+    let Ok(content) = fs::read_to_string(path) else {
+        return "(unreadable)\n".to_string();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    if line == 0 || line as usize > lines.len() {
+        return format!(
+            "(line {line} is past the end — {} lines; the trace is from another version)\n",
+            lines.len()
+        );
+    }
+    let start = line.saturating_sub(context).max(1);
+    let end = line.saturating_add(context).min(lines.len() as u32);
+    let text = lines[start as usize - 1..end as usize].join("\n");
+    crate::format::hashlines(&text, start)
+}
+
+/// The frames in `trace`, in the order given. A Rust backtrace frame at
+/// the panic's own location is merged into the panic's frame.
+fn parse(trace: &str) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = Vec::new();
+    // Rust backtraces and Go name the function on the line before its location
+    let mut pending: Option<String> = None;
+    // Index of the Rust panic location still waiting for its function
+    let mut panic: Option<usize> = None;
+    for raw in trace.lines() {
+        let line = raw.trim();
+        if let Some(frame) = rust_panic(line) {
+            panic = Some(frames.len());
+            frames.push(frame);
+            pending = None;
+        } else if let Some(frame) = python(line)
+            .or_else(|| java(line))
+            .or_else(|| go(line, &mut pending))
+            .or_else(|| at_frame(line, &mut pending))
+            .or_else(|| firefox(line))
+        {
+            match panic.map(|i| &mut frames[i]) {
+                Some(at) if same_location(at, &frame) => {
+                    at.function = frame.function;
+                    panic = None;
+                }
+                _ => frames.push(frame),
+            }
+            pending = None;
+        } else {
+            pending = function_line(line);
+        }
+    }
+    frames
+}
+
+/// Whether `frame` points where `panic` does, ignoring a leading `./`.
+fn same_location(panic: &Frame, frame: &Frame) -> bool {
+    frame.lang == "rust"
+        && frame.line == panic.line
+        && frame.file.trim_start_matches("./") == panic.file.trim_start_matches("./")
+}
+
+/// `File "app/x.py", line 42, in handle`
+fn python(line: &str) -> Option<Frame> {
+    let rest = line.strip_prefix("File \"")?;
+    let (file, rest) = rest.split_once('"')?;
+    let rest = rest.strip_prefix(", line ")?;
+    let (number, function) = match rest.split_once(", in ") {
+        Some((n, f)) => (n, Some(f.trim().to_string())),
+        None => (rest, None),
+    };
+    Some(Frame {
+        file: file.to_string(),
+        line: number.trim().parse().ok()?,
+        function,
+        lang: "python",
+    })
+}
+
+/// `at com.foo.Bar.handle(Bar.java:42)` — the file's directory comes from
+/// the class's package.
+fn java(line: &str) -> Option<Frame> {
+    let rest = line.strip_prefix("at ")?;
+    let (qualified, location) = rest.strip_suffix(')')?.split_once('(')?;
+    let (name, number) = location.split_once(':')?;
+    let jvm = [".java", ".kt", ".scala", ".groovy"];
+    if !jvm.iter().any(|ext| name.ends_with(ext)) {
+        return None;
+    }
+    // Drop a module prefix (`app//com.foo...`) and split off class.method
+    let qualified = qualified.rsplit('/').next().unwrap_or(qualified);
+    let mut package: Vec<&str> = qualified.split('.').collect();
+    package.truncate(package.len().saturating_sub(2));
+    let mut file = package.join("/");
+    if !file.is_empty() {
+        file.push('/');
+    }
+    file.push_str(name);
+    Some(Frame {
+        file,
+        line: number.parse().ok()?,
+        function: Some(qualified.to_string()),
+        lang: "java",
+    })
+}
+
+/// `/home/u/app/server.go:42 +0x1d`, after the function's own line.
+fn go(line: &str, pending: &mut Option<String>) -> Option<Frame> {
+    let location = line.split_whitespace().next()?;
+    if !location.contains(".go:") {
+        return None;
+    }
+    let (file, number) = location_parts(location)?;
+    Some(Frame {
+        file: file.to_string(),
+        line: number,
+        function: pending.take(),
+        lang: "go",
+    })
+}
+
+/// `thread 'main' panicked at src/main.rs:10:5:`, or before Rust 1.73
+/// `panicked at 'message', src/main.rs:10:5`.
+fn rust_panic(line: &str) -> Option<Frame> {
+    let (_, rest) = line.split_once("panicked at ")?;
+    let rest = match rest.strip_prefix('\'') {
+        Some(quoted) => quoted.rsplit_once("', ")?.1,
+        None => rest,
+    };
+    let (file, number) = location_parts(rest.split_whitespace().next()?)?;
+    Some(Frame {
+        file: file.to_string(),
+        line: number,
+        function: None,
+        lang: "rust",
+    })
+}
+
+/// `at handle (/app/src/x.js:42:7)`, `at /app/src/x.js:42:7`, or a Rust
+/// backtrace's `at ./src/main.rs:10:5` below its function.
+fn at_frame(line: &str, pending: &mut Option<String>) -> Option<Frame> {
+    let rest = line.strip_prefix("at ")?.trim_start_matches("async ");
+    let (function, location) = match rest.strip_suffix(')').and_then(|r| r.rsplit_once(" (")) {
+        Some((function, location)) => (Some(function), location),
+        None => (None, rest),
+    };
+    let (file, number) = location_parts(strip_url(location))?;
+    let lang = if Path::new(file).extension() == Some(OsStr::new("rs")) {
+        "rust"
+    } else {
+        "js"
+    };
+    let function = match function {
+        Some(f) => Some(f.trim_start_matches("new ").to_string()),
+        None => pending.take(),
+    };
+    Some(Frame {
+        file: file.to_string(),
+        line: number,
+        function,
+        lang,
+    })
+}
+
+/// Firefox and Safari: `handle@http://localhost:3000/src/x.js:42:7`.
+fn firefox(line: &str) -> Option<Frame> {
+    let (function, location) = line.split_once('@')?;
+    if function.contains(char::is_whitespace) {
+        return None;
+    }
+    let (file, number) = location_parts(strip_url(location))?;
+    Some(Frame {
+        file: file.to_string(),
+        line: number,
+        function: (!function.is_empty()).then(|| function.to_string()),
+        lang: "js",
+    })
+}
+
+/// The function a Rust backtrace (`  3: app::handler::run`) or Go trace
+/// (`main.(*Server).handle(0xc000010000)`) names before its location line.
+fn function_line(line: &str) -> Option<String> {
+    if let Some((index, name)) = line.split_once(": ") {
+        if index.chars().all(|c| c.is_ascii_digit()) && !index.is_empty() {
+            return Some(name.trim().to_string());
+        }
+    }
+    if line.ends_with(')') && !line.starts_with("goroutine ") && !line.contains(' ') {
+        let (name, _) = line.rsplit_once('(')?;
+        return Some(name.to_string());
+    }
+    None
+}
+
+/// `path:line` or `path:line:column` split into the path and line.
+fn location_parts(location: &str) -> Option<(&str, u32)> {
+    let location = location.trim().trim_end_matches(':');
+    let (rest, last) = location.rsplit_once(':')?;
+    let last: u32 = last.parse().ok()?;
+    let (file, line) = match rest.rsplit_once(':') {
+        Some((file, line)) => match line.parse() {
+            Ok(line) => (file, line),
+            Err(_) => (rest, last),
+        },
+        None => (rest, last),
+    };
+    (!file.is_empty()).then_some((file, line))
+}
+
+/// A location's path without its URL scheme and host: `file:///app/x.js`
+/// and `http://localhost:3000/src/x.js` become `/app/x.js` and `/src/x.js`.
+fn strip_url(location: &str) -> &str {
+    if let Some(path) = location.strip_prefix("file://") {
+        return path;
+    }
+    match location.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or(rest, |i| &rest[i..]),
+        None => location,
+    }
+}
+
+/// The file in `scope` a frame's `file` names: the path as given, relative
+/// to the scope, or else the file in the tree sharing the longest path
+/// suffix with it — at least its directory and name when it has both.
+/// `None` for runtime and dependency code and for files not in the tree.
+fn resolve(file: &str, scope: &Path, tree: &mut Option<Vec<PathBuf>>) -> Option<PathBuf> {
+    if file.starts_with('<')
+        || file.starts_with("node:")
+        || OUTSIDE.iter().any(|m| file.contains(m))
+    {
+        return None;
+    }
+    let root = crate::pathnorm::canonical(scope);
+    let path = Path::new(file);
+    let direct = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        scope.join(path)
+    };
+    if direct.is_file() && !crate::redact::hides(&direct) {
+        let direct = crate::pathnorm::canonical(&direct);
+        if crate::pathnorm::starts_with(&direct, &root) {
+            return Some(direct);
+        }
+    }
+
+    let wanted: Vec<&OsStr> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect();
+    let name = *wanted.last()?;
+    let tree = tree.get_or_insert_with(|| crate::analyze::collect_files(scope, |_| true));
+    let shared = |candidate: &Path| {
+        candidate
+            .components()
+            .rev()
+            .zip(wanted.iter().rev())
+            .take_while(|(have, want)| have.as_os_str() == **want)
+            .count()
+    };
+    let (best, matched) = tree
+        .iter()
+        .filter(|p| p.file_name() == Some(name))
+        .map(|p| (p, shared(p)))
+        .max_by(|a, b| {
+            a.1.cmp(&b.1)
+                .then_with(|| b.0.as_os_str().len().cmp(&a.0.as_os_str().len()))
+        })?;
+    (matched >= wanted.len().min(2)).then(|| crate::pathnorm::canonical(best))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(file: &str, line: u32, function: Option<&str>, lang: &'static str) -> Frame {
+        Frame {
+            file: file.to_string(),
+            line,
+            function: function.map(str::to_string),
+            lang,
+        }
+    }
+
+    #[test]
+    fn parses_each_format() {
+        let python = "Traceback (most recent call last):\n  File \"/srv/app/handlers.py\", line 42, in handle\n    return parse(body)\nValueError: bad";
+        assert_eq!(
+            parse(python),
+            vec![frame("/srv/app/handlers.py", 42, Some("handle"), "python")]
+        );
+
+        let java = "java.lang.NullPointerException\n\tat com.acme.api.Server.handle(Server.java:88)\n\tat java.base/java.lang.Thread.run(Thread.java:833)";
+        let frames = parse(java);
+        assert_eq!(frames[0].file, "com/acme/api/Server.java");
+        assert_eq!(frames[0].line, 88);
+        assert_eq!(frames[1].file, "java/lang/Thread.java");
+
+        let js = "TypeError: x is undefined\n    at handle (/app/src/server.js:12:5)\n    at async /app/src/index.mjs:3:1\nrender@http://localhost:3000/src/view.js:7:2";
+        let frames = parse(js);
+        assert_eq!(
+            frames[0],
+            frame("/app/src/server.js", 12, Some("handle"), "js")
+        );
+        assert_eq!(frames[1].file, "/app/src/index.mjs");
+        assert_eq!(frames[2], frame("/src/view.js", 7, Some("render"), "js"));
+
+        let go = "goroutine 1 [running]:\nmain.(*Server).handle(0xc000010000)\n\t/home/u/app/server.go:42 +0x1d";
+        assert_eq!(
+            parse(go),
+            vec![frame(
+                "/home/u/app/server.go",
+                42,
+                Some("main.(*Server).handle"),
+                "go"
+            )]
+        );
+
+        let rust = "thread 'main' panicked at src/main.rs:10:5:\nboom\nstack backtrace:\n   3: app::handler::run\n             at ./src/handler.rs:42:9";
+        assert_eq!(
+            parse(rust),
+            vec![
+                frame("src/main.rs", 10, None, "rust"),
+                frame("./src/handler.rs", 42, Some("app::handler::run"), "rust"),
+            ]
+        );
+        // The backtrace's own frame at the panic location isn't listed twice
+        let rust = "thread 'main' panicked at src/deadline.rs:51:9:\nboom\nstack backtrace:\n   0: tilth::deadline::reached\n             at ./src/deadline.rs:51:9\n   1: tilth::search::walk\n             at ./src/search/mod.rs:148:13";
+        assert_eq!(
+            parse(rust),
+            vec![
+                frame(
+                    "src/deadline.rs",
+                    51,
+                    Some("tilth::deadline::reached"),
+                    "rust"
+                ),
+                frame(
+                    "./src/search/mod.rs",
+                    148,
+                    Some("tilth::search::walk"),
+                    "rust"
+                ),
+            ]
+        );
+        assert_eq!(
+            parse("thread 'main' panicked at 'oops', src/lib.rs:3:1")[0].file,
+            "src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn resolves_frames_by_path_suffix() {
+        let dir = std::env::temp_dir().join("tilth_test_trace");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("app/api")).unwrap();
+        fs::create_dir_all(dir.join("other")).unwrap();
+        let body = (1..=20).fold(String::new(), |body, i| body + &format!("line {i}\n"));
+        fs::write(dir.join("app/api/handlers.py"), &body).unwrap();
+        fs::write(dir.join("other/handlers.py"), &body).unwrap();
+
+        let trace_text = "Traceback (most recent call last):\n  File \"/ci/build/app/api/handlers.py\", line 10, in handle\n  File \"/usr/lib/python3.11/json/decoder.py\", line 337, in decode\nValueError";
+        let out = trace(trace_text, &dir, 1).unwrap();
+        assert!(
+            out.starts_with("# Trace: 2 frames (python) — 1 in"),
+            "{out}"
+        );
+        assert!(
+            out.contains("## 1. app/api/handlers.py:10 in handle\n9:"),
+            "{out}"
+        );
+        assert!(out.contains("|line 11\n"), "{out}");
+        assert!(
+            out.contains("decoder.py:337 in decode [outside scope]"),
+            "{out}"
+        );
+
+        assert!(trace("no frames here", &dir, 3).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -35,6 +35,30 @@ pub enum Lang {
     Make,
 }
 
+impl std::fmt::Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Rust => "Rust",
+            Self::TypeScript => "TypeScript",
+            Self::Tsx => "TSX",
+            Self::JavaScript => "JavaScript",
+            Self::Python => "Python",
+            Self::Go => "Go",
+            Self::Java => "Java",
+            Self::Scala => "Scala",
+            Self::C => "C",
+            Self::Cpp => "C++",
+            Self::Ruby => "Ruby",
+            Self::Swift => "Swift",
+            Self::Kotlin => "Kotlin",
+            Self::CSharp => "C#",
+            Self::Dockerfile => "Dockerfile",
+            Self::Make => "Make",
+        };
+        f.write_str(name)
+    }
+}
+
 /// File type as detected by extension. Determines outline strategy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileType {
@@ -101,6 +125,20 @@ pub struct Match {
     /// For impl/implements matches: the trait or interface being implemented.
     /// None for primary definitions and plain usages.
     pub impl_target: Option<String>,
+    /// For Rust methods inside a trait or a trait impl: which side they are on.
+    pub trait_method: Option<TraitMethod>,
+}
+
+/// A Rust method definition's place relative to its trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraitMethod {
+    /// Default body written in the `trait` block itself.
+    Default { trait_name: String },
+    /// Body inside `impl Trait for Type`, replacing or providing the trait's.
+    Override {
+        trait_name: String,
+        impl_type: String,
+    },
 }
 
 /// Assembled search results before formatting.
@@ -112,6 +150,15 @@ pub struct SearchResult {
     pub total_found: usize,
     pub definitions: usize,
     pub usages: usize,
+    /// File of every match found, before `matches` was cut to the top few —
+    /// where the results concentrate, for scope hints.
+    pub match_paths: Vec<PathBuf>,
+    /// Files mentioning the query that tree-sitter couldn't parse cleanly;
+    /// definitions there were found by keyword heuristic.
+    pub unparsed: Vec<PathBuf>,
+    /// Files matched under more than one path (hard links, symlinks): the
+    /// path the matches are listed under, and the others.
+    pub aliases: Vec<(PathBuf, Vec<PathBuf>)>,
 }
 
 /// A single entry in a code outline.
@@ -124,6 +171,11 @@ pub struct OutlineEntry {
     pub signature: Option<String>,
     pub children: Vec<OutlineEntry>,
     pub doc: Option<String>,
+    /// Condition of the Rust `#[cfg(...)]` attributes gating this item, e.g. `unix`.
+    pub cfg: Option<String>,
+    /// Behavior-changing decorators/attributes as written: `@app.route("/")`,
+    /// `#[tokio::main]`. Lint, doc and derive attributes are left out.
+    pub decorators: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
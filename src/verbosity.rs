@@ -0,0 +1,112 @@
+//! How much surrounding detail a tool call returns, from its `verbosity`
+//! argument. Features ask [`current`] instead of taking their own toggle:
+//! the `── calls ──` and `── siblings ──` footers, `> Related:` hints, the
+//! outline context and enclosing-function suffix on match headers, and
+//! blank lines between sections.
+//!
+//! Like the deadline, the level is process-wide — the MCP server handles
+//! one request at a time.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Verbosity {
+    /// Matches and code only: no footers or hints, bare match headers, no
+    /// blank lines.
+    Compact,
+    /// The default.
+    Normal,
+    /// Longer footers, and outline context even for small files.
+    Rich,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+impl Verbosity {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "compact" => Some(Self::Compact),
+            "normal" => Some(Self::Normal),
+            "rich" => Some(Self::Rich),
+            _ => None,
+        }
+    }
+
+    /// Whether `── calls ──`, `── siblings ──` and `> Related:` are added.
+    pub(crate) fn footers(self) -> bool {
+        self != Self::Compact
+    }
+
+    /// How many entries a footer lists, given its cap at normal verbosity.
+    pub(crate) fn footer_cap(self, normal: usize) -> usize {
+        match self {
+            Self::Rich => normal * 2,
+            _ => normal,
+        }
+    }
+
+    /// Whether match headers carry the enclosing function and outline
+    /// context, rather than just the matched line.
+    pub(crate) fn context(self) -> bool {
+        self != Self::Compact
+    }
+
+    /// The finished response, with blank lines dropped when compact.
+    pub(crate) fn shape(self, output: String) -> String {
+        if self != Self::Compact || !output.contains("\n\n") {
+            return output;
+        }
+        let mut out = String::with_capacity(output.len());
+        for line in output.lines().filter(|l| !l.trim().is_empty()) {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(line);
+        }
+        out
+    }
+}
+
+/// Sets the level for one call; back to normal on drop.
+pub(crate) struct Level(());
+
+impl Level {
+    pub(crate) fn set(verbosity: Verbosity) -> Self {
+        LEVEL.store(verbosity as u8, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Drop for Level {
+    fn drop(&mut self) {
+        LEVEL.store(Verbosity::Normal as u8, Ordering::Relaxed);
+    }
+}
+
+/// The level of the call in progress.
+pub(crate) fn current() -> Verbosity {
+    match LEVEL.load(Ordering::Relaxed) {
+        l if l == Verbosity::Compact as u8 => Verbosity::Compact,
+        l if l == Verbosity::Rich as u8 => Verbosity::Rich,
+        _ => Verbosity::Normal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_drops_blank_lines() {
+        let output =
+            "# Search: \"a\" — 1 match\n\n## a.rs:1 [definition]\n1 │ fn a() {}\n\n\n── calls ──"
+                .to_string();
+        assert_eq!(
+            Verbosity::Compact.shape(output.clone()),
+            "# Search: \"a\" — 1 match\n## a.rs:1 [definition]\n1 │ fn a() {}\n── calls ──"
+        );
+        assert_eq!(Verbosity::Normal.shape(output.clone()), output);
+        assert!(!Verbosity::Compact.footers());
+        assert_eq!(Verbosity::Rich.footer_cap(8), 16);
+    }
+}